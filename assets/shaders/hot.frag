@@ -0,0 +1,12 @@
+#version 450
+
+#import "lighting.glsl"
+
+layout(location = 0) in vec3 v_Normal;
+
+layout(location = 0) out vec4 o_Target;
+
+void main() {
+    vec3 color = vec3(0.2, 0.6, 0.9);
+    o_Target = vec4(color * lighting(normalize(v_Normal)), 1.0);
+}
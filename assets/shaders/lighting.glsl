@@ -0,0 +1,6 @@
+const vec3 LIGHT_DIRECTION = vec3(-0.4, -1.0, -0.6);
+const float AMBIENT = 0.1;
+
+float lighting(vec3 normal) {
+    return AMBIENT + max(dot(normal, -normalize(LIGHT_DIRECTION)), 0.0);
+}
@@ -1,23 +1,25 @@
-#[cfg(feature = "dynamic_plugins")]
-use crate::plugin::dynamically_load_plugin;
 use crate::{
     app::{App, AppExit},
     event::Events,
-    plugin::Plugin,
+    plugin::{order_plugins, plugin_type_id, Plugin, PluginDependencyError},
+    plugin_group::{PluginGroup, PluginGroupBuilder},
     stage, startup_stage,
 };
-use bevy_ecs::{FromResources, IntoQuerySystem, Resources, System, World};
+use bevy_ecs::{
+    FromResources, IntoQuerySystem, Resources, RunCriterion, State, StateDriver, StateSystem,
+    System, SystemDescriptor, World,
+};
+use std::{any::TypeId, collections::HashSet};
 
 /// Configure [App]s using the builder pattern
 pub struct AppBuilder {
     pub app: App,
+    plugins: HashSet<TypeId>,
 }
 
 impl Default for AppBuilder {
     fn default() -> Self {
-        let mut app_builder = AppBuilder {
-            app: App::default(),
-        };
+        let mut app_builder = AppBuilder::empty();
 
         app_builder.add_default_stages();
         app_builder.add_event::<AppExit>();
@@ -29,6 +31,7 @@ impl AppBuilder {
     pub fn empty() -> AppBuilder {
         AppBuilder {
             app: App::default(),
+            plugins: HashSet::new(),
         }
     }
 
@@ -69,12 +72,22 @@ impl AppBuilder {
         self
     }
 
+    /// Makes `criterion` decide whether, and how many times, the given stage runs each update
+    pub fn set_run_criterion(
+        &mut self,
+        stage_name: &'static str,
+        criterion: impl RunCriterion,
+    ) -> &mut Self {
+        self.app.schedule.set_run_criterion(stage_name, criterion);
+        self
+    }
+
     pub fn add_startup_stage(&mut self, stage_name: &'static str) -> &mut Self {
         self.app.startup_schedule.add_stage(stage_name);
         self
     }
 
-    pub fn add_system(&mut self, system: Box<dyn System>) -> &mut Self {
+    pub fn add_system(&mut self, system: impl Into<SystemDescriptor>) -> &mut Self {
         self.add_system_to_stage(stage::UPDATE, system)
     }
 
@@ -101,7 +114,7 @@ impl AppBuilder {
     pub fn add_startup_system_to_stage(
         &mut self,
         stage_name: &'static str,
-        system: Box<dyn System>,
+        system: impl Into<SystemDescriptor>,
     ) -> &mut Self {
         self.app
             .startup_schedule
@@ -122,7 +135,7 @@ impl AppBuilder {
         self
     }
 
-    pub fn add_startup_system(&mut self, system: Box<dyn System>) -> &mut Self {
+    pub fn add_startup_system(&mut self, system: impl Into<SystemDescriptor>) -> &mut Self {
         self.app
             .startup_schedule
             .add_system_to_stage(startup_stage::STARTUP, system);
@@ -163,7 +176,7 @@ impl AppBuilder {
     pub fn add_system_to_stage(
         &mut self,
         stage_name: &'static str,
-        system: Box<dyn System>,
+        system: impl Into<SystemDescriptor>,
     ) -> &mut Self {
         self.app.schedule.add_system_to_stage(stage_name, system);
         self
@@ -172,7 +185,7 @@ impl AppBuilder {
     pub fn add_system_to_stage_front(
         &mut self,
         stage_name: &'static str,
-        system: Box<dyn System>,
+        system: impl Into<SystemDescriptor>,
     ) -> &mut Self {
         self.app
             .schedule
@@ -191,6 +204,52 @@ impl AppBuilder {
         self
     }
 
+    /// Adds a [State] resource starting in `initial`, and a stage after [stage::UPDATE] that runs the
+    /// systems added with [AppBuilder::on_state_enter], [AppBuilder::on_state_update] and
+    /// [AppBuilder::on_state_exit]
+    pub fn add_state<T>(&mut self, initial: T) -> &mut Self
+    where
+        T: Clone + Eq + Send + Sync + 'static,
+    {
+        let stage_name = State::<T>::stage_name();
+        self.add_resource(State::new(initial))
+            .add_stage_after(stage::UPDATE, stage_name)
+            .set_run_criterion(stage_name, StateDriver::<T>::default())
+    }
+
+    /// Adds a system that runs once whenever `state` is entered
+    pub fn on_state_enter<T>(&mut self, state: T, system: Box<dyn System>) -> &mut Self
+    where
+        T: Clone + Eq + Send + Sync + 'static,
+    {
+        self.add_system_to_stage(
+            State::<T>::stage_name(),
+            StateSystem::on_enter(state, system),
+        )
+    }
+
+    /// Adds a system that runs once every update while `state` is active
+    pub fn on_state_update<T>(&mut self, state: T, system: Box<dyn System>) -> &mut Self
+    where
+        T: Clone + Eq + Send + Sync + 'static,
+    {
+        self.add_system_to_stage(
+            State::<T>::stage_name(),
+            StateSystem::on_update(state, system),
+        )
+    }
+
+    /// Adds a system that runs once whenever `state` is left
+    pub fn on_state_exit<T>(&mut self, state: T, system: Box<dyn System>) -> &mut Self
+    where
+        T: Clone + Eq + Send + Sync + 'static,
+    {
+        self.add_system_to_stage(
+            State::<T>::stage_name(),
+            StateSystem::on_exit(state, system),
+        )
+    }
+
     pub fn add_event<T>(&mut self) -> &mut Self
     where
         T: Send + Sync + 'static,
@@ -222,20 +281,87 @@ impl AppBuilder {
         self
     }
 
+    /// Loads and builds the dynamic plugin with the given name or path, appending the platform's
+    /// library prefix and extension when they are missing (see [dynamic_plugin_path](crate::dynamic_plugin_path)).
     #[cfg(feature = "dynamic_plugins")]
-    pub fn load_plugin(&mut self, path: &str) -> &mut Self {
-        let (_lib, plugin) = dynamically_load_plugin(path);
+    pub fn load_plugin(
+        &mut self,
+        name: impl AsRef<std::path::Path>,
+    ) -> Result<&mut Self, crate::plugin::DynamicPluginLoadError> {
+        let (lib, plugin) = crate::plugin::dynamically_load_plugin(name)?;
         log::debug!("loaded plugin: {}", plugin.name());
-        plugin.build(self);
-        self
+        self.add_plugins(vec![plugin]);
+        // the plugin's systems and resources may run code from the library for the rest of the
+        // program, so it must never be unloaded
+        std::mem::forget(lib);
+        Ok(self)
     }
 
+    /// Builds `plugin` unless a plugin of the same type was already added
+    ///
+    /// Panics if any of the plugin's [dependencies](Plugin::dependencies) haven't been added yet.
     pub fn add_plugin<T>(&mut self, plugin: T) -> &mut Self
     where
         T: Plugin,
     {
-        log::debug!("added plugin: {}", plugin.name());
-        plugin.build(self);
+        let plugins: Vec<Box<dyn Plugin>> = vec![Box::new(plugin)];
+        self.add_plugins(plugins)
+    }
+
+    /// Builds the plugins of `group` after their [dependencies](Plugin::dependencies), skipping
+    /// duplicates and plugins that were already added
+    ///
+    /// Panics if a dependency is neither in the group nor already added, or if dependencies form a
+    /// cycle. See [AppBuilder::try_add_plugins] for a non-panicking version.
+    pub fn add_plugins<G: PluginGroup>(&mut self, group: G) -> &mut Self {
+        self.add_plugins_with(group, |group| group)
+    }
+
+    /// Like [AppBuilder::add_plugins], but lets `func` enable, disable or insert plugins in the
+    /// group before it is built
+    ///
+    /// ```no_run
+    /// # use bevy_app::prelude::*;
+    /// # fn group() -> Vec<Box<dyn Plugin>> { Vec::new() }
+    /// # struct WindowPlugin;
+    /// # impl Plugin for WindowPlugin { fn build(&self, _app: &mut AppBuilder) {} }
+    /// App::build().add_plugins_with(group(), |group| group.disable::<WindowPlugin>());
+    /// ```
+    pub fn add_plugins_with<G: PluginGroup>(
+        &mut self,
+        group: G,
+        func: impl FnOnce(&mut PluginGroupBuilder) -> &mut PluginGroupBuilder,
+    ) -> &mut Self {
+        if let Err(err) = self.try_add_plugins_with(group, func) {
+            panic!("{}", err);
+        }
         self
     }
+
+    /// Like [AppBuilder::add_plugins], but returns an error instead of panicking. No plugin is
+    /// built if the dependencies can't be satisfied.
+    pub fn try_add_plugins<G: PluginGroup>(
+        &mut self,
+        group: G,
+    ) -> Result<&mut Self, PluginDependencyError> {
+        self.try_add_plugins_with(group, |group| group)
+    }
+
+    /// Like [AppBuilder::add_plugins_with], but returns an error instead of panicking
+    pub fn try_add_plugins_with<G: PluginGroup>(
+        &mut self,
+        mut group: G,
+        func: impl FnOnce(&mut PluginGroupBuilder) -> &mut PluginGroupBuilder,
+    ) -> Result<&mut Self, PluginDependencyError> {
+        let mut builder = PluginGroupBuilder::default();
+        group.build(&mut builder);
+        func(&mut builder);
+        let plugins = builder.finish();
+        for plugin in order_plugins(plugins, &self.plugins)? {
+            log::debug!("added plugin: {}", plugin.name());
+            self.plugins.insert(plugin_type_id(plugin.as_ref()));
+            plugin.build(self);
+        }
+        Ok(self)
+    }
 }
@@ -7,6 +7,7 @@ mod app;
 mod app_builder;
 mod event;
 mod plugin;
+mod plugin_group;
 mod schedule_runner;
 mod task_pool_options;
 
@@ -15,6 +16,7 @@ pub use app_builder::*;
 pub use bevy_derive::DynamicPlugin;
 pub use event::*;
 pub use plugin::*;
+pub use plugin_group::*;
 pub use schedule_runner::*;
 pub use task_pool_options::*;
 
@@ -24,6 +26,7 @@ pub mod prelude {
         app_builder::AppBuilder,
         event::{EventReader, Events},
         plugin::Plugin,
+        plugin_group::PluginGroup,
         stage, DynamicPlugin,
     };
 }
@@ -1,7 +1,13 @@
 use crate::AppBuilder;
 #[cfg(feature = "dynamic_plugins")]
 use libloading::{Library, Symbol};
-use std::any::Any;
+use std::{
+    any::{Any, TypeId},
+    collections::HashSet,
+    env::consts::{DLL_PREFIX, DLL_SUFFIX},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
 
 /// A collection of Bevy App logic and configuration
 ///
@@ -11,18 +17,244 @@ pub trait Plugin: Any + Send + Sync {
     fn name(&self) -> &str {
         std::any::type_name::<Self>()
     }
+    /// The types of the plugins that must be built before this one
+    fn dependencies(&self) -> Vec<TypeId> {
+        Vec::new()
+    }
+}
+
+/// Errors in the dependencies plugins declare with [Plugin::dependencies]
+#[derive(Error, Debug)]
+pub enum PluginDependencyError {
+    #[error("Plugin {plugin} depends on a plugin that was not added ({dependency:?}).")]
+    MissingDependency { plugin: String, dependency: TypeId },
+    #[error("Plugins have cyclic dependencies: {0:?}.")]
+    Cycle(Vec<String>),
 }
 
-pub type CreatePlugin = unsafe fn() -> *mut dyn Plugin;
+/// Orders `plugins` so that each comes after its dependencies, keeping the given order where
+/// dependencies don't constrain it. Plugins whose type is in `added` or that appear more than
+/// once are dropped.
+pub(crate) fn order_plugins(
+    plugins: Vec<Box<dyn Plugin>>,
+    added: &HashSet<TypeId>,
+) -> Result<Vec<Box<dyn Plugin>>, PluginDependencyError> {
+    let mut ids = HashSet::new();
+    let mut pending = plugins
+        .into_iter()
+        .filter(|plugin| {
+            let id = plugin_type_id(plugin.as_ref());
+            !added.contains(&id) && ids.insert(id)
+        })
+        .collect::<Vec<_>>();
+
+    for plugin in pending.iter() {
+        if let Some(&dependency) = plugin
+            .dependencies()
+            .iter()
+            .find(|dependency| !added.contains(dependency) && !ids.contains(dependency))
+        {
+            return Err(PluginDependencyError::MissingDependency {
+                plugin: plugin.name().to_string(),
+                dependency,
+            });
+        }
+    }
+
+    let mut built = added.clone();
+    let mut ordered = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let next = pending
+            .iter()
+            .position(|plugin| {
+                plugin
+                    .dependencies()
+                    .iter()
+                    .all(|dependency| built.contains(dependency))
+            })
+            .ok_or_else(|| {
+                PluginDependencyError::Cycle(
+                    pending
+                        .iter()
+                        .map(|plugin| plugin.name().to_string())
+                        .collect(),
+                )
+            })?;
+        let plugin = pending.remove(next);
+        built.insert(plugin_type_id(plugin.as_ref()));
+        ordered.push(plugin);
+    }
+    Ok(ordered)
+}
+
+/// The type id of the concrete plugin behind `plugin`
+pub(crate) fn plugin_type_id(plugin: &dyn Plugin) -> TypeId {
+    Any::type_id(plugin)
+}
+
+/// The signature of the entry point a dynamic plugin library must export under the name
+/// `_create_plugin` (see [CREATE_PLUGIN_SYMBOL]).
+///
+/// The function must be `#[no_mangle] extern "C"` and return a pointer obtained from
+/// [Box::into_raw], which the loader takes ownership of. Plugin trait objects are not FFI-safe,
+/// so the library must be built with the same compiler and bevy version as the app loading it.
+/// `#[derive(DynamicPlugin)]` generates a conforming entry point.
+pub type CreatePlugin = unsafe extern "C" fn() -> *mut dyn Plugin;
+
+/// The symbol name of a dynamic plugin's [CreatePlugin] entry point
+pub const CREATE_PLUGIN_SYMBOL: &[u8] = b"_create_plugin";
+
+/// Errors that occur while loading dynamic plugins
+#[cfg(feature = "dynamic_plugins")]
+#[derive(Error, Debug)]
+pub enum DynamicPluginLoadError {
+    #[error("Dynamic plugin library not found at {0}.")]
+    NotFound(PathBuf),
+    #[error("Failed to load dynamic plugin library.")]
+    Library(#[source] libloading::Error),
+    #[error("Dynamic plugin library does not export a `_create_plugin` entry point.")]
+    MissingEntryPoint(#[source] libloading::Error),
+}
+
+/// Resolves the platform-specific file name of the dynamic library for a plugin, e.g.
+/// `target/debug/my_plugin` becomes `target/debug/libmy_plugin.so` on Linux,
+/// `target/debug/libmy_plugin.dylib` on macOS and `target/debug/my_plugin.dll` on Windows.
+/// Paths that already carry the platform's library extension are returned unchanged.
+pub fn dynamic_plugin_path(name: impl AsRef<Path>) -> PathBuf {
+    let name = name.as_ref();
+    let file_name = match name.file_name().and_then(|file_name| file_name.to_str()) {
+        Some(file_name) if !file_name.ends_with(DLL_SUFFIX) => file_name,
+        _ => return name.to_path_buf(),
+    };
+    let file_name = if file_name.starts_with(DLL_PREFIX) {
+        format!("{}{}", file_name, DLL_SUFFIX)
+    } else {
+        format!("{}{}{}", DLL_PREFIX, file_name, DLL_SUFFIX)
+    };
+    name.with_file_name(file_name)
+}
 
 #[cfg(feature = "dynamic_plugins")]
-/// Dynamically links a plugin a the given path. The plugin must export the [CreatePlugin] function.
-pub fn dynamically_load_plugin(path: &str) -> (Library, Box<dyn Plugin>) {
-    let lib = Library::new(path).unwrap();
+/// Dynamically links the plugin with the given name or path, resolved with [dynamic_plugin_path].
+/// The plugin must export the [CreatePlugin] entry point.
+pub fn dynamically_load_plugin(
+    name: impl AsRef<Path>,
+) -> Result<(Library, Box<dyn Plugin>), DynamicPluginLoadError> {
+    let path = dynamic_plugin_path(name);
+    if !path.is_file() {
+        return Err(DynamicPluginLoadError::NotFound(path));
+    }
+    let lib = Library::new(&path).map_err(DynamicPluginLoadError::Library)?;
 
     unsafe {
-        let func: Symbol<CreatePlugin> = lib.get(b"_create_plugin").unwrap();
+        let func: Symbol<CreatePlugin> = lib
+            .get(CREATE_PLUGIN_SYMBOL)
+            .map_err(DynamicPluginLoadError::MissingEntryPoint)?;
         let plugin = Box::from_raw(func());
-        (lib, plugin)
+        Ok((lib, plugin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct A;
+    struct B;
+    struct C;
+
+    impl Plugin for A {
+        fn build(&self, _app: &mut AppBuilder) {}
+    }
+
+    impl Plugin for B {
+        fn build(&self, _app: &mut AppBuilder) {}
+        fn dependencies(&self) -> Vec<TypeId> {
+            vec![TypeId::of::<C>()]
+        }
+    }
+
+    impl Plugin for C {
+        fn build(&self, _app: &mut AppBuilder) {}
+        fn dependencies(&self) -> Vec<TypeId> {
+            vec![TypeId::of::<A>()]
+        }
+    }
+
+    fn names(plugins: &[Box<dyn Plugin>]) -> Vec<&str> {
+        plugins.iter().map(|plugin| plugin.name()).collect()
+    }
+
+    #[test]
+    fn order_by_dependencies() {
+        let ordered = order_plugins(
+            vec![Box::new(B), Box::new(A), Box::new(C), Box::new(A)],
+            &HashSet::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            names(&ordered),
+            [
+                std::any::type_name::<A>(),
+                std::any::type_name::<C>(),
+                std::any::type_name::<B>()
+            ]
+        );
+
+        let added = vec![TypeId::of::<A>()].into_iter().collect();
+        let ordered = order_plugins(vec![Box::new(A), Box::new(C)], &added).unwrap();
+        assert_eq!(names(&ordered), [std::any::type_name::<C>()]);
+    }
+
+    #[test]
+    fn missing_dependency() {
+        match order_plugins(vec![Box::new(B), Box::new(A)], &HashSet::new()) {
+            Err(PluginDependencyError::MissingDependency { dependency, .. }) => {
+                assert_eq!(dependency, TypeId::of::<C>())
+            }
+            _ => panic!("expected a missing dependency"),
+        }
+    }
+
+    #[test]
+    fn dependency_cycle() {
+        struct D;
+        struct E;
+        impl Plugin for D {
+            fn build(&self, _app: &mut AppBuilder) {}
+            fn dependencies(&self) -> Vec<TypeId> {
+                vec![TypeId::of::<E>()]
+            }
+        }
+        impl Plugin for E {
+            fn build(&self, _app: &mut AppBuilder) {}
+            fn dependencies(&self) -> Vec<TypeId> {
+                vec![TypeId::of::<D>()]
+            }
+        }
+
+        let result = order_plugins(vec![Box::new(A), Box::new(D), Box::new(E)], &HashSet::new());
+        assert!(matches!(result, Err(PluginDependencyError::Cycle(plugins)) if plugins.len() == 2));
+    }
+
+    #[test]
+    fn dynamic_plugin_paths() {
+        let library = |dir: &str, name: &str| {
+            Path::new(dir).join(format!("{}{}{}", DLL_PREFIX, name, DLL_SUFFIX))
+        };
+        assert_eq!(
+            dynamic_plugin_path("target/debug/my_plugin"),
+            library("target/debug", "my_plugin")
+        );
+        assert_eq!(dynamic_plugin_path("my_plugin"), library("", "my_plugin"));
+
+        // the prefix isn't doubled, and full library names are left alone
+        let prefixed = format!("target/debug/{}my_plugin", DLL_PREFIX);
+        assert_eq!(
+            dynamic_plugin_path(&prefixed),
+            library("target/debug", "my_plugin")
+        );
+        let full = library("target/debug", "my_plugin");
+        assert_eq!(dynamic_plugin_path(&full), full);
     }
 }
@@ -0,0 +1,180 @@
+use crate::plugin::{plugin_type_id, Plugin};
+use std::{
+    any::{type_name, TypeId},
+    collections::HashMap,
+};
+
+/// A named set of plugins that can be added to an app together with
+/// [AppBuilder::add_plugins](crate::AppBuilder::add_plugins)
+pub trait PluginGroup: 'static {
+    fn build(&mut self, group: &mut PluginGroupBuilder);
+}
+
+impl PluginGroup for Vec<Box<dyn Plugin>> {
+    fn build(&mut self, group: &mut PluginGroupBuilder) {
+        for plugin in self.drain(..) {
+            group.add_boxed(plugin);
+        }
+    }
+}
+
+struct PluginEntry {
+    plugin: Box<dyn Plugin>,
+    enabled: bool,
+}
+
+/// The ordered plugins of a [PluginGroup], which can be edited before they are built
+///
+/// Disabling a plugin keeps its position, so re-enabling it restores the original order.
+#[derive(Default)]
+pub struct PluginGroupBuilder {
+    plugins: HashMap<TypeId, PluginEntry>,
+    order: Vec<TypeId>,
+}
+
+impl PluginGroupBuilder {
+    /// Appends `plugin`, or replaces the plugin of the same type in place
+    pub fn add<T: Plugin>(&mut self, plugin: T) -> &mut Self {
+        self.add_boxed(Box::new(plugin))
+    }
+
+    fn add_boxed(&mut self, plugin: Box<dyn Plugin>) -> &mut Self {
+        let id = plugin_type_id(plugin.as_ref());
+        let entry = PluginEntry {
+            plugin,
+            enabled: true,
+        };
+        if self.plugins.insert(id, entry).is_none() {
+            self.order.push(id);
+        }
+        self
+    }
+
+    /// Inserts `plugin` just before the `Target` plugin
+    ///
+    /// Panics if the group doesn't contain `Target`.
+    pub fn add_before<Target: Plugin, T: Plugin>(&mut self, plugin: T) -> &mut Self {
+        let index = self.index_of::<Target>();
+        self.insert_at(index, plugin)
+    }
+
+    /// Inserts `plugin` just after the `Target` plugin
+    ///
+    /// Panics if the group doesn't contain `Target`.
+    pub fn add_after<Target: Plugin, T: Plugin>(&mut self, plugin: T) -> &mut Self {
+        let index = self.index_of::<Target>() + 1;
+        self.insert_at(index, plugin)
+    }
+
+    /// Re-enables a previously disabled plugin
+    ///
+    /// Panics if the group doesn't contain `T`.
+    pub fn enable<T: Plugin>(&mut self) -> &mut Self {
+        self.entry_mut::<T>().enabled = true;
+        self
+    }
+
+    /// Excludes the `T` plugin from the group without changing the order of the others. Plugins
+    /// that depend on `T` must be disabled as well.
+    ///
+    /// Panics if the group doesn't contain `T`.
+    pub fn disable<T: Plugin>(&mut self) -> &mut Self {
+        self.entry_mut::<T>().enabled = false;
+        self
+    }
+
+    /// Consumes the builder, returning its enabled plugins in order
+    pub fn finish(mut self) -> Vec<Box<dyn Plugin>> {
+        let plugins = &mut self.plugins;
+        self.order
+            .iter()
+            .filter_map(|id| plugins.remove(id))
+            .filter(|entry| entry.enabled)
+            .map(|entry| entry.plugin)
+            .collect()
+    }
+
+    fn insert_at<T: Plugin>(&mut self, index: usize, plugin: T) -> &mut Self {
+        let id = TypeId::of::<T>();
+        if self.plugins.contains_key(&id) {
+            panic!("Plugin already exists in group: {}", type_name::<T>());
+        }
+        self.plugins.insert(
+            id,
+            PluginEntry {
+                plugin: Box::new(plugin),
+                enabled: true,
+            },
+        );
+        self.order.insert(index, id);
+        self
+    }
+
+    fn index_of<T: Plugin>(&self) -> usize {
+        let id = TypeId::of::<T>();
+        self.order
+            .iter()
+            .position(|&plugin| plugin == id)
+            .unwrap_or_else(|| panic!("Plugin does not exist in group: {}", type_name::<T>()))
+    }
+
+    fn entry_mut<T: Plugin>(&mut self) -> &mut PluginEntry {
+        self.plugins
+            .get_mut(&TypeId::of::<T>())
+            .unwrap_or_else(|| panic!("Plugin does not exist in group: {}", type_name::<T>()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AppBuilder;
+
+    struct A;
+    struct B;
+    struct C;
+
+    impl Plugin for A {
+        fn build(&self, _app: &mut AppBuilder) {}
+    }
+
+    impl Plugin for B {
+        fn build(&self, _app: &mut AppBuilder) {}
+    }
+
+    impl Plugin for C {
+        fn build(&self, _app: &mut AppBuilder) {}
+    }
+
+    fn names(plugins: Vec<Box<dyn Plugin>>) -> Vec<String> {
+        plugins
+            .iter()
+            .map(|plugin| plugin.name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn edit_group() {
+        let mut group = PluginGroupBuilder::default();
+        group.add(A).add(C).add_before::<C, B>(B).disable::<A>();
+        assert_eq!(names(group.finish()), [type_name::<B>(), type_name::<C>()]);
+
+        let mut group = PluginGroupBuilder::default();
+        group
+            .add(A)
+            .add(B)
+            .disable::<A>()
+            .add_after::<A, C>(C)
+            .enable::<A>();
+        assert_eq!(
+            names(group.finish()),
+            [type_name::<A>(), type_name::<C>(), type_name::<B>()]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_before_missing() {
+        PluginGroupBuilder::default().add(A).add_before::<B, C>(C);
+    }
+}
@@ -4,8 +4,7 @@ use crate::{
 };
 use anyhow::Result;
 use bevy_ecs::{Res, Resource, Resources};
-use bevy_utils::{HashMap, HashSet};
-use crossbeam_channel::TryRecvError;
+use bevy_utils::HashMap;
 use parking_lot::RwLock;
 use std::{
     env, fs, io,
@@ -30,6 +29,8 @@ pub enum AssetServerError {
     MissingAssetHandler,
     #[error("No AssetLoader found for the given extension.")]
     MissingAssetLoader,
+    #[error("An asset loader is already registered for the extension \"{0}\".")]
+    ExtensionAlreadyRegistered(String),
     #[error("Encountered an error while loading an asset.")]
     AssetLoadError(#[from] AssetLoadError),
     #[error("Encountered an io error.")]
@@ -105,10 +106,11 @@ impl Default for AssetServer {
 }
 
 impl AssetServer {
-    pub fn add_handler<T>(&mut self, asset_handler: T)
+    pub fn add_handler<T>(&mut self, asset_handler: T) -> Result<(), AssetServerError>
     where
         T: AssetLoadRequestHandler,
     {
+        Self::check_extensions(&self.extension_to_handler_index, asset_handler.extensions())?;
         let mut asset_handlers = self.asset_handlers.write();
         let handler_index = asset_handlers.len();
         for extension in asset_handler.extensions().iter() {
@@ -117,13 +119,15 @@ impl AssetServer {
         }
 
         asset_handlers.push(Box::new(asset_handler));
+        Ok(())
     }
 
-    pub fn add_loader<TLoader, TAsset>(&mut self, loader: TLoader)
+    pub fn add_loader<TLoader, TAsset>(&mut self, loader: TLoader) -> Result<(), AssetServerError>
     where
         TLoader: AssetLoader<TAsset>,
         TAsset: 'static,
     {
+        Self::check_extensions(&self.extension_to_loader_index, loader.extensions())?;
         let loader_index = self.loaders.len();
         for extension in loader.extensions().iter() {
             self.extension_to_loader_index
@@ -133,6 +137,22 @@ impl AssetServer {
         let mut resources = Resources::default();
         resources.insert::<Box<dyn AssetLoader<TAsset>>>(Box::new(loader));
         self.loaders.push(resources);
+        Ok(())
+    }
+
+    fn check_extensions(
+        registered: &HashMap<String, usize>,
+        extensions: &[&str],
+    ) -> Result<(), AssetServerError> {
+        match extensions
+            .iter()
+            .find(|extension| registered.contains_key(**extension))
+        {
+            Some(extension) => Err(AssetServerError::ExtensionAlreadyRegistered(
+                extension.to_string(),
+            )),
+            None => Ok(()),
+        }
     }
 
     pub fn load_asset_folder<P: AsRef<Path>>(
@@ -153,6 +173,14 @@ impl AssetServer {
             .map(|handle_id| Handle::from(*handle_id))
     }
 
+    /// Returns the path an asset was loaded from
+    pub fn get_handle_path<T>(&self, handle: Handle<T>) -> Option<PathBuf> {
+        self.asset_info
+            .read()
+            .get(&handle.id)
+            .map(|asset_info| asset_info.path.clone())
+    }
+
     #[cfg(feature = "filesystem_watcher")]
     fn watch_path_for_changes<P: AsRef<Path>>(
         filesystem_watcher: &mut Option<FilesystemWatcher>,
@@ -185,40 +213,17 @@ impl AssetServer {
 
     #[cfg(feature = "filesystem_watcher")]
     pub fn filesystem_watcher_system(asset_server: Res<AssetServer>) {
-        let mut changed = HashSet::default();
-
-        loop {
-            let result = {
-                let rwlock_guard = asset_server.filesystem_watcher.read();
-                if let Some(filesystem_watcher) = rwlock_guard.as_ref() {
-                    filesystem_watcher.receiver.try_recv()
-                } else {
-                    break;
-                }
-            };
-            let event = match result {
-                Ok(result) => result.unwrap(),
-                Err(TryRecvError::Empty) => break,
-                Err(TryRecvError::Disconnected) => panic!("FilesystemWatcher disconnected"),
-            };
-            if let notify::event::Event {
-                kind: notify::event::EventKind::Modify(_),
-                paths,
-                ..
-            } = event
-            {
-                for path in paths.iter() {
-                    if !changed.contains(path) {
-                        let root_path = asset_server.get_root_path().unwrap();
-                        let relative_path = path.strip_prefix(root_path).unwrap();
-                        match asset_server.load_untyped(relative_path) {
-                            Ok(_) => {}
-                            Err(AssetServerError::AssetLoadError(error)) => panic!("{:?}", error),
-                            Err(_) => {}
-                        }
-                    }
-                }
-                changed.extend(paths);
+        let changed = match asset_server.filesystem_watcher.write().as_mut() {
+            Some(filesystem_watcher) => filesystem_watcher.changed_paths(),
+            None => return,
+        };
+
+        for path in changed.iter() {
+            let root_path = asset_server.get_root_path().unwrap();
+            let relative_path = path.strip_prefix(root_path).unwrap();
+            // if the reload fails, the previous version of the asset stays in place
+            if let Err(err) = asset_server.load_untyped(relative_path) {
+                log::error!("Failed to reload asset {:?}: {:?}", relative_path, err);
             }
         }
     }
@@ -288,13 +293,10 @@ impl AssetServer {
                         .get(path)
                         .and_then(|handle_id| asset_info.get_mut(&handle_id))
                     {
-                        asset_info.load_state =
-                            if let LoadState::Loaded(_version) = asset_info.load_state {
-                                new_version += 1;
-                                LoadState::Loading(new_version)
-                            } else {
-                                LoadState::Loading(new_version)
-                            };
+                        // every reload gets a newer version, so results of stale loads that
+                        // finish late are ignored
+                        new_version = asset_info.load_state.get_version() + 1;
+                        asset_info.load_state = LoadState::Loading(new_version);
                         asset_info.handle_id
                     } else {
                         let handle_id = HandleId::new();
@@ -452,3 +454,124 @@ impl AssetServer {
         Ok(handle_ids)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AssetInfo, AssetServer, AssetServerError, LoadState};
+    use crate::{
+        update_asset_storage_system, AssetChannel, AssetLoadError, AssetLoader, AssetResult,
+        Assets, Handle, HandleId,
+    };
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+    use std::{
+        io,
+        path::{Path, PathBuf},
+    };
+
+    struct NumberLoader;
+
+    impl AssetLoader<u32> for NumberLoader {
+        fn from_bytes(&self, _asset_path: &Path, bytes: Vec<u8>) -> Result<u32, anyhow::Error> {
+            Ok(String::from_utf8(bytes)?.trim().parse()?)
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt", "num"]
+        }
+    }
+
+    struct TextLoader;
+
+    impl AssetLoader<String> for TextLoader {
+        fn from_bytes(&self, _asset_path: &Path, bytes: Vec<u8>) -> Result<String, anyhow::Error> {
+            Ok(String::from_utf8(bytes)?)
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["md", "txt"]
+        }
+    }
+
+    #[test]
+    fn extensions_can_only_be_registered_once() {
+        let mut asset_server = AssetServer::default();
+        asset_server.add_loader(NumberLoader).unwrap();
+        match asset_server.add_loader(TextLoader) {
+            Err(AssetServerError::ExtensionAlreadyRegistered(extension)) => {
+                assert_eq!(extension, "txt")
+            }
+            _ => panic!("expected the extension to already be registered"),
+        }
+
+        // the rejected loader's other extensions aren't registered either
+        assert!(!asset_server.extension_to_loader_index.contains_key("md"));
+        assert_eq!(asset_server.loaders.len(), 1);
+    }
+
+    #[test]
+    fn stale_load_results_do_not_replace_newer_versions() {
+        let handle_id = HandleId::new();
+        let handle = Handle::<u32>::from(handle_id);
+        let path = PathBuf::from("number.txt");
+        let asset_server = AssetServer::default();
+        // version 0 is still loading, but a reload to version 1 has already been requested
+        asset_server.asset_info.write().insert(
+            handle_id,
+            AssetInfo {
+                handle_id,
+                path: path.clone(),
+                load_state: LoadState::Loading(1),
+            },
+        );
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(asset_server);
+        resources.insert(AssetChannel::<u32>::new());
+        resources.insert(Assets::<u32>::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", update_asset_storage_system::<u32>.system());
+        schedule.initialize(&mut resources);
+
+        let send = |resources: &Resources, result, version| {
+            resources
+                .get::<AssetChannel<u32>>()
+                .unwrap()
+                .sender
+                .send(AssetResult {
+                    result,
+                    handle,
+                    path: path.clone(),
+                    version,
+                })
+                .unwrap();
+        };
+        let load_state = |resources: &Resources| {
+            resources
+                .get::<AssetServer>()
+                .unwrap()
+                .get_load_state(handle)
+        };
+        let stale_error = || {
+            Err(AssetLoadError::Io(io::Error::new(
+                io::ErrorKind::Other,
+                "stale",
+            )))
+        };
+
+        send(&resources, stale_error(), 0);
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(load_state(&resources), Some(LoadState::Loading(1)));
+
+        send(&resources, Ok(1), 1);
+        send(&resources, stale_error(), 0);
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(load_state(&resources), Some(LoadState::Loaded(1)));
+        assert_eq!(
+            resources.get::<Assets<u32>>().unwrap().get(&handle),
+            Some(&1)
+        );
+    }
+}
@@ -3,9 +3,9 @@ use crate::{
     Handle, HandleId,
 };
 use bevy_app::{prelude::Events, AppBuilder};
-use bevy_ecs::{FromResources, IntoQuerySystem, ResMut, Resource};
+use bevy_ecs::{FromResources, IntoQuerySystem, Local, Query, Res, ResMut, Resource};
 use bevy_type_registry::RegisterType;
-use bevy_utils::HashMap;
+use bevy_utils::{HashMap, HashSet};
 
 /// Events that happen on assets of type `T`
 pub enum AssetEvent<T: Resource> {
@@ -18,6 +18,7 @@ pub enum AssetEvent<T: Resource> {
 pub struct Assets<T: Resource> {
     assets: HashMap<Handle<T>, T>,
     events: Events<AssetEvent<T>>,
+    collectable: HashSet<Handle<T>>,
 }
 
 impl<T: Resource> Default for Assets<T> {
@@ -25,6 +26,7 @@ impl<T: Resource> Default for Assets<T> {
         Assets {
             assets: HashMap::default(),
             events: Events::default(),
+            collectable: HashSet::default(),
         }
     }
 }
@@ -98,8 +100,31 @@ impl<T: Resource> Assets<T> {
         self.assets.iter().map(|(k, v)| (*k, v))
     }
 
+    /// Unloads the asset of `handle` right away, whether or not it is still used
     pub fn remove(&mut self, handle: &Handle<T>) -> Option<T> {
-        self.assets.remove(&handle)
+        self.collectable.remove(handle);
+        let asset = self.assets.remove(&handle)?;
+        self.events.send(AssetEvent::Removed { handle: *handle });
+        Some(asset)
+    }
+
+    /// Sets whether [asset_gc_system] unloads the asset of `handle` once it is no longer used
+    ///
+    /// A collectable asset is used while any entity has a `Handle<T>` component referring to it.
+    /// Once it has been used and then no entity refers to it anymore, it is removed. Handles held
+    /// anywhere else, such as in resources, other assets or [WeakHandle](crate::WeakHandle)s,
+    /// don't keep it loaded, so only make assets collectable that are only referenced from
+    /// components. Assets aren't collectable unless this is called.
+    pub fn set_collectable(&mut self, handle: Handle<T>, collectable: bool) {
+        if collectable {
+            self.collectable.insert(handle);
+        } else {
+            self.collectable.remove(&handle);
+        }
+    }
+
+    pub fn is_collectable(&self, handle: &Handle<T>) -> bool {
+        self.collectable.contains(handle)
     }
 
     pub fn asset_event_system(
@@ -110,11 +135,82 @@ impl<T: Resource> Assets<T> {
     }
 }
 
+/// Limits how much work [asset_gc_system] does in a single frame
+pub struct AssetGcSettings {
+    /// The most assets of each type that are unloaded in one frame. Unloading a whole level
+    /// is spread over several frames instead of causing a spike.
+    pub max_unloads_per_frame: usize,
+}
+
+impl Default for AssetGcSettings {
+    fn default() -> Self {
+        AssetGcSettings {
+            max_unloads_per_frame: 16,
+        }
+    }
+}
+
+/// The collectable assets that [asset_gc_system] has seen in use
+pub struct AssetGcState<T: Resource> {
+    used: HashSet<Handle<T>>,
+}
+
+impl<T: Resource> Default for AssetGcState<T> {
+    fn default() -> Self {
+        AssetGcState {
+            used: HashSet::default(),
+        }
+    }
+}
+
+/// Unloads collectable assets that no entity's `Handle<T>` component refers to anymore
+///
+/// See [Assets::set_collectable]. An asset only counts as unused once it has been used, so an
+/// asset that was just loaded isn't removed before it is given to an entity. Asset types without
+/// collectable assets are skipped without looking at any components.
+pub fn asset_gc_system<T: Resource>(
+    mut state: Local<AssetGcState<T>>,
+    settings: Res<AssetGcSettings>,
+    mut assets: ResMut<Assets<T>>,
+    mut handle_query: Query<&Handle<T>>,
+) {
+    if assets.collectable.is_empty() {
+        state.used.clear();
+        return;
+    }
+
+    let mut in_use = HashSet::default();
+    for handle in &mut handle_query.iter() {
+        if assets.collectable.contains(handle) {
+            in_use.insert(*handle);
+        }
+    }
+
+    let collectable = &assets.collectable;
+    state.used.retain(|handle| collectable.contains(handle));
+    let mut unused = Vec::new();
+    for handle in collectable.iter() {
+        if in_use.contains(handle) {
+            state.used.insert(*handle);
+        } else if state.used.contains(handle) && assets.assets.contains_key(handle) {
+            unused.push(*handle);
+        }
+    }
+
+    for handle in unused.into_iter().take(settings.max_unloads_per_frame) {
+        state.used.remove(&handle);
+        assets.remove(&handle);
+    }
+}
+
 /// [AppBuilder] extension methods for adding new asset types
 pub trait AddAsset {
     fn add_asset<T>(&mut self) -> &mut Self
     where
         T: Send + Sync + 'static;
+    /// Registers `TLoader` to load `TAsset`s from files with the loader's extensions
+    ///
+    /// Panics if another loader is already registered for one of those extensions.
     fn add_asset_loader<TAsset, TLoader>(&mut self) -> &mut Self
     where
         TLoader: AssetLoader<TAsset> + FromResources,
@@ -128,6 +224,7 @@ impl AddAsset for AppBuilder {
     {
         self.init_resource::<Assets<T>>()
             .register_component::<Handle<T>>()
+            .add_system_to_stage(super::stage::ASSET_EVENTS, asset_gc_system::<T>.system())
             .add_system_to_stage(
                 super::stage::ASSET_EVENTS,
                 Assets::<T>::asset_event_system.system(),
@@ -156,13 +253,106 @@ impl AddAsset for AppBuilder {
                 .resources()
                 .get_mut::<AssetServer>()
                 .expect("AssetServer does not exist. Consider adding it as a resource.");
-            asset_server.add_loader(TLoader::from_resources(self.resources()));
+            asset_server
+                .add_loader(TLoader::from_resources(self.resources()))
+                .unwrap_or_else(|err| panic!("{}", err));
             let handler = ChannelAssetHandler::new(
                 TLoader::from_resources(self.resources()),
                 asset_channel.sender.clone(),
             );
-            asset_server.add_handler(handler);
+            asset_server
+                .add_handler(handler)
+                .unwrap_or_else(|err| panic!("{}", err));
         }
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{asset_gc_system, AssetEvent, AssetGcSettings, Assets};
+    use crate::{Handle, WeakHandle};
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+
+    fn setup(assets: Assets<u32>, settings: AssetGcSettings) -> (Resources, Schedule) {
+        let mut resources = Resources::default();
+        resources.insert(assets);
+        resources.insert(settings);
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", asset_gc_system::<u32>.system());
+        schedule.initialize(&mut resources);
+        (resources, schedule)
+    }
+
+    #[test]
+    fn unloads_collectable_assets_once_unused() {
+        let mut assets = Assets::<u32>::default();
+        let used = assets.add(1);
+        let still_used = assets.add(2);
+        let not_collectable = assets.add(3);
+        let never_used = assets.add(4);
+        for handle in [used, still_used, never_used].iter() {
+            assets.set_collectable(*handle, true);
+        }
+
+        let mut world = World::default();
+        let entity = world.spawn((used, used.clone_weak()));
+        world.spawn((still_used,));
+        world.spawn((not_collectable,));
+        let (mut resources, mut schedule) = setup(assets, AssetGcSettings::default());
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(resources.get::<Assets<u32>>().unwrap().iter().count(), 4);
+
+        // weak handles don't keep assets loaded
+        world.remove_one::<Handle<u32>>(entity).unwrap();
+        schedule.run(&mut world, &mut resources);
+
+        let assets = resources.get::<Assets<u32>>().unwrap();
+        assert_eq!(assets.get(&used), None);
+        assert!(!assets.is_collectable(&used));
+        assert_eq!(assets.get(&still_used), Some(&2));
+        assert_eq!(assets.get(&not_collectable), Some(&3));
+        assert_eq!(assets.get(&never_used), Some(&4));
+        let weak = world.get::<WeakHandle<u32>>(entity).unwrap();
+        assert_eq!(weak.upgrade(&assets), None);
+
+        let mut reader = assets.events.get_reader();
+        let removed = reader
+            .iter(&assets.events)
+            .filter_map(|event| match event {
+                AssetEvent::Removed { handle } => Some(*handle),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(removed, vec![used]);
+    }
+
+    #[test]
+    fn spreads_unloads_over_frames() {
+        let mut assets = Assets::<u32>::default();
+        let mut world = World::default();
+        let mut entities = Vec::new();
+        for i in 0..5 {
+            let handle = assets.add(i);
+            assets.set_collectable(handle, true);
+            entities.push(world.spawn((handle,)));
+        }
+        let (mut resources, mut schedule) = setup(
+            assets,
+            AssetGcSettings {
+                max_unloads_per_frame: 2,
+            },
+        );
+        schedule.run(&mut world, &mut resources);
+
+        for entity in entities {
+            world.despawn(entity).unwrap();
+        }
+        for remaining in [3, 1, 0].iter() {
+            schedule.run(&mut world, &mut resources);
+            let assets = resources.get::<Assets<u32>>().unwrap();
+            assert_eq!(assets.iter().count(), *remaining);
+        }
+    }
+}
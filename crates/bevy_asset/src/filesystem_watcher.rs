@@ -1,11 +1,22 @@
-use crossbeam_channel::Receiver;
-use notify::{Event, RecommendedWatcher, RecursiveMode, Result, Watcher};
-use std::path::Path;
+use bevy_utils::HashMap;
+use crossbeam_channel::{Receiver, TryRecvError};
+use notify::{
+    event::{Event, EventKind},
+    RecommendedWatcher, RecursiveMode, Result, Watcher,
+};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 /// Watches for changes to assets on the filesystem. This is used by the `AssetServer` to reload them
 pub struct FilesystemWatcher {
     pub watcher: RecommendedWatcher,
     pub receiver: Receiver<Result<Event>>,
+    /// How long a file must go without further modifications before it is reported as changed.
+    /// Editors often write a file several times in quick succession when saving it.
+    pub debounce: Duration,
+    pending: HashMap<PathBuf, Instant>,
 }
 
 impl Default for FilesystemWatcher {
@@ -15,7 +26,12 @@ impl Default for FilesystemWatcher {
             sender.send(res).expect("Watch event send failure");
         })
         .expect("Failed to create filesystem watcher");
-        FilesystemWatcher { watcher, receiver }
+        FilesystemWatcher {
+            watcher,
+            receiver,
+            debounce: Duration::from_millis(100),
+            pending: HashMap::default(),
+        }
     }
 }
 
@@ -23,4 +39,84 @@ impl FilesystemWatcher {
     pub fn watch<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
         self.watcher.watch(path, RecursiveMode::Recursive)
     }
+
+    /// Returns the modified paths that have settled, i.e. that haven't been modified again for at
+    /// least `debounce`
+    pub fn changed_paths(&mut self) -> Vec<PathBuf> {
+        self.changed_paths_at(Instant::now())
+    }
+
+    fn changed_paths_at(&mut self, now: Instant) -> Vec<PathBuf> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(Ok(Event {
+                    kind: EventKind::Modify(_),
+                    paths,
+                    ..
+                })) => {
+                    for path in paths {
+                        self.pending.insert(path, now);
+                    }
+                }
+                Ok(Ok(_)) => {}
+                Ok(Err(err)) => log::warn!("Filesystem watcher error: {:?}", err),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => panic!("FilesystemWatcher disconnected"),
+            }
+        }
+
+        let debounce = self.debounce;
+        let changed = self
+            .pending
+            .iter()
+            .filter(|(_, modified)| now.duration_since(**modified) >= debounce)
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        for path in changed.iter() {
+            self.pending.remove(path);
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FilesystemWatcher;
+    use notify::event::{Event, EventKind, ModifyKind};
+    use std::{
+        path::PathBuf,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn repeated_modifications_are_reported_once() {
+        let mut watcher = FilesystemWatcher::default();
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        watcher.receiver = receiver;
+        watcher.debounce = Duration::from_millis(100);
+
+        let path = PathBuf::from("assets/texture.png");
+        let modify = || Ok(Event::new(EventKind::Modify(ModifyKind::Any)).add_path(path.clone()));
+        let start = Instant::now();
+        for _ in 0..3 {
+            sender.send(modify()).unwrap();
+        }
+        assert!(watcher.changed_paths_at(start).is_empty());
+
+        // another write restarts the debounce
+        sender.send(modify()).unwrap();
+        let rewritten = start + Duration::from_millis(50);
+        assert!(watcher.changed_paths_at(rewritten).is_empty());
+        assert!(watcher
+            .changed_paths_at(start + Duration::from_millis(120))
+            .is_empty());
+
+        assert_eq!(
+            watcher.changed_paths_at(rewritten + Duration::from_millis(100)),
+            vec![path]
+        );
+        assert!(watcher
+            .changed_paths_at(rewritten + Duration::from_millis(200))
+            .is_empty());
+    }
 }
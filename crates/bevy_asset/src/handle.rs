@@ -3,6 +3,8 @@ use std::{
     hash::{Hash, Hasher},
 };
 
+use crate::Assets;
+use bevy_ecs::Resource;
 use bevy_property::{Properties, Property};
 use serde::{Deserialize, Serialize};
 use std::{any::TypeId, marker::PhantomData};
@@ -26,6 +28,7 @@ impl HandleId {
 /// A handle into a specific Asset of type `T`
 ///
 /// Handles contain a unique id that corresponds to a specific asset in the [Assets](crate::Assets) collection.
+/// A `Handle<T>` component keeps a collectable asset loaded, see [Assets::set_collectable].
 #[derive(Properties)]
 pub struct Handle<T>
 where
@@ -52,6 +55,14 @@ impl<T> Handle<T> {
         Handle::from_id(self.id)
     }
 
+    /// Returns a reference to the same asset that doesn't keep it loaded
+    pub fn clone_weak(&self) -> WeakHandle<T> {
+        WeakHandle {
+            id: self.id,
+            marker: PhantomData,
+        }
+    }
+
     pub const fn from_id(id: HandleId) -> Self {
         Handle {
             id,
@@ -163,6 +174,62 @@ impl<T> Copy for Handle<T> {}
 unsafe impl<T> Send for Handle<T> {}
 unsafe impl<T> Sync for Handle<T> {}
 
+/// A reference to an asset of type `T` that doesn't keep it loaded
+///
+/// Unlike a [Handle], a `WeakHandle` component doesn't count as a use of a collectable asset, so
+/// the asset can be unloaded while the `WeakHandle` still exists. Use [WeakHandle::upgrade] to
+/// check whether it is still there.
+pub struct WeakHandle<T>
+where
+    T: 'static,
+{
+    pub id: HandleId,
+    marker: PhantomData<T>,
+}
+
+impl<T: Resource> WeakHandle<T> {
+    /// Returns a handle to the asset, unless it has been unloaded
+    pub fn upgrade(&self, assets: &Assets<T>) -> Option<Handle<T>> {
+        let handle = Handle::from_id(self.id);
+        assets.get(&handle).map(|_| handle)
+    }
+}
+
+impl<T> Hash for WeakHandle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> PartialEq for WeakHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for WeakHandle<T> {}
+
+impl<T> Debug for WeakHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        let name = std::any::type_name::<T>().split("::").last().unwrap();
+        write!(f, "WeakHandle<{}>({:?})", name, self.id.0)
+    }
+}
+
+impl<T> Clone for WeakHandle<T> {
+    fn clone(&self) -> Self {
+        WeakHandle {
+            id: self.id,
+            marker: PhantomData,
+        }
+    }
+}
+impl<T> Copy for WeakHandle<T> {}
+
+// SAFE: T is phantom data and WeakHandle::id is an integer
+unsafe impl<T> Send for WeakHandle<T> {}
+unsafe impl<T> Sync for WeakHandle<T> {}
+
 /// A non-generic version of [Handle]
 ///
 /// This allows handles to be mingled in a cross asset context. For example, storing `Handle<A>` and `Handle<B>` in the same `HashSet<HandleUntyped>`.
@@ -36,6 +36,7 @@ impl Plugin for AssetPlugin {
         app.add_stage_before(bevy_app::stage::PRE_UPDATE, stage::LOAD_ASSETS)
             .add_stage_after(bevy_app::stage::POST_UPDATE, stage::ASSET_EVENTS)
             .init_resource::<AssetServer>()
+            .init_resource::<AssetGcSettings>()
             .register_property::<HandleId>();
 
         #[cfg(feature = "filesystem_watcher")]
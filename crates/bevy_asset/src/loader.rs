@@ -62,18 +62,34 @@ pub fn update_asset_storage_system<T: Resource>(
 ) {
     loop {
         match asset_channel.receiver.try_recv() {
-            Ok(result) => match result.result {
-                Ok(asset) => {
-                    assets.set(result.handle, asset);
-                    asset_server
-                        .set_load_state(result.handle.id, LoadState::Loaded(result.version));
+            Ok(result) => {
+                // a reload may have been requested while this version was loading, in which case
+                // neither its asset nor its failure may replace the newer version's
+                let latest_version = asset_server
+                    .get_load_state(result.handle)
+                    .map_or(result.version, |load_state| load_state.get_version());
+                let is_latest = result.version >= latest_version;
+                match result.result {
+                    Ok(asset) => {
+                        if is_latest {
+                            assets.set(result.handle, asset);
+                            asset_server.set_load_state(
+                                result.handle.id,
+                                LoadState::Loaded(result.version),
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        if is_latest {
+                            asset_server.set_load_state(
+                                result.handle.id,
+                                LoadState::Failed(result.version),
+                            );
+                        }
+                        log::error!("Failed to load asset: {:?}", err);
+                    }
                 }
-                Err(err) => {
-                    asset_server
-                        .set_load_state(result.handle.id, LoadState::Failed(result.version));
-                    log::error!("Failed to load asset: {:?}", err);
-                }
-            },
+            }
             Err(TryRecvError::Empty) => {
                 break;
             }
@@ -2,13 +2,27 @@ use crate::AudioSource;
 use bevy_asset::{Assets, Handle};
 use bevy_ecs::Res;
 use parking_lot::RwLock;
-use rodio::{Decoder, Device, Sink};
-use std::{collections::VecDeque, io::Cursor};
+use rodio::{Decoder, Device, Sink, Source};
+use std::{
+    collections::VecDeque,
+    io::Cursor,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 /// Used to play audio on the current "audio device"
+///
+/// Sounds are mixed and played by rodio on its own thread, so they keep playing smoothly when
+/// frames take long.
 pub struct AudioOutput {
     device: Device,
-    queue: RwLock<VecDeque<Handle<AudioSource>>>,
+    queue: RwLock<VecDeque<(Handle<AudioSource>, AudioInstance)>>,
+    /// Instances that have started, kept alive until they finish so that dropping an
+    /// [AudioInstance] doesn't stop its sound
+    playing: RwLock<Vec<AudioInstance>>,
 }
 
 impl Default for AudioOutput {
@@ -16,19 +30,26 @@ impl Default for AudioOutput {
         Self {
             device: rodio::default_output_device().unwrap(),
             queue: Default::default(),
+            playing: Default::default(),
         }
     }
 }
 
 impl AudioOutput {
-    pub fn play_source(&self, audio_source: &AudioSource) {
-        let sink = Sink::new(&self.device);
-        sink.append(Decoder::new(Cursor::new(audio_source.clone())).unwrap());
-        sink.detach();
+    pub fn play_source(&self, audio_source: &AudioSource) -> AudioInstance {
+        let instance = AudioInstance::new(&self.device);
+        self.start(audio_source, instance.clone());
+        instance
     }
 
-    pub fn play(&self, audio_source: Handle<AudioSource>) {
-        self.queue.write().push_front(audio_source);
+    /// Plays `audio_source` once it has loaded. The returned instance controls the sound, and can
+    /// be used before the sound starts.
+    pub fn play(&self, audio_source: Handle<AudioSource>) -> AudioInstance {
+        let instance = AudioInstance::new(&self.device);
+        self.queue
+            .write()
+            .push_front((audio_source, instance.clone()));
+        instance
     }
 
     pub fn try_play_queued(&self, audio_sources: &Assets<AudioSource>) {
@@ -36,15 +57,148 @@ impl AudioOutput {
         let len = queue.len();
         let mut i = 0;
         while i < len {
-            let audio_source_handle = queue.pop_back().unwrap();
+            let (audio_source_handle, instance) = queue.pop_back().unwrap();
             if let Some(audio_source) = audio_sources.get(&audio_source_handle) {
-                self.play_source(audio_source);
+                self.start(audio_source, instance);
             } else {
                 // audio source hasn't loaded yet. add it back to the queue
-                queue.push_front(audio_source_handle);
+                queue.push_front((audio_source_handle, instance));
             }
             i += 1;
         }
+
+        self.playing
+            .write()
+            .retain(|instance| !instance.is_finished());
+    }
+
+    fn start(&self, audio_source: &AudioSource, instance: AudioInstance) {
+        let source = LoopingDecoder::new(audio_source.clone(), instance.inner.looping.clone());
+        instance.inner.sink.append(source);
+        instance.inner.started.store(true, Ordering::Relaxed);
+        self.playing.write().push(instance);
+    }
+}
+
+/// Controls a sound played by [AudioOutput]
+///
+/// Clones control the same sound. Controlling a sound that has already finished or been stopped
+/// does nothing.
+#[derive(Clone)]
+pub struct AudioInstance {
+    inner: Arc<Playback>,
+}
+
+struct Playback {
+    sink: Sink,
+    looping: Arc<AtomicBool>,
+    /// Whether the source has been appended to `sink`, which is empty until then
+    started: AtomicBool,
+}
+
+impl AudioInstance {
+    fn new(device: &Device) -> Self {
+        Self {
+            inner: Arc::new(Playback {
+                sink: Sink::new(device),
+                looping: Arc::new(AtomicBool::new(false)),
+                started: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub fn pause(&self) {
+        self.inner.sink.pause();
+    }
+
+    /// Resumes a paused sound
+    pub fn resume(&self) {
+        self.inner.sink.play();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.inner.sink.is_paused()
+    }
+
+    /// Stops the sound for good. It can't be resumed afterwards.
+    pub fn stop(&self) {
+        self.inner.sink.stop();
+    }
+
+    /// Sets the volume, where `1.0` is the source's original volume
+    pub fn set_volume(&self, volume: f32) {
+        self.inner.sink.set_volume(volume);
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.inner.sink.volume()
+    }
+
+    /// Sets whether the sound starts over when it reaches its end
+    pub fn set_looping(&self, looping: bool) {
+        self.inner.looping.store(looping, Ordering::Relaxed);
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.inner.looping.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether the sound has played to its end or was stopped. Sounds that are still
+    /// waiting for their source to load aren't finished.
+    pub fn is_finished(&self) -> bool {
+        self.inner.started.load(Ordering::Relaxed) && self.inner.sink.empty()
+    }
+}
+
+/// Decodes an [AudioSource], starting over from the beginning at the end while `looping` is set
+struct LoopingDecoder {
+    audio_source: AudioSource,
+    decoder: Decoder<Cursor<AudioSource>>,
+    looping: Arc<AtomicBool>,
+}
+
+impl LoopingDecoder {
+    fn new(audio_source: AudioSource, looping: Arc<AtomicBool>) -> Self {
+        Self {
+            decoder: Decoder::new(Cursor::new(audio_source.clone())).unwrap(),
+            audio_source,
+            looping,
+        }
+    }
+}
+
+impl Iterator for LoopingDecoder {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        match self.decoder.next() {
+            Some(sample) => Some(sample),
+            None if self.looping.load(Ordering::Relaxed) => {
+                // the source decoded once already, so it decodes again
+                self.decoder = Decoder::new(Cursor::new(self.audio_source.clone())).unwrap();
+                self.decoder.next()
+            }
+            None => None,
+        }
+    }
+}
+
+impl Source for LoopingDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.decoder.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.decoder.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.decoder.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        // looping can be turned on at any time
+        None
     }
 }
 
@@ -5,7 +5,7 @@ pub use audio_output::*;
 pub use audio_source::*;
 
 pub mod prelude {
-    pub use crate::{AudioOutput, AudioSource};
+    pub use crate::{AudioInstance, AudioOutput, AudioSource};
 }
 
 use bevy_app::prelude::*;
@@ -23,4 +23,8 @@ impl Plugin for AudioPlugin {
             .add_asset_loader::<AudioSource, Mp3Loader>()
             .add_system_to_stage(stage::POST_UPDATE, play_queued_audio_system.system());
     }
+
+    fn dependencies(&self) -> Vec<std::any::TypeId> {
+        vec![std::any::TypeId::of::<bevy_asset::AssetPlugin>()]
+    }
 }
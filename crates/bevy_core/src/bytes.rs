@@ -124,6 +124,23 @@ impl FromBytes for Mat4 {
     }
 }
 
+// GLSL bools are 32 bits wide
+impl Bytes for bool {
+    fn write_bytes(&self, buffer: &mut [u8]) {
+        (*self as u32).write_bytes(buffer);
+    }
+
+    fn byte_len(&self) -> usize {
+        std::mem::size_of::<u32>()
+    }
+}
+
+impl FromBytes for bool {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        u32::from_bytes(bytes) != 0
+    }
+}
+
 impl<T> Bytes for Option<T>
 where
     T: Bytes,
@@ -198,6 +215,12 @@ mod tests {
         test_round_trip(123u32);
     }
 
+    #[test]
+    fn test_bool_bytes_round_trip() {
+        test_round_trip(true);
+        test_round_trip(false);
+    }
+
     #[test]
     fn test_f64_bytes_round_trip() {
         test_round_trip(123f64);
@@ -9,7 +9,7 @@ pub use label::*;
 pub use time::*;
 
 pub mod prelude {
-    pub use crate::{EntityLabels, Labels, Time, Timer};
+    pub use crate::{EntityLabels, FixedTime, FixedTimestep, Labels, Stopwatch, Time, Timer};
 }
 
 use bevy_app::prelude::*;
@@ -26,6 +26,7 @@ impl Plugin for CorePlugin {
         app.init_resource::<Time>()
             .init_resource::<EntityLabels>()
             .register_component::<Timer>()
+            .register_component::<Stopwatch>()
             .register_property::<Vec2>()
             .register_property::<Vec3>()
             .register_property::<Mat3>()
@@ -34,6 +35,7 @@ impl Plugin for CorePlugin {
             .register_property::<Option<String>>()
             .add_system_to_stage(stage::FIRST, time_system.system())
             .add_system_to_stage(stage::FIRST, timer_system.system())
+            .add_system_to_stage(stage::FIRST, stopwatch_system.system())
             .add_system_to_stage(stage::PRE_UPDATE, entity_labels_system.system());
     }
 }
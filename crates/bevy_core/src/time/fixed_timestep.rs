@@ -0,0 +1,137 @@
+use crate::time::Time;
+use bevy_ecs::{Resources, RunCriterion, ShouldRun, World};
+use std::time::Duration;
+
+/// The fixed timestep of the stage currently being run by a [FixedTimestep]
+///
+/// Systems in a fixed timestep stage should advance their simulation by `delta_seconds` instead of
+/// the variable frame time in [Time].
+#[derive(Debug, Clone, Default)]
+pub struct FixedTime {
+    pub delta: Duration,
+    pub delta_seconds: f32,
+    /// How far into the next step the accumulated time is, from 0 to 1. Useful for interpolating
+    /// rendered state between steps.
+    pub overstep_fraction: f32,
+}
+
+/// A [RunCriterion] that runs a stage once per elapsed `step` of real time, zero or more times per
+/// update, and exposes the step to its systems through the [FixedTime] resource
+///
+/// ```no_run
+/// # use bevy_app::prelude::*;
+/// # use bevy_core::FixedTimestep;
+/// App::build()
+///     .add_stage_after(stage::UPDATE, "fixed_update")
+///     .set_run_criterion("fixed_update", FixedTimestep::steps_per_second(60.0));
+/// ```
+pub struct FixedTimestep {
+    step: Duration,
+    max_steps: u32,
+    accumulator: Duration,
+    steps: u32,
+    looping: bool,
+}
+
+impl FixedTimestep {
+    /// The default cap on steps per update, see [FixedTimestep::with_max_steps]
+    pub const DEFAULT_MAX_STEPS: u32 = 8;
+
+    pub fn step(step: Duration) -> Self {
+        assert!(
+            step > Duration::from_secs(0),
+            "fixed timestep must be positive"
+        );
+        FixedTimestep {
+            step,
+            max_steps: Self::DEFAULT_MAX_STEPS,
+            accumulator: Duration::from_secs(0),
+            steps: 0,
+            looping: false,
+        }
+    }
+
+    pub fn steps_per_second(rate: f64) -> Self {
+        Self::step(Duration::from_secs_f64(1.0 / rate))
+    }
+
+    /// Caps the number of steps run in a single update. When the app stalls for longer than
+    /// `max_steps` steps, the excess whole steps are dropped rather than caught up on, which would
+    /// make the next update even slower.
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Advances the accumulator by `delta` at the start of an update, then returns whether another
+    /// step should run
+    fn next_step(&mut self, delta: Duration) -> bool {
+        if !self.looping {
+            self.accumulator += delta;
+            self.steps = 0;
+        }
+        if self.accumulator >= self.step && self.steps < self.max_steps {
+            self.accumulator -= self.step;
+            self.steps += 1;
+            self.looping = true;
+            return true;
+        }
+
+        if self.accumulator >= self.step {
+            // spiral of death: keep only the time towards the next step
+            let step = self.step.as_nanos();
+            self.accumulator = Duration::from_nanos((self.accumulator.as_nanos() % step) as u64);
+        }
+        self.looping = false;
+        false
+    }
+}
+
+impl RunCriterion for FixedTimestep {
+    fn should_run(&mut self, _world: &mut World, resources: &mut Resources) -> ShouldRun {
+        let delta = resources
+            .get::<Time>()
+            .map_or(Duration::from_secs(0), |time| time.delta);
+        let should_run = self.next_step(delta);
+        resources.insert(FixedTime {
+            delta: self.step,
+            delta_seconds: self.step.as_secs_f32(),
+            overstep_fraction: self.accumulator.as_secs_f32() / self.step.as_secs_f32(),
+        });
+        if should_run {
+            ShouldRun::YesAndLoop
+        } else {
+            ShouldRun::No
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixedTimestep;
+    use std::time::Duration;
+
+    fn steps(timestep: &mut FixedTimestep, delta: Duration) -> u32 {
+        let mut steps = 0;
+        while timestep.next_step(delta) {
+            steps += 1;
+        }
+        steps
+    }
+
+    #[test]
+    fn carries_leftover_time() {
+        let mut timestep = FixedTimestep::step(Duration::from_millis(10));
+        assert_eq!(steps(&mut timestep, Duration::from_millis(15)), 1);
+        assert_eq!(steps(&mut timestep, Duration::from_millis(4)), 0);
+        assert_eq!(steps(&mut timestep, Duration::from_millis(1)), 1);
+        assert_eq!(steps(&mut timestep, Duration::from_millis(25)), 2);
+    }
+
+    #[test]
+    fn caps_steps_per_update() {
+        let mut timestep = FixedTimestep::step(Duration::from_millis(10)).with_max_steps(3);
+        assert_eq!(steps(&mut timestep, Duration::from_millis(1005)), 3);
+        assert_eq!(steps(&mut timestep, Duration::from_millis(5)), 1);
+    }
+}
@@ -1,6 +1,10 @@
+mod fixed_timestep;
+mod stopwatch;
 #[allow(clippy::module_inception)]
 mod time;
 mod timer;
 
+pub use fixed_timestep::*;
+pub use stopwatch::*;
 pub use time::*;
 pub use timer::*;
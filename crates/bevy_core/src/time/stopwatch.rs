@@ -0,0 +1,88 @@
+use crate::time::Time;
+use bevy_ecs::prelude::*;
+use bevy_property::Properties;
+
+/// Measures the time elapsed since it was started. Unlike a [Timer](super::Timer), a stopwatch
+/// never finishes.
+#[derive(Clone, Debug, Default, Properties)]
+pub struct Stopwatch {
+    elapsed: f32,
+    paused: bool,
+}
+
+impl Stopwatch {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the time elapsed on the stopwatch, in seconds
+    #[inline]
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    #[inline]
+    pub fn set_elapsed(&mut self, elapsed: f32) {
+        self.elapsed = elapsed;
+    }
+
+    /// Advances the stopwatch by `delta` seconds, unless it is paused
+    pub fn tick(&mut self, delta: f32) -> &Self {
+        if !self.paused {
+            self.elapsed += delta;
+        }
+
+        self
+    }
+
+    #[inline]
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    #[inline]
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    #[inline]
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Sets the elapsed time back to zero. This doesn't unpause the stopwatch.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+    }
+}
+
+pub(crate) fn stopwatch_system(time: Res<Time>, mut query: Query<&mut Stopwatch>) {
+    for mut stopwatch in &mut query.iter() {
+        stopwatch.tick(time.delta_seconds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stopwatch;
+
+    #[test]
+    fn test_stopwatch() {
+        let mut stopwatch = Stopwatch::new();
+        stopwatch.tick(1.5);
+        assert_eq!(stopwatch.elapsed(), 1.5);
+
+        stopwatch.pause();
+        stopwatch.tick(1.0);
+        assert!(stopwatch.paused());
+        assert_eq!(stopwatch.elapsed(), 1.5);
+
+        stopwatch.unpause();
+        stopwatch.tick(0.5);
+        assert_eq!(stopwatch.elapsed(), 2.0);
+
+        stopwatch.reset();
+        assert_eq!(stopwatch.elapsed(), 0.0);
+    }
+}
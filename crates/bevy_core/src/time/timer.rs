@@ -7,14 +7,15 @@ use std::time::Duration;
 ///
 /// Non repeating timers will stop tracking and stay in the finished state until reset.
 /// Repeating timers will only be in the finished state on each tick `duration` is reached or exceeded, and can still be reset at any given point.
+/// The time by which a repeating timer overshoots `duration` is carried over to its next cycle, so it doesn't drift.
 #[derive(Clone, Debug, Default, Properties)]
 pub struct Timer {
-    pub elapsed: f32,
-    pub duration: f32,
-    pub finished: bool,
+    elapsed: f32,
+    duration: f32,
+    finished: bool,
     /// Will only be true on the tick `duration` is reached or exceeded.
-    pub just_finished: bool,
-    pub repeating: bool,
+    just_finished: bool,
+    repeating: bool,
 }
 
 impl Timer {
@@ -34,7 +35,69 @@ impl Timer {
         }
     }
 
-    pub fn tick(&mut self, delta: f32) {
+    /// Returns the time elapsed on the timer, in seconds
+    #[inline]
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    #[inline]
+    pub fn set_elapsed(&mut self, elapsed: f32) {
+        self.elapsed = elapsed;
+    }
+
+    /// Returns the time it takes for the timer to finish, in seconds
+    #[inline]
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    #[inline]
+    pub fn set_duration(&mut self, duration: f32) {
+        self.duration = duration;
+    }
+
+    /// Returns whether the timer is finished. Repeating timers are only finished on the tick they
+    /// reach their duration.
+    #[inline]
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns whether the timer reached its duration on the last tick
+    #[inline]
+    pub fn just_finished(&self) -> bool {
+        self.just_finished
+    }
+
+    #[inline]
+    pub fn repeating(&self) -> bool {
+        self.repeating
+    }
+
+    #[inline]
+    pub fn set_repeating(&mut self, repeating: bool) {
+        self.repeating = repeating;
+    }
+
+    /// Returns how far along the timer is, from 0.0 to 1.0
+    #[inline]
+    pub fn percent(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.elapsed / self.duration).min(1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns how much of the timer is left, from 1.0 to 0.0
+    #[inline]
+    pub fn percent_left(&self) -> f32 {
+        1.0 - self.percent()
+    }
+
+    /// Advances the timer by `delta` seconds
+    pub fn tick(&mut self, delta: f32) -> &Self {
         let prev_finished = self.elapsed >= self.duration;
         if !prev_finished {
             self.elapsed += delta;
@@ -43,9 +106,20 @@ impl Timer {
         self.finished = self.elapsed >= self.duration;
         self.just_finished = !prev_finished && self.finished;
 
-        if self.repeating && self.finished {
-            self.elapsed %= self.duration;
+        if self.finished {
+            if self.repeating {
+                // keep the overshoot, so that the timer doesn't drift
+                self.elapsed = if self.duration > 0.0 {
+                    self.elapsed % self.duration
+                } else {
+                    0.0
+                };
+            } else {
+                self.elapsed = self.duration;
+            }
         }
+
+        self
     }
 
     pub fn reset(&mut self) {
@@ -60,3 +134,56 @@ pub(crate) fn timer_system(time: Res<Time>, mut query: Query<&mut Timer>) {
         timer.tick(time.delta_seconds);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Timer;
+
+    #[test]
+    fn test_non_repeating() {
+        let mut timer = Timer::from_seconds(10.0, false);
+        timer.tick(0.25);
+        assert_eq!(timer.elapsed(), 0.25);
+        assert!(!timer.finished());
+        assert!(!timer.just_finished());
+        assert_eq!(timer.percent(), 0.025);
+
+        timer.tick(10.0);
+        assert_eq!(timer.elapsed(), 10.0);
+        assert!(timer.finished());
+        assert!(timer.just_finished());
+        assert_eq!(timer.percent(), 1.0);
+
+        // stays finished, but only just finished on the tick it crossed its duration
+        timer.tick(1.0);
+        assert_eq!(timer.elapsed(), 10.0);
+        assert!(timer.finished());
+        assert!(!timer.just_finished());
+
+        timer.reset();
+        assert!(!timer.finished());
+        assert_eq!(timer.elapsed(), 0.0);
+    }
+
+    #[test]
+    fn test_repeating() {
+        let mut timer = Timer::from_seconds(2.0, true);
+        timer.tick(1.5);
+        assert!(!timer.finished());
+
+        // the overshoot carries over to the next cycle
+        timer.tick(1.0);
+        assert!(timer.finished());
+        assert!(timer.just_finished());
+        assert_eq!(timer.elapsed(), 0.5);
+
+        timer.tick(1.0);
+        assert!(!timer.finished());
+        assert!(!timer.just_finished());
+        assert_eq!(timer.elapsed(), 1.5);
+
+        timer.tick(0.5);
+        assert!(timer.just_finished());
+        assert_eq!(timer.elapsed(), 0.0);
+    }
+}
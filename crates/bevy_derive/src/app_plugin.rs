@@ -8,7 +8,8 @@ pub fn derive_dynamic_plugin(input: TokenStream) -> TokenStream {
 
     TokenStream::from(quote! {
         #[no_mangle]
-        pub extern "C" fn _create_plugin() -> *mut bevy::app::Plugin {
+        #[allow(improper_ctypes_definitions)]
+        pub extern "C" fn _create_plugin() -> *mut dyn bevy::app::Plugin {
             // TODO: without this the assembly does nothing. why is that the case?
             print!("");
             // make sure the constructor is the correct type.
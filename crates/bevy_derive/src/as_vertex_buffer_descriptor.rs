@@ -37,12 +37,17 @@ pub fn derive_as_vertex_buffer_descriptor(input: TokenStream) -> TokenStream {
                     .find(|a| *a.path.get_ident().as_ref().unwrap() == VERTEX_ATTRIBUTE_NAME)
                     .map_or_else(VertexAttributes::default, |a| {
                         syn::custom_keyword!(ignore);
+                        syn::custom_keyword!(instance);
                         let mut vertex_attributes = VertexAttributes::default();
                         a.parse_args_with(|input: ParseStream| {
                             if input.parse::<Option<ignore>>()?.is_some() {
                                 vertex_attributes.ignore = true;
                                 return Ok(());
                             }
+                            if input.parse::<Option<instance>>()?.is_some() {
+                                vertex_attributes.instance = true;
+                                return Ok(());
+                            }
                             Ok(())
                         })
                         .expect("invalid 'vertex' attribute format");
@@ -71,6 +76,15 @@ pub fn derive_as_vertex_buffer_descriptor(input: TokenStream) -> TokenStream {
         });
     }
 
+    let step_mode = if field_attributes
+        .iter()
+        .any(|(_, attrs)| attrs.instance && !attrs.ignore)
+    {
+        quote! { #bevy_render_path::pipeline::InputStepMode::Instance }
+    } else {
+        quote! { #bevy_render_path::pipeline::InputStepMode::Vertex }
+    };
+
     let struct_name_string = struct_name.to_string();
     let struct_name_uppercase = struct_name_string.to_uppercase();
     let vertex_buffer_descriptor_ident =
@@ -110,7 +124,7 @@ pub fn derive_as_vertex_buffer_descriptor(input: TokenStream) -> TokenStream {
                 #bevy_render_path::pipeline::VertexBufferDescriptor {
                     attributes: vertex_attribute_descriptors,
                     name: #struct_name_string.into(),
-                    step_mode: #bevy_render_path::pipeline::InputStepMode::Instance,
+                    step_mode: #step_mode,
                     stride: offset,
                 }
             });
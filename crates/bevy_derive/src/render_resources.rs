@@ -9,6 +9,7 @@ use syn::{
 struct RenderResourceFieldAttributes {
     pub ignore: bool,
     pub buffer: bool,
+    pub normal_map: bool,
 }
 
 #[derive(Default)]
@@ -93,12 +94,15 @@ pub fn derive_render_resources(input: TokenStream) -> TokenStream {
                         .map_or_else(RenderResourceFieldAttributes::default, |a| {
                             syn::custom_keyword!(ignore);
                             syn::custom_keyword!(buffer);
+                            syn::custom_keyword!(normal_map);
                             let mut attributes = RenderResourceFieldAttributes::default();
                             a.parse_args_with(|input: ParseStream| {
                                 if input.parse::<Option<ignore>>()?.is_some() {
                                     attributes.ignore = true;
                                 } else if input.parse::<Option<buffer>>()?.is_some() {
                                     attributes.buffer = true;
+                                } else if input.parse::<Option<normal_map>>()?.is_some() {
+                                    attributes.normal_map = true;
                                 }
                                 Ok(())
                             })
@@ -124,6 +128,10 @@ pub fn derive_render_resources(input: TokenStream) -> TokenStream {
             if attrs.buffer {
                 render_resource_hints
                     .push(quote! {Some(#bevy_render_path::renderer::RenderResourceHints::BUFFER)})
+            } else if attrs.normal_map {
+                render_resource_hints.push(
+                    quote! {Some(#bevy_render_path::renderer::RenderResourceHints::NORMAL_MAP)},
+                )
             } else {
                 render_resource_hints.push(quote! {None})
             }
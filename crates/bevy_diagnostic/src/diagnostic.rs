@@ -63,14 +63,16 @@ impl Diagnostic {
         }
     }
 
+    /// The most recent measurement
     pub fn value(&self) -> Option<f64> {
-        self.history.back().map(|measurement| measurement.value)
+        self.history.front().map(|measurement| measurement.value)
     }
 
     pub fn sum(&self) -> f64 {
         self.sum
     }
 
+    /// The average of the measurements in the history
     pub fn average(&self) -> Option<f64> {
         if !self.history.is_empty() {
             Some(self.sum / self.history.len() as f64)
@@ -100,6 +102,11 @@ impl Diagnostic {
     pub fn get_max_history_length(&self) -> usize {
         self.max_history_length
     }
+
+    /// Iterates over the measurements in the history, from newest to oldest
+    pub fn measurements(&self) -> impl Iterator<Item = &DiagnosticMeasurement> {
+        self.history.iter()
+    }
 }
 
 /// A collection of [Diagnostic]s
@@ -137,3 +144,42 @@ impl Diagnostics {
         self.diagnostics.values()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Diagnostic, DiagnosticId, Diagnostics};
+
+    #[test]
+    fn test_rolling_history() {
+        let mut diagnostic = Diagnostic::new(DiagnosticId::default(), "test", 3);
+        assert_eq!(diagnostic.value(), None);
+        assert_eq!(diagnostic.average(), None);
+
+        for value in 1..=5 {
+            diagnostic.add_measurement(value as f64);
+        }
+        assert_eq!(diagnostic.history_len(), 3);
+        assert_eq!(diagnostic.value(), Some(5.0));
+        assert_eq!(diagnostic.sum(), 12.0);
+        assert_eq!(diagnostic.average(), Some(4.0));
+        assert_eq!(
+            diagnostic
+                .measurements()
+                .map(|measurement| measurement.value)
+                .collect::<Vec<_>>(),
+            vec![5.0, 4.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn test_unregistered_measurement_is_ignored() {
+        let id = DiagnosticId::default();
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.add_measurement(id, 1.0);
+        assert!(diagnostics.get(id).is_none());
+
+        diagnostics.add(Diagnostic::new(id, "test", 10));
+        diagnostics.add_measurement(id, 1.0);
+        assert_eq!(diagnostics.get_measurement(id).unwrap().value, 1.0);
+    }
+}
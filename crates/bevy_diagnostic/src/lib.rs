@@ -1,15 +1,23 @@
 mod diagnostic;
+mod entity_count_diagnostics_plugin;
 mod frame_time_diagnostics_plugin;
-mod print_diagnostics_plugin;
+mod log_diagnostics_plugin;
 #[cfg(feature = "profiler")]
 mod system_profiler;
 pub use diagnostic::*;
+pub use entity_count_diagnostics_plugin::EntityCountDiagnosticsPlugin;
 pub use frame_time_diagnostics_plugin::FrameTimeDiagnosticsPlugin;
-pub use print_diagnostics_plugin::PrintDiagnosticsPlugin;
+#[allow(deprecated)]
+pub use log_diagnostics_plugin::{LogDiagnosticsPlugin, PrintDiagnosticsPlugin};
+#[cfg(feature = "profiler")]
+pub use system_profiler::SystemProfiler;
 
 use bevy_app::prelude::*;
 
 /// Adds core diagnostics resources to an App.
+///
+/// With the "profiler" feature enabled, this also records how long each system takes to run, as
+/// diagnostics named after the systems. See `SystemProfiler`.
 #[derive(Default)]
 pub struct DiagnosticsPlugin;
 
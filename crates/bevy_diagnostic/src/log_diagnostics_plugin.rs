@@ -0,0 +1,115 @@
+use super::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy_app::prelude::*;
+use bevy_core::{Time, Timer};
+use bevy_ecs::{IntoQuerySystem, Res, ResMut};
+use std::time::Duration;
+
+/// An App Plugin that logs diagnostics to the console
+pub struct LogDiagnosticsPlugin {
+    pub debug: bool,
+    pub wait_duration: Duration,
+    pub filter: Option<Vec<DiagnosticId>>,
+}
+
+#[deprecated(note = "renamed to LogDiagnosticsPlugin")]
+pub type PrintDiagnosticsPlugin = LogDiagnosticsPlugin;
+
+/// State used by the [LogDiagnosticsPlugin]
+pub struct LogDiagnosticsState {
+    timer: Timer,
+    filter: Option<Vec<DiagnosticId>>,
+}
+
+impl Default for LogDiagnosticsPlugin {
+    fn default() -> Self {
+        LogDiagnosticsPlugin {
+            debug: false,
+            wait_duration: Duration::from_secs(1),
+            filter: None,
+        }
+    }
+}
+
+impl Plugin for LogDiagnosticsPlugin {
+    fn build(&self, app: &mut bevy_app::AppBuilder) {
+        app.add_resource(LogDiagnosticsState {
+            timer: Timer::new(self.wait_duration, true),
+            filter: self.filter.clone(),
+        });
+
+        if self.debug {
+            app.add_system_to_stage(
+                stage::POST_UPDATE,
+                Self::log_diagnostics_debug_system.system(),
+            );
+        } else {
+            app.add_system_to_stage(stage::POST_UPDATE, Self::log_diagnostics_system.system());
+        }
+    }
+}
+
+impl LogDiagnosticsPlugin {
+    pub fn filtered(filter: Vec<DiagnosticId>) -> Self {
+        LogDiagnosticsPlugin {
+            filter: Some(filter),
+            ..Default::default()
+        }
+    }
+
+    fn log_diagnostic(diagnostic: &Diagnostic) {
+        if let Some(value) = diagnostic.value() {
+            if let Some(average) = diagnostic.average() {
+                log::info!(
+                    "{:<65}: {:<10.6}  (avg {:.6})",
+                    diagnostic.name,
+                    value,
+                    average
+                );
+            } else {
+                log::info!("{:<65}: {:<10.6}", diagnostic.name, value);
+            }
+        }
+    }
+
+    pub fn log_diagnostics_system(
+        mut state: ResMut<LogDiagnosticsState>,
+        time: Res<Time>,
+        diagnostics: Res<Diagnostics>,
+    ) {
+        state.timer.tick(time.delta_seconds);
+        if state.timer.finished() {
+            log::info!("Diagnostics:");
+            log::info!("{}", "-".repeat(93));
+            if let Some(ref filter) = state.filter {
+                for diagnostic in filter.iter().filter_map(|id| diagnostics.get(*id)) {
+                    Self::log_diagnostic(diagnostic);
+                }
+            } else {
+                for diagnostic in diagnostics.iter() {
+                    Self::log_diagnostic(diagnostic);
+                }
+            }
+        }
+    }
+
+    pub fn log_diagnostics_debug_system(
+        mut state: ResMut<LogDiagnosticsState>,
+        time: Res<Time>,
+        diagnostics: Res<Diagnostics>,
+    ) {
+        state.timer.tick(time.delta_seconds);
+        if state.timer.finished() {
+            log::info!("Diagnostics (Debug):");
+            log::info!("{}", "-".repeat(93));
+            if let Some(ref filter) = state.filter {
+                for diagnostic in filter.iter().filter_map(|id| diagnostics.get(*id)) {
+                    log::info!("{:#?}", diagnostic);
+                }
+            } else {
+                for diagnostic in diagnostics.iter() {
+                    log::info!("{:#?}", diagnostic);
+                }
+            }
+        }
+    }
+}
@@ -17,12 +17,28 @@ struct SystemProfiles {
     current_start: Option<Instant>,
 }
 
-/// Profiles systems by recording their run duration as diagnostics.
+/// Profiles systems by recording their run duration as diagnostics, named after the system.
+///
+/// Durations are wall-clock time from the start to the end of each system run. Systems that run in
+/// parallel are timed independently, so the durations of a stage's systems can add up to more than
+/// the time the stage took. A system that waits for a thread (for example behind a lock) has the
+/// wait counted in its duration.
 #[derive(Default)]
 pub struct SystemProfiler {
     system_profiles: Arc<RwLock<HashMap<Cow<'static, str>, SystemProfiles>>>,
 }
 
+impl SystemProfiler {
+    /// Returns the id of the diagnostic that holds the run durations of the system with the given
+    /// name, once the system has run
+    pub fn system_diagnostic_id(&self, system_name: &str) -> Option<DiagnosticId> {
+        self.system_profiles
+            .read()
+            .get(system_name)
+            .map(|profiles| profiles.diagnostic_id)
+    }
+}
+
 impl Profiler for SystemProfiler {
     fn start(&self, scope: Cow<'static, str>) {
         let mut system_profiles = self.system_profiles.write();
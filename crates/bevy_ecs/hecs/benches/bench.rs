@@ -62,6 +62,25 @@ fn spawn_batch(b: &mut Bencher) {
     });
 }
 
+fn spawn_batch_empty(b: &mut Bencher) {
+    let mut world = World::new();
+    b.iter(|| {
+        world
+            .spawn_batch((0..1_000_000).map(|_| ()))
+            .for_each(|_| {});
+        world.clear();
+    });
+}
+
+fn insert_remove(b: &mut Bencher) {
+    let mut world = World::new();
+    let entity = world.spawn((Position(0.0),));
+    b.iter(|| {
+        world.insert_one(entity, Velocity(0.0)).unwrap();
+        world.remove_one::<Velocity>(entity).unwrap();
+    });
+}
+
 fn iterate_100k(b: &mut Bencher) {
     let mut world = World::new();
     for i in 0..100_000 {
@@ -74,6 +93,18 @@ fn iterate_100k(b: &mut Bencher) {
     })
 }
 
+fn iterate_mut_100k(b: &mut Bencher) {
+    let mut world = World::new();
+    for i in 0..100_000 {
+        world.spawn((Position(-(i as f32)), Velocity(i as f32)));
+    }
+    b.iter(|| {
+        for (mut pos, vel) in &mut world.query_mut::<(&mut Position, &Velocity)>() {
+            pos.0 += vel.0;
+        }
+    })
+}
+
 fn build(b: &mut Bencher) {
     let mut world = World::new();
     let mut builder = EntityBuilder::new();
@@ -88,7 +119,10 @@ benchmark_group!(
     spawn_tuple,
     spawn_static,
     spawn_batch,
+    spawn_batch_empty,
+    insert_remove,
     iterate_100k,
+    iterate_mut_100k,
     build
 );
 benchmark_main!(benches);
@@ -0,0 +1,34 @@
+//! Integrating a million positions on every core with `QueryBorrow::par_iter`, compared to the
+//! equivalent single-threaded loop.
+
+use bevy_hecs::*;
+use rayon::iter::ParallelIterator;
+use std::time::Instant;
+
+struct Position(f32);
+struct Velocity(f32);
+
+fn main() {
+    let mut world = World::new();
+    world.spawn_batch((0..1_000_000).map(|i| (Position(0.0), Velocity(i as f32))));
+
+    let start = Instant::now();
+    for (mut pos, vel) in &mut world.query::<(&mut Position, &Velocity)>() {
+        pos.0 += vel.0;
+    }
+    println!("sequential: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    world
+        .query::<(&mut Position, &Velocity)>()
+        .par_iter(4096)
+        .for_each(|batch| {
+            for (mut pos, vel) in batch {
+                pos.0 += vel.0;
+            }
+        });
+    println!("parallel:   {:?}", start.elapsed());
+
+    let sum: f32 = world.query::<&Position>().iter().map(|pos| pos.0).sum();
+    println!("sum of positions: {}", sum);
+}
@@ -26,8 +26,14 @@ use syn::{parse_macro_input, DeriveInput, Path};
 ///
 /// Using derived `Bundle` impls improves spawn performance and can be convenient when combined with
 /// other derives like `serde::Deserialize`.
+///
+/// Fields annotated with `#[bundle]` must themselves be bundles. Their components are flattened
+/// into the outer bundle, so the whole struct still spawns into a single archetype. Using the same
+/// nested bundle type twice is a compile error. The derive can't see the components of nested
+/// bundles, so a component type occurring in more than one field or nested bundle is only caught
+/// at runtime: the bundle panics when it is first used.
 #[allow(clippy::cognitive_complexity)]
-#[proc_macro_derive(Bundle)]
+#[proc_macro_derive(Bundle, attributes(bundle))]
 pub fn derive_bundle(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     if !input.generics.params.is_empty() {
@@ -44,7 +50,6 @@ pub fn derive_bundle(input: TokenStream) -> TokenStream {
         }
     };
     let ident = input.ident;
-    let (tys, fields) = struct_fields(&data.fields);
     let path_str = if crate_name("bevy").is_ok() {
         "bevy::ecs"
     } else if crate_name("bevy_ecs").is_ok() {
@@ -55,8 +60,28 @@ pub fn derive_bundle(input: TokenStream) -> TokenStream {
 
     let path: Path = syn::parse(path_str.parse::<TokenStream>().unwrap()).unwrap();
 
-    let n = tys.len();
+    let mut bundle_tys = Vec::new();
+    let mut bundle_fields = Vec::new();
+    let mut component_tys = Vec::new();
+    let mut component_fields = Vec::new();
+    for (ty, field, is_bundle) in struct_fields(&data.fields) {
+        if is_bundle {
+            bundle_tys.push(ty);
+            bundle_fields.push(field);
+        } else {
+            component_tys.push(ty);
+            component_fields.push(field);
+        }
+    }
+
     let code = quote! {
+        // Implementing a local trait twice for the same nested bundle type doesn't compile
+        const _: () = {
+            #[allow(dead_code)]
+            trait NestedBundleOccursOnce {}
+            #(impl NestedBundleOccursOnce for #bundle_tys {})*
+        };
+
         impl #path::DynamicBundle for #ident {
             fn with_ids<T>(&self, f: impl FnOnce(&[std::any::TypeId]) -> T) -> T {
                 Self::with_static_ids(f)
@@ -68,35 +93,36 @@ pub fn derive_bundle(input: TokenStream) -> TokenStream {
 
             unsafe fn put(mut self, mut f: impl FnMut(*mut u8, std::any::TypeId, usize) -> bool) {
                 #(
-                    if f((&mut self.#fields as *mut #tys).cast::<u8>(), std::any::TypeId::of::<#tys>(), std::mem::size_of::<#tys>()) {
+                    if f((&mut self.#component_fields as *mut #component_tys).cast::<u8>(), std::any::TypeId::of::<#component_tys>(), std::mem::size_of::<#component_tys>()) {
                         #[allow(clippy::forget_copy)]
-                        std::mem::forget(self.#fields);
+                        std::mem::forget(self.#component_fields);
                     }
                 )*
+                #(
+                    #path::DynamicBundle::put(self.#bundle_fields, &mut f);
+                )*
             }
         }
 
         impl #path::Bundle for #ident {
             fn with_static_ids<T>(f: impl FnOnce(&[std::any::TypeId]) -> T) -> T {
                 use std::any::TypeId;
-                use std::mem;
 
                 #path::lazy_static::lazy_static! {
-                    static ref ELEMENTS: [TypeId; #n] = {
+                    static ref ELEMENTS: Vec<TypeId> = {
                         let mut dedup = #path::bevy_utils::HashSet::default();
-                        for &(ty, name) in [#((std::any::TypeId::of::<#tys>(), std::any::type_name::<#tys>())),*].iter() {
+                        let components: &[(TypeId, &str)] = &[#((std::any::TypeId::of::<#component_tys>(), std::any::type_name::<#component_tys>())),*];
+                        for &(ty, name) in components {
                             if !dedup.insert(ty) {
                                 panic!("{} has multiple {} fields; each type must occur at most once!", stringify!(#ident), name);
                             }
                         }
 
-                        let mut tys = [#((mem::align_of::<#tys>(), TypeId::of::<#tys>())),*];
-                        tys.sort_unstable_by(|x, y| x.0.cmp(&y.0).reverse().then(x.1.cmp(&y.1)));
-                        let mut ids = [TypeId::of::<()>(); #n];
-                        for (id, info) in ids.iter_mut().zip(tys.iter()) {
-                            *id = info.1;
+                        let info = <#ident as #path::Bundle>::static_type_info();
+                        if info.windows(2).any(|pair| pair[0].id() == pair[1].id()) {
+                            panic!("{} has a component type in more than one field or nested bundle; each type must occur at most once!", stringify!(#ident));
                         }
-                        ids
+                        info.iter().map(|ty| ty.id()).collect()
                     };
                 }
 
@@ -104,7 +130,10 @@ pub fn derive_bundle(input: TokenStream) -> TokenStream {
             }
 
             fn static_type_info() -> Vec<#path::TypeInfo> {
-                let mut info = vec![#(#path::TypeInfo::of::<#tys>()),*];
+                let mut info = vec![#(#path::TypeInfo::of::<#component_tys>()),*];
+                #(
+                    info.extend(<#bundle_tys as #path::Bundle>::static_type_info());
+                )*
                 info.sort_unstable();
                 info
             }
@@ -113,31 +142,49 @@ pub fn derive_bundle(input: TokenStream) -> TokenStream {
                 mut f: impl FnMut(std::any::TypeId, usize) -> Option<std::ptr::NonNull<u8>>,
             ) -> Result<Self, #path::MissingComponent> {
                 #(
-                    let #fields = f(std::any::TypeId::of::<#tys>(), std::mem::size_of::<#tys>())
-                            .ok_or_else(#path::MissingComponent::new::<#tys>)?
-                            .cast::<#tys>()
+                    let #component_fields = f(std::any::TypeId::of::<#component_tys>(), std::mem::size_of::<#component_tys>())
+                            .ok_or_else(#path::MissingComponent::new::<#component_tys>)?
+                            .cast::<#component_tys>()
                         .as_ptr();
                 )*
-                Ok(Self { #( #fields: #fields.read(), )* })
+                // Nested bundles are read as they are fetched. If a later field is missing, the
+                // copies read so far must be forgotten, as the originals are still in place.
+                #(
+                    let #bundle_fields = std::mem::ManuallyDrop::new(
+                        <#bundle_tys as #path::Bundle>::get(&mut f)?,
+                    );
+                )*
+                Ok(Self {
+                    #( #component_fields: #component_fields.read(), )*
+                    #( #bundle_fields: std::mem::ManuallyDrop::into_inner(#bundle_fields), )*
+                })
             }
         }
     };
     TokenStream::from(code)
 }
 
-fn struct_fields(fields: &syn::Fields) -> (Vec<&syn::Type>, Vec<syn::Ident>) {
+/// The type and name of each field of a struct, and whether it's a nested `#[bundle]`
+fn struct_fields(fields: &syn::Fields) -> Vec<(&syn::Type, syn::Ident, bool)> {
+    let is_bundle = |field: &syn::Field| field.attrs.iter().any(|a| a.path.is_ident("bundle"));
     match fields {
         syn::Fields::Named(ref fields) => fields
             .named
             .iter()
-            .map(|f| (&f.ty, f.ident.clone().unwrap()))
-            .unzip(),
+            .map(|f| (&f.ty, f.ident.clone().unwrap(), is_bundle(f)))
+            .collect(),
         syn::Fields::Unnamed(ref fields) => fields
             .unnamed
             .iter()
             .enumerate()
-            .map(|(i, f)| (&f.ty, syn::Ident::new(&i.to_string(), Span::call_site())))
-            .unzip(),
-        syn::Fields::Unit => (Vec::new(), Vec::new()),
+            .map(|(i, f)| {
+                (
+                    &f.ty,
+                    syn::Ident::new(&i.to_string(), Span::call_site()),
+                    is_bundle(f),
+                )
+            })
+            .collect(),
+        syn::Fields::Unit => Vec::new(),
     }
 }
@@ -17,7 +17,6 @@
 use crate::alloc::{
     alloc::{alloc, dealloc, Layout},
     boxed::Box,
-    vec,
     vec::Vec,
 };
 use bevy_utils::{HashMap, HashMapExt};
@@ -26,9 +25,14 @@ use core::{
     cell::UnsafeCell,
     mem,
     ptr::{self, NonNull},
+    slice,
 };
 
-use crate::{borrow::AtomicBorrow, query::Fetch, Access, Component, Query};
+use crate::{
+    borrow::{AtomicBorrow, ComponentSlice},
+    query::Fetch,
+    Access, Component, Entity, Query,
+};
 
 /// A collection of entities having the same component types
 ///
@@ -197,6 +201,24 @@ impl Archetype {
         }
     }
 
+    #[inline]
+    pub(crate) fn borrow_mut_pair<T: Component>(&self) {
+        if self
+            .state
+            .get(&TypeId::of::<T>())
+            .map_or(false, |x| !x.borrow.borrow_mut_pair())
+        {
+            panic!("{} already borrowed", type_name::<T>());
+        }
+    }
+
+    #[inline]
+    pub(crate) fn release_mut_pair<T: Component>(&self) {
+        if let Some(x) = self.state.get(&TypeId::of::<T>()) {
+            x.borrow.release_mut_pair();
+        }
+    }
+
     #[allow(missing_docs)]
     #[inline]
     pub fn release<T: Component>(&self) {
@@ -230,6 +252,38 @@ impl Archetype {
         self.entities.iter().take(self.len as usize)
     }
 
+    /// The entities stored in this archetype, in the order of their components in
+    /// `component_slice`
+    pub fn entity_ids(&self) -> &[Entity] {
+        let ids = &self.entities[..self.len as usize];
+        // SAFE: Entity is a repr(transparent) u128
+        unsafe { slice::from_raw_parts(ids.as_ptr().cast::<Entity>(), ids.len()) }
+    }
+
+    /// Borrow the `T` components of every entity in this archetype at once, or `None` if the
+    /// archetype doesn't store `T`
+    ///
+    /// Together with `entity_ids`, this lets whole columns be processed without going through
+    /// each entity, e.g. when serializing.
+    ///
+    /// Panics if the components are already borrowed uniquely.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((1, true));
+    /// let b = world.spawn((2, true));
+    /// let archetype = world.archetypes().find(|x| x.has::<bool>()).unwrap();
+    /// let numbers = archetype.component_slice::<i32>().unwrap();
+    /// let pairs = archetype.entity_ids().iter().zip(numbers.iter()).collect::<Vec<_>>();
+    /// assert_eq!(pairs, &[(&a, &1), (&b, &2)]);
+    /// assert!(archetype.component_slice::<&str>().is_none());
+    /// ```
+    pub fn component_slice<T: Component>(&self) -> Option<ComponentSlice<'_, T>> {
+        ComponentSlice::new(self)
+    }
+
     #[inline]
     pub(crate) fn entities(&self) -> NonNull<u128> {
         unsafe { NonNull::new_unchecked(self.entities.as_ptr() as *mut _) }
@@ -279,10 +333,31 @@ impl Archetype {
         }
     }
 
-    fn capacity(&self) -> u32 {
+    pub(crate) fn reserve_exact(&mut self, additional: u32) {
+        if additional > (self.capacity() - self.len()) {
+            self.grow(additional);
+        }
+    }
+
+    /// Number of entities this archetype can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> u32 {
         self.entities.len() as u32
     }
 
+    /// Bytes of heap memory this archetype holds, including capacity reserved for entities that
+    /// haven't been allocated yet: the component columns, the entity ID array and the change
+    /// trackers
+    pub fn allocated_bytes(&self) -> usize {
+        let trackers = self
+            .state
+            .values()
+            .map(|state| state.mutated_entities.capacity() + state.added_entities.capacity())
+            .sum::<usize>()
+            * mem::size_of::<bool>();
+        self.data_size + self.entities.len() * mem::size_of::<u128>() + trackers
+    }
+
     #[allow(missing_docs)]
     pub fn clear_trackers(&mut self) {
         for type_state in self.state.values_mut() {
@@ -290,44 +365,105 @@ impl Archetype {
         }
     }
 
+    #[allow(missing_docs)]
+    pub fn clear_trackers_for<T: Component>(&mut self) {
+        if let Some(type_state) = self.state.get_mut(&TypeId::of::<T>()) {
+            type_state.clear_trackers();
+        }
+    }
+
+    /// Release storage beyond what the live entities need
+    pub(crate) fn shrink_to_fit(&mut self) {
+        if self.capacity() > self.len {
+            // Growing by nothing reallocates to exactly the live entities
+            self.grow(0);
+        }
+        for type_state in self.state.values_mut() {
+            type_state.mutated_entities.shrink_to_fit();
+            type_state.added_entities.shrink_to_fit();
+        }
+    }
+
     fn grow(&mut self, increment: u32) {
+        if self.try_grow(increment).is_err() {
+            panic!("failed to allocate archetype storage");
+        }
+    }
+
+    /// Ensure `additional` more entities can be allocated, reporting allocation failure instead of
+    /// panicking
+    ///
+    /// The archetype is left unchanged if allocation fails.
+    pub(crate) fn try_reserve(&mut self, additional: u32) -> Result<(), AllocError> {
+        if additional > (self.capacity() - self.len()) {
+            self.try_grow(additional.max(self.len.max(self.grow_size)))?;
+        }
+        Ok(())
+    }
+
+    /// Grow storage to hold `increment` more entities than are currently live
+    ///
+    /// Everything that may fail is allocated before any state is modified, so the archetype is
+    /// left unchanged on failure.
+    fn try_grow(&mut self, increment: u32) -> Result<(), AllocError> {
         unsafe {
             let old_count = self.len as usize;
             let count = old_count + increment as usize;
-            let mut new_entities = vec![!0; count].into_boxed_slice();
-            new_entities[0..old_count].copy_from_slice(&self.entities[0..old_count]);
-            self.entities = new_entities;
-
-            for type_state in self.state.values_mut() {
-                type_state.mutated_entities.resize_with(count, || false);
-                type_state.added_entities.resize_with(count, || false);
-            }
 
-            let old_data_size = mem::replace(&mut self.data_size, 0);
-            let mut old_offsets = Vec::with_capacity(self.types.len());
+            let mut data_size = 0;
+            let mut new_offsets = Vec::with_capacity(self.types.len());
             for ty in &self.types {
-                self.data_size = align(self.data_size, ty.layout.align());
-                let ty_state = self.state.get_mut(&ty.id).unwrap();
-                old_offsets.push(ty_state.offset);
-                ty_state.offset = self.data_size;
-                self.data_size += ty.layout.size() * count;
+                data_size = align(data_size, ty.layout.align());
+                new_offsets.push(data_size);
+                data_size += ty.layout.size() * count;
             }
-            let new_data = if self.data_size == 0 {
+            let data_align = self.types.first().map_or(1, |x| x.layout.align());
+            let new_data = if data_size == 0 {
                 NonNull::dangling()
             } else {
                 NonNull::new(alloc(
-                    Layout::from_size_align(
-                        self.data_size,
-                        self.types.first().map_or(1, |x| x.layout.align()),
-                    )
-                    .unwrap(),
+                    Layout::from_size_align(data_size, data_align).unwrap(),
                 ))
-                .unwrap()
+                .ok_or(AllocError)?
             };
-            if old_data_size != 0 {
-                for (i, ty) in self.types.iter().enumerate() {
-                    let old_off = old_offsets[i];
-                    let new_off = self.state.get(&ty.id).unwrap().offset;
+            let release_new_data = || {
+                if data_size != 0 {
+                    dealloc(
+                        new_data.as_ptr(),
+                        Layout::from_size_align_unchecked(data_size, data_align),
+                    );
+                }
+            };
+
+            let mut new_entities = Vec::new();
+            if new_entities.try_reserve_exact(count).is_err() {
+                release_new_data();
+                return Err(AllocError);
+            }
+            for type_state in self.state.values_mut() {
+                let additional = count.saturating_sub(type_state.mutated_entities.len());
+                if type_state.mutated_entities.try_reserve(additional).is_err()
+                    || type_state.added_entities.try_reserve(additional).is_err()
+                {
+                    release_new_data();
+                    return Err(AllocError);
+                }
+            }
+
+            new_entities.extend_from_slice(&self.entities[0..old_count]);
+            new_entities.resize(count, !0);
+            self.entities = new_entities.into_boxed_slice();
+
+            for type_state in self.state.values_mut() {
+                type_state.mutated_entities.resize_with(count, || false);
+                type_state.added_entities.resize_with(count, || false);
+            }
+
+            let old_data_size = mem::replace(&mut self.data_size, data_size);
+            for (ty, new_off) in self.types.iter().zip(new_offsets) {
+                let ty_state = self.state.get_mut(&ty.id).unwrap();
+                let old_off = mem::replace(&mut ty_state.offset, new_off);
+                if old_data_size != 0 {
                     ptr::copy_nonoverlapping(
                         (*self.data.get()).as_ptr().add(old_off),
                         new_data.as_ptr().add(new_off),
@@ -335,9 +471,16 @@ impl Archetype {
                     );
                 }
             }
+            if old_data_size != 0 {
+                dealloc(
+                    (*self.data.get()).as_ptr(),
+                    Layout::from_size_align_unchecked(old_data_size, data_align),
+                );
+            }
 
             self.data = UnsafeCell::new(new_data);
         }
+        Ok(())
     }
 
     /// Returns the ID of the entity moved into `index`, if any
@@ -556,6 +699,10 @@ impl PartialEq for TypeInfo {
 
 impl Eq for TypeInfo {}
 
+/// Error indicating that archetype storage could not be allocated
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct AllocError;
+
 fn align(x: usize, alignment: usize) -> usize {
     debug_assert!(alignment.is_power_of_two());
     (x + alignment - 1) & (!alignment + 1)
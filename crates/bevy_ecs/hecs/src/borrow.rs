@@ -16,8 +16,10 @@
 
 use core::{
     fmt::Debug,
+    mem,
     ops::{Deref, DerefMut},
     ptr::NonNull,
+    slice,
     sync::atomic::{AtomicUsize, Ordering},
 };
 
@@ -60,9 +62,69 @@ impl AtomicBorrow {
         let value = self.0.fetch_and(!UNIQUE_BIT, Ordering::Release);
         debug_assert_ne!(value & UNIQUE_BIT, 0, "unique release of shared borrow");
     }
+
+    /// Unique borrow shared by two holders, released once both have called `release_mut_pair`
+    pub fn borrow_mut_pair(&self) -> bool {
+        self.0
+            .compare_exchange(
+                0,
+                UNIQUE_BIT | PAIR_BIT,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+
+    pub fn release_mut_pair(&self) {
+        let value = self.0.fetch_and(!PAIR_BIT, Ordering::Release);
+        debug_assert_ne!(value & UNIQUE_BIT, 0, "unique release of shared borrow");
+        if value & PAIR_BIT == 0 {
+            // The other holder has already released
+            self.release_mut();
+        }
+    }
 }
 
 const UNIQUE_BIT: usize = !(usize::max_value() >> 1);
+const PAIR_BIT: usize = UNIQUE_BIT >> 1;
+
+/// Shared borrow of all `T` components in an archetype, in the order of `Archetype::entity_ids`
+///
+/// Obtained from `Archetype::component_slice`.
+pub struct ComponentSlice<'a, T: Component> {
+    archetype: &'a Archetype,
+    slice: &'a [T],
+}
+
+impl<'a, T: Component> ComponentSlice<'a, T> {
+    pub(crate) fn new(archetype: &'a Archetype) -> Option<Self> {
+        let mut target = archetype.get::<T>()?;
+        if archetype.is_empty() || mem::size_of::<T>() == 0 {
+            // storage isn't allocated, so the pointer may not be aligned for `T`
+            target = NonNull::dangling();
+        }
+        archetype.borrow::<T>();
+        let slice = unsafe { slice::from_raw_parts(target.as_ptr(), archetype.len() as usize) };
+        Some(Self { archetype, slice })
+    }
+}
+
+unsafe impl<T: Component> Send for ComponentSlice<'_, T> {}
+unsafe impl<T: Component> Sync for ComponentSlice<'_, T> {}
+
+impl<'a, T: Component> Drop for ComponentSlice<'a, T> {
+    fn drop(&mut self) {
+        self.archetype.release::<T>();
+    }
+}
+
+impl<'a, T: Component> Deref for ComponentSlice<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.slice
+    }
+}
 
 /// Shared borrow of an entity's component
 #[derive(Clone)]
@@ -117,10 +179,14 @@ where
 }
 
 /// Unique borrow of an entity's component
+///
+/// The component is only flagged as mutated when it is dereferenced mutably, so reading through a
+/// `RefMut` doesn't trigger `Mutated` queries.
 pub struct RefMut<'a, T: Component> {
     archetype: &'a Archetype,
     target: NonNull<T>,
     modified: &'a mut bool,
+    paired: bool,
 }
 
 impl<'a, T: Component> RefMut<'a, T> {
@@ -130,6 +196,52 @@ impl<'a, T: Component> RefMut<'a, T> {
     ///
     /// - the index of the component must be valid
     pub unsafe fn new(archetype: &'a Archetype, index: u32) -> Result<Self, MissingComponent> {
+        let (target, modified) = Self::locate(archetype, index)?;
+        archetype.borrow_mut::<T>();
+        Ok(Self {
+            archetype,
+            target,
+            modified: &mut *modified,
+            paired: false,
+        })
+    }
+
+    /// Creates unique borrows of the components of two entities in the same archetype
+    ///
+    /// The archetype's borrow of `T` is released once both are dropped.
+    ///
+    /// # Safety
+    ///
+    /// - both indices must be valid and distinct
+    pub(crate) unsafe fn new_pair(
+        archetype: &'a Archetype,
+        a: u32,
+        b: u32,
+    ) -> Result<(Self, Self), MissingComponent> {
+        debug_assert_ne!(a, b, "aliasing pair of unique borrows");
+        let (target_a, modified_a) = Self::locate(archetype, a)?;
+        let (target_b, modified_b) = Self::locate(archetype, b)?;
+        archetype.borrow_mut_pair::<T>();
+        Ok((
+            Self {
+                archetype,
+                target: target_a,
+                modified: &mut *modified_a,
+                paired: true,
+            },
+            Self {
+                archetype,
+                target: target_b,
+                modified: &mut *modified_b,
+                paired: true,
+            },
+        ))
+    }
+
+    unsafe fn locate(
+        archetype: &Archetype,
+        index: u32,
+    ) -> Result<(NonNull<T>, *mut bool), MissingComponent> {
         let target = NonNull::new_unchecked(
             archetype
                 .get::<T>()
@@ -137,17 +249,12 @@ impl<'a, T: Component> RefMut<'a, T> {
                 .as_ptr()
                 .add(index as usize),
         );
-        archetype.borrow_mut::<T>();
         let modified = archetype
             .get_mutated::<T>()
             .unwrap()
             .as_ptr()
             .add(index as usize);
-        Ok(Self {
-            archetype,
-            target,
-            modified: &mut *modified,
-        })
+        Ok((target, modified))
     }
 }
 
@@ -156,7 +263,11 @@ unsafe impl<T: Component> Sync for RefMut<'_, T> {}
 
 impl<'a, T: Component> Drop for RefMut<'a, T> {
     fn drop(&mut self) {
-        self.archetype.release_mut::<T>();
+        if self.paired {
+            self.archetype.release_mut_pair::<T>();
+        } else {
+            self.archetype.release_mut::<T>();
+        }
     }
 }
 
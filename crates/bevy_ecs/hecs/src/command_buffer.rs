@@ -0,0 +1,110 @@
+// modified by Bevy contributors
+
+use crate::alloc::{boxed::Box, vec::Vec};
+
+use crate::{Bundle, DynamicBundle, Entity, EntityGenerator, World};
+
+/// Records structural changes to a `World` to be applied later with `World::apply_commands`
+///
+/// Useful while the world is borrowed, e.g. inside a query loop. Commands are applied in the order
+/// they were recorded. Commands that target an entity which no longer exists when the buffer is
+/// applied, or that remove components the entity doesn't have, are skipped.
+///
+/// # Example
+/// ```
+/// # use bevy_hecs::*;
+/// let mut world = World::new();
+/// let a = world.spawn((123,));
+/// let mut buffer = CommandBuffer::new();
+/// for (id, &x) in &mut world.query::<(Entity, &i32)>() {
+///     buffer.despawn(id);
+///     let b = buffer.spawn((x + 1,));
+///     buffer.insert(b, (true,));
+/// }
+/// world.apply_commands(buffer);
+/// assert!(!world.contains(a));
+/// let (&x, &flag) = world.query::<(&i32, &bool)>().iter().next().unwrap();
+/// assert_eq!((x, flag), (124, true));
+/// ```
+#[derive(Default)]
+pub struct CommandBuffer {
+    commands: Vec<Box<dyn FnOnce(&mut World) + Send + Sync>>,
+    entity_generator: Option<EntityGenerator>,
+}
+
+impl CommandBuffer {
+    /// Create an empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty buffer that takes the IDs of spawned entities from `entity_generator`,
+    /// usually the `World::entity_generator` of the world it will be applied to, or a
+    /// `sub_sequence` of it if several buffers are recorded in parallel. Otherwise IDs are random,
+    /// which worlds with a deterministic `EntityGenerator` can't reproduce.
+    pub fn with_entity_generator(entity_generator: EntityGenerator) -> Self {
+        Self {
+            commands: Vec::new(),
+            entity_generator: Some(entity_generator),
+        }
+    }
+
+    /// Record the creation of an entity with certain components
+    ///
+    /// The returned ID is allocated immediately and becomes the spawned entity's ID once the
+    /// buffer is applied, so it can be passed to later commands in the same buffer.
+    pub fn spawn(&mut self, components: impl DynamicBundle + Send + Sync + 'static) -> Entity {
+        let entity = self
+            .entity_generator
+            .as_ref()
+            .map_or_else(Entity::new, EntityGenerator::generate);
+        self.commands.push(Box::new(move |world: &mut World| {
+            world.spawn_as_entity(entity, components);
+        }));
+        entity
+    }
+
+    /// Record the destruction of `entity`
+    pub fn despawn(&mut self, entity: Entity) -> &mut Self {
+        self.commands.push(Box::new(move |world: &mut World| {
+            let _ = world.despawn(entity);
+        }));
+        self
+    }
+
+    /// Record the addition of `components` to `entity`, replacing any of the same types
+    pub fn insert(
+        &mut self,
+        entity: Entity,
+        components: impl DynamicBundle + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.commands.push(Box::new(move |world: &mut World| {
+            let _ = world.insert(entity, components);
+        }));
+        self
+    }
+
+    /// Record the removal of the components in `T` from `entity`
+    pub fn remove<T: Bundle + 'static>(&mut self, entity: Entity) -> &mut Self {
+        self.commands.push(Box::new(move |world: &mut World| {
+            let _ = world.remove::<T>(entity);
+        }));
+        self
+    }
+
+    /// Number of recorded commands
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Whether no commands have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub(crate) fn apply(self, world: &mut World) {
+        for command in self.commands {
+            command(world);
+        }
+    }
+}
@@ -1,7 +1,13 @@
 // modified by Bevy contributors
 
+use crate::alloc::{sync::Arc, vec::Vec};
 use bevy_utils::HashMap;
-use core::fmt;
+use core::{
+    fmt,
+    marker::PhantomData,
+    ops::Range,
+    sync::atomic::{AtomicU64, Ordering},
+};
 #[cfg(feature = "std")]
 use std::error::Error;
 
@@ -9,6 +15,7 @@ use std::error::Error;
 ///
 /// Obtained from `World::spawn`. Can be stored to refer to an entity in the future.
 #[derive(Debug, Clone, Copy, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[repr(transparent)]
 pub struct Entity(u128);
 
 #[allow(clippy::new_without_default)]
@@ -35,6 +42,224 @@ impl Entity {
     }
 }
 
+/// Hands out the IDs of new entities
+///
+/// IDs are `seed << 64 | sub_sequence << 40 | n`, where `n` counts up from 0 and is never reused,
+/// even after an entity is despawned. Each `World` owns a generator for sub-sequence 0: `World::new`
+/// picks a random seed, so its IDs won't collide with those of other worlds, e.g. IDs restored from
+/// a saved world. Worlds created with `World::with_entity_generator` and the same seed generate the
+/// same IDs as long as entities are spawned in the same order, which deterministic lockstep
+/// networking relies on.
+///
+/// Clones share the same sequence, so command buffers can generate IDs ahead of time without
+/// colliding with the world's own spawns. When several buffers are recorded in parallel, the order
+/// in which they draw from a shared sequence isn't deterministic, so each should be given its own
+/// `sub_sequence` instead.
+#[derive(Debug, Clone)]
+pub struct EntityGenerator {
+    seed: u64,
+    sub_sequence: u64,
+    next: Arc<AtomicU64>,
+    /// Shared by all sub-sequences of a generator
+    next_sub_sequence: Arc<AtomicU64>,
+}
+
+/// The number of bits of an ID's sequence number, below the sub-sequence
+const SUB_SEQUENCE_SHIFT: u32 = 40;
+
+impl EntityGenerator {
+    /// Create a generator with a random seed
+    pub fn new() -> Self {
+        Self::from_seed(rand::random())
+    }
+
+    /// Create a generator that yields the same IDs as any other generator with the same seed
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            sub_sequence: 0,
+            next: Arc::new(AtomicU64::new(0)),
+            next_sub_sequence: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    /// Create a generator for the next unused sub-sequence of this generator's IDs
+    ///
+    /// Sub-sequences are numbered in the order they are created, so as long as that order is
+    /// deterministic, so are the IDs each of them generates, no matter how generating from
+    /// different sub-sequences interleaves.
+    pub fn sub_sequence(&self) -> Self {
+        Self {
+            seed: self.seed,
+            sub_sequence: self.next_sub_sequence.fetch_add(1, Ordering::Relaxed),
+            next: Arc::new(AtomicU64::new(0)),
+            next_sub_sequence: self.next_sub_sequence.clone(),
+        }
+    }
+
+    /// Whether `self` is `other` or one of its sub-sequences
+    pub fn shares_sequence_with(&self, other: &EntityGenerator) -> bool {
+        self.seed == other.seed && Arc::ptr_eq(&self.next_sub_sequence, &other.next_sub_sequence)
+    }
+
+    #[allow(missing_docs)]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Generate the next ID in the sequence
+    pub fn generate(&self) -> Entity {
+        let n = self.next.fetch_add(1, Ordering::Relaxed);
+        debug_assert!(n < 1 << SUB_SEQUENCE_SHIFT, "entity ID sequence exhausted");
+        Entity(
+            (u128::from(self.seed) << 64)
+                | u128::from((self.sub_sequence << SUB_SEQUENCE_SHIFT) | n),
+        )
+    }
+
+    /// The sub-sequence and sequence number of `entity`, or `None` if it wasn't generated from
+    /// this generator's seed
+    fn split(&self, entity: Entity) -> Option<(u64, u64)> {
+        if (entity.0 >> 64) as u64 == self.seed {
+            let n = entity.0 as u64;
+            Some((n >> SUB_SEQUENCE_SHIFT, n & ((1 << SUB_SEQUENCE_SHIFT) - 1)))
+        } else {
+            None
+        }
+    }
+}
+
+/// Which IDs of a world's `EntityGenerator` sequence have been used, for `World::validate`
+///
+/// The world's own sub-sequence is tracked by the generator. Other sub-sequences are generated
+/// elsewhere, e.g. by command buffers, so they are tracked as their entities are spawned.
+///
+/// Spawning an ID ahead of its sequence moves the sequence past it, so the IDs in between are
+/// never handed out. They are recorded as gaps rather than counted as generated.
+#[derive(Default)]
+pub(crate) struct GeneratedEntities {
+    /// One past the highest spawned sequence number of each sub-sequence other than 0
+    sub_sequence_ends: HashMap<u64, u64>,
+    /// Sequence numbers that were skipped over, by sub-sequence
+    gaps: Vec<(u64, Range<u64>)>,
+}
+
+impl GeneratedEntities {
+    /// Record that `entity` was spawned, making sure `generator` never generates it
+    pub fn spawned(&mut self, generator: &EntityGenerator, entity: Entity) {
+        let (sub_sequence, n) = match generator.split(entity) {
+            Some(split) => split,
+            None => return,
+        };
+        let end = if sub_sequence == 0 {
+            generator.next.fetch_max(n + 1, Ordering::Relaxed)
+        } else {
+            let end = self.sub_sequence_ends.entry(sub_sequence).or_insert(0);
+            let previous_end = *end;
+            *end = previous_end.max(n + 1);
+            previous_end
+        };
+
+        if n > end {
+            self.gaps.push((sub_sequence, end..n));
+        } else if n < end {
+            self.fill_gap(sub_sequence, n);
+        }
+    }
+
+    /// Whether `entity` was generated already, or `None` if it isn't part of `generator`'s sequence
+    pub fn has_generated(&self, generator: &EntityGenerator, entity: Entity) -> Option<bool> {
+        let (sub_sequence, n) = generator.split(entity)?;
+        let end = if sub_sequence == 0 {
+            generator.next.load(Ordering::Relaxed)
+        } else {
+            self.sub_sequence_ends
+                .get(&sub_sequence)
+                .copied()
+                .unwrap_or(0)
+        };
+        Some(n < end && self.gap_index(sub_sequence, n).is_none())
+    }
+
+    fn gap_index(&self, sub_sequence: u64, n: u64) -> Option<usize> {
+        self.gaps.iter().position(|(gap_sub_sequence, gap)| {
+            *gap_sub_sequence == sub_sequence && gap.contains(&n)
+        })
+    }
+
+    /// Remove `n` from the gap containing it, if any
+    fn fill_gap(&mut self, sub_sequence: u64, n: u64) {
+        if let Some(index) = self.gap_index(sub_sequence, n) {
+            let (_, gap) = self.gaps.swap_remove(index);
+            if gap.start < n {
+                self.gaps.push((sub_sequence, gap.start..n));
+            }
+            if n + 1 < gap.end {
+                self.gaps.push((sub_sequence, n + 1..gap.end));
+            }
+        }
+    }
+}
+
+impl Default for EntityGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An `Entity` known to have been spawned with at least the components in `B`
+///
+/// Obtained from `World::spawn_typed`. Lets accessors like `World::get_typed` return components
+/// directly instead of a `Result`. The guarantee only holds while the entity keeps those
+/// components: despawning the entity or removing any of them invalidates it, which is not tracked.
+pub struct TypedEntity<B> {
+    entity: Entity,
+    _marker: PhantomData<fn() -> B>,
+}
+
+impl<B> TypedEntity<B> {
+    pub(crate) fn new(entity: Entity) -> Self {
+        Self {
+            entity,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The untyped ID of this entity
+    #[inline]
+    pub fn entity(self) -> Entity {
+        self.entity
+    }
+}
+
+impl<B> Clone for TypedEntity<B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B> Copy for TypedEntity<B> {}
+
+impl<B> fmt::Debug for TypedEntity<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.entity, f)
+    }
+}
+
+impl<B> PartialEq for TypedEntity<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entity == other.entity
+    }
+}
+
+impl<B> Eq for TypedEntity<B> {}
+
+impl<B> From<TypedEntity<B>> for Entity {
+    fn from(typed: TypedEntity<B>) -> Self {
+        typed.entity
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct Entities {
     pub entity_locations: HashMap<Entity, Location>,
@@ -61,10 +286,19 @@ impl Entities {
         self.entity_locations.contains_key(&entity)
     }
 
+    /// Number of live entities, including reserved ones
+    pub fn len(&self) -> usize {
+        self.entity_locations.len()
+    }
+
     pub fn clear(&mut self) {
         self.entity_locations.clear();
     }
 
+    pub fn shrink_to_fit(&mut self) {
+        self.entity_locations.shrink_to_fit();
+    }
+
     /// Access the location storage of an entity
     pub fn get_mut(&mut self, entity: Entity) -> Result<&mut Location, NoSuchEntity> {
         self.entity_locations
@@ -92,6 +326,26 @@ pub struct Location {
     pub index: u32,
 }
 
+/// What is known about an entity ID, as returned by `World::validate`
+///
+/// IDs are never reused, so an `Entity` kept after its entity was despawned can't refer to a
+/// different entity later. It just stops resolving, and this tells why.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EntityStatus {
+    /// The entity exists
+    Alive,
+    /// The ID is from the world's `EntityGenerator` sequence and was handed out or spawned
+    /// explicitly, but the entity has since been despawned
+    Despawned,
+    /// The ID is part of the world's `EntityGenerator` sequence but hasn't been handed out yet,
+    /// e.g. because it belongs to another world with the same seed, or it was skipped over when a
+    /// later ID was spawned explicitly
+    NeverAllocated,
+    /// The entity doesn't exist and the ID isn't from the world's `EntityGenerator`, so it may
+    /// have been despawned or come from another world
+    Unknown,
+}
+
 /// Error indicating that no entity with a particular ID exists
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct NoSuchEntity;
@@ -86,6 +86,64 @@ impl EntityBuilder {
         self
     }
 
+    /// Add a component described by `ty`, initialized in place by `write`
+    ///
+    /// # Safety
+    ///
+    /// `write` must initialize a valid value of the type described by `ty` at the pointer it is
+    /// given, which may be unaligned.
+    pub(crate) unsafe fn add_dynamic(
+        &mut self,
+        ty: TypeInfo,
+        write: impl FnOnce(*mut u8),
+    ) -> &mut Self {
+        if !self.id_set.insert(ty.id()) {
+            return self;
+        }
+        let end = self.cursor + ty.layout().size();
+        if end > self.storage.len() {
+            self.grow(end);
+        }
+        write(self.storage.as_mut_ptr().add(self.cursor).cast());
+        self.info.push((ty, self.cursor));
+        self.cursor += ty.layout().size();
+        self
+    }
+
+    /// Take back a previously `add`ed component, if present
+    ///
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let mut builder = EntityBuilder::new();
+    /// builder.add(123).add("abc");
+    /// assert_eq!(builder.remove::<i32>(), Some(123));
+    /// assert_eq!(builder.remove::<i32>(), None);
+    /// let e = world.spawn(builder.build());
+    /// assert!(world.get::<i32>(e).is_err());
+    /// assert_eq!(*world.get::<&str>(e).unwrap(), "abc");
+    /// ```
+    pub fn remove<T: Component>(&mut self) -> Option<T> {
+        if !self.id_set.remove(&TypeId::of::<T>()) {
+            return None;
+        }
+        let index = self
+            .info
+            .iter()
+            .position(|x| x.0.id() == TypeId::of::<T>())
+            .unwrap();
+        let (_, offset) = self.info.swap_remove(index);
+        unsafe {
+            Some(
+                self.storage
+                    .as_ptr()
+                    .add(offset)
+                    .cast::<T>()
+                    .read_unaligned(),
+            )
+        }
+    }
+
     fn grow(&mut self, min_size: usize) {
         let new_len = min_size.next_power_of_two().max(64);
         let mut new_storage = vec![MaybeUninit::uninit(); new_len].into_boxed_slice();
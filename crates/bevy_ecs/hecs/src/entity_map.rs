@@ -0,0 +1,103 @@
+// modified by Bevy contributors
+
+use crate::alloc::vec::Vec;
+use bevy_utils::HashMap;
+
+use crate::{Component, Entity, NoSuchEntity, World};
+
+/// Components that refer to other entities, which must be rewritten when those entities are
+/// given new IDs, e.g. while loading a saved world
+pub trait MapEntities {
+    /// Replace every entity this value refers to with its counterpart in `entity_map`
+    ///
+    /// Should fail with `NoSuchEntity` if any referenced entity isn't in the map.
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), NoSuchEntity>;
+}
+
+impl MapEntities for Entity {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), NoSuchEntity> {
+        *self = entity_map.get(*self)?;
+        Ok(())
+    }
+}
+
+/// A mapping from old entity IDs to new ones
+///
+/// # Example
+/// ```
+/// # use bevy_hecs::*;
+/// struct Parent(Entity);
+///
+/// impl MapEntities for Parent {
+///     fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), NoSuchEntity> {
+///         self.0.map_entities(entity_map)
+///     }
+/// }
+///
+/// let mut world = World::new();
+/// let (old_parent, old_despawned) = (Entity::new(), Entity::new());
+/// let parent = world.spawn(());
+/// let a = world.spawn((Parent(old_parent),));
+/// let b = world.spawn((Parent(old_despawned),));
+///
+/// let mut entity_map = EntityMap::default();
+/// entity_map.insert(old_parent, parent);
+/// assert_eq!(entity_map.remap::<Parent>(&mut world), &[b]);
+/// assert_eq!(world.get::<Parent>(a).unwrap().0, parent);
+/// assert!(world.get::<Parent>(b).is_err());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct EntityMap {
+    map: HashMap<Entity, Entity>,
+}
+
+impl EntityMap {
+    /// Map `from` to `to`, returning the previous mapping of `from`, if any
+    pub fn insert(&mut self, from: Entity, to: Entity) -> Option<Entity> {
+        self.map.insert(from, to)
+    }
+
+    /// Remove the mapping of `from`, returning the entity it was mapped to, if any
+    pub fn remove(&mut self, from: Entity) -> Option<Entity> {
+        self.map.remove(&from)
+    }
+
+    /// Look up the new ID of `entity`
+    pub fn get(&self, entity: Entity) -> Result<Entity, NoSuchEntity> {
+        self.map.get(&entity).copied().ok_or(NoSuchEntity)
+    }
+
+    /// Iterate over the `(from, to)` pairs in the map
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.map.iter().map(|(&from, &to)| (from, to))
+    }
+
+    /// Number of mapped entities
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Whether no entities are mapped
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Rewrite the entity references held by every `T` component in `world`
+    ///
+    /// `T` components that refer to an entity missing from the map, such as one that was despawned
+    /// before the world was saved, are removed rather than left dangling. Returns the entities
+    /// they were removed from.
+    pub fn remap<T: MapEntities + Component>(&self, world: &mut World) -> Vec<Entity> {
+        let dangling = world
+            .query::<(Entity, &mut T)>()
+            .iter()
+            .filter_map(|(entity, mut component)| {
+                component.map_entities(self).err().map(|_| entity)
+            })
+            .collect::<Vec<_>>();
+        for &entity in &dangling {
+            world.remove_one::<T>(entity).unwrap();
+        }
+        dangling
+    }
+}
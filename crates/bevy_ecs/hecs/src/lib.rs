@@ -67,8 +67,10 @@ macro_rules! smaller_tuples_too {
 mod archetype;
 mod borrow;
 mod bundle;
+mod command_buffer;
 mod entities;
 mod entity_builder;
+mod entity_map;
 mod query;
 mod query_one;
 #[cfg(feature = "serde")]
@@ -76,16 +78,21 @@ mod serde;
 mod world;
 
 pub use archetype::Archetype;
-pub use borrow::{EntityRef, Ref, RefMut};
+pub use borrow::{ComponentSlice, EntityRef, Ref, RefMut};
 pub use bundle::{Bundle, DynamicBundle, MissingComponent};
-pub use entities::{Entity, Location, NoSuchEntity};
+pub use command_buffer::CommandBuffer;
+pub use entities::{Entity, EntityGenerator, EntityStatus, Location, NoSuchEntity, TypedEntity};
 pub use entity_builder::{BuiltEntity, EntityBuilder};
+pub use entity_map::{EntityMap, MapEntities};
 pub use query::{
-    Access, Added, BatchedIter, Changed, Mut, Mutated, Or, Query, QueryBorrow, QueryIter, With,
-    Without,
+    Access, Added, ArchetypeFilter, Batch, BatchedIter, Changed, DenseIndex, IndexedIter, JoinIter,
+    Mut, Mutated, Or, Query, QueryBorrow, QueryConflict, QueryIter, SilentIter, SliceIter, With,
+    WithAdded, WithChanged, Without,
 };
 pub use query_one::QueryOne;
-pub use world::{ArchetypesGeneration, Component, ComponentError, Iter, SpawnBatchIter, World};
+pub use world::{
+    ArchetypesGeneration, Component, ComponentError, InsertError, Iter, SpawnBatchIter, World,
+};
 
 // Unstable implementation details needed by the macros
 #[doc(hidden)]
@@ -96,7 +103,7 @@ pub use bevy_utils;
 #[doc(hidden)]
 pub use lazy_static;
 #[doc(hidden)]
-pub use query::Fetch;
+pub use query::{Fetch, FetchSlice};
 
 #[cfg(feature = "macros")]
 pub use bevy_hecs_macros::Bundle;
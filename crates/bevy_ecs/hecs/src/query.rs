@@ -14,13 +14,23 @@
 
 // modified by Bevy contributors
 
+use crate::alloc::vec::Vec;
 use core::{
+    any::TypeId,
+    cmp::Ordering,
+    fmt,
     marker::PhantomData,
     ops::{Deref, DerefMut},
-    ptr::NonNull,
+    ptr::{self, NonNull},
+    slice,
 };
 
-use crate::{archetype::Archetype, Component, Entity};
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::{
+    archetype::Archetype, entities::Entities, ArchetypesGeneration, Component, Entity, World,
+};
 
 /// A collection of component types to fetch from a `World`
 pub trait Query {
@@ -46,6 +56,10 @@ pub trait Fetch<'a>: Sized {
     /// Release dynamic borrows acquired by `borrow`
     fn release(archetype: &Archetype);
 
+    /// Report each component this fetch accesses in `archetype`, and how. Only called for
+    /// archetypes that `access` accepted.
+    fn component_access(_archetype: &Archetype, _f: &mut dyn FnMut(TypeId, Access)) {}
+
     /// if this returns true, the current item will be skipped during iteration
     ///
     /// # Safety
@@ -64,6 +78,19 @@ pub trait Fetch<'a>: Sized {
     unsafe fn next(&mut self) -> Self::Item;
 }
 
+/// Fetches that can expose all of an archetype's results at once as contiguous slices
+pub trait FetchSlice<'a>: Fetch<'a> {
+    /// Type of the slices fetched
+    type Slice;
+
+    /// Access the results for every entity in `archetype`
+    ///
+    /// # Safety
+    /// - Must only be called after `borrow`, for an archetype that `access` accepted
+    /// - `release` must not be called while `'a` is still live
+    unsafe fn get_slice(archetype: &'a Archetype) -> Self::Slice;
+}
+
 /// Type of access a `Query` may have to an `Archetype`
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Access {
@@ -111,6 +138,18 @@ impl<'a> Fetch<'a> for EntityFetch {
     }
 }
 
+impl<'a> FetchSlice<'a> for EntityFetch {
+    type Slice = &'a [Entity];
+
+    unsafe fn get_slice(archetype: &'a Archetype) -> &'a [Entity] {
+        // `Entity` is a transparent wrapper around its ID
+        slice::from_raw_parts(
+            archetype.entities().as_ptr().cast::<Entity>(),
+            archetype.len() as usize,
+        )
+    }
+}
+
 impl<'a, T: Component> Query for &'a T {
     type Fetch = FetchRead<T>;
 }
@@ -139,6 +178,10 @@ impl<'a, T: Component> Fetch<'a> for FetchRead<T> {
             .map(|x| Self(NonNull::new_unchecked(x.as_ptr().add(offset))))
     }
 
+    fn component_access(_archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+        f(TypeId::of::<T>(), Access::Read);
+    }
+
     fn release(archetype: &Archetype) {
         archetype.release::<T>();
     }
@@ -151,6 +194,17 @@ impl<'a, T: Component> Fetch<'a> for FetchRead<T> {
     }
 }
 
+impl<'a, T: Component> FetchSlice<'a> for FetchRead<T> {
+    type Slice = &'a [T];
+
+    unsafe fn get_slice(archetype: &'a Archetype) -> &'a [T] {
+        slice::from_raw_parts(
+            archetype.get::<T>().unwrap().as_ptr(),
+            archetype.len() as usize,
+        )
+    }
+}
+
 impl<'a, T: Component> Query for &'a mut T {
     type Fetch = FetchMut<T>;
 }
@@ -160,6 +214,9 @@ impl<T: Query> Query for Option<T> {
 }
 
 /// Unique borrow of an entity's component
+///
+/// The component is only flagged as mutated when it is dereferenced mutably, so reading through a
+/// `Mut` doesn't trigger `Mutated` queries.
 pub struct Mut<'a, T: Component> {
     value: &'a mut T,
     mutated: &'a mut bool,
@@ -223,6 +280,10 @@ impl<'a, T: Component> Fetch<'a> for FetchMut<T> {
             })
     }
 
+    fn component_access(_archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+        f(TypeId::of::<T>(), Access::Write);
+    }
+
     fn release(archetype: &Archetype) {
         archetype.release_mut::<T>();
     }
@@ -240,6 +301,20 @@ impl<'a, T: Component> Fetch<'a> for FetchMut<T> {
     }
 }
 
+impl<'a, T: Component> FetchSlice<'a> for FetchMut<T> {
+    type Slice = &'a mut [T];
+
+    unsafe fn get_slice(archetype: &'a Archetype) -> &'a mut [T] {
+        let (components, mutated) = archetype.get_with_mutated::<T>().unwrap();
+        let len = archetype.len() as usize;
+        // Writes through the slice can't be observed, so conservatively flag every entity
+        for mutated in slice::from_raw_parts_mut(mutated.as_ptr(), len) {
+            *mutated = true;
+        }
+        slice::from_raw_parts_mut(components.as_ptr(), len)
+    }
+}
+
 macro_rules! impl_or_query {
     ( $( $T:ident ),+ ) => {
         impl<$( $T: Query ),+> Query for Or<($( $T ),+)> {
@@ -267,6 +342,14 @@ macro_rules! impl_or_query {
                 Some(Self(( $( $T::get(archetype, offset)?),+ )))
             }
 
+            fn component_access(archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+                $(
+                if $T::access(archetype).is_some() {
+                    $T::component_access(archetype, f);
+                }
+                )+
+            }
+
             fn release(archetype: &Archetype) {
                 $(
                     $T::release(archetype);
@@ -371,6 +454,10 @@ impl<'a, T: Component> Fetch<'a> for FetchMutated<T> {
             })
     }
 
+    fn component_access(_archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+        f(TypeId::of::<T>(), Access::Read);
+    }
+
     fn release(archetype: &Archetype) {
         archetype.release::<T>();
     }
@@ -434,6 +521,10 @@ impl<'a, T: Component> Fetch<'a> for FetchAdded<T> {
         })
     }
 
+    fn component_access(_archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+        f(TypeId::of::<T>(), Access::Read);
+    }
+
     fn release(archetype: &Archetype) {
         archetype.release::<T>();
     }
@@ -501,6 +592,10 @@ impl<'a, T: Component> Fetch<'a> for FetchChanged<T> {
             })
     }
 
+    fn component_access(_archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+        f(TypeId::of::<T>(), Access::Read);
+    }
+
     fn release(archetype: &Archetype) {
         archetype.release::<T>();
     }
@@ -538,6 +633,12 @@ impl<'a, T: Fetch<'a>> Fetch<'a> for TryFetch<T> {
         Some(Self(T::get(archetype, offset)))
     }
 
+    fn component_access(archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+        if T::access(archetype).is_some() {
+            T::component_access(archetype, f);
+        }
+    }
+
     fn release(archetype: &Archetype) {
         T::release(archetype)
     }
@@ -599,6 +700,10 @@ impl<'a, T: Component, F: Fetch<'a>> Fetch<'a> for FetchWithout<T, F> {
         Some(Self(F::get(archetype, offset)?, PhantomData))
     }
 
+    fn component_access(archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+        F::component_access(archetype, f);
+    }
+
     fn release(archetype: &Archetype) {
         F::release(archetype)
     }
@@ -612,6 +717,14 @@ impl<'a, T: Component, F: Fetch<'a>> Fetch<'a> for FetchWithout<T, F> {
     }
 }
 
+impl<'a, T: Component, F: FetchSlice<'a>> FetchSlice<'a> for FetchWithout<T, F> {
+    type Slice = F::Slice;
+
+    unsafe fn get_slice(archetype: &'a Archetype) -> F::Slice {
+        F::get_slice(archetype)
+    }
+}
+
 /// Query transformer skipping entities that do not have a `T` component
 ///
 /// See also `QueryBorrow::with`.
@@ -662,6 +775,10 @@ impl<'a, T: Component, F: Fetch<'a>> Fetch<'a> for FetchWith<T, F> {
         Some(Self(F::get(archetype, offset)?, PhantomData))
     }
 
+    fn component_access(archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+        F::component_access(archetype, f);
+    }
+
     fn release(archetype: &Archetype) {
         F::release(archetype)
     }
@@ -675,20 +792,164 @@ impl<'a, T: Component, F: Fetch<'a>> Fetch<'a> for FetchWith<T, F> {
     }
 }
 
+impl<'a, T: Component, F: FetchSlice<'a>> FetchSlice<'a> for FetchWith<T, F> {
+    type Slice = F::Slice;
+
+    unsafe fn get_slice(archetype: &'a Archetype) -> F::Slice {
+        F::get_slice(archetype)
+    }
+}
+
+/// Query transformer skipping entities whose `T` component has not been added since the last
+/// call to `World::clear_trackers`
+///
+/// `T` itself is not fetched, but it is borrowed immutably to read its trackers, so `Q` must not
+/// borrow `T` uniquely. See also `QueryBorrow::with_added`.
+pub struct WithAdded<T, Q>(PhantomData<(Q, fn(T))>);
+
+impl<T: Component, Q: Query> Query for WithAdded<T, Q> {
+    type Fetch = FetchWithAdded<T, Q::Fetch>;
+}
+
+#[doc(hidden)]
+pub struct FetchWithAdded<T, F>(F, NonNull<bool>, PhantomData<fn(T)>);
+
+impl<'a, T: Component, F: Fetch<'a>> Fetch<'a> for FetchWithAdded<T, F> {
+    type Item = F::Item;
+
+    fn access(archetype: &Archetype) -> Option<Access> {
+        if archetype.has::<T>() {
+            F::access(archetype).map(|access| access.max(Access::Read))
+        } else {
+            None
+        }
+    }
+
+    fn borrow(archetype: &Archetype) {
+        F::borrow(archetype);
+        archetype.borrow::<T>();
+    }
+
+    unsafe fn get(archetype: &'a Archetype, offset: usize) -> Option<Self> {
+        let added = archetype.get_added::<T>()?;
+        Some(Self(
+            F::get(archetype, offset)?,
+            NonNull::new_unchecked(added.as_ptr().add(offset)),
+            PhantomData,
+        ))
+    }
+
+    fn component_access(archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+        F::component_access(archetype, f);
+    }
+
+    fn release(archetype: &Archetype) {
+        F::release(archetype);
+        archetype.release::<T>();
+    }
+
+    unsafe fn next(&mut self) -> F::Item {
+        self.1 = NonNull::new_unchecked(self.1.as_ptr().add(1));
+        self.0.next()
+    }
+
+    unsafe fn should_skip(&self) -> bool {
+        // skip if the current item's `T` wasn't added
+        !*self.1.as_ref() || self.0.should_skip()
+    }
+}
+
+/// Query transformer skipping entities whose `T` component has been neither added nor mutated
+/// since the last call to `World::clear_trackers`
+///
+/// `T` itself is not fetched, but it is borrowed immutably to read its trackers, so `Q` must not
+/// borrow `T` uniquely. See also `QueryBorrow::with_changed`.
+pub struct WithChanged<T, Q>(PhantomData<(Q, fn(T))>);
+
+impl<T: Component, Q: Query> Query for WithChanged<T, Q> {
+    type Fetch = FetchWithChanged<T, Q::Fetch>;
+}
+
+#[doc(hidden)]
+pub struct FetchWithChanged<T, F>(F, NonNull<bool>, NonNull<bool>, PhantomData<fn(T)>);
+
+impl<'a, T: Component, F: Fetch<'a>> Fetch<'a> for FetchWithChanged<T, F> {
+    type Item = F::Item;
+
+    fn access(archetype: &Archetype) -> Option<Access> {
+        if archetype.has::<T>() {
+            F::access(archetype).map(|access| access.max(Access::Read))
+        } else {
+            None
+        }
+    }
+
+    fn borrow(archetype: &Archetype) {
+        F::borrow(archetype);
+        archetype.borrow::<T>();
+    }
+
+    unsafe fn get(archetype: &'a Archetype, offset: usize) -> Option<Self> {
+        let added = archetype.get_added::<T>()?;
+        let mutated = archetype.get_mutated::<T>()?;
+        Some(Self(
+            F::get(archetype, offset)?,
+            NonNull::new_unchecked(added.as_ptr().add(offset)),
+            NonNull::new_unchecked(mutated.as_ptr().add(offset)),
+            PhantomData,
+        ))
+    }
+
+    fn component_access(archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+        F::component_access(archetype, f);
+    }
+
+    fn release(archetype: &Archetype) {
+        F::release(archetype);
+        archetype.release::<T>();
+    }
+
+    unsafe fn next(&mut self) -> F::Item {
+        self.1 = NonNull::new_unchecked(self.1.as_ptr().add(1));
+        self.2 = NonNull::new_unchecked(self.2.as_ptr().add(1));
+        self.0.next()
+    }
+
+    unsafe fn should_skip(&self) -> bool {
+        // skip if the current item's `T` wasn't added or mutated
+        (!*self.1.as_ref() && !*self.2.as_ref()) || self.0.should_skip()
+    }
+}
+
 /// A borrow of a `World` sufficient to execute the query `Q`
 ///
 /// Note that borrows are not released until this object is dropped.
 pub struct QueryBorrow<'w, Q: Query> {
     archetypes: &'w [Archetype],
+    entities: &'w Entities,
     borrowed: bool,
+    exclusive: bool,
     _marker: PhantomData<Q>,
 }
 
 impl<'w, Q: Query> QueryBorrow<'w, Q> {
-    pub(crate) fn new(archetypes: &'w [Archetype]) -> Self {
+    pub(crate) fn new(archetypes: &'w [Archetype], entities: &'w Entities) -> Self {
         Self {
             archetypes,
+            entities,
             borrowed: false,
+            exclusive: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Construct a query whose archetypes can't be accessed by anything else while it's live
+    pub(crate) fn new_exclusive(archetypes: &'w mut [Archetype], entities: &'w Entities) -> Self {
+        Self {
+            archetypes,
+            entities,
+            borrowed: false,
+            exclusive: true,
             _marker: PhantomData,
         }
     }
@@ -705,6 +966,101 @@ impl<'w, Q: Query> QueryBorrow<'w, Q> {
         }
     }
 
+    /// Like `iter`, but also yields each entity's index in `dense_index`
+    ///
+    /// Indices are unique and below `dense_index().len()`, so per-entity results computed
+    /// elsewhere, e.g. on other threads, can be stored in a `Vec` and matched back to their
+    /// entities without hashing. Entities skipped by filters like `Changed` leave gaps.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((1,));
+    /// let b = world.spawn((2, true));
+    /// let mut query = world.query::<&i32>();
+    /// let index = query.dense_index();
+    /// let mut doubled = vec![0; index.len()];
+    /// for (i, &x) in query.iter_indexed() {
+    ///     doubled[i] = x * 2;
+    /// }
+    /// assert_eq!(doubled[index.index_of(a).unwrap()], 2);
+    /// assert_eq!(doubled[index.index_of(b).unwrap()], 4);
+    /// ```
+    pub fn iter_indexed<'q>(&'q mut self) -> IndexedIter<'q, 'w, Q> {
+        self.borrow();
+        IndexedIter {
+            borrow: self,
+            archetype_index: 0,
+            offset: 0,
+            iter: None,
+        }
+    }
+
+    /// A mapping between the entities matching the query and contiguous indices starting at 0
+    ///
+    /// The mapping only changes when entities are spawned, despawned or change archetype, which
+    /// can't happen while the world is borrowed by this query.
+    pub fn dense_index(&self) -> DenseIndex<'w> {
+        let mut offsets = Vec::with_capacity(self.archetypes.len());
+        let mut len = 0;
+        for archetype in self.archetypes {
+            if Q::Fetch::access(archetype).is_some() {
+                offsets.push(Some(len));
+                len += archetype.len() as usize;
+            } else {
+                offsets.push(None);
+            }
+        }
+        DenseIndex {
+            archetypes: self.archetypes,
+            entities: self.entities,
+            offsets,
+            len,
+        }
+    }
+
+    /// Fetch the query's result for a single entity, or `None` if the entity doesn't exist or
+    /// doesn't match the query
+    ///
+    /// Unlike `World::query_one`, this doesn't take a new borrow of the world for every entity.
+    /// The first call borrows the query's archetypes like `iter` does, and they stay borrowed until
+    /// the query is dropped, so a query that `get` was called on can't be iterated.
+    ///
+    /// The result uniquely borrows the query, so it can't alias another result of the same query.
+    /// Getting components mutably while another query iterates them is caught by the dynamic
+    /// borrow checks, and panics.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// struct Target(Entity);
+    /// let mut world = World::new();
+    /// let a = world.spawn((1,));
+    /// world.spawn((2, Target(a)));
+    /// {
+    ///     let mut numbers = world.query::<&mut i32>();
+    ///     for target in world.query::<&Target>().iter() {
+    ///         *numbers.get(target.0).unwrap() += 10;
+    ///     }
+    /// }
+    /// assert_eq!(*world.get::<i32>(a).unwrap(), 11);
+    /// ```
+    pub fn get(&mut self, entity: Entity) -> Option<<Q::Fetch as Fetch<'_>>::Item> {
+        let location = self.entities.get(entity).ok()?;
+        if !self.borrowed {
+            self.borrow();
+        }
+        let archetype = &self.archetypes[location.archetype as usize];
+        unsafe {
+            let mut fetch = Q::Fetch::get(archetype, location.index as usize)?;
+            if fetch.should_skip() {
+                return None;
+            }
+            Some(fetch.next())
+        }
+    }
+
     /// Like `iter`, but returns child iterators of at most `batch_size` elements
     ///
     /// Useful for distributing work over a threadpool.
@@ -718,16 +1074,233 @@ impl<'w, Q: Query> QueryBorrow<'w, Q> {
         }
     }
 
+    /// Execute the query, pairing each result with the result of `Q2` for the same entity in
+    /// `other`
+    ///
+    /// The second element is `None` if the entity doesn't exist in `other` or doesn't match `Q2`.
+    /// `other` may be the world this query borrows from or an unrelated one.
+    ///
+    /// Both queries stay borrowed for as long as the returned iterator is live, so they must not
+    /// alias. When `other` is the same world, access is checked per component up front: in any
+    /// archetype both queries match, a component written by one query may not be read or written
+    /// by the other, otherwise `QueryConflict` is returned. Joining against a different world
+    /// never conflicts.
+    ///
+    /// Must be called only once per query.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((123, true));
+    /// world.spawn((456,));
+    /// let mut names = World::new();
+    /// names.spawn_as_entity(a, ("abc",));
+    /// for (mut number, name) in world.query::<&mut i32>().join::<&&str>(&names).unwrap() {
+    ///     if name.is_some() {
+    ///         *number *= 2;
+    ///     }
+    /// }
+    /// assert_eq!(*world.get::<i32>(a).unwrap(), 246);
+    /// ```
+    pub fn join<'q, Q2: Query>(
+        &'q mut self,
+        other: &'q World,
+    ) -> Result<JoinIter<'q, 'w, Q, Q2>, QueryConflict> {
+        if ptr::eq(self.archetypes.as_ptr(), other.archetypes.as_ptr()) {
+            let mut access = Vec::new();
+            for x in self.archetypes {
+                if Q::Fetch::access(x).is_none() || Q2::Fetch::access(x).is_none() {
+                    continue;
+                }
+                access.clear();
+                Q::Fetch::component_access(x, &mut |ty, a| access.push((ty, a)));
+                let mut conflict = false;
+                Q2::Fetch::component_access(x, &mut |ty, b| {
+                    conflict |= access
+                        .iter()
+                        .any(|&(other_ty, a)| other_ty == ty && a.max(b) == Access::Write);
+                });
+                if conflict {
+                    return Err(QueryConflict);
+                }
+            }
+        }
+        self.borrow();
+        for x in &other.archetypes {
+            if Q2::Fetch::access(x) >= Some(Access::Read) {
+                Q2::Fetch::borrow(x);
+            }
+        }
+        Ok(JoinIter {
+            borrow: self,
+            other,
+            archetype_index: 0,
+            iter: None,
+        })
+    }
+
+    /// Like `iter`, but yields the results for each matching archetype at once as contiguous
+    /// slices
+    ///
+    /// `&T` is fetched as `&[T]`, `&mut T` as `&mut [T]` and `Entity` as `&[Entity]`, with tuples
+    /// of those yielding tuples of slices whose elements correspond to the same entities. Useful
+    /// for vectorized math or uploading data directly. Since writes through a `&mut [T]` can't be
+    /// tracked individually, every `T` in the slice is flagged as mutated.
+    ///
+    /// Queries with per-entity filters like `Option`, `Added` or `Mutated` can't be fetched as
+    /// slices. The same dynamic borrow checks as `iter` apply, so no two slices alias a column.
+    ///
+    /// Must be called only once per query.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// world.spawn((1.0f32, 2.0f64));
+    /// world.spawn((3.0f32, 4.0f64));
+    /// world.spawn((5.0f32, 6.0f64, true));
+    /// for (xs, ys) in world.query::<(&mut f32, &f64)>().iter_slices() {
+    ///     assert_eq!(xs.len(), ys.len());
+    ///     for (x, y) in xs.iter_mut().zip(ys) {
+    ///         *x += *y as f32;
+    ///     }
+    /// }
+    /// let sum = world.query::<&f32>().iter().sum::<f32>();
+    /// assert_eq!(sum, 21.0);
+    /// ```
+    pub fn iter_slices<'q>(&'q mut self) -> SliceIter<'q, 'w, Q>
+    where
+        Q::Fetch: for<'a> FetchSlice<'a>,
+    {
+        self.borrow();
+        SliceIter {
+            borrow: self,
+            archetype_index: 0,
+        }
+    }
+
+    /// Fill `buf` with the entities matching the query, ordered by comparing their query results
+    ///
+    /// `buf` is cleared first, so the same buffer can be reused across calls to avoid
+    /// reallocating. The sort is stable: entities that compare equal keep a consistent relative
+    /// order as long as the world isn't restructured.
+    ///
+    /// Must be called only once per query.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// struct Z(f32);
+    /// let mut world = World::new();
+    /// let a = world.spawn((Z(2.0),));
+    /// let b = world.spawn((Z(-1.0), true));
+    /// let c = world.spawn((Z(0.5),));
+    /// let mut order = Vec::new();
+    /// world
+    ///     .query::<&Z>()
+    ///     .sorted_by(&mut order, |x, y| x.0.partial_cmp(&y.0).unwrap());
+    /// assert_eq!(order, &[b, c, a]);
+    /// ```
+    pub fn sorted_by<'q, F>(&'q mut self, buf: &mut Vec<Entity>, mut cmp: F)
+    where
+        F: FnMut(&<Q::Fetch as Fetch<'q>>::Item, &<Q::Fetch as Fetch<'q>>::Item) -> Ordering,
+    {
+        self.borrow();
+        let archetypes = self.archetypes;
+        buf.clear();
+
+        // Sort locations packed into the entity IDs, so items can be fetched during comparison
+        // without a side table, then swap in the real IDs afterwards
+        let pack = |archetype: usize, index: usize| {
+            Entity::from_id(((archetype as u128) << 32) | index as u128)
+        };
+        let unpack = |entity: &Entity| {
+            let id = entity.id();
+            ((id >> 32) as usize, id as u32 as usize)
+        };
+        for (archetype_index, archetype) in archetypes.iter().enumerate() {
+            if Q::Fetch::access(archetype).is_none() {
+                continue;
+            }
+            for index in 0..archetype.len() as usize {
+                unsafe {
+                    if let Some(fetch) = Q::Fetch::get(archetype, index) {
+                        if !fetch.should_skip() {
+                            buf.push(pack(archetype_index, index));
+                        }
+                    }
+                }
+            }
+        }
+
+        buf.sort_by(|x, y| {
+            let (x_archetype, x_index) = unpack(x);
+            let (y_archetype, y_index) = unpack(y);
+            unsafe {
+                let x = Q::Fetch::get(&archetypes[x_archetype], x_index)
+                    .unwrap()
+                    .next();
+                let y = Q::Fetch::get(&archetypes[y_archetype], y_index)
+                    .unwrap()
+                    .next();
+                cmp(&x, &y)
+            }
+        });
+
+        for entity in buf.iter_mut() {
+            let (archetype, index) = unpack(entity);
+            *entity = Entity::from_id(archetypes[archetype].entity_id(index as u32));
+        }
+    }
+
+    /// Like `iter_batched`, but distributes the batches across rayon's thread pool
+    ///
+    /// Each archetype is split into batches of at most `batch_size` entities. Batches never
+    /// overlap, so components accessed through `&mut T` are still uniquely borrowed, and the same
+    /// dynamic borrow checks as `iter` apply.
+    ///
+    /// Must be called only once per query.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// use rayon::iter::ParallelIterator;
+    /// let mut world = World::new();
+    /// world.spawn_batch((0..1_000).map(|i| (i, 1u8)));
+    /// world.query::<(&mut i32, &u8)>().par_iter(64).for_each(|batch| {
+    ///     for (mut x, &dx) in batch {
+    ///         *x += dx as i32;
+    ///     }
+    /// });
+    /// assert_eq!(world.query::<&i32>().iter().map(|&x| x).sum::<i32>(), 500_500);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_iter<'q>(&'q mut self, batch_size: u32) -> rayon::vec::IntoIter<Batch<'q, Q>> {
+        use rayon::iter::IntoParallelIterator;
+
+        self.iter_batched(batch_size)
+            .collect::<Vec<_>>()
+            .into_par_iter()
+    }
+
     fn borrow(&mut self) {
         if self.borrowed {
             panic!(
-                "called QueryBorrow::iter twice on the same borrow; construct a new query instead"
+                "called QueryBorrow::iter twice, or after QueryBorrow::get, on the same borrow; construct a new query instead"
             );
         }
+        let mut access = Vec::new();
         for x in self.archetypes {
             // TODO: Release prior borrows on failure?
             if Q::Fetch::access(x) >= Some(Access::Read) {
-                Q::Fetch::borrow(x);
+                if self.exclusive {
+                    // Nothing else can hold a borrow, so only `Q` aliasing itself has to be ruled
+                    // out, which doesn't need the archetype's borrow state
+                    assert_no_self_alias::<Q>(x, &mut access);
+                } else {
+                    Q::Fetch::borrow(x);
+                }
             }
         }
         self.borrowed = true;
@@ -781,12 +1354,62 @@ impl<'w, Q: Query> QueryBorrow<'w, Q> {
         self.transform()
     }
 
-    /// Helper to change the type of the query
-    fn transform<R: Query>(mut self) -> QueryBorrow<'w, R> {
-        let x = QueryBorrow {
-            archetypes: self.archetypes,
-            borrowed: self.borrowed,
-            _marker: PhantomData,
+    /// Transform the query into one that skips entities whose `T` component wasn't added since
+    /// the last call to `World::clear_trackers`, without fetching `T`
+    ///
+    /// Equivalent to using a query type wrapped in `WithAdded`. `T` is borrowed immutably to read
+    /// its trackers, so the query must not access `T` mutably.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((123, true));
+    /// world.clear_trackers();
+    /// let b = world.spawn((456, false));
+    /// let entities = world.query::<Entity>()
+    ///     .with_added::<bool>()
+    ///     .iter()
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(entities, &[b]);
+    /// ```
+    pub fn with_added<T: Component>(self) -> QueryBorrow<'w, WithAdded<T, Q>> {
+        self.transform()
+    }
+
+    /// Transform the query into one that skips entities whose `T` component was neither added nor
+    /// mutated since the last call to `World::clear_trackers`, without fetching `T`
+    ///
+    /// Equivalent to using a query type wrapped in `WithChanged`. `T` is borrowed immutably to read
+    /// its trackers, so the query must not access `T` mutably.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((123, true));
+    /// let b = world.spawn((456, false));
+    /// world.clear_trackers();
+    /// *world.get_mut::<bool>(a).unwrap() = false;
+    /// let entities = world.query::<(Entity, &i32)>()
+    ///     .with_changed::<bool>()
+    ///     .iter()
+    ///     .map(|(e, &i)| (e, i))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(entities, &[(a, 123)]);
+    /// ```
+    pub fn with_changed<T: Component>(self) -> QueryBorrow<'w, WithChanged<T, Q>> {
+        self.transform()
+    }
+
+    /// Helper to change the type of the query
+    fn transform<R: Query>(mut self) -> QueryBorrow<'w, R> {
+        let x = QueryBorrow {
+            archetypes: self.archetypes,
+            entities: self.entities,
+            borrowed: self.borrowed,
+            exclusive: self.exclusive,
+            _marker: PhantomData,
         };
         // Ensure `Drop` won't fire redundantly
         self.borrowed = false;
@@ -794,12 +1417,62 @@ impl<'w, Q: Query> QueryBorrow<'w, Q> {
     }
 }
 
+impl<'w, 'a, T: Component> QueryBorrow<'w, &'a mut T> {
+    /// Like `iter`, but yields plain `&mut T` without flagging components as mutated
+    ///
+    /// Useful for passes that touch every component each frame (e.g. integrators) where doing so
+    /// would otherwise make `Mutated<T>` and `Changed<T>` match everything. Access is still
+    /// exclusive: the same unique borrow of `T` is taken as with `iter`, only the tracker state is
+    /// left untouched.
+    ///
+    /// Must be called only once per query.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// world.spawn((123,));
+    /// world.clear_trackers();
+    /// for x in world.query::<&mut i32>().iter_silent() {
+    ///     *x += 1;
+    /// }
+    /// assert_eq!(world.query::<Changed<i32>>().iter().count(), 0);
+    /// ```
+    pub fn iter_silent<'q>(&'q mut self) -> SilentIter<'q, 'w, T> {
+        self.borrow();
+        SilentIter {
+            archetypes: self.archetypes,
+            archetype_index: 0,
+            iter: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Panics if `Q` accesses a component of `archetype` uniquely and also accesses it some other
+/// way, like taking its dynamic borrows would
+fn assert_no_self_alias<Q: Query>(archetype: &Archetype, access: &mut Vec<(TypeId, Access)>) {
+    access.clear();
+    Q::Fetch::component_access(archetype, &mut |ty, a| {
+        if access
+            .iter()
+            .any(|&(other_ty, b)| other_ty == ty && a.max(b) == Access::Write)
+        {
+            panic!(
+                "{:?} is accessed uniquely and another way by the same query",
+                ty
+            );
+        }
+        access.push((ty, a));
+    });
+}
+
 unsafe impl<'w, Q: Query> Send for QueryBorrow<'w, Q> {}
 unsafe impl<'w, Q: Query> Sync for QueryBorrow<'w, Q> {}
 
 impl<'w, Q: Query> Drop for QueryBorrow<'w, Q> {
     fn drop(&mut self) {
-        if self.borrowed {
+        if self.borrowed && !self.exclusive {
             for x in self.archetypes {
                 if Q::Fetch::access(x) >= Some(Access::Read) {
                     Q::Fetch::release(x);
@@ -875,6 +1548,104 @@ impl<'q, 'w, Q: Query> ExactSizeIterator for QueryIter<'q, 'w, Q> {
     }
 }
 
+/// Iterator over the set of entities with the components in `Q` and their dense indices,
+/// returned by `QueryBorrow::iter_indexed`
+pub struct IndexedIter<'q, 'w, Q: Query> {
+    borrow: &'q mut QueryBorrow<'w, Q>,
+    archetype_index: u32,
+    /// Dense index of the first entity of the current archetype
+    offset: usize,
+    iter: Option<(ChunkIter<Q>, u32)>,
+}
+
+unsafe impl<'q, 'w, Q: Query> Send for IndexedIter<'q, 'w, Q> {}
+unsafe impl<'q, 'w, Q: Query> Sync for IndexedIter<'q, 'w, Q> {}
+
+impl<'q, 'w, Q: Query> Iterator for IndexedIter<'q, 'w, Q> {
+    type Item = (usize, <Q::Fetch as Fetch<'q>>::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.iter {
+                None => {
+                    let archetype = self.borrow.archetypes.get(self.archetype_index as usize)?;
+                    self.archetype_index += 1;
+                    unsafe {
+                        self.iter = Q::Fetch::get(archetype, 0).map(|fetch| {
+                            let iter = ChunkIter {
+                                fetch,
+                                len: archetype.len(),
+                            };
+                            (iter, archetype.len())
+                        });
+                    }
+                }
+                Some((ref mut iter, len)) => match unsafe { iter.next() } {
+                    None => {
+                        self.offset += len as usize;
+                        self.iter = None;
+                    }
+                    Some(components) => {
+                        let index = self.offset + (len - iter.len - 1) as usize;
+                        return Some((index, components));
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// A mapping between the entities matching a query and contiguous indices, returned by
+/// `QueryBorrow::dense_index`
+///
+/// Entities are numbered in iteration order: archetype by archetype, then by position within the
+/// archetype.
+#[derive(Clone)]
+pub struct DenseIndex<'w> {
+    archetypes: &'w [Archetype],
+    entities: &'w Entities,
+    /// Dense index of the first entity of each archetype, if it matches the query
+    offsets: Vec<Option<usize>>,
+    len: usize,
+}
+
+impl<'w> DenseIndex<'w> {
+    /// Number of indices, i.e. of entities matching the query, ignoring filters like `Changed`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no entity matches the query
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The index of `entity`, or `None` if it doesn't exist or doesn't match the query
+    pub fn index_of(&self, entity: Entity) -> Option<usize> {
+        let location = self.entities.get(entity).ok()?;
+        let offset = self.offsets[location.archetype as usize]?;
+        Some(offset + location.index as usize)
+    }
+
+    /// The entity with index `index`, or `None` if `index` is out of range
+    pub fn entity(&self, index: usize) -> Option<Entity> {
+        if index >= self.len {
+            return None;
+        }
+        // Offsets grow with the archetype id, so `index` is in the last non-empty matching
+        // archetype that starts at or before it
+        let (archetype, offset) = self
+            .offsets
+            .iter()
+            .enumerate()
+            .filter_map(|(archetype, offset)| Some((archetype, (*offset)?)))
+            .take_while(|&(_, offset)| offset <= index)
+            .filter(|&(archetype, _)| !self.archetypes[archetype].is_empty())
+            .last()?;
+        Some(self.archetypes[archetype].entity_ids()[index - offset])
+    }
+}
+
 struct ChunkIter<Q: Query> {
     fetch: Q::Fetch,
     len: u32,
@@ -899,6 +1670,260 @@ impl<Q: Query> ChunkIter<Q> {
     }
 }
 
+/// Iterator over the results of a query paired with those of a second query, returned by
+/// `QueryBorrow::join`
+pub struct JoinIter<'q, 'w, Q: Query, Q2: Query> {
+    borrow: &'q mut QueryBorrow<'w, Q>,
+    other: &'q World,
+    archetype_index: u32,
+    iter: Option<ChunkIter<(Entity, Q)>>,
+}
+
+unsafe impl<'q, 'w, Q: Query, Q2: Query> Send for JoinIter<'q, 'w, Q, Q2> {}
+unsafe impl<'q, 'w, Q: Query, Q2: Query> Sync for JoinIter<'q, 'w, Q, Q2> {}
+
+impl<'q, 'w, Q: Query, Q2: Query> Iterator for JoinIter<'q, 'w, Q, Q2> {
+    type Item = (
+        <Q::Fetch as Fetch<'q>>::Item,
+        Option<<Q2::Fetch as Fetch<'q>>::Item>,
+    );
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.iter {
+                None => {
+                    let archetype = self.borrow.archetypes.get(self.archetype_index as usize)?;
+                    self.archetype_index += 1;
+                    unsafe {
+                        self.iter = <(Entity, Q) as Query>::Fetch::get(archetype, 0).map(|fetch| {
+                            ChunkIter {
+                                fetch,
+                                len: archetype.len(),
+                            }
+                        });
+                    }
+                }
+                Some(ref mut iter) => match unsafe { iter.next() } {
+                    None => {
+                        self.iter = None;
+                        continue;
+                    }
+                    Some((entity, item)) => {
+                        let other = self.other;
+                        let joined = other.get_entity_location(entity).and_then(|loc| unsafe {
+                            let archetype = &other.archetypes[loc.archetype as usize];
+                            let mut fetch = Q2::Fetch::get(archetype, loc.index as usize)?;
+                            if fetch.should_skip() {
+                                None
+                            } else {
+                                Some(fetch.next())
+                            }
+                        });
+                        return Some((item, joined));
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl<'q, 'w, Q: Query, Q2: Query> Drop for JoinIter<'q, 'w, Q, Q2> {
+    fn drop(&mut self) {
+        for x in &self.other.archetypes {
+            if Q2::Fetch::access(x) >= Some(Access::Read) {
+                Q2::Fetch::release(x);
+            }
+        }
+    }
+}
+
+/// The archetypes of a `World` that match a query, and the components the query accesses in them
+///
+/// Computing this once and reusing it avoids testing every archetype whenever the query runs.
+/// It is only valid for the archetypes generation it was computed at, see `update`.
+///
+/// # Example
+/// ```
+/// # use bevy_hecs::*;
+/// let mut world = World::new();
+/// world.spawn((123, true));
+/// world.spawn((456,));
+/// let mut numbers = world.archetype_filter::<&mut i32>();
+/// let flags = world.archetype_filter::<(&i32, &bool)>();
+/// assert_eq!(numbers.archetypes().len(), 2);
+/// assert!(!numbers.is_disjoint(&flags));
+/// assert!(world.archetype_filter::<&bool>().is_disjoint(&numbers));
+///
+/// world.spawn((789, "abc"));
+/// assert!(numbers.is_stale(&world));
+/// assert!(numbers.update(&world));
+/// assert!(!numbers.update(&world));
+/// assert_eq!(numbers.archetypes().len(), 3);
+/// ```
+pub struct ArchetypeFilter<Q: Query> {
+    generation: ArchetypesGeneration,
+    archetypes: Vec<u32>,
+    access: Vec<(TypeId, Access)>,
+    _marker: PhantomData<fn(Q)>,
+}
+
+impl<Q: Query> ArchetypeFilter<Q> {
+    pub(crate) fn new(world: &World) -> Self {
+        let mut filter = Self {
+            generation: world.archetypes_generation(),
+            archetypes: Vec::new(),
+            access: Vec::new(),
+            _marker: PhantomData,
+        };
+        filter.compute(world);
+        filter
+    }
+
+    fn compute(&mut self, world: &World) {
+        let archetypes = &mut self.archetypes;
+        let access = &mut self.access;
+        archetypes.clear();
+        access.clear();
+        for (index, archetype) in world.archetypes().enumerate() {
+            if Q::Fetch::access(archetype).is_none() {
+                continue;
+            }
+            archetypes.push(index as u32);
+            Q::Fetch::component_access(archetype, &mut |ty, component_access| {
+                if let Some((_, existing)) = access.iter_mut().find(|(other, _)| *other == ty) {
+                    *existing = (*existing).max(component_access);
+                } else {
+                    access.push((ty, component_access));
+                }
+            });
+        }
+        access.sort_unstable();
+        self.generation = world.archetypes_generation();
+    }
+
+    /// Indices of the matching archetypes, in the order `World::archetypes` yields them
+    pub fn archetypes(&self) -> &[u32] {
+        &self.archetypes
+    }
+
+    /// The components the query accesses in any matching archetype, sorted by `TypeId`
+    pub fn access(&self) -> &[(TypeId, Access)] {
+        &self.access
+    }
+
+    /// The archetypes generation this filter was computed at
+    pub fn generation(&self) -> ArchetypesGeneration {
+        self.generation
+    }
+
+    /// Whether archetypes were added or rearranged since this filter was computed
+    pub fn is_stale(&self, world: &World) -> bool {
+        self.generation != world.archetypes_generation()
+    }
+
+    /// Recomputes the filter if it is stale. Returns whether it was recomputed.
+    pub fn update(&mut self, world: &World) -> bool {
+        if !self.is_stale(world) {
+            return false;
+        }
+        self.compute(world);
+        true
+    }
+
+    /// Whether the two queries can run at the same time: either they match no common archetype, or
+    /// neither writes a component the other accesses. Both filters must be computed for the same
+    /// world and generation.
+    pub fn is_disjoint<Q2: Query>(&self, other: &ArchetypeFilter<Q2>) -> bool {
+        let shares_archetype = self
+            .archetypes
+            .iter()
+            .any(|archetype| other.archetypes.binary_search(archetype).is_ok());
+        if !shares_archetype {
+            return true;
+        }
+        !self.access.iter().any(|(ty, access)| {
+            match other
+                .access
+                .binary_search_by_key(ty, |(other_ty, _)| *other_ty)
+            {
+                Ok(index) => (*access).max(other.access[index].1) == Access::Write,
+                Err(_) => false,
+            }
+        })
+    }
+}
+
+impl<Q: Query> Clone for ArchetypeFilter<Q> {
+    fn clone(&self) -> Self {
+        Self {
+            generation: self.generation,
+            archetypes: self.archetypes.clone(),
+            access: self.access.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Q: Query> fmt::Debug for ArchetypeFilter<Q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchetypeFilter")
+            .field("generation", &self.generation)
+            .field("archetypes", &self.archetypes)
+            .field("access", &self.access)
+            .finish()
+    }
+}
+
+/// Error indicating that two queries could expose the same component both mutably and otherwise
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct QueryConflict;
+
+impl fmt::Display for QueryConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("queries have conflicting access")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for QueryConflict {}
+
+/// Iterator over `&mut T` that does not update mutation trackers, returned by
+/// `QueryBorrow::iter_silent`
+pub struct SilentIter<'q, 'w, T: Component> {
+    archetypes: &'w [Archetype],
+    archetype_index: u32,
+    iter: Option<(NonNull<T>, u32)>,
+    _marker: PhantomData<&'q mut T>,
+}
+
+unsafe impl<'q, 'w, T: Component> Send for SilentIter<'q, 'w, T> {}
+unsafe impl<'q, 'w, T: Component> Sync for SilentIter<'q, 'w, T> {}
+
+impl<'q, 'w, T: Component> Iterator for SilentIter<'q, 'w, T> {
+    type Item = &'q mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.iter {
+                Some((ref mut ptr, ref mut len)) if *len > 0 => {
+                    let component = ptr.as_ptr();
+                    *len -= 1;
+                    unsafe {
+                        *ptr = NonNull::new_unchecked(component.add(1));
+                        return Some(&mut *component);
+                    }
+                }
+                _ => {
+                    let archetype = self.archetypes.get(self.archetype_index as usize)?;
+                    self.archetype_index += 1;
+                    self.iter = archetype.get::<T>().map(|ptr| (ptr, archetype.len()));
+                }
+            }
+        }
+    }
+}
+
 /// Batched version of `QueryIter`
 pub struct BatchedIter<'q, 'w, Q: Query> {
     borrow: &'q mut QueryBorrow<'w, Q>,
@@ -943,6 +1968,34 @@ impl<'q, 'w, Q: Query> Iterator for BatchedIter<'q, 'w, Q> {
     }
 }
 
+/// Iterator over the slices of each archetype matching a query, returned by
+/// `QueryBorrow::iter_slices`
+pub struct SliceIter<'q, 'w, Q: Query> {
+    borrow: &'q mut QueryBorrow<'w, Q>,
+    archetype_index: u32,
+}
+
+unsafe impl<'q, 'w, Q: Query> Send for SliceIter<'q, 'w, Q> {}
+unsafe impl<'q, 'w, Q: Query> Sync for SliceIter<'q, 'w, Q> {}
+
+impl<'q, 'w, Q: Query> Iterator for SliceIter<'q, 'w, Q>
+where
+    Q::Fetch: for<'a> FetchSlice<'a>,
+{
+    type Item = <Q::Fetch as FetchSlice<'q>>::Slice;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let archetype = self.borrow.archetypes.get(self.archetype_index as usize)?;
+            self.archetype_index += 1;
+            if archetype.is_empty() || Q::Fetch::access(archetype).is_none() {
+                continue;
+            }
+            return Some(unsafe { <Q::Fetch as FetchSlice<'q>>::get_slice(archetype) });
+        }
+    }
+}
+
 /// A sequence of entities yielded by `BatchedIter`
 pub struct Batch<'q, Q: Query> {
     _marker: PhantomData<&'q ()>,
@@ -988,6 +2041,11 @@ macro_rules! tuple_impl {
                 $($name::release(archetype);)*
             }
 
+            #[allow(unused_variables)]
+            fn component_access(archetype: &Archetype, f: &mut dyn FnMut(TypeId, Access)) {
+                $($name::component_access(archetype, f);)*
+            }
+
             #[allow(unused_variables)]
             unsafe fn next(&mut self) -> Self::Item {
                 #[allow(non_snake_case)]
@@ -1002,6 +2060,15 @@ macro_rules! tuple_impl {
             }
         }
 
+        impl<'a, $($name: FetchSlice<'a>),*> FetchSlice<'a> for ($($name,)*) {
+            type Slice = ($($name::Slice,)*);
+
+            #[allow(unused_variables)]
+            unsafe fn get_slice(archetype: &'a Archetype) -> Self::Slice {
+                ($($name::get_slice(archetype),)*)
+            }
+        }
+
         impl<$($name: Query),*> Query for ($($name,)*) {
             type Fetch = ($($name::Fetch,)*);
         }
@@ -1124,6 +2191,37 @@ mod tests {
             .is_empty());
     }
 
+    #[test]
+    fn mutated_only_on_write() {
+        let mut world = World::default();
+        let e1 = world.spawn((A(0),));
+
+        fn is_mutated(world: &World, entity: Entity) -> bool {
+            let location = world.get_entity_location(entity).unwrap();
+            let archetype = world.archetypes().nth(location.archetype as usize).unwrap();
+            let mutated = archetype.get_mutated::<A>().unwrap();
+            unsafe { *mutated.as_ptr().add(location.index as usize) }
+        }
+
+        // reading through a unique borrow doesn't flag the component
+        assert_eq!(world.get_mut::<A>(e1).unwrap().0, 0);
+        assert!(!is_mutated(&world, e1));
+        for a in world.query::<Mut<A>>().iter() {
+            assert_eq!(a.0, 0);
+        }
+        assert!(!is_mutated(&world, e1));
+        assert!(world.query::<Mutated<A>>().iter().next().is_none());
+
+        world.get_mut::<A>(e1).unwrap().0 = 1;
+        assert!(is_mutated(&world, e1));
+
+        world.clear_trackers();
+        for mut a in world.query::<Mut<A>>().iter() {
+            a.0 += 1;
+        }
+        assert!(is_mutated(&world, e1));
+    }
+
     #[test]
     fn multiple_mutated_query() {
         let mut world = World::default();
@@ -1191,4 +2289,84 @@ mod tests {
         *world.get_mut(e1).unwrap() = A(1);
         assert_eq!(get_changed(&world), vec![e1]);
     }
+
+    #[test]
+    fn with_added_and_changed_filters() {
+        let mut world = World::default();
+        let e1 = world.spawn((A(0), B(0)));
+        let e2 = world.spawn((A(0), B(0)));
+        world.clear_trackers();
+        let e3 = world.spawn((A(0), B(0)));
+        world.get_mut::<B>(e1).unwrap().0 += 1;
+
+        fn filtered(world: &World, added: bool) -> Vec<Entity> {
+            if added {
+                world
+                    .query::<(Entity, &A)>()
+                    .with_added::<B>()
+                    .iter()
+                    .map(|(e, _a)| e)
+                    .collect()
+            } else {
+                world
+                    .query::<(Entity, &A)>()
+                    .with_changed::<B>()
+                    .iter()
+                    .map(|(e, _a)| e)
+                    .collect()
+            }
+        }
+
+        // newly spawned entities count as both added and changed
+        assert_eq!(filtered(&world, true), vec![e3]);
+        assert_eq!(filtered(&world, false), vec![e1, e3]);
+
+        world.clear_trackers();
+        assert!(filtered(&world, true).is_empty());
+        assert!(filtered(&world, false).is_empty());
+
+        world.get_mut::<B>(e2).unwrap().0 += 1;
+        assert_eq!(filtered(&world, false), vec![e2]);
+    }
+
+    #[test]
+    fn clear_trackers_for_one_type() {
+        let mut world = World::default();
+        let e1 = world.spawn((A(0), B(0)));
+        world.clear_trackers();
+
+        world.get_mut::<A>(e1).unwrap().0 += 1;
+        world.get_mut::<B>(e1).unwrap().0 += 1;
+        world.clear_trackers_for::<A>();
+
+        assert!(world.query::<Changed<A>>().iter().next().is_none());
+        assert_eq!(
+            world
+                .query::<(Changed<B>, Entity)>()
+                .iter()
+                .map(|(_b, e)| e)
+                .collect::<Vec<Entity>>(),
+            vec![e1]
+        );
+    }
+
+    #[test]
+    fn silent_mutation() {
+        let mut world = World::default();
+        let e1 = world.spawn((A(0), B(0)));
+        world.spawn((A(0),));
+        world.clear_trackers();
+
+        for a in world.query::<&mut A>().iter_silent() {
+            a.0 += 1;
+        }
+        assert_eq!(world.get::<A>(e1).unwrap().0, 1);
+        assert!(world.query::<Changed<A>>().iter().next().is_none());
+        assert!(world.query::<Mutated<A>>().iter().next().is_none());
+
+        for mut a in world.query::<Mut<A>>().iter() {
+            a.0 += 1;
+        }
+        assert_eq!(world.query::<Changed<A>>().iter().count(), 2);
+    }
 }
@@ -32,30 +32,51 @@ impl<'a, Q: Query> QueryOne<'a, Q> {
 
     /// Get the query result, or `None` if the entity does not satisfy the query
     ///
+    /// The entity is matched the same way `World::query` matches entities, so optional components
+    /// are `None` when missing, and filters like `With`, `Without` and `Mutated` make this return
+    /// `None` when the entity wouldn't be iterated.
+    ///
     /// Must be called at most once.
     ///
     /// Panics if called more than once or if it would construct a borrow that clashes with another
     /// pre-existing borrow.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((123, true));
+    /// let b = world.spawn((456,));
+    /// let mut query = world.query_one::<(&i32, Option<&bool>)>(b).unwrap();
+    /// assert_eq!(query.get(), Some((&456, None)));
+    /// assert!(world.query_one::<&i32>(b).unwrap().with::<bool>().get().is_none());
+    /// assert!(world.query_one::<&i32>(a).unwrap().without::<bool>().get().is_none());
+    /// ```
     pub fn get(&mut self) -> Option<<Q::Fetch as Fetch<'_>>::Item> {
         if self.borrowed {
-            panic!("called QueryOnce::get twice; construct a new query instead");
+            panic!("called QueryOne::get twice; construct a new query instead");
         }
         unsafe {
             let mut fetch = Q::Fetch::get(self.archetype, self.index as usize)?;
+            if fetch.should_skip() {
+                return None;
+            }
             self.borrowed = true;
             Q::Fetch::borrow(self.archetype);
             Some(fetch.next())
         }
     }
 
-    /// Transform the query into one that requires a certain component without borrowing it
+    /// Transform the query into one that requires a certain component without borrowing it, so
+    /// `get` returns `None` if the entity doesn't have it
     ///
     /// See `QueryBorrow::with` for details.
     pub fn with<T: Component>(self) -> QueryOne<'a, With<T, Q>> {
         self.transform()
     }
 
-    /// Transform the query into one that skips entities having a certain component
+    /// Transform the query into one that skips entities having a certain component, so `get`
+    /// returns `None` if the entity has it
     ///
     /// See `QueryBorrow::without` for details.
     pub fn without<T: Component>(self) -> QueryOne<'a, Without<T, Q>> {
@@ -14,18 +14,23 @@
 
 // modified by Bevy contributors
 
-use crate::alloc::vec::Vec;
-use bevy_utils::{HashMap, HashSet};
-use core::{any::TypeId, convert::TryFrom, fmt, mem, ptr};
+use crate::alloc::{boxed::Box, vec, vec::Vec};
+use bevy_utils::HashMap;
+use core::{
+    any::{type_name, Any, TypeId},
+    convert::TryFrom,
+    fmt, mem, ptr,
+};
+use smallvec::SmallVec;
 
 #[cfg(feature = "std")]
 use std::error::Error;
 
 use crate::{
-    archetype::Archetype,
-    entities::{Entities, Location},
-    Bundle, DynamicBundle, Entity, EntityRef, MissingComponent, NoSuchEntity, Query, QueryBorrow,
-    QueryOne, Ref, RefMut,
+    archetype::{Archetype, TypeInfo},
+    entities::{Entities, EntityGenerator, EntityStatus, GeneratedEntities, Location},
+    ArchetypeFilter, Bundle, CommandBuffer, DynamicBundle, Entity, EntityBuilder, EntityRef, Fetch,
+    MissingComponent, NoSuchEntity, Query, QueryBorrow, QueryOne, Ref, RefMut, TypedEntity,
 };
 
 /// An unordered collection of entities, each having any number of distinctly typed components
@@ -37,11 +42,19 @@ use crate::{
 /// runs, allowing for extremely fast, cache-friendly iteration.
 pub struct World {
     entities: Entities,
-    index: HashMap<Vec<TypeId>, u32>,
+    entity_generator: EntityGenerator,
+    generated_entities: GeneratedEntities,
+    index: HashMap<SmallVec<[TypeId; 8]>, u32>,
     removed_components: HashMap<TypeId, Vec<Entity>>,
+    removed_values: HashMap<TypeId, RemovedValues>,
+    remove_hooks: HashMap<TypeId, RemoveHook>,
+    add_hooks: HashMap<TypeId, AddHook>,
+    cloners: HashMap<TypeId, unsafe fn(*const u8, *mut u8)>,
+    resources: HashMap<TypeId, Archetype>,
     #[allow(missing_docs)]
     pub archetypes: Vec<Archetype>,
     archetype_generation: u64,
+    archetype_moves: u64,
 }
 
 impl World {
@@ -51,16 +64,50 @@ impl World {
         let mut archetypes = Vec::new();
         archetypes.push(Archetype::new(Vec::new()));
         let mut index = HashMap::default();
-        index.insert(Vec::new(), 0);
+        index.insert(SmallVec::new(), 0);
         Self {
             entities: Entities::default(),
+            entity_generator: EntityGenerator::new(),
+            generated_entities: GeneratedEntities::default(),
             index,
             archetypes,
             archetype_generation: 0,
+            archetype_moves: 0,
             removed_components: HashMap::default(),
+            removed_values: HashMap::default(),
+            remove_hooks: HashMap::default(),
+            add_hooks: HashMap::default(),
+            cloners: HashMap::default(),
+            resources: HashMap::default(),
         }
     }
 
+    /// Create an empty world whose entity IDs are generated from `seed`
+    ///
+    /// Spawning the same entities in the same order in two worlds with the same seed yields the
+    /// same IDs. See `EntityGenerator` for how IDs are generated.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut a = World::with_entity_generator(42);
+    /// let mut b = World::with_entity_generator(42);
+    /// assert_eq!(a.spawn((123,)), b.spawn(("abc",)));
+    /// ```
+    pub fn with_entity_generator(seed: u64) -> Self {
+        let mut world = Self::new();
+        world.entity_generator = EntityGenerator::from_seed(seed);
+        world
+    }
+
+    /// The generator this world takes the IDs of new entities from
+    ///
+    /// Clones of it can be used to pick IDs for entities that are spawned later, e.g. with
+    /// `spawn_as_entity`.
+    pub fn entity_generator(&self) -> &EntityGenerator {
+        &self.entity_generator
+    }
+
     /// Create an entity with certain components
     ///
     /// Returns the ID of the newly created entity.
@@ -80,11 +127,122 @@ impl World {
     /// let b = world.spawn((456, true));
     /// ```
     pub fn spawn(&mut self, components: impl DynamicBundle) -> Entity {
-        let entity = Entity::new();
+        let entity = self.entity_generator.generate();
         self.spawn_as_entity(entity, components);
         entity
     }
 
+    /// Allocate an entity ID without any components
+    ///
+    /// The entity lives in the empty archetype: `contains` returns `true`, `get` reports a missing
+    /// component, and components can be added later with `insert`. Useful when IDs must be handed
+    /// out before the entity's components are known.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let e = world.reserve_entity();
+    /// assert!(world.contains(e));
+    /// assert!(world.get::<i32>(e).is_err());
+    /// world.insert(e, (123,)).unwrap();
+    /// assert_eq!(*world.get::<i32>(e).unwrap(), 123);
+    /// ```
+    pub fn reserve_entity(&mut self) -> Entity {
+        let entity = self.entity_generator.generate();
+        let index = unsafe { self.archetypes[0].allocate(entity.id()) };
+        self.entities.insert(
+            entity,
+            Location {
+                archetype: 0,
+                index,
+            },
+        );
+        entity
+    }
+
+    /// Create an entity with certain components, remembering their types
+    ///
+    /// The returned `TypedEntity` can be passed to `get_typed` and `get_typed_mut` to access
+    /// components in `B` without handling a missing-component error. See `TypedEntity` for the
+    /// limits of this guarantee.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let e = world.spawn_typed((123, "abc"));
+    /// assert_eq!(*world.get_typed::<i32, _>(e), 123);
+    /// ```
+    pub fn spawn_typed<B: Bundle>(&mut self, components: B) -> TypedEntity<B> {
+        TypedEntity::new(self.spawn(components))
+    }
+
+    /// Create an entity whose components are produced from its own ID
+    ///
+    /// The entity ID is allocated first and passed to `f`; the archetype is resolved from the
+    /// bundle `f` returns. Avoids a spawn-then-insert round trip when a component needs to refer
+    /// to the entity it belongs to.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let e = world.spawn_with_id(|id| (id, 123));
+    /// assert_eq!(*world.get::<Entity>(e).unwrap(), e);
+    /// ```
+    pub fn spawn_with_id<B: DynamicBundle>(&mut self, f: impl FnOnce(Entity) -> B) -> Entity {
+        let entity = self.entity_generator.generate();
+        self.spawn_as_entity(entity, f(entity));
+        entity
+    }
+
+    /// Allow `clone_entity` to duplicate `C` components
+    pub fn register_cloneable<C: Component + Clone>(&mut self) {
+        unsafe fn clone<C: Clone>(src: *const u8, dst: *mut u8) {
+            dst.cast::<C>().write_unaligned((*src.cast::<C>()).clone());
+        }
+
+        self.cloners.insert(TypeId::of::<C>(), clone::<C>);
+    }
+
+    /// Create a new entity with clones of the components of `entity`
+    ///
+    /// Only component types registered with `register_cloneable` are cloned. The others are
+    /// skipped and their `TypeId`s returned alongside the new entity, so callers know what was
+    /// left out.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// # use std::any::TypeId;
+    /// let mut world = World::new();
+    /// world.register_cloneable::<i32>();
+    /// let a = world.spawn((123, true));
+    /// let (b, skipped) = world.clone_entity(a).unwrap();
+    /// assert_eq!(*world.get::<i32>(b).unwrap(), 123);
+    /// assert!(world.get::<bool>(b).is_err());
+    /// assert_eq!(skipped, &[TypeId::of::<bool>()]);
+    /// ```
+    pub fn clone_entity(&mut self, entity: Entity) -> Result<(Entity, Vec<TypeId>), NoSuchEntity> {
+        let loc = self.entities.get(entity)?;
+        let archetype = &self.archetypes[loc.archetype as usize];
+        let mut builder = EntityBuilder::new();
+        let mut skipped = Vec::new();
+        for ty in archetype.types() {
+            match self.cloners.get(&ty.id()).copied() {
+                Some(clone) => unsafe {
+                    let src = archetype
+                        .get_dynamic(ty.id(), ty.layout().size(), loc.index)
+                        .unwrap();
+                    builder.add_dynamic(*ty, |dst| clone(src.as_ptr(), dst));
+                },
+                None => skipped.push(ty.id()),
+            }
+        }
+        Ok((self.spawn(builder.build()), skipped))
+    }
+
     /// Create an entity with the given Entity id and the given components
     ///
     /// Arguments can be tuples, structs annotated with `#[derive(Bundle)]`, or the result of
@@ -93,12 +251,18 @@ impl World {
     /// `(x,)`.
     ///
     /// Any type that satisfies `Send + Sync + 'static` can be used as a component.
+    ///
+    /// IDs restored from e.g. a saved world will not be handed out again by `spawn`, see
+    /// `EntityGenerator`. `entity` must not already exist. Components referring to other entities
+    /// can be fixed up with `EntityMap` when the original IDs can't be kept.
     pub fn spawn_as_entity(&mut self, entity: Entity, components: impl DynamicBundle) {
+        self.generated_entities
+            .spawned(&self.entity_generator, entity);
         let archetype_id = components.with_ids(|ids| {
             self.index.get(ids).copied().unwrap_or_else(|| {
                 let x = self.archetypes.len() as u32;
                 self.archetypes.push(Archetype::new(components.type_info()));
-                self.index.insert(ids.to_vec(), x);
+                self.index.insert(SmallVec::from_slice(ids), x);
                 self.archetype_generation += 1;
                 x
             })
@@ -111,6 +275,13 @@ impl World {
                 archetype.put_dynamic(ptr, ty, size, index, true);
                 true
             });
+            notify_added(
+                &mut self.add_hooks,
+                archetype,
+                entity,
+                index,
+                archetype.types(),
+            );
             self.entities.insert(
                 entity,
                 Location {
@@ -121,9 +292,17 @@ impl World {
         }
     }
 
+    /// Apply the structural changes recorded in `buffer`, in the order they were recorded
+    ///
+    /// See `CommandBuffer` for details.
+    pub fn apply_commands(&mut self, buffer: CommandBuffer) {
+        buffer.apply(self);
+    }
+
     /// Efficiently spawn a large number of entities with the same components
     ///
-    /// Faster than calling `spawn` repeatedly with the same components.
+    /// Faster than calling `spawn` repeatedly with the same components. Batches of empty bundles
+    /// (e.g. `()`) are allocated directly into the component-less archetype.
     ///
     /// # Example
     /// ```
@@ -148,17 +327,107 @@ impl World {
         SpawnBatchIter {
             inner: iter,
             entities: &mut self.entities,
+            entity_generator: &self.entity_generator,
+            add_hooks: &mut self.add_hooks,
             archetype_id,
             archetype: &mut self.archetypes[archetype_id as usize],
         }
     }
 
+    /// Efficiently spawn a large number of entities with the same components, keeping the given
+    /// entity IDs
+    ///
+    /// Like `spawn_batch`, storage for the whole batch is reserved up front, which makes this the
+    /// fast way to restore entities whose IDs must be preserved, e.g. when deserializing. An ID
+    /// that is already live is despawned first, so its old components are replaced rather than
+    /// merged. Later calls to `spawn` won't collide with the supplied IDs, see `EntityGenerator`.
+    ///
+    /// Returns the number of entities spawned.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut source = World::new();
+    /// let entities = source.spawn_batch((0..100).map(|i| (i,))).collect::<Vec<_>>();
+    /// let mut world = World::new();
+    /// let count = world.spawn_batch_as_entities(
+    ///     source.query::<(Entity, &i32)>().iter().map(|(e, &i)| (e, (i,))),
+    /// );
+    /// assert_eq!(count, 100);
+    /// for (i, entity) in entities.into_iter().enumerate() {
+    ///     assert_eq!(*world.get::<i32>(entity).unwrap(), i as i32);
+    /// }
+    /// ```
+    pub fn spawn_batch_as_entities<I, B>(&mut self, iter: I) -> usize
+    where
+        I: IntoIterator<Item = (Entity, B)>,
+        B: Bundle,
+    {
+        let iter = iter.into_iter();
+        let (lower, upper) = iter.size_hint();
+        let archetype_id = self
+            .reserve_inner::<B>(u32::try_from(upper.unwrap_or(lower)).expect("iterator too large"));
+
+        let mut count = 0;
+        for (entity, components) in iter {
+            self.generated_entities
+                .spawned(&self.entity_generator, entity);
+            if self.entities.contains(entity) {
+                self.despawn(entity).unwrap();
+            }
+            let archetype = &mut self.archetypes[archetype_id as usize];
+            unsafe {
+                let index = archetype.allocate(entity.id());
+                // Archetype 0 has no columns, so empty bundles have nothing to write
+                if archetype_id != 0 {
+                    components.put(|ptr, ty, size| {
+                        archetype.put_dynamic(ptr, ty, size, index, true);
+                        true
+                    });
+                    notify_added(
+                        &mut self.add_hooks,
+                        archetype,
+                        entity,
+                        index,
+                        archetype.types(),
+                    );
+                }
+                self.entities.insert(
+                    entity,
+                    Location {
+                        archetype: archetype_id,
+                        index,
+                    },
+                );
+            }
+            count += 1;
+        }
+        count
+    }
+
     /// Destroy an entity and all its components
     pub fn despawn(&mut self, entity: Entity) -> Result<(), NoSuchEntity> {
+        self.despawn_notifying(entity).map(|_| ())
+    }
+
+    /// Destroy an entity and all its components, returning the entity moved into its slot
+    ///
+    /// Entities are swap-removed from their archetype, so the last entity of the archetype (if it
+    /// isn't `entity` itself) takes over the freed `Location::index`. Callers keeping side tables
+    /// keyed by storage index can use the returned entity to invalidate cached positions.
+    pub fn despawn_notifying(&mut self, entity: Entity) -> Result<Option<Entity>, NoSuchEntity> {
         let loc = self.entities.free(entity)?;
         let archetype = &mut self.archetypes[loc.archetype as usize];
-        if let Some(moved) = unsafe { archetype.remove(loc.index) } {
-            self.entities.get_mut(Entity::from_id(moved)).unwrap().index = loc.index;
+        notify_removed(
+            &mut self.removed_values,
+            &mut self.remove_hooks,
+            archetype,
+            entity,
+            loc.index,
+        );
+        let moved = unsafe { archetype.remove(loc.index) }.map(Entity::from_id);
+        if let Some(moved) = moved {
+            self.entities.get_mut(moved).unwrap().index = loc.index;
         }
         for ty in archetype.types() {
             let removed_entities = self
@@ -167,7 +436,105 @@ impl World {
                 .or_insert_with(Vec::new);
             removed_entities.push(entity);
         }
-        Ok(())
+        Ok(moved)
+    }
+
+    /// Destroy many entities and all their components at once
+    ///
+    /// Entities are grouped by archetype and removed back-to-front, and removed component types
+    /// are recorded once per archetype rather than once per entity. Entities that don't exist (or
+    /// appear more than once) are skipped rather than aborting the batch.
+    ///
+    /// Returns the number of entities that were despawned.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let entities = world.spawn_batch((0..10).map(|i| (i,))).collect::<Vec<_>>();
+    /// assert_eq!(world.despawn_batch(entities[..5].iter().copied()), 5);
+    /// assert_eq!(world.despawn_batch(entities[..5].iter().copied()), 0);
+    /// assert_eq!(world.query::<&i32>().iter().count(), 5);
+    /// ```
+    pub fn despawn_batch<I: IntoIterator<Item = Entity>>(&mut self, iter: I) -> usize {
+        let entities = &mut self.entities;
+        let mut locations = iter
+            .into_iter()
+            .filter_map(|entity| entities.free(entity).ok().map(|loc| (entity, loc)))
+            .collect::<Vec<_>>();
+        // Highest index first within each archetype, so entities swapped into freed slots are
+        // never ones that still need to be removed
+        locations.sort_unstable_by(|(_, a), (_, b)| {
+            a.archetype
+                .cmp(&b.archetype)
+                .then_with(|| b.index.cmp(&a.index))
+        });
+
+        let mut start = 0;
+        while start < locations.len() {
+            let archetype_id = locations[start].1.archetype;
+            let end = locations[start..]
+                .iter()
+                .position(|(_, loc)| loc.archetype != archetype_id)
+                .map_or(locations.len(), |len| start + len);
+            let group = &locations[start..end];
+
+            let archetype = &mut self.archetypes[archetype_id as usize];
+            for &(entity, loc) in group {
+                notify_removed(
+                    &mut self.removed_values,
+                    &mut self.remove_hooks,
+                    archetype,
+                    entity,
+                    loc.index,
+                );
+                if let Some(moved) = unsafe { archetype.remove(loc.index) } {
+                    self.entities.get_mut(Entity::from_id(moved)).unwrap().index = loc.index;
+                }
+            }
+            for ty in archetype.types() {
+                let removed_entities = self
+                    .removed_components
+                    .entry(ty.id())
+                    .or_insert_with(Vec::new);
+                removed_entities.extend(group.iter().map(|&(entity, _)| entity));
+            }
+
+            start = end;
+        }
+        locations.len()
+    }
+
+    /// Destroy every entity matching `Q` for which `predicate` returns `true`
+    ///
+    /// Entities that don't match `Q`, or for which `predicate` returns `false`, are untouched.
+    /// Matching IDs are collected while the query is borrowed and only despawned after the borrow
+    /// ends, so the predicate never observes a partially despawned world.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((1, true));
+    /// let b = world.spawn((2, true));
+    /// let c = world.spawn((true,));
+    /// world.despawn_where::<&i32, _>(|&x| x > 1);
+    /// assert!(world.contains(a));
+    /// assert!(!world.contains(b));
+    /// assert!(world.contains(c));
+    /// ```
+    pub fn despawn_where<Q: Query, F>(&mut self, mut predicate: F)
+    where
+        F: for<'a> FnMut(<Q::Fetch as Fetch<'a>>::Item) -> bool,
+    {
+        let entities = self
+            .query::<(Entity, Q)>()
+            .iter()
+            .filter_map(|(entity, item)| if predicate(item) { Some(entity) } else { None })
+            .collect::<Vec<_>>();
+        for entity in entities {
+            self.despawn(entity).unwrap();
+        }
     }
 
     /// Ensure `additional` entities with exact components `T` can be spawned without reallocating
@@ -175,21 +542,35 @@ impl World {
         self.reserve_inner::<T>(additional);
     }
 
+    /// Ensure `additional` entities with exact components `T` can be spawned without reallocating,
+    /// without reserving any more space than that
+    ///
+    /// Like `Vec::reserve_exact`, this skips the archetype's growth heuristics, which is useful in
+    /// memory-sensitive contexts. Prefer `reserve` if more entities are likely to follow.
+    pub fn reserve_exact<T: Bundle>(&mut self, additional: u32) {
+        self.entities.reserve(additional);
+        let archetype_id = self.bundle_archetype::<T>();
+        self.archetypes[archetype_id as usize].reserve_exact(additional);
+    }
+
     fn reserve_inner<T: Bundle>(&mut self, additional: u32) -> u32 {
         self.entities.reserve(additional);
+        let archetype_id = self.bundle_archetype::<T>();
+        self.archetypes[archetype_id as usize].reserve(additional);
+        archetype_id
+    }
 
-        let archetype_id = T::with_static_ids(|ids| {
+    /// Find or create the archetype storing exactly the components of `T`
+    fn bundle_archetype<T: Bundle>(&mut self) -> u32 {
+        T::with_static_ids(|ids| {
             self.index.get(ids).copied().unwrap_or_else(|| {
                 let x = self.archetypes.len() as u32;
                 self.archetypes.push(Archetype::new(T::static_type_info()));
-                self.index.insert(ids.to_vec(), x);
+                self.index.insert(SmallVec::from_slice(ids), x);
                 self.archetype_generation += 1;
                 x
             })
-        });
-
-        self.archetypes[archetype_id as usize].reserve(additional);
-        archetype_id
+        })
     }
 
     /// Despawn all entities
@@ -204,16 +585,199 @@ impl World {
                     .or_insert_with(Vec::new);
                 removed_entities.extend(archetype.iter_entities().map(|id| Entity::from_id(*id)));
             }
+            for index in 0..archetype.len() {
+                let entity = Entity::from_id(archetype.entity_id(index));
+                notify_removed(
+                    &mut self.removed_values,
+                    &mut self.remove_hooks,
+                    archetype,
+                    entity,
+                    index,
+                );
+            }
             archetype.clear();
         }
         self.entities.clear();
     }
 
+    /// Release memory held by archetypes that no longer contain any entities
+    ///
+    /// Empty archetypes other than the component-less one are dropped, and the storage of the rest
+    /// is shrunk to fit their entities. Since this renumbers archetypes, `archetypes_generation`
+    /// changes if any were dropped. Useful after `clear`, which keeps archetypes around for reuse.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((123, "abc"));
+    /// let b = world.spawn((456,));
+    /// world.despawn(a).unwrap();
+    /// world.shrink_to_fit();
+    /// assert_eq!(world.archetypes().len(), 2);
+    /// assert_eq!(*world.get::<i32>(b).unwrap(), 456);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        let mut new_ids = Vec::with_capacity(self.archetypes.len());
+        let mut next = 0;
+        for (id, archetype) in self.archetypes.iter().enumerate() {
+            if id == 0 || !archetype.is_empty() {
+                new_ids.push(Some(next));
+                next += 1;
+            } else {
+                new_ids.push(None);
+            }
+        }
+
+        if next as usize != self.archetypes.len() {
+            let mut id = 0;
+            self.archetypes.retain(|_| {
+                id += 1;
+                new_ids[id - 1].is_some()
+            });
+            self.index.retain(|_, id| match new_ids[*id as usize] {
+                Some(new_id) => {
+                    *id = new_id;
+                    true
+                }
+                None => false,
+            });
+            for (new_id, archetype) in self.archetypes.iter().enumerate() {
+                for id in archetype.iter_entities() {
+                    let loc = self.entities.get_mut(Entity::from_id(*id)).unwrap();
+                    loc.archetype = new_id as u32;
+                }
+            }
+            self.archetype_generation += 1;
+        }
+
+        for archetype in self.archetypes.iter_mut() {
+            archetype.shrink_to_fit();
+        }
+        self.index.shrink_to_fit();
+        self.entities.shrink_to_fit();
+    }
+
+    /// Bytes of heap memory held by the archetypes of this world, see `Archetype::allocated_bytes`
+    ///
+    /// Storage isn't released when entities are despawned, so this can stay high after many
+    /// spawn/despawn cycles or large reservations. `shrink_to_fit` releases what isn't in use.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let entities = world.spawn_batch((0..1_000).map(|i| (i,))).collect::<Vec<_>>();
+    /// for entity in entities {
+    ///     world.despawn(entity).unwrap();
+    /// }
+    /// let before = world.memory_usage();
+    /// world.shrink_to_fit();
+    /// assert!(world.memory_usage() < before);
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        self.archetypes.iter().map(Archetype::allocated_bytes).sum()
+    }
+
     /// Whether `entity` still exists
     pub fn contains(&self, entity: Entity) -> bool {
         self.entities.contains(entity)
     }
 
+    /// Find out why `entity` does or doesn't resolve, to debug dangling entity references
+    ///
+    /// Unlike `contains`, this distinguishes IDs of despawned entities from IDs this world never
+    /// handed out. See `EntityStatus`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::with_entity_generator(1);
+    /// let a = world.spawn((123,));
+    /// assert_eq!(world.validate(a), EntityStatus::Alive);
+    /// world.despawn(a).unwrap();
+    /// assert_eq!(world.validate(a), EntityStatus::Despawned);
+    ///
+    /// let mut other = World::with_entity_generator(1);
+    /// other.spawn((123,));
+    /// let b = other.spawn((456,));
+    /// assert_eq!(world.validate(b), EntityStatus::NeverAllocated);
+    /// assert_eq!(world.validate(Entity::new()), EntityStatus::Unknown);
+    /// ```
+    pub fn validate(&self, entity: Entity) -> EntityStatus {
+        if self.entities.contains(entity) {
+            return EntityStatus::Alive;
+        }
+        match self
+            .generated_entities
+            .has_generated(&self.entity_generator, entity)
+        {
+            Some(true) => EntityStatus::Despawned,
+            Some(false) => EntityStatus::NeverAllocated,
+            None => EntityStatus::Unknown,
+        }
+    }
+
+    /// Whether `entity` exists and has a `T` component
+    ///
+    /// Cheaper than `get`, since nothing is borrowed.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let e = world.spawn((123,));
+    /// assert!(world.contains_component::<i32>(e));
+    /// assert!(!world.contains_component::<bool>(e));
+    /// ```
+    pub fn contains_component<T: Component>(&self, entity: Entity) -> bool {
+        self.entities.get(entity).map_or(false, |loc| {
+            self.archetypes[loc.archetype as usize].has::<T>()
+        })
+    }
+
+    /// Iterate over the types of the components of `entity`
+    ///
+    /// Types are yielded in an unspecified but consistent order.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// # use std::any::TypeId;
+    /// let mut world = World::new();
+    /// let e = world.spawn((123, true));
+    /// let types = world.component_types(e).unwrap().collect::<Vec<_>>();
+    /// assert_eq!(types.len(), 2);
+    /// assert!(types.contains(&TypeId::of::<i32>()));
+    /// assert!(types.contains(&TypeId::of::<bool>()));
+    /// ```
+    pub fn component_types(
+        &self,
+        entity: Entity,
+    ) -> Result<impl ExactSizeIterator<Item = TypeId> + '_, NoSuchEntity> {
+        let loc = self.entities.get(entity)?;
+        Ok(self.archetypes[loc.archetype as usize]
+            .types()
+            .iter()
+            .map(|ty| ty.id()))
+    }
+
+    /// Number of live entities, including those created by `reserve_entity`
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((123,));
+    /// world.spawn((456, true));
+    /// assert_eq!(world.entity_count(), 2);
+    /// world.despawn(a).unwrap();
+    /// assert_eq!(world.entity_count(), 1);
+    /// ```
+    pub fn entity_count(&self) -> usize {
+        self.entities.len()
+    }
+
     /// Efficiently iterate over all entities that have certain components
     ///
     /// Calling `iter` on the returned value yields `(Entity, Q)` tuples, where `Q` is some query
@@ -254,7 +818,30 @@ impl World {
     /// assert!(entities.contains(&(b, 456, false)));
     /// ```
     pub fn query<Q: Query>(&self) -> QueryBorrow<'_, Q> {
-        QueryBorrow::new(&self.archetypes)
+        QueryBorrow::new(&self.archetypes, &self.entities)
+    }
+
+    /// Efficiently iterate over all entities that have certain components, using the unique
+    /// borrow of the world in place of dynamic borrow tracking
+    ///
+    /// Behaves like `query`, but since nothing else can access the world while the result is live,
+    /// the archetypes' dynamic borrow state is never touched: no atomic borrow is taken or released
+    /// for each archetype, and other borrows can't cause a panic. Queries that access the same
+    /// component uniquely twice, like `(&mut T, &mut T)`, are still rejected with a panic when
+    /// iteration starts.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((123, true));
+    /// for (mut x, &flag) in world.query_mut::<(&mut i32, &bool)>().iter() {
+    ///     if flag { *x *= 2; }
+    /// }
+    /// assert_eq!(*world.get::<i32>(a).unwrap(), 246);
+    /// ```
+    pub fn query_mut<Q: Query>(&mut self) -> QueryBorrow<'_, Q> {
+        QueryBorrow::new_exclusive(&mut self.archetypes, &self.entities)
     }
 
     /// Prepare a query against a single entity
@@ -304,6 +891,87 @@ impl World {
         Ok(unsafe { RefMut::new(&self.archetypes[loc.archetype as usize], loc.index)? })
     }
 
+    /// Uniquely borrow the `T` components of two different entities at once
+    ///
+    /// Unlike calling `get_mut` twice, this succeeds when both entities share an archetype. Fails
+    /// with `ComponentError::SameEntity` if `a == b`. Panics if `T` is already borrowed from either
+    /// entity's archetype.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((123,));
+    /// let b = world.spawn((456,));
+    /// {
+    ///     let (mut x, mut y) = world.get_mut_pair::<i32>(a, b).unwrap();
+    ///     core::mem::swap(&mut *x, &mut *y);
+    /// }
+    /// assert_eq!(*world.get::<i32>(a).unwrap(), 456);
+    /// assert_eq!(*world.get::<i32>(b).unwrap(), 123);
+    /// assert_eq!(
+    ///     world.get_mut_pair::<i32>(a, a).unwrap_err(),
+    ///     ComponentError::SameEntity
+    /// );
+    /// ```
+    pub fn get_mut_pair<T: Component>(
+        &self,
+        a: Entity,
+        b: Entity,
+    ) -> Result<(RefMut<'_, T>, RefMut<'_, T>), ComponentError> {
+        if a == b {
+            return Err(ComponentError::SameEntity);
+        }
+        let loc_a = self.entities.get(a)?;
+        let loc_b = self.entities.get(b)?;
+        if loc_a.archetype == 0 || loc_b.archetype == 0 {
+            return Err(MissingComponent::new::<T>().into());
+        }
+        unsafe {
+            if loc_a.archetype == loc_b.archetype {
+                Ok(RefMut::new_pair(
+                    &self.archetypes[loc_a.archetype as usize],
+                    loc_a.index,
+                    loc_b.index,
+                )?)
+            } else {
+                let x = RefMut::new(&self.archetypes[loc_a.archetype as usize], loc_a.index)?;
+                let y = RefMut::new(&self.archetypes[loc_b.archetype as usize], loc_b.index)?;
+                Ok((x, y))
+            }
+        }
+    }
+
+    /// Borrow the `T` component of an entity spawned with `spawn_typed`
+    ///
+    /// `T` must be one of the components in `B`. Panics if it isn't, if the entity was despawned
+    /// or had `T` removed, or if the component is already uniquely borrowed from another entity
+    /// with the same components.
+    pub fn get_typed<T: Component, B: Bundle>(&self, entity: TypedEntity<B>) -> Ref<'_, T> {
+        debug_assert!(
+            B::with_static_ids(|ids| ids.contains(&TypeId::of::<T>())),
+            "{} is not part of the entity's bundle",
+            type_name::<T>()
+        );
+        self.get(entity.entity())
+            .unwrap_or_else(|err| panic!("invalid typed entity: {}", err))
+    }
+
+    /// Uniquely borrow the `T` component of an entity spawned with `spawn_typed`
+    ///
+    /// `T` must be one of the components in `B`. Panics if it isn't, if the entity was despawned
+    /// or had `T` removed, or if the component is already borrowed from another entity with the
+    /// same components.
+    pub fn get_typed_mut<T: Component, B: Bundle>(&self, entity: TypedEntity<B>) -> RefMut<'_, T> {
+        debug_assert!(
+            B::with_static_ids(|ids| ids.contains(&TypeId::of::<T>())),
+            "{} is not part of the entity's bundle",
+            type_name::<T>()
+        );
+        self.get_mut(entity.entity())
+            .unwrap_or_else(|err| panic!("invalid typed entity: {}", err))
+    }
+
     /// Access an entity regardless of its component types
     ///
     /// Does not immediately borrow any component.
@@ -331,7 +999,29 @@ impl World {
     /// assert!(ids.contains(&b));
     /// ```
     pub fn iter(&self) -> Iter<'_> {
-        Iter::new(&self.archetypes, &self.entities)
+        Iter::new(&self.archetypes, &self.entities, None)
+    }
+
+    /// Iterate over all entities in the world that have a `T` component
+    ///
+    /// A convenience over `World::query` for tooling such as inspectors, which need to filter by a
+    /// single component while still having type-erased access to all of an entity's data.
+    /// Archetypes lacking `T` are skipped entirely.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((123, true));
+    /// let b = world.spawn((456,));
+    /// world.spawn((false,));
+    /// let ids = world.iter_with::<i32>().map(|(id, _)| id).collect::<Vec<_>>();
+    /// assert_eq!(ids.len(), 2);
+    /// assert!(ids.contains(&a));
+    /// assert!(ids.contains(&b));
+    /// ```
+    pub fn iter_with<T: Component>(&self) -> Iter<'_> {
+        Iter::new(&self.archetypes, &self.entities, Some(TypeId::of::<T>()))
     }
 
     #[allow(missing_docs)]
@@ -341,6 +1031,104 @@ impl World {
             .map_or(&[], |entities| entities.as_slice())
     }
 
+    /// Start keeping clones of `C` components when they are removed
+    ///
+    /// Once enabled, removing `C` from an entity (through `remove`, `despawn`, `clear` or any
+    /// other operation that takes it off the entity) clones the value before it goes away. The
+    /// clones can be read with `removed_with_values` until the next `clear_trackers`. Component
+    /// types that weren't opted into are never cloned.
+    ///
+    /// Replacing a `C` with `insert` or `insert_one` isn't a removal, as the entity still has a
+    /// `C` afterwards, so the replaced value isn't captured. This matches `removed` and
+    /// `on_remove`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// world.track_removed_values::<i32>();
+    /// let e = world.spawn((123, true));
+    /// world.despawn(e).unwrap();
+    /// assert_eq!(world.removed_with_values::<i32>(), &[(e, 123)]);
+    /// ```
+    pub fn track_removed_values<C: Component + Clone>(&mut self) {
+        self.removed_values
+            .entry(TypeId::of::<C>())
+            .or_insert_with(RemovedValues::new::<C>);
+    }
+
+    /// Call `hook` with each `T` component just after it is added to an entity
+    ///
+    /// Runs when an entity is spawned with a `T` or gains one through `insert` and the like, but
+    /// not when an existing `T` is replaced. Replaces any hook previously registered for `T`.
+    /// Component types without a hook incur no overhead. See `on_remove` for the opposite event.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// # use std::sync::{Arc, Mutex};
+    /// let added = Arc::new(Mutex::new(Vec::new()));
+    /// let mut world = World::new();
+    /// let log = added.clone();
+    /// world.on_add::<i32>(move |entity, &x| log.lock().unwrap().push((entity, x)));
+    /// let a = world.spawn((123, true));
+    /// let b = world.spawn((true,));
+    /// world.insert_one(b, 456).unwrap();
+    /// world.insert_one(b, 789).unwrap();
+    /// assert_eq!(*added.lock().unwrap(), &[(a, 123), (b, 456)]);
+    /// ```
+    pub fn on_add<T: Component>(
+        &mut self,
+        mut hook: impl FnMut(Entity, &T) + Send + Sync + 'static,
+    ) {
+        self.add_hooks.insert(
+            TypeId::of::<T>(),
+            // Only ever called with pointers to live `T`s, see `notify_added`
+            Box::new(move |entity, component| hook(entity, unsafe { &*component.cast::<T>() })),
+        );
+    }
+
+    /// Call `hook` with each `T` component just before it is removed from an entity
+    ///
+    /// Runs for every operation that drops or takes a `T`: `remove`, `despawn`, `clear` and the
+    /// like. Replaces any hook previously registered for `T`. Component types without a hook incur
+    /// no overhead.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// # use std::sync::{Arc, Mutex};
+    /// let freed = Arc::new(Mutex::new(Vec::new()));
+    /// let mut world = World::new();
+    /// let log = freed.clone();
+    /// world.on_remove::<i32>(move |entity, &x| log.lock().unwrap().push((entity, x)));
+    /// let a = world.spawn((123, true));
+    /// let b = world.spawn((456,));
+    /// world.despawn(a).unwrap();
+    /// world.remove_one::<i32>(b).unwrap();
+    /// assert_eq!(*freed.lock().unwrap(), &[(a, 123), (b, 456)]);
+    /// ```
+    pub fn on_remove<T: Component>(
+        &mut self,
+        mut hook: impl FnMut(Entity, &T) + Send + Sync + 'static,
+    ) {
+        self.remove_hooks.insert(
+            TypeId::of::<T>(),
+            // Only ever called with pointers to live `T`s, see `notify_removed`
+            Box::new(move |entity, component| hook(entity, unsafe { &*component.cast::<T>() })),
+        );
+    }
+
+    /// Clones of the `C` components removed since the last `clear_trackers`, along with the
+    /// entities they were removed from
+    ///
+    /// Always empty unless `track_removed_values::<C>` was called first.
+    pub fn removed_with_values<C: Component + Clone>(&self) -> &[(Entity, C)] {
+        self.removed_values
+            .get(&TypeId::of::<C>())
+            .map_or(&[], |values| values.values::<C>())
+    }
+
     /// Add `components` to `entity`
     ///
     /// Computational cost is proportional to the number of components `entity` has. If an entity
@@ -362,71 +1150,209 @@ impl World {
         entity: Entity,
         components: impl DynamicBundle,
     ) -> Result<(), NoSuchEntity> {
-        use std::collections::hash_map::Entry;
+        match self.try_insert(entity, components) {
+            Ok(()) => Ok(()),
+            Err(InsertError::NoSuchEntity) => Err(NoSuchEntity),
+            Err(InsertError::AllocFailed) => panic!("failed to allocate archetype storage"),
+        }
+    }
 
-        let loc = self.entities.get_mut(entity)?;
+    /// Add `components` to `entity`, reporting allocation failure instead of panicking
+    ///
+    /// Behaves like `insert`, except that storage for `entity` in its destination archetype is
+    /// allocated before anything is moved. If that allocation fails, `InsertError::AllocFailed` is
+    /// returned and `entity` is left unchanged in its original archetype, with `components`
+    /// dropped.
+    pub fn try_insert(
+        &mut self,
+        entity: Entity,
+        components: impl DynamicBundle,
+    ) -> Result<(), InsertError> {
+        let loc = self.entities.get(entity)?;
+        let (target, replaced) = self.insert_target(loc.archetype, &components.type_info());
+        if target != loc.archetype {
+            self.archetypes[target as usize]
+                .try_reserve(1)
+                .map_err(|_| InsertError::AllocFailed)?;
+        }
         unsafe {
-            // Assemble Vec<TypeInfo> for the final entity
-            let arch = &mut self.archetypes[loc.archetype as usize];
-            let mut info = arch.types().to_vec();
-            for ty in components.type_info() {
-                if let Some(ptr) = arch.get_dynamic(ty.id(), ty.layout().size(), loc.index) {
-                    ty.drop(ptr.as_ptr());
-                } else {
-                    info.push(ty);
-                }
-            }
-            info.sort();
+            self.insert_into(entity, components, target, &replaced);
+        }
+        Ok(())
+    }
 
-            // Find the archetype it'll live in
-            let elements = info.iter().map(|x| x.id()).collect::<Vec<_>>();
-            let target = match self.index.entry(elements) {
-                Entry::Occupied(x) => *x.get(),
-                Entry::Vacant(x) => {
-                    let index = self.archetypes.len() as u32;
-                    self.archetypes.push(Archetype::new(info));
-                    x.insert(index);
-                    self.archetype_generation += 1;
-                    index
+    /// Add the same set of component types to many entities
+    ///
+    /// Faster than calling `insert` repeatedly: the destination archetype of each source archetype
+    /// is only looked up once, and destination storage is reserved up front. Entities that don't
+    /// exist are skipped and returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// struct Burning;
+    /// let mut world = World::new();
+    /// let a = world.spawn((123,));
+    /// let b = world.spawn((456, "abc"));
+    /// let c = world.spawn((789,));
+    /// world.despawn(c).unwrap();
+    /// let missing = world.insert_batch(vec![a, b, c].into_iter().map(|e| (e, (Burning,))));
+    /// assert_eq!(missing, &[c]);
+    /// assert!(world.get::<Burning>(a).is_ok());
+    /// assert!(world.get::<Burning>(b).is_ok());
+    /// ```
+    pub fn insert_batch<I, B>(&mut self, iter: I) -> Vec<Entity>
+    where
+        I: IntoIterator<Item = (Entity, B)>,
+        B: Bundle,
+    {
+        let mut missing = Vec::new();
+        let mut pending = Vec::new();
+        let mut counts = HashMap::<u32, u32>::default();
+        for (entity, components) in iter {
+            match self.entities.get(entity) {
+                Ok(loc) => {
+                    *counts.entry(loc.archetype).or_insert(0) += 1;
+                    pending.push((entity, components));
                 }
-            };
+                Err(NoSuchEntity) => missing.push(entity),
+            }
+        }
 
-            if target == loc.archetype {
-                // Update components in the current archetype
-                let arch = &mut self.archetypes[loc.archetype as usize];
-                components.put(|ptr, ty, size| {
-                    arch.put_dynamic(ptr, ty, size, loc.index, false);
-                    true
-                });
-                return Ok(());
+        let info = B::static_type_info();
+        let mut targets = HashMap::<u32, (u32, Vec<TypeInfo>)>::default();
+        for (source, count) in counts {
+            let (target, replaced) = self.insert_target(source, &info);
+            if target != source {
+                self.archetypes[target as usize].reserve(count);
             }
+            targets.insert(source, (target, replaced));
+        }
 
-            // Move into a new archetype
-            let (source_arch, target_arch) = index2(
-                &mut self.archetypes,
-                loc.archetype as usize,
-                target as usize,
-            );
-            let target_index = target_arch.allocate(entity.id());
-            loc.archetype = target;
-            let old_index = mem::replace(&mut loc.index, target_index);
-            if let Some(moved) =
-                source_arch.move_to(old_index, |ptr, ty, size, is_added, is_mutated| {
-                    target_arch.put_dynamic(ptr, ty, size, target_index, false);
-                    let type_state = target_arch.get_type_state_mut(ty).unwrap();
-                    type_state.added_entities[target_index as usize] = is_added;
-                    type_state.mutated_entities[target_index as usize] = is_mutated;
-                })
-            {
-                self.entities.get_mut(Entity::from_id(moved)).unwrap().index = old_index;
+        for (entity, components) in pending {
+            // Entities listed twice have already moved once, so their source may be new
+            let source = self.entities.get(entity).unwrap().archetype;
+            if !targets.contains_key(&source) {
+                let target = self.insert_target(source, &info);
+                targets.insert(source, target);
+            }
+            let (target, ref replaced) = targets[&source];
+            unsafe {
+                self.insert_into(entity, components, target, replaced);
+            }
+        }
+        missing
+    }
+
+    /// Find or create the archetype that an entity in `source` moves to when `components` are
+    /// inserted, along with the types in `source` they replace
+    fn insert_target(&mut self, source: u32, components: &[TypeInfo]) -> (u32, Vec<TypeInfo>) {
+        use std::collections::hash_map::Entry;
+
+        // Assemble Vec<TypeInfo> for the final entity
+        let arch = &self.archetypes[source as usize];
+        let mut info = arch.types().to_vec();
+        let mut replaced = Vec::new();
+        for &ty in components {
+            if arch.has_dynamic(ty.id()) {
+                replaced.push(ty);
+            } else {
+                info.push(ty);
+            }
+        }
+        info.sort();
+
+        // Find the archetype it'll live in
+        let elements = info.iter().map(|x| x.id()).collect::<SmallVec<_>>();
+        let target = match self.index.entry(elements) {
+            Entry::Occupied(x) => *x.get(),
+            Entry::Vacant(x) => {
+                let index = self.archetypes.len() as u32;
+                self.archetypes.push(Archetype::new(info));
+                x.insert(index);
+                self.archetype_generation += 1;
+                index
             }
+        };
+        (target, replaced)
+    }
+
+    /// Move `entity` into `target` and write `components` to it, dropping the `replaced`
+    /// components it already has
+    ///
+    /// # Safety
+    ///
+    /// `entity` must exist, and `target` and `replaced` must have been computed by `insert_target`
+    /// for its current archetype and the types of `components`.
+    unsafe fn insert_into(
+        &mut self,
+        entity: Entity,
+        components: impl DynamicBundle,
+        target: u32,
+        replaced: &[TypeInfo],
+    ) {
+        // Replaced components don't count as added
+        let added = if self.add_hooks.is_empty() {
+            Vec::new()
+        } else {
+            components
+                .type_info()
+                .into_iter()
+                .filter(|ty| !replaced.contains(ty))
+                .collect::<Vec<_>>()
+        };
+        let loc = self.entities.get_mut(entity).unwrap();
+
+        // Drop components that are about to be replaced
+        let arch = &mut self.archetypes[loc.archetype as usize];
+        for ty in replaced {
+            let ptr = arch
+                .get_dynamic(ty.id(), ty.layout().size(), loc.index)
+                .unwrap();
+            ty.drop(ptr.as_ptr());
+        }
 
+        if target == loc.archetype {
+            // Update components in the current archetype
             components.put(|ptr, ty, size| {
-                target_arch.put_dynamic(ptr, ty, size, target_index, true);
+                arch.put_dynamic(ptr, ty, size, loc.index, false);
                 true
             });
+            return;
         }
-        Ok(())
+
+        // Move into a new archetype
+        let (source_arch, target_arch) = index2(
+            &mut self.archetypes,
+            loc.archetype as usize,
+            target as usize,
+        );
+        let target_index = target_arch.allocate(entity.id());
+        loc.archetype = target;
+        self.archetype_moves += 1;
+        let old_index = mem::replace(&mut loc.index, target_index);
+        if let Some(moved) =
+            source_arch.move_to(old_index, |ptr, ty, size, is_added, is_mutated| {
+                target_arch.put_dynamic(ptr, ty, size, target_index, false);
+                let type_state = target_arch.get_type_state_mut(ty).unwrap();
+                type_state.added_entities[target_index as usize] = is_added;
+                type_state.mutated_entities[target_index as usize] = is_mutated;
+            })
+        {
+            self.entities.get_mut(Entity::from_id(moved)).unwrap().index = old_index;
+        }
+
+        components.put(|ptr, ty, size| {
+            target_arch.put_dynamic(ptr, ty, size, target_index, true);
+            true
+        });
+        notify_added(
+            &mut self.add_hooks,
+            target_arch,
+            entity,
+            target_index,
+            &added,
+        );
     }
 
     /// Add `component` to `entity`
@@ -464,14 +1390,15 @@ impl World {
 
         let loc = self.entities.get_mut(entity)?;
         unsafe {
-            let removed = T::with_static_ids(|ids| ids.iter().copied().collect::<HashSet<_>>());
-            let info = self.archetypes[loc.archetype as usize]
-                .types()
-                .iter()
-                .cloned()
-                .filter(|x| !removed.contains(&x.id()))
-                .collect::<Vec<_>>();
-            let elements = info.iter().map(|x| x.id()).collect::<Vec<_>>();
+            let info = T::with_static_ids(|removed| {
+                self.archetypes[loc.archetype as usize]
+                    .types()
+                    .iter()
+                    .cloned()
+                    .filter(|x| !removed.contains(&x.id()))
+                    .collect::<Vec<_>>()
+            });
+            let elements = info.iter().map(|x| x.id()).collect::<SmallVec<_>>();
             let target = match self.index.entry(elements) {
                 Entry::Occupied(x) => *x.get(),
                 Entry::Vacant(x) => {
@@ -492,8 +1419,11 @@ impl World {
             );
             let target_index = target_arch.allocate(entity.id());
             loc.archetype = target;
+            self.archetype_moves += 1;
             loc.index = target_index;
             let removed_components = &mut self.removed_components;
+            let removed_values = &mut self.removed_values;
+            let remove_hooks = &mut self.remove_hooks;
             if let Some(moved) =
                 source_arch.move_to(old_index, |src, ty, size, is_added, is_mutated| {
                     // Only move the components present in the target archetype, i.e. the non-removed ones.
@@ -503,6 +1433,13 @@ impl World {
                         state.added_entities[target_index as usize] = is_added;
                         state.mutated_entities[target_index as usize] = is_mutated;
                     } else {
+                        // The value was read into `bundle` but its bits are still intact
+                        if let Some(hook) = remove_hooks.get_mut(&ty) {
+                            hook(entity, src);
+                        }
+                        if let Some(values) = removed_values.get_mut(&ty) {
+                            (values.capture)(values, entity, src);
+                        }
                         let removed_entities =
                             removed_components.entry(ty).or_insert_with(Vec::new);
                         removed_entities.push(entity);
@@ -522,6 +1459,155 @@ impl World {
         self.remove::<(T,)>(entity).map(|(x,)| x)
     }
 
+    /// Replace the `Old` component of `entity` with the components in `new`, returning the
+    /// removed `Old`
+    ///
+    /// Equivalent to `remove_one::<Old>` followed by `insert`, but the destination archetype is
+    /// computed once and the entity is moved only once. Components in `new` that `entity` already
+    /// has are dropped and replaced, as with `insert`. If `entity` has no `Old` component, nothing
+    /// is changed and an error is returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let e = world.spawn((123, "abc"));
+    /// assert_eq!(world.swap_component_type::<i32, _>(e, (true,)), Ok(123));
+    /// assert!(world.get::<i32>(e).is_err());
+    /// assert_eq!(*world.get::<bool>(e).unwrap(), true);
+    /// ```
+    pub fn swap_component_type<Old: Component, New: Bundle>(
+        &mut self,
+        entity: Entity,
+        new: New,
+    ) -> Result<Old, ComponentError> {
+        use std::collections::hash_map::Entry;
+
+        let loc = self.entities.get_mut(entity)?;
+        unsafe {
+            let old_id = TypeId::of::<Old>();
+            let source_arch = &self.archetypes[loc.archetype as usize];
+            if !source_arch.has_dynamic(old_id) {
+                return Err(MissingComponent::new::<Old>().into());
+            }
+
+            // Assemble Vec<TypeInfo> for the final entity
+            let mut info = source_arch
+                .types()
+                .iter()
+                .cloned()
+                .filter(|x| x.id() != old_id)
+                .collect::<Vec<_>>();
+            let mut replaced = Vec::new();
+            for ty in new.type_info() {
+                if info.contains(&ty) {
+                    replaced.push(ty);
+                } else {
+                    info.push(ty);
+                }
+            }
+            let added = if self.add_hooks.is_empty() {
+                Vec::new()
+            } else {
+                new.type_info()
+                    .into_iter()
+                    .filter(|ty| !replaced.contains(ty))
+                    .collect::<Vec<_>>()
+            };
+            info.sort();
+
+            // Find the archetype it'll live in
+            let elements = info.iter().map(|x| x.id()).collect::<SmallVec<_>>();
+            let target = match self.index.entry(elements) {
+                Entry::Occupied(x) => *x.get(),
+                Entry::Vacant(x) => {
+                    let index = self.archetypes.len() as u32;
+                    self.archetypes.push(Archetype::new(info));
+                    x.insert(index);
+                    self.archetype_generation += 1;
+                    index
+                }
+            };
+
+            // Take `Old` out and drop components that are about to be replaced. Their slots are
+            // overwritten below, either in place or after being moved.
+            let source_arch = &mut self.archetypes[loc.archetype as usize];
+            let old = source_arch
+                .get_dynamic(old_id, mem::size_of::<Old>(), loc.index)
+                .unwrap()
+                .cast::<Old>()
+                .as_ptr()
+                .read();
+            for ty in replaced {
+                let ptr = source_arch
+                    .get_dynamic(ty.id(), ty.layout().size(), loc.index)
+                    .unwrap();
+                ty.drop(ptr.as_ptr());
+            }
+
+            if target == loc.archetype {
+                // `new` contains `Old` and nothing else the entity lacks
+                new.put(|ptr, ty, size| {
+                    source_arch.put_dynamic(ptr, ty, size, loc.index, false);
+                    true
+                });
+                notify_added(&mut self.add_hooks, source_arch, entity, loc.index, &added);
+                return Ok(old);
+            }
+
+            // Move into the new archetype
+            let (source_arch, target_arch) = index2(
+                &mut self.archetypes,
+                loc.archetype as usize,
+                target as usize,
+            );
+            let target_index = target_arch.allocate(entity.id());
+            loc.archetype = target;
+            self.archetype_moves += 1;
+            let old_index = mem::replace(&mut loc.index, target_index);
+            let removed_components = &mut self.removed_components;
+            let removed_values = &mut self.removed_values;
+            let remove_hooks = &mut self.remove_hooks;
+            if let Some(moved) =
+                source_arch.move_to(old_index, |src, ty, size, is_added, is_mutated| {
+                    if let Some(dst) = target_arch.get_dynamic(ty, size, target_index) {
+                        ptr::copy_nonoverlapping(src, dst.as_ptr(), size);
+                        let state = target_arch.get_type_state_mut(ty).unwrap();
+                        state.added_entities[target_index as usize] = is_added;
+                        state.mutated_entities[target_index as usize] = is_mutated;
+                    } else {
+                        // Only `Old` is left behind; its value has already been read out, but
+                        // its bits are still intact
+                        if let Some(hook) = remove_hooks.get_mut(&ty) {
+                            hook(entity, src);
+                        }
+                        if let Some(values) = removed_values.get_mut(&ty) {
+                            (values.capture)(values, entity, src);
+                        }
+                        let removed_entities =
+                            removed_components.entry(ty).or_insert_with(Vec::new);
+                        removed_entities.push(entity);
+                    }
+                })
+            {
+                self.entities.get_mut(Entity::from_id(moved)).unwrap().index = old_index;
+            }
+
+            new.put(|ptr, ty, size| {
+                target_arch.put_dynamic(ptr, ty, size, target_index, true);
+                true
+            });
+            notify_added(
+                &mut self.add_hooks,
+                target_arch,
+                entity,
+                target_index,
+                &added,
+            );
+            Ok(old)
+        }
+    }
+
     /// Borrow the `T` component of `entity` without safety checks
     ///
     /// Should only be used as a building block for safe abstractions.
@@ -573,6 +1659,67 @@ impl World {
         self.archetypes.iter()
     }
 
+    /// Compute the archetypes that match `Q` and the components it accesses in them, so that
+    /// queries can be checked for conflicts and scheduled without testing every archetype again
+    ///
+    /// See `ArchetypeFilter` for an example.
+    pub fn archetype_filter<Q: Query>(&self) -> ArchetypeFilter<Q> {
+        ArchetypeFilter::new(self)
+    }
+
+    /// Iterate over the entities stored in the archetype at `index`, or `None` if there is no
+    /// such archetype
+    ///
+    /// Useful for tooling that walks `archetypes` explicitly. Entities are yielded in physical
+    /// storage order, which shifts as entities are swap-removed by `despawn`, `insert` or `remove`.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let a = world.spawn((123,));
+    /// let b = world.spawn((456,));
+    /// let index = world.get_entity_location(a).unwrap().archetype;
+    /// let entities = world.archetype_entities(index).unwrap().collect::<Vec<_>>();
+    /// assert_eq!(entities, &[a, b]);
+    /// assert!(world.archetype_entities(100).is_none());
+    /// ```
+    pub fn archetype_entities(
+        &self,
+        index: u32,
+    ) -> Option<impl ExactSizeIterator<Item = Entity> + '_> {
+        let archetype = self.archetypes.get(index as usize)?;
+        Some(archetype.iter_entities().map(|id| Entity::from_id(*id)))
+    }
+
+    /// Iterate over each archetype's component types and the number of entities stored in it
+    ///
+    /// Useful for diagnosing archetype fragmentation. Archetypes are yielded in the same order as
+    /// `archetypes`, including those that are currently empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// world.spawn((123,));
+    /// world.spawn((456,));
+    /// world.spawn((789, true));
+    /// let mut counts = world
+    ///     .archetype_component_counts()
+    ///     .filter(|&(_, len)| len > 0)
+    ///     .map(|(types, len)| (types.len(), len))
+    ///     .collect::<Vec<_>>();
+    /// counts.sort();
+    /// assert_eq!(counts, &[(1, 2), (2, 1)]);
+    /// ```
+    pub fn archetype_component_counts(
+        &self,
+    ) -> impl ExactSizeIterator<Item = (&'_ [TypeInfo], usize)> + '_ {
+        self.archetypes
+            .iter()
+            .map(|archetype| (archetype.types(), archetype.len() as usize))
+    }
+
     /// Returns a distinct value after `archetypes` is changed
     ///
     /// Store the current value after deriving information from `archetypes`, then check whether the
@@ -595,6 +1742,25 @@ impl World {
         ArchetypesGeneration(self.archetype_generation)
     }
 
+    /// The number of times an entity has moved from one archetype to another
+    ///
+    /// Every `insert` of a new component type or `remove` moves an entity, copying all of its
+    /// components. Useful for measuring how much of that churn a workload causes.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let e = world.spawn((123,));
+    /// world.insert_one(e, 456).unwrap();
+    /// assert_eq!(world.archetype_moves(), 0);
+    /// world.insert_one(e, true).unwrap();
+    /// assert_eq!(world.archetype_moves(), 1);
+    /// ```
+    pub fn archetype_moves(&self) -> u64 {
+        self.archetype_moves
+    }
+
     /// Retrieves the entity's current location, if it exists
     pub fn get_entity_location(&self, entity: Entity) -> Option<Location> {
         self.entities.get(entity).ok()
@@ -607,6 +1773,187 @@ impl World {
         }
 
         self.removed_components.clear();
+        for values in self.removed_values.values_mut() {
+            (values.clear)(values);
+        }
+    }
+
+    /// Clears each entity's tracker state for the `T` component only, along with the record of
+    /// removed `T` components. Trackers of other component types are left untouched, so systems
+    /// running at different cadences can each manage their own change window.
+    pub fn clear_trackers_for<T: Component>(&mut self) {
+        for archetype in self.archetypes.iter_mut() {
+            archetype.clear_trackers_for::<T>();
+        }
+
+        self.removed_components.remove(&TypeId::of::<T>());
+        if let Some(values) = self.removed_values.get_mut(&TypeId::of::<T>()) {
+            (values.clear)(values);
+        }
+    }
+
+    /// Store a singleton value of type `T`, replacing any previous one
+    ///
+    /// Resources are kept apart from entities: they aren't visited by queries and are unaffected
+    /// by `clear`, and borrowing a resource never conflicts with querying components of the same
+    /// type.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// struct Time(f64);
+    /// let mut world = World::new();
+    /// world.insert_resource(Time(0.0));
+    /// world.get_resource_mut::<Time>().unwrap().0 += 0.5;
+    /// assert_eq!(world.get_resource::<Time>().unwrap().0, 0.5);
+    /// assert_eq!(world.remove_resource::<Time>().map(|x| x.0), Some(0.5));
+    /// assert!(world.get_resource::<Time>().is_none());
+    /// ```
+    pub fn insert_resource<T: Component>(&mut self, mut value: T) {
+        let archetype = self
+            .resources
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Archetype::with_grow(vec![TypeInfo::of::<T>()], 1));
+        unsafe {
+            if archetype.is_empty() {
+                archetype.allocate(0);
+                archetype.put_dynamic(
+                    (&mut value as *mut T).cast(),
+                    TypeId::of::<T>(),
+                    mem::size_of::<T>(),
+                    0,
+                    true,
+                );
+                mem::forget(value);
+            } else {
+                *archetype.get::<T>().unwrap().as_ptr() = value;
+                archetype.get_mutated::<T>().unwrap().as_ptr().write(true);
+            }
+        }
+    }
+
+    /// Borrow the resource of type `T`, if one has been inserted
+    pub fn get_resource<T: Component>(&self) -> Option<Ref<'_, T>> {
+        let archetype = self.resources.get(&TypeId::of::<T>())?;
+        unsafe { Ref::new(archetype, 0).ok() }
+    }
+
+    /// Uniquely borrow the resource of type `T`, if one has been inserted
+    pub fn get_resource_mut<T: Component>(&self) -> Option<RefMut<'_, T>> {
+        let archetype = self.resources.get(&TypeId::of::<T>())?;
+        unsafe { RefMut::new(archetype, 0).ok() }
+    }
+
+    /// Take the resource of type `T` out of the world, if one has been inserted
+    pub fn remove_resource<T: Component>(&mut self) -> Option<T> {
+        let mut archetype = self.resources.remove(&TypeId::of::<T>())?;
+        unsafe {
+            let value = archetype.get::<T>().unwrap().as_ptr().read();
+            // Forget the moved-out value so dropping the archetype doesn't drop it again
+            archetype.move_to(0, |_, _, _, _, _| {});
+            Some(value)
+        }
+    }
+}
+
+/// Clones of removed components of a type opted into with `World::track_removed_values`
+struct RemovedValues {
+    values: Box<dyn Any + Send + Sync>,
+    capture: unsafe fn(&mut RemovedValues, Entity, *const u8),
+    clear: fn(&mut RemovedValues),
+}
+
+impl RemovedValues {
+    fn new<C: Component + Clone>() -> Self {
+        unsafe fn capture<C: Component + Clone>(
+            this: &mut RemovedValues,
+            entity: Entity,
+            component: *const u8,
+        ) {
+            let value = (*component.cast::<C>()).clone();
+            this.values_mut::<C>().push((entity, value));
+        }
+
+        fn clear<C: Component>(this: &mut RemovedValues) {
+            this.values_mut::<C>().clear();
+        }
+
+        Self {
+            values: Box::new(Vec::<(Entity, C)>::new()),
+            capture: capture::<C>,
+            clear: clear::<C>,
+        }
+    }
+
+    fn values<C: Component>(&self) -> &[(Entity, C)] {
+        self.values.downcast_ref::<Vec<(Entity, C)>>().unwrap()
+    }
+
+    fn values_mut<C: Component>(&mut self) -> &mut Vec<(Entity, C)> {
+        self.values.downcast_mut::<Vec<(Entity, C)>>().unwrap()
+    }
+}
+
+/// Callback registered with `World::on_remove`, taking a pointer to the component being removed
+type RemoveHook = Box<dyn FnMut(Entity, *const u8) + Send + Sync>;
+
+/// Callback registered with `World::on_add`, taking a pointer to the component just added
+type AddHook = Box<dyn FnMut(Entity, *const u8) + Send + Sync>;
+
+/// Run the add hooks of the `added` components of the entity at `index` in `archetype`
+fn notify_added(
+    add_hooks: &mut HashMap<TypeId, AddHook>,
+    archetype: &Archetype,
+    entity: Entity,
+    index: u32,
+    added: &[TypeInfo],
+) {
+    if add_hooks.is_empty() {
+        return;
+    }
+    for ty in added {
+        if let Some(hook) = add_hooks.get_mut(&ty.id()) {
+            unsafe {
+                let component = archetype
+                    .get_dynamic(ty.id(), ty.layout().size(), index)
+                    .unwrap()
+                    .as_ptr();
+                hook(entity, component);
+            }
+        }
+    }
+}
+
+/// Run the removal hooks of, and clone the tracked values of, the components of the entity at
+/// `index` in `archetype` before they are dropped
+fn notify_removed(
+    removed_values: &mut HashMap<TypeId, RemovedValues>,
+    remove_hooks: &mut HashMap<TypeId, RemoveHook>,
+    archetype: &Archetype,
+    entity: Entity,
+    index: u32,
+) {
+    if removed_values.is_empty() && remove_hooks.is_empty() {
+        return;
+    }
+    for ty in archetype.types() {
+        let hook = remove_hooks.get_mut(&ty.id());
+        let values = removed_values.get_mut(&ty.id());
+        if hook.is_none() && values.is_none() {
+            continue;
+        }
+        unsafe {
+            let component = archetype
+                .get_dynamic(ty.id(), ty.layout().size(), index)
+                .unwrap()
+                .as_ptr();
+            if let Some(hook) = hook {
+                hook(entity, component);
+            }
+            if let Some(values) = values {
+                (values.capture)(values, entity, component);
+            }
+        }
     }
 }
 
@@ -643,6 +1990,8 @@ pub enum ComponentError {
     NoSuchEntity,
     /// The entity did not have a requested component
     MissingComponent(MissingComponent),
+    /// The same entity was passed where distinct entities are required
+    SameEntity,
 }
 
 #[cfg(feature = "std")]
@@ -654,6 +2003,7 @@ impl fmt::Display for ComponentError {
         match *self {
             NoSuchEntity => f.write_str("no such entity"),
             MissingComponent(ref x) => x.fmt(f),
+            SameEntity => f.write_str("entities must be distinct"),
         }
     }
 }
@@ -670,6 +2020,34 @@ impl From<MissingComponent> for ComponentError {
     }
 }
 
+/// Errors that arise when inserting components with `World::try_insert`
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum InsertError {
+    /// The entity was already despawned
+    NoSuchEntity,
+    /// Storage for the entity in its new archetype could not be allocated
+    AllocFailed,
+}
+
+#[cfg(feature = "std")]
+impl Error for InsertError {}
+
+impl fmt::Display for InsertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use InsertError::*;
+        match *self {
+            NoSuchEntity => f.write_str("no such entity"),
+            AllocFailed => f.write_str("failed to allocate archetype storage"),
+        }
+    }
+}
+
+impl From<NoSuchEntity> for InsertError {
+    fn from(NoSuchEntity: NoSuchEntity) -> Self {
+        InsertError::NoSuchEntity
+    }
+}
+
 /// Types that can be components, implemented automatically for all `Send + Sync + 'static` types
 ///
 /// This is just a convenient shorthand for `Send + Sync + 'static`, and never needs to be
@@ -683,15 +2061,17 @@ pub struct Iter<'a> {
     entities: &'a Entities,
     current: Option<&'a Archetype>,
     index: u32,
+    required: Option<TypeId>,
 }
 
 impl<'a> Iter<'a> {
-    fn new(archetypes: &'a [Archetype], entities: &'a Entities) -> Self {
+    fn new(archetypes: &'a [Archetype], entities: &'a Entities, required: Option<TypeId>) -> Self {
         Self {
             archetypes: archetypes.iter(),
             entities,
             current: None,
             index: 0,
+            required,
         }
     }
 }
@@ -706,7 +2086,13 @@ impl<'a> Iterator for Iter<'a> {
         loop {
             match self.current {
                 None => {
-                    self.current = Some(self.archetypes.next()?);
+                    let archetype = self.archetypes.next()?;
+                    if let Some(required) = self.required {
+                        if !archetype.has_dynamic(required) {
+                            continue;
+                        }
+                    }
+                    self.current = Some(archetype);
                     self.index = 0;
                 }
                 Some(current) => {
@@ -761,6 +2147,8 @@ where
 {
     inner: I,
     entities: &'a mut Entities,
+    entity_generator: &'a EntityGenerator,
+    add_hooks: &'a mut HashMap<TypeId, AddHook>,
     archetype_id: u32,
     archetype: &'a mut Archetype,
 }
@@ -784,13 +2172,23 @@ where
 
     fn next(&mut self) -> Option<Entity> {
         let components = self.inner.next()?;
-        let entity = Entity::new();
+        let entity = self.entity_generator.generate();
         unsafe {
             let index = self.archetype.allocate(entity.id());
-            components.put(|ptr, ty, size| {
-                self.archetype.put_dynamic(ptr, ty, size, index, true);
-                true
-            });
+            // Archetype 0 has no columns, so empty bundles have nothing to write
+            if self.archetype_id != 0 {
+                components.put(|ptr, ty, size| {
+                    self.archetype.put_dynamic(ptr, ty, size, index, true);
+                    true
+                });
+                notify_added(
+                    self.add_hooks,
+                    self.archetype,
+                    entity,
+                    index,
+                    self.archetype.types(),
+                );
+            }
             self.entities.insert(
                 entity,
                 Location {
@@ -0,0 +1,59 @@
+// modified by Bevy contributors
+
+//! Allocation failure tests live in their own binary so the failing global allocator doesn't
+//! affect other tests.
+
+use bevy_hecs::*;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    ptr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Allocations at least this large fail while `FAIL_LARGE` is set
+const LARGE: usize = 1 << 24;
+static FAIL_LARGE: AtomicBool = AtomicBool::new(false);
+
+struct FailingAllocator;
+
+unsafe impl GlobalAlloc for FailingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() >= LARGE && FAIL_LARGE.load(Ordering::SeqCst) {
+            ptr::null_mut()
+        } else {
+            System.alloc(layout)
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: FailingAllocator = FailingAllocator;
+
+/// Large enough that an archetype containing it needs a `LARGE` allocation to grow
+struct Big([u8; 1 << 18]);
+
+#[test]
+fn try_insert_alloc_failure() {
+    let mut world = World::new();
+    let e = world.spawn(("abc", 123));
+    let f = world.spawn(("def", 456));
+
+    FAIL_LARGE.store(true, Ordering::SeqCst);
+    let result = world.try_insert(e, (Big([1; 1 << 18]),));
+    FAIL_LARGE.store(false, Ordering::SeqCst);
+
+    assert_eq!(result, Err(InsertError::AllocFailed));
+    assert!(world.get::<Big>(e).is_err());
+    assert_eq!(*world.get::<&str>(e).unwrap(), "abc");
+    assert_eq!(*world.get::<i32>(e).unwrap(), 123);
+    assert_eq!(*world.get::<i32>(f).unwrap(), 456);
+    assert_eq!(world.query::<(&i32, &&str)>().iter().count(), 2);
+
+    world.try_insert(e, (Big([1; 1 << 18]),)).unwrap();
+    assert_eq!(world.get::<Big>(e).unwrap().0[0], 1);
+    assert_eq!(*world.get::<i32>(e).unwrap(), 123);
+}
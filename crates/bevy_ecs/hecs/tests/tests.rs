@@ -29,6 +29,53 @@ fn random_access() {
     assert_eq!(*world.get::<i32>(f).unwrap(), 42);
 }
 
+#[test]
+fn reserve_entity() {
+    let mut world = World::new();
+    let e = world.reserve_entity();
+    assert!(world.contains(e));
+    assert_eq!(
+        world.get::<i32>(e).err(),
+        Some(ComponentError::MissingComponent(
+            MissingComponent::new::<i32>()
+        ))
+    );
+    assert_eq!(world.iter().count(), 1);
+    world.insert(e, ("abc", 123)).unwrap();
+    assert_eq!(*world.get::<i32>(e).unwrap(), 123);
+    world.despawn(e).unwrap();
+    assert!(!world.contains(e));
+}
+
+#[test]
+fn spawn_with_id() {
+    struct Owner(Entity);
+
+    let mut world = World::new();
+    let e = world.spawn_with_id(|id| (Owner(id), 123));
+    assert_eq!(world.get::<Owner>(e).unwrap().0, e);
+    assert_eq!(*world.get::<i32>(e).unwrap(), 123);
+}
+
+#[test]
+fn typed_entity() {
+    let mut world = World::new();
+    let e = world.spawn_typed(("abc", 123));
+    assert_eq!(*world.get_typed::<&str, _>(e), "abc");
+    *world.get_typed_mut::<i32, _>(e) = 456;
+    assert_eq!(*world.get_typed::<i32, _>(e), 456);
+    assert_eq!(*world.get::<i32>(e.entity()).unwrap(), 456);
+}
+
+#[test]
+#[should_panic(expected = "invalid typed entity")]
+fn typed_entity_despawned() {
+    let mut world = World::new();
+    let e = world.spawn_typed(("abc", 123));
+    world.despawn(e.entity()).unwrap();
+    world.get_typed::<i32, _>(e);
+}
+
 #[test]
 fn despawn() {
     let mut world = World::new();
@@ -43,6 +90,67 @@ fn despawn() {
     assert_eq!(*world.get::<i32>(f).unwrap(), 456);
 }
 
+#[test]
+fn despawn_batch() {
+    let mut world = World::new();
+    let a = world.spawn(("abc", 1));
+    let b = world.spawn(("def", 2));
+    let c = world.spawn(("ghi", 3));
+    let d = world.spawn(("jkl", 4, true));
+    let e = world.spawn(("mno", 5, true));
+    world.despawn(e).unwrap();
+
+    assert_eq!(world.despawn_batch(vec![a, c, d, e, a]), 3);
+    assert!(!world.contains(a));
+    assert!(world.contains(b));
+    assert!(!world.contains(c));
+    assert!(!world.contains(d));
+    assert_eq!(*world.get::<i32>(b).unwrap(), 2);
+    assert_eq!(*world.get::<&str>(b).unwrap(), "def");
+    assert_eq!(world.query::<&i32>().iter().count(), 1);
+
+    let mut removed = world.removed::<i32>().to_vec();
+    removed.sort();
+    let mut expected = vec![a, c, d, e];
+    expected.sort();
+    assert_eq!(removed, expected);
+    assert_eq!(world.removed::<bool>().len(), 2);
+}
+
+#[test]
+fn despawn_notifying() {
+    let mut world = World::new();
+    let a = world.spawn(("abc", 123));
+    let b = world.spawn(("def", 456));
+    let c = world.spawn(("ghi", 789));
+    let freed = world.get_entity_location(a).unwrap();
+    let moved = world.despawn_notifying(a).unwrap();
+    assert_eq!(moved, Some(c));
+    let location = world.get_entity_location(c).unwrap();
+    assert_eq!(location.archetype, freed.archetype);
+    assert_eq!(location.index, freed.index);
+    assert_eq!(*world.get::<i32>(c).unwrap(), 789);
+
+    // removing the last entity of an archetype moves nothing
+    assert_eq!(world.despawn_notifying(b).unwrap(), None);
+    assert!(world.despawn_notifying(b).is_err());
+}
+
+#[test]
+fn despawn_where() {
+    let mut world = World::new();
+    let a = world.spawn(("abc", 1));
+    let b = world.spawn(("def", 2));
+    let c = world.spawn(("ghi", 3, true));
+    let d = world.spawn(("jkl",));
+    world.despawn_where::<(&i32, &&str), _>(|(&i, _)| i >= 2);
+    assert!(world.contains(a));
+    assert!(!world.contains(b));
+    assert!(!world.contains(c));
+    assert!(world.contains(d));
+    assert_eq!(world.query::<&i32>().iter().count(), 1);
+}
+
 #[test]
 fn query_all() {
     let mut world = World::new();
@@ -79,6 +187,254 @@ fn query_single_component() {
     assert!(ents.contains(&(f, 456)));
 }
 
+#[test]
+fn query_with_without() {
+    let mut world = World::new();
+    let a = world.spawn(("abc", 123, true));
+    let b = world.spawn(("def", 456));
+
+    // Filters don't borrow the filtered component, so they can run while it is uniquely borrowed
+    let mut flags = world.query::<&mut bool>();
+    let _flags = flags.iter();
+
+    let with = world
+        .query::<(Entity, &i32)>()
+        .with::<bool>()
+        .iter()
+        .map(|(e, &i)| (e, i))
+        .collect::<Vec<_>>();
+    assert_eq!(with, &[(a, 123)]);
+
+    let without = world
+        .query::<(Entity, &i32)>()
+        .without::<bool>()
+        .iter()
+        .map(|(e, &i)| (e, i))
+        .collect::<Vec<_>>();
+    assert_eq!(without, &[(b, 456)]);
+
+    assert_eq!(
+        world
+            .query::<&&str>()
+            .with::<i32>()
+            .without::<bool>()
+            .iter()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn query_mut() {
+    let mut world = World::new();
+    let a = world.spawn(("abc", 123));
+    let b = world.spawn(("def", 456, true));
+    let mut query = world.query_mut::<(&mut i32, Option<&bool>)>();
+    for (mut x, flag) in query.iter() {
+        if flag.is_some() {
+            *x += 1;
+        }
+    }
+    drop(query);
+    assert_eq!(*world.get::<i32>(a).unwrap(), 123);
+    assert_eq!(*world.get::<i32>(b).unwrap(), 457);
+    assert!(world.query::<Mutated<i32>>().iter().next().is_some());
+}
+
+#[test]
+fn query_get() {
+    struct Target(Entity);
+
+    let mut world = World::new();
+    let a = world.spawn((1,));
+    let b = world.spawn((2, Target(a)));
+    let c = world.spawn((Target(b), "no number"));
+    {
+        let mut numbers = world.query::<&mut i32>();
+        for target in world.query::<&Target>().iter() {
+            if let Some(mut number) = numbers.get(target.0) {
+                *number += 10;
+            }
+        }
+        assert!(numbers.get(c).is_none());
+    }
+    world.despawn(c).unwrap();
+    assert!(world.query::<&Target>().get(c).is_none());
+    assert_eq!(*world.get::<i32>(a).unwrap(), 11);
+    assert_eq!(*world.get::<i32>(b).unwrap(), 12);
+
+    world.clear_trackers();
+    assert!(world.query::<Mutated<i32>>().get(a).is_none());
+}
+
+#[test]
+#[should_panic]
+fn query_get_alias() {
+    let mut world = World::new();
+    let a = world.spawn((1,));
+    let mut numbers = world.query::<&i32>();
+    for _ in world.query::<&mut i32>().iter() {
+        numbers.get(a);
+    }
+}
+
+#[test]
+fn dense_index() {
+    let mut world = World::new();
+    let a = world.spawn((1, true));
+    let b = world.spawn(("abc",));
+    let c = world.spawn((2,));
+    let d = world.spawn((3, true));
+    world.spawn((false,));
+
+    let mut query = world.query::<(Entity, &i32)>();
+    let index = query.dense_index();
+    assert_eq!(index.len(), 3);
+    assert_eq!(index.index_of(b), None);
+    let mut seen = vec![None; index.len()];
+    for (i, (entity, &x)) in query.iter_indexed() {
+        assert!(seen[i].is_none());
+        assert_eq!(index.index_of(entity), Some(i));
+        assert_eq!(index.entity(i), Some(entity));
+        seen[i] = Some(x);
+    }
+    assert_eq!(seen[index.index_of(a).unwrap()], Some(1));
+    assert_eq!(seen[index.index_of(c).unwrap()], Some(2));
+    assert_eq!(seen[index.index_of(d).unwrap()], Some(3));
+    assert_eq!(index.entity(3), None);
+}
+
+#[test]
+fn archetype_filter() {
+    let mut world = World::new();
+    world.spawn((1, true));
+    world.spawn((2,));
+    world.spawn(("abc",));
+
+    let mut numbers = world.archetype_filter::<&mut i32>();
+    assert_eq!(numbers.archetypes().len(), 2);
+    assert_eq!(
+        numbers.access(),
+        &[(std::any::TypeId::of::<i32>(), Access::Write)]
+    );
+
+    // optional components are only accessed in archetypes that have them
+    let optional = world.archetype_filter::<(&i32, Option<&bool>)>();
+    assert_eq!(optional.archetypes(), numbers.archetypes());
+    assert_eq!(optional.access().len(), 2);
+
+    let strings = world.archetype_filter::<&mut &str>();
+    assert!(numbers.is_disjoint(&strings));
+    assert!(!numbers.is_disjoint(&optional));
+    assert!(world
+        .archetype_filter::<(&i32, &bool)>()
+        .is_disjoint(&optional));
+    assert!(world
+        .archetype_filter::<Without<bool, &mut i32>>()
+        .is_disjoint(&world.archetype_filter::<(&i32, &bool)>()));
+
+    let mut strings = strings;
+    assert!(!strings.update(&world));
+    world.spawn(("def", 3));
+    assert!(strings.is_stale(&world));
+    assert!(strings.update(&world));
+    assert!(
+        strings.is_disjoint(&numbers),
+        "stale filters miss new archetypes"
+    );
+    numbers.update(&world);
+    assert!(!strings.is_disjoint(&numbers));
+}
+
+#[test]
+#[should_panic]
+fn query_mut_alias() {
+    let mut world = World::new();
+    world.spawn(("abc", 123));
+    world.query_mut::<(&mut i32, &mut i32)>().iter();
+}
+
+#[test]
+#[should_panic]
+fn query_mut_alias_through_option() {
+    let mut world = World::new();
+    world.spawn(("abc", 123));
+    world.query_mut::<(&i32, Option<&mut i32>)>().iter();
+}
+
+#[test]
+fn query_sorted_by() {
+    let mut world = World::new();
+    let a = world.spawn((3, "abc"));
+    let b = world.spawn((1,));
+    let c = world.spawn((3,));
+    let d = world.spawn((2, "def"));
+    let e = world.spawn((1, "ghi"));
+
+    let mut order = vec![a];
+    world.query::<&i32>().sorted_by(&mut order, |x, y| x.cmp(y));
+    assert_eq!(order.len(), 5);
+    assert_eq!(order[2], d);
+    let mut ones = order[..2].to_vec();
+    ones.sort();
+    let mut expected = vec![b, e];
+    expected.sort();
+    assert_eq!(ones, expected);
+
+    // Ties keep the same relative order from one call to the next
+    let mut again = Vec::with_capacity(order.len());
+    world.query::<&i32>().sorted_by(&mut again, |x, y| x.cmp(y));
+    assert_eq!(again, order);
+
+    world
+        .query::<(Entity, &i32)>()
+        .with::<&str>()
+        .sorted_by(&mut order, |x, y| y.1.cmp(x.1));
+    assert_eq!(order, &[a, d, e]);
+    assert!(!order.contains(&c));
+}
+
+#[test]
+fn query_slices() {
+    let mut world = World::new();
+    let a = world.spawn(("abc", 1));
+    let b = world.spawn(("def", 2));
+    let c = world.spawn(("ghi", 3, true));
+    world.spawn((true,));
+    world.clear_trackers();
+
+    let mut slices = world
+        .query::<(Entity, &mut i32, &&str)>()
+        .iter_slices()
+        .map(|(entities, xs, names)| {
+            for x in xs.iter_mut() {
+                *x *= 10;
+            }
+            (entities.to_vec(), xs.to_vec(), names.to_vec())
+        })
+        .collect::<Vec<_>>();
+    slices.sort_by_key(|x| x.0.len());
+    assert_eq!(slices.len(), 2);
+    assert_eq!(slices[0], (vec![c], vec![30], vec!["ghi"]));
+    assert_eq!(slices[1].1.len(), 2);
+    for (i, &entity) in slices[1].0.iter().enumerate() {
+        assert_eq!(slices[1].1[i], *world.get::<i32>(entity).unwrap());
+        assert!(entity == a || entity == b);
+    }
+    assert_eq!(world.query::<Mutated<i32>>().iter().count(), 3);
+
+    let empty = world.query::<&i32>().with::<f64>().iter_slices().count();
+    assert_eq!(empty, 0);
+}
+
+#[test]
+#[should_panic]
+fn query_slices_alias() {
+    let mut world = World::new();
+    world.spawn(("abc", 123));
+    world.query::<(&mut i32, &mut i32)>().iter_slices();
+}
+
 #[test]
 fn query_missing_component() {
     let mut world = World::new();
@@ -132,6 +488,54 @@ fn build_entity() {
     assert_eq!(*world.get::<i32>(f).unwrap(), 456);
 }
 
+#[test]
+fn build_entity_remove() {
+    let mut world = World::new();
+    let mut entity = EntityBuilder::new();
+    entity.add(String::from("abc"));
+    entity.add(123);
+    assert_eq!(entity.remove::<String>().as_deref(), Some("abc"));
+    entity.add(String::from("def"));
+    let e = world.spawn(entity.build());
+    assert_eq!(*world.get::<String>(e).unwrap(), "def");
+    assert_eq!(*world.get::<i32>(e).unwrap(), 123);
+
+    entity.add(String::from("ghi"));
+    entity.clear();
+    assert!(entity.remove::<String>().is_none());
+}
+
+#[test]
+fn clone_entity() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Name(String);
+
+    let mut world = World::new();
+    world.register_cloneable::<Name>();
+    world.register_cloneable::<i32>();
+    let a = world.spawn((Name("abc".to_string()), 123));
+    let b = world.spawn((Name("def".to_string()), 456, true));
+
+    let (c, skipped) = world.clone_entity(a).unwrap();
+    assert!(skipped.is_empty());
+    assert_ne!(a, c);
+    assert_eq!(*world.get::<Name>(c).unwrap(), Name("abc".to_string()));
+    assert_eq!(*world.get::<i32>(c).unwrap(), 123);
+    assert_eq!(
+        world.get_entity_location(a).unwrap().archetype,
+        world.get_entity_location(c).unwrap().archetype
+    );
+
+    let (d, skipped) = world.clone_entity(b).unwrap();
+    assert_eq!(skipped, &[std::any::TypeId::of::<bool>()]);
+    assert_eq!(*world.get::<Name>(d).unwrap(), Name("def".to_string()));
+    assert!(world.get::<bool>(d).is_err());
+
+    world.despawn(a).unwrap();
+    assert!(world.clone_entity(a).is_err());
+    assert_eq!(*world.get::<Name>(c).unwrap(), Name("abc".to_string()));
+}
+
 #[test]
 fn dynamic_components() {
     let mut world = World::new();
@@ -241,6 +645,94 @@ fn bad_bundle_derive() {
     world.spawn(Foo { x: 42, y: 42 });
 }
 
+#[test]
+#[cfg(feature = "macros")]
+fn nested_bundle_derive() {
+    #[derive(Bundle)]
+    struct Inner {
+        x: i32,
+        y: f64,
+    }
+
+    #[derive(Bundle)]
+    struct Outer {
+        name: &'static str,
+        #[bundle]
+        inner: Inner,
+    }
+
+    let mut world = World::new();
+    let e = world.spawn(Outer {
+        name: "abc",
+        inner: Inner { x: 42, y: 1.0 },
+    });
+    assert_eq!(*world.get::<&str>(e).unwrap(), "abc");
+    assert_eq!(*world.get::<i32>(e).unwrap(), 42);
+    assert_eq!(*world.get::<f64>(e).unwrap(), 1.0);
+    assert_eq!(world.archetypes().count(), 2);
+
+    assert!(world.remove::<Outer>(e).is_ok());
+    assert!(world.get::<i32>(e).is_err());
+
+    // A missing nested component leaves everything in place
+    let e = world.spawn(("abc", 42, true));
+    assert!(world.remove::<Outer>(e).is_err());
+    assert_eq!(*world.get::<i32>(e).unwrap(), 42);
+}
+
+#[test]
+#[cfg(feature = "macros")]
+#[should_panic(expected = "each type must occur at most once")]
+fn bad_nested_bundle_derive() {
+    #[derive(Bundle)]
+    struct Inner {
+        x: i32,
+    }
+
+    #[derive(Bundle)]
+    struct Outer {
+        x: i32,
+        #[bundle]
+        inner: Inner,
+    }
+
+    let mut world = World::new();
+    world.spawn(Outer {
+        x: 42,
+        inner: Inner { x: 42 },
+    });
+}
+
+#[test]
+#[cfg(feature = "macros")]
+#[should_panic(expected = "each type must occur at most once")]
+fn overlapping_nested_bundles_derive() {
+    #[derive(Bundle)]
+    struct Position {
+        x: i32,
+    }
+
+    #[derive(Bundle)]
+    struct Body {
+        x: i32,
+        mass: f32,
+    }
+
+    #[derive(Bundle)]
+    struct Outer {
+        #[bundle]
+        position: Position,
+        #[bundle]
+        body: Body,
+    }
+
+    let mut world = World::new();
+    world.spawn(Outer {
+        position: Position { x: 1 },
+        body: Body { x: 2, mass: 1.0 },
+    });
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn spawn_many() {
@@ -272,6 +764,142 @@ fn alias() {
     let _b = q.iter().collect::<Vec<_>>();
 }
 
+#[test]
+fn swap_component_type() {
+    let mut world = World::new();
+    let e = world.spawn(("abc", 123));
+    let f = world.spawn(("def", 456));
+    let moves = world.archetype_moves();
+
+    assert_eq!(world.swap_component_type::<i32, _>(e, (1.5, true)), Ok(123));
+    assert_eq!(
+        world.archetype_moves(),
+        moves + 1,
+        "the entity should move straight to its destination archetype"
+    );
+    assert!(world.get::<i32>(e).is_err());
+    assert_eq!(*world.get::<&str>(e).unwrap(), "abc");
+    assert_eq!(*world.get::<f64>(e).unwrap(), 1.5);
+    assert_eq!(*world.get::<bool>(e).unwrap(), true);
+    assert_eq!(*world.get::<i32>(f).unwrap(), 456);
+    assert_eq!(world.removed::<i32>(), &[e]);
+
+    assert!(world.swap_component_type::<i32, _>(e, (0u8,)).is_err());
+    assert_eq!(*world.get::<f64>(e).unwrap(), 1.5);
+    assert_eq!(world.archetype_moves(), moves + 1);
+
+    // removing and then inserting moves twice
+    world.remove_one::<i32>(f).unwrap();
+    world.insert(f, (1.5, true)).unwrap();
+    assert_eq!(world.archetype_moves(), moves + 3);
+}
+
+#[test]
+fn command_buffer() {
+    let mut world = World::new();
+    let a = world.spawn(("abc", 123));
+    let b = world.spawn(("def", 456));
+
+    let mut buffer = CommandBuffer::new();
+    let c = buffer.spawn((789,));
+    buffer.insert(c, (true,));
+    buffer.remove::<(i32,)>(a);
+    buffer.despawn(b);
+    buffer.despawn(b);
+    assert_eq!(buffer.len(), 5);
+    assert!(!world.contains(c));
+
+    world.apply_commands(buffer);
+    assert_eq!(*world.get::<i32>(c).unwrap(), 789);
+    assert_eq!(*world.get::<bool>(c).unwrap(), true);
+    assert!(world.get::<i32>(a).is_err());
+    assert_eq!(*world.get::<&str>(a).unwrap(), "abc");
+    assert!(!world.contains(b));
+}
+
+#[test]
+fn resources() {
+    let mut world = World::new();
+    let e = world.spawn((123,));
+    world.insert_resource(456);
+    world.insert_resource("abc");
+
+    {
+        // Resources don't alias components of the same type
+        let mut resource = world.get_resource_mut::<i32>().unwrap();
+        for mut x in &mut world.query::<&mut i32>() {
+            *x += *resource;
+            *resource = 0;
+        }
+    }
+    assert_eq!(*world.get::<i32>(e).unwrap(), 579);
+    assert_eq!(*world.get_resource::<i32>().unwrap(), 0);
+
+    world.insert_resource(789);
+    world.clear();
+    assert_eq!(*world.get_resource::<i32>().unwrap(), 789);
+    assert_eq!(world.remove_resource::<&str>(), Some("abc"));
+    assert_eq!(world.remove_resource::<&str>(), None);
+    assert!(world.get_resource::<bool>().is_none());
+}
+
+#[test]
+fn insert_batch() {
+    let mut world = World::new();
+    let a = world.spawn(("abc", 1));
+    let b = world.spawn(("def", 2, true));
+    let c = world.spawn(("ghi", 3));
+    let d = world.spawn(("jkl",));
+    world.despawn(d).unwrap();
+
+    let missing = world.insert_batch(vec![
+        (a, (10, 1.5)),
+        (d, (0, 0.0)),
+        (b, (20, 2.5)),
+        (a, (30, 3.5)),
+    ]);
+    assert_eq!(missing, &[d]);
+    assert_eq!(*world.get::<i32>(a).unwrap(), 30);
+    assert_eq!(*world.get::<f64>(a).unwrap(), 3.5);
+    assert_eq!(*world.get::<&str>(a).unwrap(), "abc");
+    assert_eq!(*world.get::<i32>(b).unwrap(), 20);
+    assert_eq!(*world.get::<bool>(b).unwrap(), true);
+    assert_eq!(*world.get::<i32>(c).unwrap(), 3);
+    assert!(world.get::<f64>(c).is_err());
+}
+
+#[test]
+fn shrink_to_fit() {
+    let mut world = World::new();
+    let a = world.spawn(("abc", 123));
+    let b = world.spawn((true,));
+    world.spawn_batch((0..100).map(|i| (i, 0.5)));
+    world.despawn(a).unwrap();
+    let c = world.spawn((1.5, false));
+    world.clear();
+
+    let d = world.spawn((456, true));
+    let e = world.spawn(("def", 789));
+    let generation = world.archetypes_generation();
+    world.shrink_to_fit();
+    assert_ne!(world.archetypes_generation(), generation);
+    assert_eq!(world.archetypes().len(), 3);
+    assert!(world.archetypes().next().unwrap().types().is_empty());
+    assert!(world.archetypes().all(|x| x.capacity() == x.len()));
+    assert!(!world.contains(b));
+    assert!(!world.contains(c));
+    assert_eq!(*world.get::<i32>(d).unwrap(), 456);
+    assert_eq!(*world.get::<bool>(d).unwrap(), true);
+    assert_eq!(*world.get::<&str>(e).unwrap(), "def");
+
+    // Dropped archetypes are recreated on demand
+    let f = world.spawn((2.5, true));
+    assert_eq!(*world.get::<f64>(f).unwrap(), 2.5);
+    world.insert_one(e, 3.5).unwrap();
+    assert_eq!(*world.get::<f64>(e).unwrap(), 3.5);
+    assert_eq!(world.query::<&i32>().iter().count(), 2);
+}
+
 #[test]
 fn remove_missing() {
     let mut world = World::new();
@@ -319,6 +947,257 @@ fn spawn_batch() {
     assert_eq!(entities.len(), 100);
 }
 
+#[test]
+fn spawn_batch_as_entities() {
+    let mut world = World::new();
+    let a = world.spawn((1, "abc"));
+    let b = Entity::new();
+    let count = world.spawn_batch_as_entities(vec![(a, (10, true)), (b, (20, false))]);
+    assert_eq!(count, 2);
+    assert_eq!(world.iter().count(), 2);
+    // the live entity's components are replaced, not merged
+    assert!(world.get::<&str>(a).is_err());
+    assert_eq!(*world.get::<i32>(a).unwrap(), 10);
+    assert!(!*world.get::<bool>(b).unwrap());
+    assert_eq!(world.query::<(&i32, &bool)>().iter().count(), 2);
+    world.despawn(b).unwrap();
+    assert_eq!(*world.get::<i32>(a).unwrap(), 10);
+}
+
+#[test]
+fn deterministic_entity_ids() {
+    fn spawn_sequence(world: &mut World) -> Vec<Entity> {
+        let mut entities = vec![world.spawn((1, "abc")), world.reserve_entity()];
+        entities.extend(world.spawn_batch((0..10).map(|x| (x,))));
+        world.despawn(entities[0]).unwrap();
+        entities.push(world.spawn_with_id(|id| (id,)));
+        let mut buffer = CommandBuffer::with_entity_generator(world.entity_generator().clone());
+        entities.push(buffer.spawn((true,)));
+        world.apply_commands(buffer);
+        entities.push(world.spawn((2,)));
+        entities
+    }
+
+    let mut a = World::with_entity_generator(7);
+    let mut b = World::with_entity_generator(7);
+    let a_entities = spawn_sequence(&mut a);
+    assert_eq!(a_entities, spawn_sequence(&mut b));
+    // despawned IDs aren't reused
+    let mut unique = a_entities.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), a_entities.len());
+
+    // IDs from the world's own sequence that were spawned explicitly are skipped
+    let mut c = World::with_entity_generator(7);
+    c.spawn_as_entity(a_entities[1], ());
+    assert_eq!(c.spawn((3,)), a_entities[2]);
+    assert_ne!(World::with_entity_generator(8).spawn((1,)), a_entities[0]);
+
+    // sub-sequences generate the same IDs no matter how their use interleaves
+    let (a_first, a_second) = (
+        a.entity_generator().sub_sequence(),
+        a.entity_generator().sub_sequence(),
+    );
+    let (b_first, b_second) = (
+        b.entity_generator().sub_sequence(),
+        b.entity_generator().sub_sequence(),
+    );
+    let a_ids = vec![a_first.generate(), a_second.generate(), a_first.generate()];
+    let b_second_id = b_second.generate();
+    let b_ids = vec![b_first.generate(), b_second_id, b_first.generate()];
+    assert_eq!(a_ids, b_ids);
+    assert!(!a_ids.contains(&a.spawn(())));
+    assert_eq!(a.validate(a_ids[1]), EntityStatus::NeverAllocated);
+    a.spawn_as_entity(a_ids[1], ());
+    a.despawn(a_ids[1]).unwrap();
+    assert_eq!(a.validate(a_ids[1]), EntityStatus::Despawned);
+}
+
+#[test]
+fn validate_entity() {
+    let mut world = World::with_entity_generator(3);
+    let a = world.spawn((1,));
+    let b = world.spawn((2,));
+    world.despawn(a).unwrap();
+    assert_eq!(world.validate(a), EntityStatus::Despawned);
+    assert_eq!(world.validate(b), EntityStatus::Alive);
+
+    // explicitly spawned IDs from the sequence count as allocated, the ones skipped over don't
+    let mut other = World::with_entity_generator(3);
+    let ids = (0..6).map(|_| other.spawn(())).collect::<Vec<_>>();
+    assert_eq!(world.validate(ids[2]), EntityStatus::NeverAllocated);
+    world.spawn_as_entity(ids[4], (3,));
+    world.despawn(ids[4]).unwrap();
+    assert_eq!(world.validate(ids[4]), EntityStatus::Despawned);
+    assert_eq!(world.validate(ids[2]), EntityStatus::NeverAllocated);
+    assert_eq!(world.validate(ids[3]), EntityStatus::NeverAllocated);
+    assert_eq!(world.validate(ids[5]), EntityStatus::NeverAllocated);
+    assert_eq!(world.spawn(()), ids[5]);
+
+    // spawning a skipped ID later fills in the gap
+    world.spawn_as_entity(ids[2], ());
+    assert_eq!(world.validate(ids[2]), EntityStatus::Alive);
+    assert_eq!(world.validate(ids[3]), EntityStatus::NeverAllocated);
+    world.despawn(ids[2]).unwrap();
+    assert_eq!(world.validate(ids[2]), EntityStatus::Despawned);
+
+    let foreign = World::with_entity_generator(4).spawn(());
+    assert_eq!(world.validate(foreign), EntityStatus::Unknown);
+    world.spawn_as_entity(foreign, ());
+    assert_eq!(world.validate(foreign), EntityStatus::Alive);
+}
+
+#[test]
+fn spawn_batch_empty() {
+    let mut world = World::new();
+    let entities = world.spawn_batch((0..100).map(|_| ())).collect::<Vec<_>>();
+    assert_eq!(entities.len(), 100);
+    assert!(entities.iter().all(|&e| world.contains(e)));
+    assert_eq!(world.iter().count(), 100);
+    world.insert_one(entities[0], 42).unwrap();
+    assert_eq!(*world.get::<i32>(entities[0]).unwrap(), 42);
+}
+
+#[test]
+fn reserve_exact() {
+    let mut world = World::new();
+    world.reserve_exact::<(i32, bool)>(100);
+    let archetype = world.archetypes().find(|a| a.has::<i32>()).unwrap();
+    assert_eq!(archetype.capacity(), 100);
+    assert!(archetype.is_empty());
+
+    world.spawn_batch((0..100).map(|i| (i, true)));
+    let archetype = world.archetypes().find(|a| a.has::<i32>()).unwrap();
+    assert_eq!(archetype.capacity(), 100);
+    assert_eq!(archetype.len(), 100);
+}
+
+#[test]
+fn component_slice() {
+    let mut world = World::new();
+    let entities = world
+        .spawn_batch((0..10).map(|i| (i, i as u64)))
+        .collect::<Vec<_>>();
+    world.despawn(entities[3]).unwrap();
+    let archetype = world.archetypes().find(|a| a.has::<u64>()).unwrap();
+    let numbers = archetype.component_slice::<i32>().unwrap();
+    assert_eq!(numbers.len(), 9);
+    for (entity, number) in archetype.entity_ids().iter().zip(numbers.iter()) {
+        assert_eq!(*world.get::<i32>(*entity).unwrap(), *number);
+    }
+    // shared borrows of the same component can coexist
+    let numbers_again = archetype.component_slice::<i32>().unwrap();
+    assert_eq!(numbers_again.len(), 9);
+    assert!(world.get::<i32>(entities[0]).is_ok());
+    drop(numbers_again);
+    // other components can still be borrowed uniquely
+    assert!(world.get_mut::<u64>(entities[0]).is_ok());
+    drop(numbers);
+    assert!(world.get_mut::<i32>(entities[0]).is_ok());
+
+    world.reserve_exact::<(bool,)>(10);
+    let empty = world.archetypes().find(|a| a.has::<bool>()).unwrap();
+    assert!(empty.component_slice::<bool>().unwrap().is_empty());
+    assert!(empty.entity_ids().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "already borrowed uniquely")]
+fn component_slice_conflict() {
+    let mut world = World::new();
+    let a = world.spawn((1,));
+    let _number = world.get_mut::<i32>(a).unwrap();
+    let archetype = world.archetypes().find(|a| a.has::<i32>()).unwrap();
+    archetype.component_slice::<i32>();
+}
+
+#[test]
+fn memory_usage() {
+    use std::mem::size_of;
+
+    let mut world = World::new();
+    let empty = world.memory_usage();
+    world.reserve_exact::<(i32, bool)>(100);
+    let archetype = world.archetypes().find(|a| a.has::<i32>()).unwrap();
+    let columns = 100 * (size_of::<i32>() + size_of::<bool>());
+    let ids = 100 * size_of::<u128>();
+    let trackers = 2 * 2 * 100 * size_of::<bool>();
+    assert!(archetype.allocated_bytes() >= columns + ids + trackers);
+    assert_eq!(world.memory_usage(), empty + archetype.allocated_bytes());
+
+    // spawning into reserved capacity doesn't allocate
+    let reserved = world.memory_usage();
+    let entities = world
+        .spawn_batch((0..100).map(|i| (i, true)))
+        .collect::<Vec<_>>();
+    assert_eq!(world.memory_usage(), reserved);
+
+    // neither does despawning free anything, until the world is shrunk
+    for entity in entities {
+        world.despawn(entity).unwrap();
+    }
+    assert_eq!(world.memory_usage(), reserved);
+    world.shrink_to_fit();
+    assert_eq!(world.memory_usage(), empty);
+}
+
+#[test]
+fn get_mut_pair() {
+    let mut world = World::new();
+    let a = world.spawn((1,));
+    let b = world.spawn((2,));
+    let c = world.spawn((3, true));
+    let d = world.spawn((true,));
+
+    {
+        let (mut x, y) = world.get_mut_pair::<i32>(a, b).unwrap();
+        drop(y);
+        *x = 10;
+    }
+    assert_eq!(*world.get::<i32>(a).unwrap(), 10);
+    assert!(world.get_mut::<i32>(b).is_ok(), "borrow released");
+
+    {
+        let (mut x, mut y) = world.get_mut_pair::<i32>(c, b).unwrap();
+        *x += *y;
+        *y = 0;
+    }
+    assert_eq!(*world.get::<i32>(c).unwrap(), 5);
+    assert_eq!(*world.get::<i32>(b).unwrap(), 0);
+
+    assert_eq!(
+        world.get_mut_pair::<i32>(a, a).err(),
+        Some(ComponentError::SameEntity)
+    );
+    assert_eq!(
+        world.get_mut_pair::<i32>(a, d).err(),
+        Some(ComponentError::MissingComponent(
+            MissingComponent::new::<i32>()
+        ))
+    );
+    assert!(
+        world.get_mut::<i32>(a).is_ok(),
+        "failed pair releases its borrow"
+    );
+    world.despawn(b).unwrap();
+    assert_eq!(
+        world.get_mut_pair::<i32>(a, b).err(),
+        Some(ComponentError::NoSuchEntity)
+    );
+}
+
+#[test]
+#[should_panic]
+fn get_mut_pair_half_released() {
+    let mut world = World::new();
+    let a = world.spawn((1,));
+    let b = world.spawn((2,));
+    let (_x, y) = world.get_mut_pair::<i32>(a, b).unwrap();
+    drop(y);
+    world.get::<i32>(a).unwrap();
+}
+
 #[test]
 fn query_one() {
     let mut world = World::new();
@@ -336,6 +1215,173 @@ fn query_one() {
     assert!(world.query_one::<&i32>(a).is_err());
 }
 
+#[test]
+fn query_one_filters() {
+    let mut world = World::new();
+    let a = world.spawn((123, true));
+    let b = world.spawn((456,));
+    assert_eq!(
+        world.query_one::<(&i32, Option<&bool>)>(a).unwrap().get(),
+        Some((&123, Some(&true)))
+    );
+    assert_eq!(
+        world.query_one::<(&i32, Option<&bool>)>(b).unwrap().get(),
+        Some((&456, None))
+    );
+    assert_eq!(
+        world.query_one::<&i32>(a).unwrap().with::<bool>().get(),
+        Some(&123)
+    );
+    assert!(world
+        .query_one::<&i32>(b)
+        .unwrap()
+        .with::<bool>()
+        .get()
+        .is_none());
+    assert!(world
+        .query_one::<&i32>(a)
+        .unwrap()
+        .without::<bool>()
+        .get()
+        .is_none());
+    assert_eq!(
+        world.query_one::<Without<bool, &i32>>(b).unwrap().get(),
+        Some(&456)
+    );
+
+    world.clear_trackers();
+    *world.get_mut::<i32>(b).unwrap() += 1;
+    assert!(world.query_one::<Mutated<i32>>(a).unwrap().get().is_none());
+    assert_eq!(
+        world
+            .query_one::<Mutated<i32>>(b)
+            .unwrap()
+            .get()
+            .map(|x| *x),
+        Some(457)
+    );
+    // a skipped entity isn't borrowed
+    let mut query = world.query_one::<Mutated<i32>>(a).unwrap();
+    assert!(query.get().is_none());
+    *world.get_mut::<i32>(a).unwrap() += 1;
+}
+
+#[test]
+fn query_join() {
+    let mut world = World::new();
+    let a = world.spawn((123, true));
+    let b = world.spawn((456, false));
+    let c = world.spawn((789,));
+
+    let joined = world
+        .query::<(Entity, &i32)>()
+        .join::<&bool>(&world)
+        .unwrap()
+        .map(|((e, &i), b)| (e, i, b.copied()))
+        .collect::<Vec<_>>();
+    assert_eq!(joined.len(), 3);
+    assert!(joined.contains(&(a, 123, Some(true))));
+    assert!(joined.contains(&(b, 456, Some(false))));
+    assert!(joined.contains(&(c, 789, None)));
+
+    assert!(world.query::<&mut i32>().join::<&bool>(&world).is_ok());
+    assert_eq!(
+        world.query::<&mut i32>().join::<&i32>(&world).err(),
+        Some(QueryConflict)
+    );
+
+    let mut scales = World::new();
+    scales.spawn_as_entity(a, (2,));
+    scales.spawn_as_entity(c, (3,));
+    for (mut i, scale) in world.query::<&mut i32>().join::<&i32>(&scales).unwrap() {
+        *i *= scale.copied().unwrap_or(1);
+    }
+    assert_eq!(*world.get::<i32>(a).unwrap(), 246);
+    assert_eq!(*world.get::<i32>(b).unwrap(), 456);
+    assert_eq!(*world.get::<i32>(c).unwrap(), 2367);
+}
+
+#[test]
+fn removed_values() {
+    let mut world = World::new();
+    world.track_removed_values::<i32>();
+    let a = world.spawn(("abc", 1));
+    let b = world.spawn(("def", 2, true));
+    let c = world.spawn(("ghi", 3));
+
+    world.despawn(a).unwrap();
+    assert_eq!(world.remove_one::<i32>(b), Ok(2));
+    world.insert_one(c, 4).unwrap();
+    assert_eq!(world.removed_with_values::<i32>(), &[(a, 1), (b, 2)]);
+    assert!(
+        world.removed_with_values::<bool>().is_empty(),
+        "types that weren't opted into aren't captured"
+    );
+
+    world.clear_trackers();
+    assert!(world.removed_with_values::<i32>().is_empty());
+
+    world.clear();
+    assert_eq!(world.removed_with_values::<i32>(), &[(c, 4)]);
+}
+
+#[test]
+fn replaced_values_are_not_removed() {
+    let mut world = World::new();
+    world.track_removed_values::<i32>();
+    let a = world.spawn(("abc", 1));
+    let b = world.spawn(("def", 2));
+
+    // in place, and while moving to another archetype
+    world.insert_one(a, 3).unwrap();
+    world.insert(b, (4, true)).unwrap();
+    assert!(world.removed_with_values::<i32>().is_empty());
+    assert!(world.removed::<i32>().is_empty());
+
+    world.despawn(b).unwrap();
+    assert_eq!(world.removed_with_values::<i32>(), &[(b, 4)]);
+}
+
+#[test]
+fn on_remove() {
+    use std::sync::{Arc, Mutex};
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let mut world = World::new();
+    let log = seen.clone();
+    world.on_remove::<i32>(move |entity, &x| log.lock().unwrap().push((entity, x)));
+    let a = world.spawn(("abc", 1));
+    let b = world.spawn(("def", 2, true));
+    let c = world.spawn(("ghi", 3));
+    let d = world.spawn((4,));
+
+    world.despawn(a).unwrap();
+    world.remove::<(i32, bool)>(b).unwrap();
+    world.remove_one::<&str>(c).unwrap();
+    world.despawn_batch(Some(d));
+    assert_eq!(*seen.lock().unwrap(), &[(a, 1), (b, 2), (d, 4)]);
+
+    world.clear();
+    assert_eq!(*seen.lock().unwrap(), &[(a, 1), (b, 2), (d, 4), (c, 3)]);
+}
+
+#[test]
+fn on_add() {
+    use std::sync::{Arc, Mutex};
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let mut world = World::new();
+    let log = seen.clone();
+    world.on_add::<i32>(move |entity, &x| log.lock().unwrap().push((entity, x)));
+    let a = world.spawn(("abc", 1));
+    let b = world.spawn(("def",));
+    let c = world.spawn_batch(vec![(2,)]).next().unwrap();
+    world.insert(b, (3, true)).unwrap();
+    world.insert_one(a, 4).unwrap();
+    world.swap_component_type::<bool, _>(b, (false,)).unwrap();
+    assert_eq!(*seen.lock().unwrap(), &[(a, 1), (c, 2), (b, 3)]);
+}
+
 #[test]
 fn remove_tracking() {
     let mut world = World::new();
@@ -12,6 +12,7 @@ pub use world::*;
 pub mod prelude {
     pub use crate::{
         resource::{FromResources, Local, Res, ResMut, Resource, Resources},
+        schedule::{IntoSystemDescriptor, ShouldRun, State},
         system::{
             Commands, IntoForEachSystem, IntoQuerySystem, IntoThreadLocalSystem, Query, System,
         },
@@ -1,6 +1,12 @@
 mod parallel_executor;
+mod run_criterion;
 #[allow(clippy::module_inception)]
 mod schedule;
+mod state;
+mod system_descriptor;
 
 pub use parallel_executor::*;
+pub use run_criterion::*;
 pub use schedule::*;
+pub use state::*;
+pub use system_descriptor::*;
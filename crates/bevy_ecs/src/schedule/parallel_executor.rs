@@ -1,9 +1,14 @@
-use super::Schedule;
+use super::{
+    run_criterion::{run_with_criterion, ShouldRun},
+    schedule::StageOrder,
+    Schedule,
+};
 use crate::{
     resource::Resources,
     system::{ArchetypeAccess, System, ThreadLocalExecution, TypeAccess},
 };
 use bevy_hecs::{ArchetypesGeneration, World};
+use bevy_utils::HashSet;
 use crossbeam_channel::{Receiver, Sender};
 use fixedbitset::FixedBitSet;
 use parking_lot::Mutex;
@@ -16,6 +21,10 @@ use std::{ops::Range, sync::Arc};
 /// * in a given stage, systems the read archetype X cannot run before systems registered before them that write archetype X
 /// * in a given stage, systems that mutate resource Y cannot run before systems registered before them that read/write resource Y
 /// * in a given stage, systems the read resource Y cannot run before systems registered before them that write resource Y
+/// * in a given stage, systems cannot run before the systems they are explicitly ordered after (see [SystemDescriptor](super::SystemDescriptor))
+///
+/// Labeled systems that access the same data as a system they aren't explicitly ordered with are
+/// reported with a warning, as their order then only depends on the order they were added in.
 
 #[derive(Debug)]
 pub struct ParallelExecutor {
@@ -43,6 +52,7 @@ impl ParallelExecutor {
     }
 
     pub fn run(&mut self, schedule: &mut Schedule, world: &mut World, resources: &mut Resources) {
+        schedule.order_systems();
         let schedule_generation = schedule.generation();
         let schedule_changed = schedule.generation() != self.last_schedule_generation;
         if schedule_changed {
@@ -53,7 +63,19 @@ impl ParallelExecutor {
         for (stage_name, executor_stage) in schedule.stage_order.iter().zip(self.stages.iter_mut())
         {
             if let Some(stage_systems) = schedule.stages.get_mut(stage_name) {
-                executor_stage.run(world, resources, stage_systems, schedule_changed);
+                let criterion = schedule.run_criteria.get_mut(stage_name);
+                let stage_order = schedule.stage_orders.get(stage_name);
+                let mut schedule_changed = schedule_changed;
+                run_with_criterion(criterion, world, resources, |world, resources| {
+                    executor_stage.run(
+                        world,
+                        resources,
+                        stage_systems,
+                        stage_order,
+                        schedule_changed,
+                    );
+                    schedule_changed = false;
+                });
             }
         }
 
@@ -77,6 +99,10 @@ pub struct ExecutorStage {
     /// the currently finished systems
     finished_systems: FixedBitSet,
     running_systems: FixedBitSet,
+    /// the systems whose run criteria skip them this run
+    skipped_systems: FixedBitSet,
+    /// the pairs of systems that were already reported as ambiguously ordered
+    reported_ambiguities: HashSet<(usize, usize)>,
 
     sender: Sender<usize>,
     receiver: Receiver<usize>,
@@ -93,6 +119,8 @@ impl Default for ExecutorStage {
             next_thread_local_index: 0,
             finished_systems: Default::default(),
             running_systems: Default::default(),
+            skipped_systems: Default::default(),
+            reported_ambiguities: Default::default(),
             sender,
             receiver,
             last_archetypes_generation: ArchetypesGeneration(u64::MAX), // MAX forces prepare to run the first time
@@ -115,6 +143,7 @@ impl ExecutorStage {
         &mut self,
         world: &World,
         systems: &[Arc<Mutex<Box<dyn System>>>],
+        stage_order: Option<&StageOrder>,
         schedule_changed: bool,
     ) {
         let (prepare_system_start_index, last_thread_local_index) =
@@ -185,6 +214,28 @@ impl ExecutorStage {
                                     self.system_dependents[earlier_system_index].push(system_index);
                                     self.system_dependencies[system_index]
                                         .insert(earlier_system_index);
+                                    if let Some(stage_order) = stage_order {
+                                        self.report_ambiguity(
+                                            stage_order,
+                                            earlier_system_index,
+                                            &**earlier_system,
+                                            system_index,
+                                            &**system,
+                                        );
+                                    }
+                                }
+                            }
+                        }
+
+                        // explicit dependencies on systems before the last thread local system are
+                        // already covered by depending on it
+                        if let Some(stage_order) = stage_order {
+                            for &dependency in stage_order.dependencies[system_index].iter() {
+                                if dependency >= prepare_system_index_range.start
+                                    && !self.system_dependencies[system_index].contains(dependency)
+                                {
+                                    self.system_dependents[dependency].push(system_index);
+                                    self.system_dependencies[system_index].insert(dependency);
                                 }
                             }
                         }
@@ -211,6 +262,33 @@ impl ExecutorStage {
         self.next_thread_local_index += 1;
     }
 
+    /// Warns about two conflicting systems whose order only depends on the order they were added in,
+    /// if either of them is labeled
+    fn report_ambiguity(
+        &mut self,
+        stage_order: &StageOrder,
+        earlier_system_index: usize,
+        earlier_system: &dyn System,
+        system_index: usize,
+        system: &dyn System,
+    ) {
+        let labeled = stage_order.labeled.contains(earlier_system_index)
+            || stage_order.labeled.contains(system_index);
+        if labeled
+            && !stage_order.ancestors[system_index].contains(earlier_system_index)
+            && self
+                .reported_ambiguities
+                .insert((earlier_system_index, system_index))
+        {
+            log::warn!(
+                "Systems {} and {} access the same data but aren't ordered relative to each other. {} runs first because it was added first.",
+                earlier_system.name(),
+                system.name(),
+                earlier_system.name(),
+            );
+        }
+    }
+
     fn run_ready_systems<'run>(
         &mut self,
         systems: &[Arc<Mutex<Box<dyn System>>>],
@@ -258,13 +336,24 @@ impl ExecutorStage {
                     }
                 }
 
+                // skipped systems finish right away, so that their dependents can run
+                if self.skipped_systems.contains(system_index) {
+                    self.running_systems.insert(system_index);
+                    self.sender.send(system_index).unwrap();
+                    continue;
+                }
+
                 // handle multi-threaded system
                 let sender = self.sender.clone();
                 self.running_systems.insert(system_index);
 
                 scope.spawn(async move {
                     let mut system = system.lock();
+                    #[cfg(feature = "profiler")]
+                    crate::profiler_start(resources, system.name().clone());
                     system.run(world, resources);
+                    #[cfg(feature = "profiler")]
+                    crate::profiler_stop(resources, system.name().clone());
                     sender.send(system_index).unwrap();
                 });
 
@@ -280,6 +369,7 @@ impl ExecutorStage {
         world: &mut World,
         resources: &mut Resources,
         systems: &[Arc<Mutex<Box<dyn System>>>],
+        stage_order: Option<&StageOrder>,
         schedule_changed: bool,
     ) {
         let start_archetypes_generation = world.archetypes_generation();
@@ -299,6 +389,8 @@ impl ExecutorStage {
 
             self.finished_systems.grow(systems.len());
             self.running_systems.grow(systems.len());
+            self.skipped_systems.grow(systems.len());
+            self.reported_ambiguities.clear();
 
             for (system_index, system) in systems.iter().enumerate() {
                 let system = system.lock();
@@ -308,8 +400,16 @@ impl ExecutorStage {
             }
         }
 
+        // run criteria are evaluated before any system runs, as they need exclusive access
+        self.skipped_systems.clear();
+        for (system_index, system) in systems.iter().enumerate() {
+            if system.lock().should_run(world, resources) == ShouldRun::No {
+                self.skipped_systems.insert(system_index);
+            }
+        }
+
         self.next_thread_local_index = 0;
-        self.prepare_to_next_thread_local(world, systems, schedule_changed);
+        self.prepare_to_next_thread_local(world, systems, stage_order, schedule_changed);
 
         self.finished_systems.clear();
         self.running_systems.clear();
@@ -344,12 +444,18 @@ impl ExecutorStage {
                 // if a thread local system is ready to run, run it exclusively on the main thread
                 let mut system = systems[thread_local_index].lock();
                 self.running_systems.insert(thread_local_index);
-                system.run(world, resources);
-                system.run_thread_local(world, resources);
+                if !self.skipped_systems.contains(thread_local_index) {
+                    #[cfg(feature = "profiler")]
+                    crate::profiler_start(resources, system.name().clone());
+                    system.run(world, resources);
+                    system.run_thread_local(world, resources);
+                    #[cfg(feature = "profiler")]
+                    crate::profiler_stop(resources, system.name().clone());
+                }
                 self.finished_systems.insert(thread_local_index);
                 self.sender.send(thread_local_index).unwrap();
 
-                self.prepare_to_next_thread_local(world, systems, schedule_changed);
+                self.prepare_to_next_thread_local(world, systems, stage_order, schedule_changed);
 
                 run_ready_result = RunReadyResult::Ok;
             } else {
@@ -381,7 +487,10 @@ impl ExecutorStage {
         }
 
         // "flush"
-        for system in systems.iter() {
+        for (system_index, system) in systems.iter().enumerate() {
+            if self.skipped_systems.contains(system_index) {
+                continue;
+            }
             let mut system = system.lock();
             match system.thread_local_execution() {
                 ThreadLocalExecution::NextFlush => system.run_thread_local(world, resources),
@@ -402,7 +511,7 @@ mod tests {
     use super::ParallelExecutor;
     use crate::{
         resource::{Res, ResMut, Resources},
-        schedule::Schedule,
+        schedule::{IntoSystemDescriptor, Schedule, ShouldRun},
         system::{IntoQuerySystem, IntoThreadLocalSystem, Query},
         Commands,
     };
@@ -448,6 +557,51 @@ mod tests {
         executor.run(&mut schedule, &mut world, &mut resources);
     }
 
+    #[test]
+    fn parallel_command_spawns_are_deterministic() {
+        fn spawn_u32(mut commands: Commands) {
+            for i in 0..100u32 {
+                commands.spawn((i,));
+            }
+        }
+
+        fn spawn_bool(mut commands: Commands) {
+            for _ in 0..100 {
+                commands.spawn((true,));
+            }
+        }
+
+        fn run(seed: u64) -> Vec<Entity> {
+            let mut world = World::with_entity_generator(seed);
+            let mut resources = Resources::default();
+            resources.insert(ComputeTaskPool(TaskPool::default()));
+
+            let mut schedule = Schedule::default();
+            schedule.add_stage("update");
+            schedule.add_system_to_stage("update", spawn_u32.system());
+            schedule.add_system_to_stage("update", spawn_bool.system());
+
+            let mut executor = ParallelExecutor::default();
+            let mut entities = Vec::new();
+            for _ in 0..3 {
+                executor.run(&mut schedule, &mut world, &mut resources);
+                entities.extend(world.query::<(Entity, &u32)>().iter().map(|(e, _)| e));
+                entities.extend(world.query::<(Entity, &bool)>().iter().map(|(e, _)| e));
+            }
+            entities.sort();
+            entities
+        }
+
+        let entities = run(5);
+        assert_eq!(entities.len(), (100 + 200 + 300) * 2);
+        for _ in 0..5 {
+            assert_eq!(run(5), entities);
+        }
+        let mut unique = entities.clone();
+        unique.dedup();
+        assert_eq!(unique.len(), 600);
+    }
+
     #[test]
     fn intra_stage_archetype_change_prepare() {
         let mut world = World::new();
@@ -478,6 +632,151 @@ mod tests {
         executor.run(&mut schedule, &mut world, &mut resources);
     }
 
+    #[test]
+    fn run_criteria() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+        resources.insert(0usize);
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("skipped");
+        schedule.add_stage("looped");
+
+        fn increment(mut count: ResMut<usize>) {
+            *count += 1;
+        }
+
+        schedule.add_system_to_stage("skipped", increment.system());
+        schedule.add_system_to_stage("looped", increment.system());
+        schedule.set_run_criterion("skipped", |_: &mut World, _: &mut Resources| ShouldRun::No);
+        let mut remaining = 3;
+        schedule.set_run_criterion("looped", move |_: &mut World, _: &mut Resources| {
+            remaining -= 1;
+            match remaining {
+                0 => ShouldRun::Yes,
+                _ => ShouldRun::YesAndLoop,
+            }
+        });
+
+        let mut executor = ParallelExecutor::default();
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(*resources.get::<usize>().unwrap(), 3);
+    }
+
+    #[derive(Default)]
+    struct Log(Arc<Mutex<Vec<&'static str>>>);
+
+    #[test]
+    fn system_ordering() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+        resources.insert(Log::default());
+
+        // the systems only read the log resource, so nothing but their labels orders them
+        fn movement(log: Res<Log>) {
+            log.0.lock().push("movement");
+        }
+        fn input(log: Res<Log>) {
+            log.0.lock().push("input");
+        }
+        fn render(log: Res<Log>) {
+            log.0.lock().push("render");
+        }
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", render.system().label("render"));
+        schedule.add_system_to_stage("update", movement.system().after("input").before("render"));
+        schedule.add_system_to_stage("update", input.system().label("input"));
+
+        let mut executor = ParallelExecutor::default();
+        for _ in 0..10 {
+            executor.run(&mut schedule, &mut world, &mut resources);
+            let log = resources.get::<Log>().unwrap();
+            assert_eq!(*log.0.lock(), vec!["input", "movement", "render"]);
+            log.0.lock().clear();
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "has a cycle")]
+    fn system_ordering_cycle() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+
+        fn a() {}
+        fn b() {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", a.system().label("a").after("b"));
+        schedule.add_system_to_stage("update", b.system().label("b").after("a"));
+
+        let mut executor = ParallelExecutor::default();
+        executor.run(&mut schedule, &mut world, &mut resources);
+    }
+
+    #[test]
+    #[should_panic(expected = "no system in stage update has it")]
+    fn system_ordering_unknown_label() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+
+        fn a() {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", a.system().after("missing"));
+
+        let mut executor = ParallelExecutor::default();
+        executor.run(&mut schedule, &mut world, &mut resources);
+    }
+
+    #[test]
+    fn system_run_criteria() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+        resources.insert(0usize);
+        resources.insert(false);
+
+        fn increment(mut count: ResMut<usize>) {
+            *count += 1;
+        }
+
+        fn unpaused(_: &mut World, resources: &mut Resources) -> ShouldRun {
+            if *resources.get::<bool>().unwrap() {
+                ShouldRun::No
+            } else {
+                ShouldRun::Yes
+            }
+        }
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage(
+            "update",
+            increment
+                .system()
+                .label("increment")
+                .with_run_criteria(unpaused),
+        );
+        // systems ordered after a skipped system still run
+        schedule.add_system_to_stage("update", increment.system().after("increment"));
+
+        let mut executor = ParallelExecutor::default();
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(*resources.get::<usize>().unwrap(), 2);
+
+        *resources.get_mut::<bool>().unwrap() = true;
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(*resources.get::<usize>().unwrap(), 3);
+    }
+
     #[test]
     fn schedule() {
         let mut world = World::new();
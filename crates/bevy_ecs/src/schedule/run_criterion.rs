@@ -0,0 +1,115 @@
+use crate::{
+    resource::Resources,
+    system::{ArchetypeAccess, System, SystemId, ThreadLocalExecution, TypeAccess},
+};
+use bevy_hecs::World;
+use std::borrow::Cow;
+
+/// Whether a stage's systems should run, as decided by its [RunCriterion]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShouldRun {
+    /// Skip the stage for the rest of this update
+    No,
+    /// Run the stage once
+    Yes,
+    /// Run the stage, then evaluate the criterion again
+    YesAndLoop,
+}
+
+/// Decides whether, and how many times, the systems of a stage run during a schedule update
+///
+/// The criterion is evaluated before each run of the stage, until it returns [ShouldRun::No] or
+/// [ShouldRun::Yes].
+pub trait RunCriterion: Send + Sync + 'static {
+    fn should_run(&mut self, world: &mut World, resources: &mut Resources) -> ShouldRun;
+}
+
+impl<F> RunCriterion for F
+where
+    F: FnMut(&mut World, &mut Resources) -> ShouldRun + Send + Sync + 'static,
+{
+    fn should_run(&mut self, world: &mut World, resources: &mut Resources) -> ShouldRun {
+        self(world, resources)
+    }
+}
+
+/// Runs `run_stage` as many times as `criterion` asks for, or once if there is no criterion
+pub(crate) fn run_with_criterion(
+    criterion: Option<&mut Box<dyn RunCriterion>>,
+    world: &mut World,
+    resources: &mut Resources,
+    mut run_stage: impl FnMut(&mut World, &mut Resources),
+) {
+    let criterion = match criterion {
+        Some(criterion) => criterion,
+        None => return run_stage(world, resources),
+    };
+    loop {
+        match criterion.should_run(world, resources) {
+            ShouldRun::No => return,
+            ShouldRun::Yes => return run_stage(world, resources),
+            ShouldRun::YesAndLoop => run_stage(world, resources),
+        }
+    }
+}
+
+/// Wraps a [System] so that it only runs when its [RunCriterion] allows it. Unlike stages, systems
+/// don't loop: [ShouldRun::YesAndLoop] runs them once.
+pub struct RunCriteriaSystem {
+    system: Box<dyn System>,
+    criterion: Box<dyn RunCriterion>,
+}
+
+impl RunCriteriaSystem {
+    pub fn new(system: Box<dyn System>, criterion: impl RunCriterion) -> Box<dyn System> {
+        Box::new(RunCriteriaSystem {
+            system,
+            criterion: Box::new(criterion),
+        })
+    }
+}
+
+impl System for RunCriteriaSystem {
+    fn name(&self) -> Cow<'static, str> {
+        self.system.name()
+    }
+
+    fn id(&self) -> SystemId {
+        self.system.id()
+    }
+
+    fn update_archetype_access(&mut self, world: &World) {
+        self.system.update_archetype_access(world);
+    }
+
+    fn archetype_access(&self) -> &ArchetypeAccess {
+        self.system.archetype_access()
+    }
+
+    fn resource_access(&self) -> &TypeAccess {
+        self.system.resource_access()
+    }
+
+    fn thread_local_execution(&self) -> ThreadLocalExecution {
+        self.system.thread_local_execution()
+    }
+
+    fn run(&mut self, world: &World, resources: &Resources) {
+        self.system.run(world, resources);
+    }
+
+    fn run_thread_local(&mut self, world: &mut World, resources: &mut Resources) {
+        self.system.run_thread_local(world, resources);
+    }
+
+    fn initialize(&mut self, resources: &mut Resources) {
+        self.system.initialize(resources);
+    }
+
+    fn should_run(&mut self, world: &mut World, resources: &mut Resources) -> ShouldRun {
+        match self.criterion.should_run(world, resources) {
+            ShouldRun::No => ShouldRun::No,
+            _ => self.system.should_run(world, resources),
+        }
+    }
+}
@@ -1,9 +1,14 @@
+use super::{
+    run_criterion::{run_with_criterion, RunCriterion, ShouldRun},
+    system_descriptor::{SystemDescriptor, SystemLabels},
+};
 use crate::{
     resource::Resources,
     system::{System, SystemId, ThreadLocalExecution},
 };
 use bevy_hecs::World;
 use bevy_utils::{HashMap, HashSet};
+use fixedbitset::FixedBitSet;
 use parking_lot::Mutex;
 use std::{borrow::Cow, sync::Arc};
 
@@ -15,8 +20,24 @@ pub struct Schedule {
     pub(crate) stages: HashMap<Cow<'static, str>, Vec<Arc<Mutex<Box<dyn System>>>>>,
     pub(crate) stage_order: Vec<Cow<'static, str>>,
     pub(crate) system_ids: HashSet<SystemId>,
+    pub(crate) run_criteria: HashMap<Cow<'static, str>, Box<dyn RunCriterion>>,
+    pub(crate) system_labels: HashMap<SystemId, SystemLabels>,
+    pub(crate) stage_orders: HashMap<Cow<'static, str>, StageOrder>,
     generation: usize,
     last_initialize_generation: usize,
+    last_order_generation: usize,
+}
+
+/// The explicit ordering of a stage's systems, built from their labels
+#[derive(Debug, Default)]
+pub(crate) struct StageOrder {
+    /// For each system, the systems that must finish before it starts. They always come earlier in
+    /// the stage.
+    pub(crate) dependencies: Vec<Vec<usize>>,
+    /// For each system, every system that is ordered before it, directly or transitively
+    pub(crate) ancestors: Vec<FixedBitSet>,
+    /// The systems that have a label or are ordered relative to one
+    pub(crate) labeled: FixedBitSet,
 }
 
 impl Schedule {
@@ -76,12 +97,27 @@ impl Schedule {
         self.stage_order.insert(target_index, stage);
     }
 
+    /// Makes `criterion` decide whether, and how many times, the given stage runs each update
+    pub fn set_run_criterion(
+        &mut self,
+        stage_name: impl Into<Cow<'static, str>>,
+        criterion: impl RunCriterion,
+    ) -> &mut Self {
+        let stage_name = stage_name.into();
+        if !self.stages.contains_key(&stage_name) {
+            panic!("Stage does not exist: {}", stage_name);
+        }
+        self.run_criteria.insert(stage_name, Box::new(criterion));
+        self
+    }
+
     pub fn add_system_to_stage(
         &mut self,
         stage_name: impl Into<Cow<'static, str>>,
-        system: Box<dyn System>,
+        system: impl Into<SystemDescriptor>,
     ) -> &mut Self {
         let stage_name = stage_name.into();
+        let SystemDescriptor { system, labels } = system.into();
         let systems = self
             .stages
             .get_mut(&stage_name)
@@ -94,6 +130,9 @@ impl Schedule {
             );
         }
         self.system_ids.insert(system.id());
+        if !labels.is_empty() {
+            self.system_labels.insert(system.id(), labels);
+        }
         systems.push(Arc::new(Mutex::new(system)));
 
         self.generation += 1;
@@ -103,9 +142,10 @@ impl Schedule {
     pub fn add_system_to_stage_front(
         &mut self,
         stage_name: impl Into<Cow<'static, str>>,
-        system: Box<dyn System>,
+        system: impl Into<SystemDescriptor>,
     ) -> &mut Self {
         let stage_name = stage_name.into();
+        let SystemDescriptor { system, labels } = system.into();
         let systems = self
             .stages
             .get_mut(&stage_name)
@@ -118,6 +158,9 @@ impl Schedule {
             );
         }
         self.system_ids.insert(system.id());
+        if !labels.is_empty() {
+            self.system_labels.insert(system.id(), labels);
+        }
         systems.insert(0, Arc::new(Mutex::new(system)));
 
         self.generation += 1;
@@ -125,40 +168,60 @@ impl Schedule {
     }
 
     pub fn run(&mut self, world: &mut World, resources: &mut Resources) {
+        self.order_systems();
         for stage_name in self.stage_order.iter() {
             if let Some(stage_systems) = self.stages.get_mut(stage_name) {
-                for system in stage_systems.iter_mut() {
-                    let mut system = system.lock();
-                    #[cfg(feature = "profiler")]
-                    crate::profiler_start(resources, system.name().clone());
-                    system.update_archetype_access(world);
-                    match system.thread_local_execution() {
-                        ThreadLocalExecution::NextFlush => system.run(world, resources),
-                        ThreadLocalExecution::Immediate => {
-                            system.run(world, resources);
-                            // NOTE: when this is made parallel a full sync is required here
-                            system.run_thread_local(world, resources);
-                        }
-                    }
-                    #[cfg(feature = "profiler")]
-                    crate::profiler_stop(resources, system.name().clone());
-                }
+                let criterion = self.run_criteria.get_mut(stage_name);
+                run_with_criterion(criterion, world, resources, |world, resources| {
+                    Self::run_stage(stage_systems, world, resources)
+                });
+            }
+        }
 
-                // "flush"
-                // NOTE: when this is made parallel a full sync is required here
-                for system in stage_systems.iter_mut() {
-                    let mut system = system.lock();
-                    match system.thread_local_execution() {
-                        ThreadLocalExecution::NextFlush => {
-                            system.run_thread_local(world, resources)
-                        }
-                        ThreadLocalExecution::Immediate => { /* already ran immediate */ }
-                    }
+        world.clear_trackers();
+    }
+
+    fn run_stage(
+        stage_systems: &mut [Arc<Mutex<Box<dyn System>>>],
+        world: &mut World,
+        resources: &mut Resources,
+    ) {
+        let skipped_systems = stage_systems
+            .iter()
+            .map(|system| system.lock().should_run(world, resources) == ShouldRun::No)
+            .collect::<Vec<_>>();
+        for (system, skipped) in stage_systems.iter_mut().zip(skipped_systems.iter()) {
+            if *skipped {
+                continue;
+            }
+            let mut system = system.lock();
+            #[cfg(feature = "profiler")]
+            crate::profiler_start(resources, system.name().clone());
+            system.update_archetype_access(world);
+            match system.thread_local_execution() {
+                ThreadLocalExecution::NextFlush => system.run(world, resources),
+                ThreadLocalExecution::Immediate => {
+                    system.run(world, resources);
+                    // NOTE: when this is made parallel a full sync is required here
+                    system.run_thread_local(world, resources);
                 }
             }
+            #[cfg(feature = "profiler")]
+            crate::profiler_stop(resources, system.name().clone());
         }
 
-        world.clear_trackers();
+        // "flush"
+        // NOTE: when this is made parallel a full sync is required here
+        for (system, skipped) in stage_systems.iter_mut().zip(skipped_systems.iter()) {
+            if *skipped {
+                continue;
+            }
+            let mut system = system.lock();
+            match system.thread_local_execution() {
+                ThreadLocalExecution::NextFlush => system.run_thread_local(world, resources),
+                ThreadLocalExecution::Immediate => { /* already ran immediate */ }
+            }
+        }
     }
 
     // TODO: move this code to ParallelExecutor
@@ -180,4 +243,138 @@ impl Schedule {
     pub fn generation(&self) -> usize {
         self.generation
     }
+
+    /// Sorts the systems of every stage so that systems come after the systems they are ordered
+    /// after, keeping the order they were added in otherwise
+    ///
+    /// # Panics
+    /// Panics if a system is ordered relative to a label no system in its stage has, or if the
+    /// ordering has a cycle.
+    pub(crate) fn order_systems(&mut self) {
+        if self.last_order_generation == self.generation {
+            return;
+        }
+
+        self.stage_orders.clear();
+        for (stage_name, systems) in self.stages.iter_mut() {
+            let order = order_stage(stage_name, systems, &self.system_labels);
+            self.stage_orders.insert(stage_name.clone(), order);
+        }
+
+        self.last_order_generation = self.generation;
+    }
+}
+
+fn order_stage(
+    stage_name: &str,
+    systems: &mut Vec<Arc<Mutex<Box<dyn System>>>>,
+    system_labels: &HashMap<SystemId, SystemLabels>,
+) -> StageOrder {
+    let empty_labels = SystemLabels::default();
+    let (names, labels): (Vec<_>, Vec<_>) = systems
+        .iter()
+        .map(|system| {
+            let system = system.lock();
+            let labels = system_labels.get(&system.id()).unwrap_or(&empty_labels);
+            (system.name(), labels)
+        })
+        .unzip();
+
+    let mut labeled_systems = HashMap::<&str, Vec<usize>>::default();
+    for (index, labels) in labels.iter().enumerate() {
+        for label in labels.labels.iter() {
+            labeled_systems
+                .entry(label.as_ref())
+                .or_default()
+                .push(index);
+        }
+    }
+    let find_labeled = |index: usize, label: &str| {
+        labeled_systems.get(label).unwrap_or_else(|| {
+            panic!(
+                "System {} is ordered relative to label \"{}\", but no system in stage {} has it",
+                names[index], label, stage_name
+            )
+        })
+    };
+
+    // edges point from a system to the systems that must run after it
+    let mut dependents = vec![Vec::new(); systems.len()];
+    for (index, labels) in labels.iter().enumerate() {
+        for label in labels.after.iter() {
+            for &earlier in find_labeled(index, label) {
+                if earlier != index {
+                    dependents[earlier].push(index);
+                }
+            }
+        }
+        for label in labels.before.iter() {
+            for &later in find_labeled(index, label) {
+                if later != index {
+                    dependents[index].push(later);
+                }
+            }
+        }
+    }
+
+    // topologically sort the systems, always picking the earliest added system that is ready so
+    // that unordered systems keep their order
+    let mut dependency_counts = vec![0; systems.len()];
+    for later in dependents.iter().flatten() {
+        dependency_counts[*later] += 1;
+    }
+    let mut sorted = Vec::with_capacity(systems.len());
+    let mut ready = (0..systems.len())
+        .filter(|index| dependency_counts[*index] == 0)
+        .collect::<Vec<_>>();
+    while let Some(index) = ready.iter().min().cloned() {
+        ready.retain(|ready_index| *ready_index != index);
+        sorted.push(index);
+        for &later in dependents[index].iter() {
+            dependency_counts[later] -= 1;
+            if dependency_counts[later] == 0 {
+                ready.push(later);
+            }
+        }
+    }
+
+    if sorted.len() < systems.len() {
+        let cycle = (0..systems.len())
+            .filter(|index| dependency_counts[*index] > 0)
+            .map(|index| names[index].clone())
+            .collect::<Vec<_>>();
+        panic!(
+            "The ordering of systems in stage {} has a cycle between: {}",
+            stage_name,
+            cycle.join(", ")
+        );
+    }
+
+    let mut sorted_indices = vec![0; systems.len()];
+    for (sorted_index, index) in sorted.iter().enumerate() {
+        sorted_indices[*index] = sorted_index;
+    }
+    let mut order = StageOrder {
+        dependencies: vec![Vec::new(); systems.len()],
+        ancestors: vec![FixedBitSet::with_capacity(systems.len()); systems.len()],
+        labeled: FixedBitSet::with_capacity(systems.len()),
+    };
+    for (index, later_systems) in dependents.iter().enumerate() {
+        if !labels[index].is_empty() {
+            order.labeled.insert(sorted_indices[index]);
+        }
+        for later in later_systems.iter() {
+            order.dependencies[sorted_indices[*later]].push(sorted_indices[index]);
+        }
+    }
+    for index in 0..systems.len() {
+        for dependency in order.dependencies[index].clone() {
+            let dependency_ancestors = order.ancestors[dependency].clone();
+            order.ancestors[index].union_with(&dependency_ancestors);
+            order.ancestors[index].insert(dependency);
+        }
+    }
+
+    *systems = sorted.iter().map(|index| systems[*index].clone()).collect();
+    order
 }
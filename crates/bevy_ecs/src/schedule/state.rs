@@ -0,0 +1,339 @@
+use super::run_criterion::{RunCriterion, ShouldRun};
+use crate::{
+    resource::Resources,
+    system::{ArchetypeAccess, System, SystemId, ThreadLocalExecution, TypeAccess},
+};
+use bevy_hecs::World;
+use std::{any::TypeId, borrow::Cow, marker::PhantomData};
+
+/// The part of a state's lifetime a [StateSystem] runs in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatePhase {
+    /// Runs once, right after the state is entered
+    Enter,
+    /// Runs once every update while the state is active
+    Update,
+    /// Runs once, right before the state is left
+    Exit,
+}
+
+/// A resource that holds the current value of a state machine, like the screen a game is on.
+///
+/// Changes are queued with [State::set] and applied by the stage driven by [StateDriver], between
+/// runs of the stage's systems. Exit systems of the old state run before the state changes and
+/// enter systems of the new state run after, so no system ever sees a half applied transition.
+#[derive(Debug)]
+pub struct State<T> {
+    current: T,
+    previous: Option<T>,
+    next: Option<T>,
+    restart: bool,
+    phase: Option<StatePhase>,
+}
+
+impl<T: Clone + Eq + Send + Sync + 'static> State<T> {
+    pub fn new(initial: T) -> Self {
+        State {
+            current: initial,
+            previous: None,
+            next: None,
+            restart: false,
+            phase: None,
+        }
+    }
+
+    /// The name of the stage that runs the systems of this state
+    pub fn stage_name() -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    pub fn current(&self) -> &T {
+        &self.current
+    }
+
+    /// The state that was active before the last transition
+    pub fn previous(&self) -> Option<&T> {
+        self.previous.as_ref()
+    }
+
+    /// The state that will be entered by the queued transition, if there is one
+    pub fn next(&self) -> Option<&T> {
+        self.next.as_ref()
+    }
+
+    /// Queues a transition to `next`, replacing any transition queued before it. Setting the state
+    /// that is already active does nothing, see [State::restart].
+    pub fn set(&mut self, next: T) {
+        self.next = Some(next);
+        self.restart = false;
+    }
+
+    /// Queues a transition from the current state to itself, which runs its exit and enter systems
+    pub fn restart(&mut self) {
+        self.next = Some(self.current.clone());
+        self.restart = true;
+    }
+
+    /// Returns whether systems of `state` should run in the given phase
+    fn is_running(&self, state: &T, phase: StatePhase) -> bool {
+        self.phase == Some(phase) && self.current == *state
+    }
+
+    /// Drops the queued transition if it would re-enter the current state without a restart, then
+    /// returns whether a transition is queued
+    fn has_transition(&mut self) -> bool {
+        if !self.restart && self.next.as_ref() == Some(&self.current) {
+            self.next = None;
+        }
+        self.next.is_some()
+    }
+
+    fn apply_transition(&mut self) {
+        if let Some(next) = self.next.take() {
+            self.previous = Some(std::mem::replace(&mut self.current, next));
+        }
+        self.restart = false;
+    }
+}
+
+/// A [RunCriterion] that runs a stage once for every phase of the [State] its systems belong to.
+///
+/// The first update enters the initial state. After that, every update runs the update systems of
+/// the current state, and queued transitions are processed (exit, then enter) until none are left.
+/// Transitions queued by update systems are processed before the update ends.
+pub struct StateDriver<T> {
+    entered: bool,
+    updated: bool,
+    marker: PhantomData<T>,
+}
+
+impl<T> Default for StateDriver<T> {
+    fn default() -> Self {
+        StateDriver {
+            entered: false,
+            updated: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone + Eq + Send + Sync + 'static> RunCriterion for StateDriver<T> {
+    fn should_run(&mut self, _world: &mut World, resources: &mut Resources) -> ShouldRun {
+        let mut state = resources.get_mut::<State<T>>().unwrap_or_else(|| {
+            panic!(
+                "StateDriver requires a {} resource",
+                std::any::type_name::<State<T>>()
+            )
+        });
+        let phase = match state.phase {
+            Some(StatePhase::Exit) => {
+                state.apply_transition();
+                Some(StatePhase::Enter)
+            }
+            _ if !self.entered => {
+                self.entered = true;
+                Some(StatePhase::Enter)
+            }
+            _ if state.has_transition() => Some(StatePhase::Exit),
+            _ if !self.updated => {
+                self.updated = true;
+                Some(StatePhase::Update)
+            }
+            _ => None,
+        };
+
+        state.phase = phase;
+        match phase {
+            Some(_) => ShouldRun::YesAndLoop,
+            None => {
+                self.updated = false;
+                ShouldRun::No
+            }
+        }
+    }
+}
+
+/// Wraps a [System] so that it only runs during one [StatePhase] of a [State] value
+pub struct StateSystem<T> {
+    system: Box<dyn System>,
+    state: T,
+    phase: StatePhase,
+    resource_access: TypeAccess,
+}
+
+impl<T: Clone + Eq + Send + Sync + 'static> StateSystem<T> {
+    pub fn new(state: T, phase: StatePhase, system: Box<dyn System>) -> Box<dyn System> {
+        let mut resource_access = system.resource_access().clone();
+        resource_access.immutable.insert(TypeId::of::<State<T>>());
+        Box::new(StateSystem {
+            system,
+            state,
+            phase,
+            resource_access,
+        })
+    }
+
+    pub fn on_enter(state: T, system: Box<dyn System>) -> Box<dyn System> {
+        Self::new(state, StatePhase::Enter, system)
+    }
+
+    pub fn on_update(state: T, system: Box<dyn System>) -> Box<dyn System> {
+        Self::new(state, StatePhase::Update, system)
+    }
+
+    pub fn on_exit(state: T, system: Box<dyn System>) -> Box<dyn System> {
+        Self::new(state, StatePhase::Exit, system)
+    }
+
+    fn should_run(&self, resources: &Resources) -> bool {
+        resources
+            .get::<State<T>>()
+            .map_or(false, |state| state.is_running(&self.state, self.phase))
+    }
+}
+
+impl<T: Clone + Eq + Send + Sync + 'static> System for StateSystem<T> {
+    fn name(&self) -> Cow<'static, str> {
+        self.system.name()
+    }
+
+    fn id(&self) -> SystemId {
+        self.system.id()
+    }
+
+    fn update_archetype_access(&mut self, world: &World) {
+        self.system.update_archetype_access(world);
+    }
+
+    fn archetype_access(&self) -> &ArchetypeAccess {
+        self.system.archetype_access()
+    }
+
+    fn resource_access(&self) -> &TypeAccess {
+        &self.resource_access
+    }
+
+    fn thread_local_execution(&self) -> ThreadLocalExecution {
+        self.system.thread_local_execution()
+    }
+
+    fn run(&mut self, world: &World, resources: &Resources) {
+        if self.should_run(resources) {
+            self.system.run(world, resources);
+        }
+    }
+
+    fn run_thread_local(&mut self, world: &mut World, resources: &mut Resources) {
+        if self.should_run(resources) {
+            self.system.run_thread_local(world, resources);
+        }
+    }
+
+    fn initialize(&mut self, resources: &mut Resources) {
+        self.system.initialize(resources);
+    }
+
+    fn should_run(&mut self, world: &mut World, resources: &mut Resources) -> ShouldRun {
+        self.system.should_run(world, resources)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{State, StateDriver, StateSystem};
+    use crate::{
+        resource::{Res, ResMut, Resources},
+        schedule::{ParallelExecutor, Schedule},
+        system::IntoQuerySystem,
+    };
+    use bevy_hecs::World;
+    use bevy_tasks::{ComputeTaskPool, TaskPool};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum AppState {
+        Menu,
+        Playing,
+    }
+
+    #[test]
+    fn state_transitions() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+        resources.insert(State::new(AppState::Menu));
+        resources.insert(Vec::<&'static str>::new());
+
+        fn enter_menu(mut log: ResMut<Vec<&'static str>>) {
+            log.push("enter menu");
+        }
+        fn update_menu(mut log: ResMut<Vec<&'static str>>, mut state: ResMut<State<AppState>>) {
+            log.push("update menu");
+            state.set(AppState::Playing);
+        }
+        fn exit_menu(mut log: ResMut<Vec<&'static str>>, state: Res<State<AppState>>) {
+            // the state only changes after every exit system has run
+            assert_eq!(*state.current(), AppState::Menu);
+            log.push("exit menu");
+        }
+        fn enter_playing(mut log: ResMut<Vec<&'static str>>) {
+            log.push("enter playing");
+        }
+        fn update_playing(mut log: ResMut<Vec<&'static str>>, mut state: ResMut<State<AppState>>) {
+            log.push("update playing");
+            // re-entering the active state does nothing
+            state.set(AppState::Playing);
+        }
+
+        let stage = State::<AppState>::stage_name();
+        let mut schedule = Schedule::default();
+        schedule.add_stage(stage);
+        schedule.set_run_criterion(stage, StateDriver::<AppState>::default());
+        schedule.add_system_to_stage(
+            stage,
+            StateSystem::on_enter(AppState::Menu, enter_menu.system()),
+        );
+        schedule.add_system_to_stage(
+            stage,
+            StateSystem::on_update(AppState::Menu, update_menu.system()),
+        );
+        schedule.add_system_to_stage(
+            stage,
+            StateSystem::on_exit(AppState::Menu, exit_menu.system()),
+        );
+        schedule.add_system_to_stage(
+            stage,
+            StateSystem::on_enter(AppState::Playing, enter_playing.system()),
+        );
+        schedule.add_system_to_stage(
+            stage,
+            StateSystem::on_update(AppState::Playing, update_playing.system()),
+        );
+
+        let mut executor = ParallelExecutor::default();
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(
+            *resources.get::<Vec<&'static str>>().unwrap(),
+            vec!["enter menu", "update menu", "exit menu", "enter playing"]
+        );
+        {
+            let state = resources.get::<State<AppState>>().unwrap();
+            assert_eq!(*state.current(), AppState::Playing);
+            assert_eq!(state.previous(), Some(&AppState::Menu));
+        }
+
+        resources.get_mut::<Vec<&'static str>>().unwrap().clear();
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(
+            *resources.get::<Vec<&'static str>>().unwrap(),
+            vec!["update playing"]
+        );
+
+        resources.get_mut::<Vec<&'static str>>().unwrap().clear();
+        resources.get_mut::<State<AppState>>().unwrap().restart();
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(
+            *resources.get::<Vec<&'static str>>().unwrap(),
+            vec!["enter playing", "update playing"]
+        );
+    }
+}
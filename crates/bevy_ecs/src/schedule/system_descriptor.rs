@@ -0,0 +1,80 @@
+use super::run_criterion::{RunCriteriaSystem, RunCriterion};
+use crate::system::System;
+use std::borrow::Cow;
+
+/// The labels of a system, and the labels of the systems it runs before and after
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SystemLabels {
+    pub(crate) labels: Vec<Cow<'static, str>>,
+    pub(crate) before: Vec<Cow<'static, str>>,
+    pub(crate) after: Vec<Cow<'static, str>>,
+}
+
+impl SystemLabels {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.before.is_empty() && self.after.is_empty()
+    }
+}
+
+/// A [System] and its place in the order of the stage it is added to
+///
+/// Systems in a stage run in the order they were added, unless they are ordered explicitly with
+/// labels:
+/// ```no_run
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::Schedule;
+/// # fn input() {}
+/// # fn movement() {}
+/// let mut schedule = Schedule::default();
+/// schedule.add_stage("update");
+/// schedule.add_system_to_stage("update", movement.system().after("input"));
+/// schedule.add_system_to_stage("update", input.system().label("input"));
+/// ```
+pub struct SystemDescriptor {
+    pub(crate) system: Box<dyn System>,
+    pub(crate) labels: SystemLabels,
+}
+
+impl From<Box<dyn System>> for SystemDescriptor {
+    fn from(system: Box<dyn System>) -> Self {
+        SystemDescriptor {
+            system,
+            labels: SystemLabels::default(),
+        }
+    }
+}
+
+/// Configures how a system is ordered within its stage, and when it runs
+pub trait IntoSystemDescriptor: Into<SystemDescriptor> {
+    /// Names the system, so that other systems in its stage can be ordered relative to it. Several
+    /// systems can share a label.
+    fn label(self, label: impl Into<Cow<'static, str>>) -> SystemDescriptor {
+        let mut descriptor = self.into();
+        descriptor.labels.labels.push(label.into());
+        descriptor
+    }
+
+    /// Makes the system finish before every system with the given label starts
+    fn before(self, label: impl Into<Cow<'static, str>>) -> SystemDescriptor {
+        let mut descriptor = self.into();
+        descriptor.labels.before.push(label.into());
+        descriptor
+    }
+
+    /// Makes every system with the given label finish before the system starts
+    fn after(self, label: impl Into<Cow<'static, str>>) -> SystemDescriptor {
+        let mut descriptor = self.into();
+        descriptor.labels.after.push(label.into());
+        descriptor
+    }
+
+    /// Skips the system whenever `criterion` returns [ShouldRun::No](super::ShouldRun::No). The
+    /// criterion is evaluated every time the system's stage runs.
+    fn with_run_criteria(self, criterion: impl RunCriterion) -> SystemDescriptor {
+        let mut descriptor = self.into();
+        descriptor.system = RunCriteriaSystem::new(descriptor.system, criterion);
+        descriptor
+    }
+}
+
+impl<T: Into<SystemDescriptor>> IntoSystemDescriptor for T {}
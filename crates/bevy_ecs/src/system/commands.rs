@@ -1,6 +1,6 @@
 use super::SystemId;
 use crate::resource::{Resource, Resources};
-use bevy_hecs::{Bundle, Component, DynamicBundle, Entity, World};
+use bevy_hecs::{Bundle, Component, DynamicBundle, Entity, EntityGenerator, World};
 use parking_lot::Mutex;
 use std::{marker::PhantomData, sync::Arc};
 
@@ -158,11 +158,21 @@ impl<T: Resource> ResourcesWriter for InsertLocalResource<T> {
 pub struct CommandsInternal {
     pub commands: Vec<Command>,
     pub current_entity: Option<Entity>,
+    /// Where the ids of spawned entities come from. Random ids are used when this isn't set.
+    pub entity_generator: Option<EntityGenerator>,
 }
 
 impl CommandsInternal {
     pub fn spawn(&mut self, components: impl DynamicBundle + Send + Sync + 'static) -> &mut Self {
-        self.spawn_as_entity(Entity::new(), components)
+        let entity = self.generate_entity();
+        self.spawn_as_entity(entity, components)
+    }
+
+    /// Picks the id of an entity that will be spawned by these commands
+    pub fn generate_entity(&self) -> Entity {
+        self.entity_generator
+            .as_ref()
+            .map_or_else(Entity::new, EntityGenerator::generate)
     }
 
     pub fn spawn_as_entity(
@@ -224,7 +234,11 @@ pub struct Commands {
 
 impl Commands {
     pub fn spawn(&mut self, components: impl DynamicBundle + Send + Sync + 'static) -> &mut Self {
-        self.spawn_as_entity(Entity::new(), components)
+        {
+            let mut commands = self.commands.lock();
+            commands.spawn(components);
+        }
+        self
     }
 
     pub fn spawn_as_entity(
@@ -239,6 +253,35 @@ impl Commands {
         self
     }
 
+    /// Takes the ids of spawned entities from `entity_generator`, usually the
+    /// [World::entity_generator] of the world the commands are applied to
+    pub fn set_entity_generator(&mut self, entity_generator: EntityGenerator) -> &mut Self {
+        self.commands.lock().entity_generator = Some(entity_generator);
+        self
+    }
+
+    /// Takes the ids of spawned entities from a new sub-sequence of `world`'s
+    /// [World::entity_generator], unless they already come from one. Systems do this when they are
+    /// first prepared, which happens in schedule order, so entities spawned by their commands get
+    /// deterministic ids in worlds created with [World::with_entity_generator] even when the
+    /// systems run in parallel.
+    pub fn use_entity_sub_sequence(&mut self, world: &World) -> &mut Self {
+        {
+            let mut commands = self.commands.lock();
+            let world_generator = world.entity_generator();
+            if !commands
+                .entity_generator
+                .as_ref()
+                .map_or(false, |generator| {
+                    generator.shares_sequence_with(world_generator)
+                })
+            {
+                commands.entity_generator = Some(world_generator.sub_sequence());
+            }
+        }
+        self
+    }
+
     pub fn spawn_batch<I>(&mut self, components_iter: I) -> &mut Self
     where
         I: IntoIterator + Send + Sync + 'static,
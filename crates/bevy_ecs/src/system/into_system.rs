@@ -121,9 +121,10 @@ macro_rules! impl_into_foreach_system {
                     },
                     resource_access: <<($($resource,)*) as ResourceQuery>::Fetch as FetchResource>::access(),
                     archetype_access: ArchetypeAccess::default(),
-                    set_archetype_access: |world, archetype_access, _state| {
+                    set_archetype_access: |world, archetype_access, state| {
                         archetype_access.clear();
                         archetype_access.set_access_for_query::<($($component,)*)>(world);
+                        state.use_entity_sub_sequence(world);
                     },
                 })
             }
@@ -197,6 +198,7 @@ macro_rules! impl_into_query_system {
                     archetype_access: ArchetypeAccess::default(),
                     set_archetype_access: |world, archetype_access, state| {
                         archetype_access.clear();
+                        state.commands.use_entity_sub_sequence(world);
                         let mut i = 0;
                         let mut access: &mut ArchetypeAccess;
                         $(
@@ -415,4 +417,41 @@ mod tests {
 
         assert!(*resources.get::<bool>().unwrap(), "system ran");
     }
+
+    struct Target(Entity);
+
+    #[test]
+    fn query_borrow_get() {
+        fn follow_targets(
+            mut ran: ResMut<bool>,
+            mut target_query: Query<&Target>,
+            mut value_query: Query<&mut u32>,
+        ) {
+            let mut values = value_query.iter();
+            for target in &mut target_query.iter() {
+                // targets without a value don't match the query
+                if let Some(mut value) = values.get(target.0) {
+                    *value += 10;
+                }
+            }
+            *ran = true;
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(false);
+        let a = world.spawn((1u32,));
+        let b = world.spawn((2u32, Target(a)));
+        let c = world.spawn((Target(b),));
+        world.spawn((Target(c),));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", follow_targets.system());
+        schedule.run(&mut world, &mut resources);
+
+        assert!(*resources.get::<bool>().unwrap(), "system ran");
+        assert_eq!(*world.get::<u32>(a).unwrap(), 11);
+        assert_eq!(*world.get::<u32>(b).unwrap(), 12);
+    }
 }
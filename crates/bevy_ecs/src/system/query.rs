@@ -33,7 +33,7 @@ impl<'a, Q: HecsQuery> Query<'a, Q> {
 
     #[inline]
     pub fn iter(&mut self) -> QueryBorrow<'_, Q> {
-        QueryBorrow::new(&self.world.archetypes, self.archetype_access)
+        QueryBorrow::new(self.world, self.archetype_access)
     }
 
     /// Gets a reference to the entity's component of the given type. This will fail if the entity does not have
@@ -116,13 +116,15 @@ impl<'a, Q: HecsQuery> Query<'a, Q> {
 ///
 /// Note that borrows are not released until this object is dropped.
 pub struct QueryBorrow<'w, Q: HecsQuery> {
+    world: &'w World,
     archetypes: &'w [Archetype],
     archetype_access: &'w ArchetypeAccess,
     _marker: PhantomData<Q>,
 }
 
 impl<'w, Q: HecsQuery> QueryBorrow<'w, Q> {
-    pub(crate) fn new(archetypes: &'w [Archetype], archetype_access: &'w ArchetypeAccess) -> Self {
+    pub(crate) fn new(world: &'w World, archetype_access: &'w ArchetypeAccess) -> Self {
+        let archetypes = &world.archetypes;
         for index in archetype_access.immutable.ones() {
             Q::Fetch::borrow(&archetypes[index]);
         }
@@ -131,12 +133,38 @@ impl<'w, Q: HecsQuery> QueryBorrow<'w, Q> {
             Q::Fetch::borrow(&archetypes[index]);
         }
         Self {
+            world,
             archetypes,
             archetype_access,
             _marker: PhantomData,
         }
     }
 
+    /// Gets the query's result for a single entity, using the archetype borrows this query already
+    /// holds. Returns `None` if the entity doesn't exist or doesn't match the query.
+    ///
+    /// The result uniquely borrows the query, so it can't alias the query's iterator or another
+    /// result of `get`.
+    pub fn get(&mut self, entity: Entity) -> Option<<Q::Fetch as Fetch<'_>>::Item> {
+        let location = self.world.get_entity_location(entity)?;
+        let archetype_index = location.archetype as usize;
+        // only the archetypes in the query's access are borrowed
+        if !self.archetype_access.immutable.contains(archetype_index)
+            && !self.archetype_access.mutable.contains(archetype_index)
+        {
+            return None;
+        }
+
+        unsafe {
+            let mut fetch =
+                Q::Fetch::get(&self.archetypes[archetype_index], location.index as usize)?;
+            if fetch.should_skip() {
+                return None;
+            }
+            Some(fetch.next())
+        }
+    }
+
     /// Execute the query
     ///
     /// Must be called only once per query.
@@ -1,4 +1,4 @@
-use crate::resource::Resources;
+use crate::{resource::Resources, schedule::ShouldRun};
 use bevy_hecs::{Access, Query, World};
 use bevy_utils::HashSet;
 use fixedbitset::FixedBitSet;
@@ -32,6 +32,11 @@ pub trait System: Send + Sync {
     fn run(&mut self, world: &World, resources: &Resources);
     fn run_thread_local(&mut self, world: &mut World, resources: &mut Resources);
     fn initialize(&mut self, _resources: &mut Resources) {}
+    /// Decides whether the system runs the next time its stage does. Stages evaluate this on the
+    /// main thread, before any of their systems start.
+    fn should_run(&mut self, _world: &mut World, _resources: &mut Resources) -> ShouldRun {
+        ShouldRun::Yes
+    }
 }
 
 /// Provides information about the archetypes a [System] reads and writes
@@ -22,7 +22,7 @@ pub struct WorldBuilder<'a> {
 
 impl<'a> WorldBuilder<'a> {
     pub fn entity(&mut self) -> &mut Self {
-        self.current_entity = Some(Entity::new());
+        self.current_entity = Some(self.world.entity_generator().generate());
         self
     }
 
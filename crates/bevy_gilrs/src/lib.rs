@@ -0,0 +1,106 @@
+mod converters;
+
+use bevy_app::prelude::*;
+use bevy_ecs::{IntoThreadLocalSystem, Resources, World};
+use bevy_input::{
+    gamepad::{
+        GamepadAxis, GamepadAxisInput, GamepadButton, GamepadButtonInput, GamepadEvent,
+        GamepadEventType,
+    },
+    keyboard::ElementState,
+};
+use converters::{convert_axis, convert_button, convert_gamepad_id};
+use gilrs::{EventType, Gilrs};
+use std::cell::RefCell;
+
+/// Adds gamepad support to an App, using gilrs as the backend
+#[derive(Default)]
+pub struct GilrsPlugin;
+
+impl Plugin for GilrsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(gilrs_startup_system.thread_local_system())
+            .add_system_to_stage(stage::FIRST, gilrs_event_system.thread_local_system());
+    }
+
+    fn dependencies(&self) -> Vec<std::any::TypeId> {
+        vec![std::any::TypeId::of::<bevy_input::InputPlugin>()]
+    }
+}
+
+thread_local! {
+    // Gilrs isn't Send on every platform, so it lives on the thread that runs thread local systems
+    static GILRS: RefCell<Option<Gilrs>> = RefCell::new(None);
+}
+
+/// Connects to the gamepad backend and sends a [GamepadEvent] for each gamepad that is already
+/// connected
+pub fn gilrs_startup_system(_world: &mut World, resources: &mut Resources) {
+    let gilrs = match Gilrs::new() {
+        Ok(gilrs) => gilrs,
+        Err(err) => {
+            log::error!("failed to initialize gamepad support: {}", err);
+            return;
+        }
+    };
+
+    let mut gamepad_events = resources.get_mut::<Events<GamepadEvent>>().unwrap();
+    for (id, _) in gilrs.gamepads() {
+        gamepad_events.send(GamepadEvent(
+            convert_gamepad_id(id),
+            GamepadEventType::Connected,
+        ));
+    }
+    GILRS.with(|cell| *cell.borrow_mut() = Some(gilrs));
+}
+
+/// Forwards the gamepad events received since the last update
+pub fn gilrs_event_system(_world: &mut World, resources: &mut Resources) {
+    GILRS.with(|cell| {
+        let mut gilrs = cell.borrow_mut();
+        let gilrs = match gilrs.as_mut() {
+            Some(gilrs) => gilrs,
+            None => return,
+        };
+
+        let mut gamepad_events = resources.get_mut::<Events<GamepadEvent>>().unwrap();
+        let mut button_events = resources.get_mut::<Events<GamepadButtonInput>>().unwrap();
+        let mut axis_events = resources.get_mut::<Events<GamepadAxisInput>>().unwrap();
+        while let Some(gilrs_event) = gilrs.next_event() {
+            let gamepad = convert_gamepad_id(gilrs_event.id);
+            match gilrs_event.event {
+                EventType::Connected => {
+                    gamepad_events.send(GamepadEvent(gamepad, GamepadEventType::Connected))
+                }
+                EventType::Disconnected => {
+                    gamepad_events.send(GamepadEvent(gamepad, GamepadEventType::Disconnected))
+                }
+                EventType::ButtonPressed(button, _) => {
+                    if let Some(button_type) = convert_button(button) {
+                        button_events.send(GamepadButtonInput {
+                            button: GamepadButton(gamepad, button_type),
+                            state: ElementState::Pressed,
+                        });
+                    }
+                }
+                EventType::ButtonReleased(button, _) => {
+                    if let Some(button_type) = convert_button(button) {
+                        button_events.send(GamepadButtonInput {
+                            button: GamepadButton(gamepad, button_type),
+                            state: ElementState::Released,
+                        });
+                    }
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    if let Some(axis_type) = convert_axis(axis) {
+                        axis_events.send(GamepadAxisInput {
+                            axis: GamepadAxis(gamepad, axis_type),
+                            value,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+}
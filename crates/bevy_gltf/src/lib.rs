@@ -13,4 +13,8 @@ impl Plugin for GltfPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_asset_loader::<Mesh, GltfLoader>();
     }
+
+    fn dependencies(&self) -> Vec<std::any::TypeId> {
+        vec![std::any::TypeId::of::<bevy_render::RenderPlugin>()]
+    }
 }
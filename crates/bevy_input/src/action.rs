@@ -0,0 +1,274 @@
+use crate::{gamepad::GamepadButton, keyboard::KeyCode, mouse::MouseButton, Input};
+use bevy_app::AppBuilder;
+use bevy_ecs::{IntoQuerySystem, Res, ResMut};
+use bevy_utils::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// A physical button that can be bound to an action
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputButton {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl From<KeyCode> for InputButton {
+    fn from(key_code: KeyCode) -> Self {
+        InputButton::Key(key_code)
+    }
+}
+
+impl From<MouseButton> for InputButton {
+    fn from(mouse_button: MouseButton) -> Self {
+        InputButton::Mouse(mouse_button)
+    }
+}
+
+impl From<GamepadButton> for InputButton {
+    fn from(gamepad_button: GamepadButton) -> Self {
+        InputButton::Gamepad(gamepad_button)
+    }
+}
+
+/// A chord of buttons that must all be held to trigger an action
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Binding {
+    buttons: Vec<InputButton>,
+}
+
+impl Binding {
+    /// A chord such as `Binding::chord(&[KeyCode::LControl.into(), KeyCode::S.into()])`
+    pub fn chord(buttons: &[InputButton]) -> Self {
+        let mut chord = Vec::with_capacity(buttons.len());
+        for button in buttons {
+            if !chord.contains(button) {
+                chord.push(*button);
+            }
+        }
+        Binding { buttons: chord }
+    }
+
+    pub fn buttons(&self) -> &[InputButton] {
+        &self.buttons
+    }
+
+    /// Whether `other` holds every button of this binding and more
+    fn is_part_of(&self, other: &Binding) -> bool {
+        self.buttons.len() < other.buttons.len()
+            && self
+                .buttons
+                .iter()
+                .all(|button| other.buttons.contains(button))
+    }
+}
+
+impl PartialEq for Binding {
+    fn eq(&self, other: &Binding) -> bool {
+        // chords are equal regardless of the order their buttons were listed in
+        self.buttons.len() == other.buttons.len()
+            && self
+                .buttons
+                .iter()
+                .all(|button| other.buttons.contains(button))
+    }
+}
+
+impl Eq for Binding {}
+
+impl<T: Into<InputButton>> From<T> for Binding {
+    fn from(button: T) -> Self {
+        Binding {
+            buttons: vec![button.into()],
+        }
+    }
+}
+
+/// The bindings of the actions `A`, which can be changed at runtime to rebind them
+///
+/// When the buttons of one binding are all part of a longer binding that is held, only the longer
+/// one triggers its action, so `Ctrl+S` doesn't also trigger an action bound to `S`.
+pub struct ActionMap<A> {
+    bindings: HashMap<A, Vec<Binding>>,
+}
+
+impl<A> Default for ActionMap<A> {
+    fn default() -> Self {
+        ActionMap {
+            bindings: HashMap::default(),
+        }
+    }
+}
+
+impl<A> ActionMap<A>
+where
+    A: Copy + Eq + Hash,
+{
+    /// Adds a binding that triggers `action`, in addition to its existing ones
+    pub fn bind(&mut self, action: A, binding: impl Into<Binding>) -> &mut Self {
+        let binding = binding.into();
+        let bindings = self.bindings.entry(action).or_insert_with(Vec::new);
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Removes every binding of `action`
+    pub fn unbind(&mut self, action: A) -> &mut Self {
+        self.bindings.remove(&action);
+        self
+    }
+
+    /// Replaces the bindings of `action`
+    pub fn rebind(&mut self, action: A, binding: impl Into<Binding>) -> &mut Self {
+        self.unbind(action).bind(action, binding)
+    }
+
+    pub fn bindings(&self, action: A) -> &[Binding] {
+        self.bindings
+            .get(&action)
+            .map(|bindings| bindings.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The actions whose bindings are held, according to `held`
+    fn held_actions(&self, held: impl Fn(InputButton) -> bool) -> HashSet<A> {
+        let held_bindings = self
+            .bindings
+            .iter()
+            .flat_map(|(action, bindings)| bindings.iter().map(move |binding| (*action, binding)))
+            .filter(|(_, binding)| binding.buttons.iter().all(|button| held(*button)))
+            .collect::<Vec<_>>();
+        held_bindings
+            .iter()
+            .filter(|(_, binding)| {
+                !held_bindings
+                    .iter()
+                    .any(|(_, other)| binding.is_part_of(other))
+            })
+            .map(|(action, _)| *action)
+            .collect()
+    }
+}
+
+/// The state of the actions `A`, updated each frame from their [ActionMap] bindings
+pub struct Actions<A> {
+    input: Input<A>,
+}
+
+impl<A> Default for Actions<A> {
+    fn default() -> Self {
+        Actions {
+            input: Input::default(),
+        }
+    }
+}
+
+impl<A> Actions<A>
+where
+    A: Copy + Eq + Hash,
+{
+    pub fn pressed(&self, action: A) -> bool {
+        self.input.pressed(action)
+    }
+
+    pub fn just_pressed(&self, action: A) -> bool {
+        self.input.just_pressed(action)
+    }
+
+    pub fn just_released(&self, action: A) -> bool {
+        self.input.just_released(action)
+    }
+
+    fn update(&mut self, held_actions: HashSet<A>) {
+        self.input.update();
+        let released = self
+            .input
+            .get_pressed()
+            .filter(|action| !held_actions.contains(*action))
+            .copied()
+            .collect::<Vec<_>>();
+        for action in released {
+            self.input.release(action);
+        }
+        for action in held_actions {
+            self.input.press(action);
+        }
+    }
+}
+
+/// Updates the Actions<A> resource from the held keys, mouse buttons and gamepad buttons
+pub fn action_system<A>(
+    action_map: Res<ActionMap<A>>,
+    mut actions: ResMut<Actions<A>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    gamepad_button_input: Res<Input<GamepadButton>>,
+) where
+    A: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    let held_actions = action_map.held_actions(|button| match button {
+        InputButton::Key(key_code) => keyboard_input.pressed(key_code),
+        InputButton::Mouse(mouse_button) => mouse_button_input.pressed(mouse_button),
+        InputButton::Gamepad(gamepad_button) => gamepad_button_input.pressed(gamepad_button),
+    });
+    actions.update(held_actions);
+}
+
+pub trait AddActions {
+    /// Adds the actions `A` with their initial bindings. Query them with the `Actions<A>` resource
+    /// and rebind them with the `ActionMap<A>` resource.
+    fn add_actions<A>(&mut self, action_map: ActionMap<A>) -> &mut Self
+    where
+        A: Copy + Eq + Hash + Send + Sync + 'static;
+}
+
+impl AddActions for AppBuilder {
+    fn add_actions<A>(&mut self, action_map: ActionMap<A>) -> &mut Self
+    where
+        A: Copy + Eq + Hash + Send + Sync + 'static,
+    {
+        // input resources are updated during EVENT_UPDATE
+        self.add_resource(action_map)
+            .init_resource::<Actions<A>>()
+            .add_system_to_stage(bevy_app::stage::PRE_UPDATE, action_system::<A>.system())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+    enum Action {
+        Jump,
+        Save,
+    }
+
+    #[test]
+    fn chords_and_rebinding() {
+        let mut action_map = ActionMap::default();
+        action_map.bind(Action::Jump, KeyCode::S).bind(
+            Action::Save,
+            Binding::chord(&[KeyCode::LControl.into(), KeyCode::S.into()]),
+        );
+        let mut actions = Actions::default();
+
+        actions.update(action_map.held_actions(|button| button == KeyCode::S.into()));
+        assert!(actions.just_pressed(Action::Jump));
+        assert!(!actions.pressed(Action::Save));
+
+        actions.update(action_map.held_actions(|button| {
+            button == KeyCode::S.into() || button == KeyCode::LControl.into()
+        }));
+        assert!(actions.just_released(Action::Jump));
+        assert!(actions.just_pressed(Action::Save));
+
+        action_map.rebind(Action::Jump, KeyCode::Space);
+        actions.update(action_map.held_actions(|button| button == KeyCode::Space.into()));
+        assert!(actions.just_pressed(Action::Jump));
+        assert!(actions.just_released(Action::Save));
+    }
+}
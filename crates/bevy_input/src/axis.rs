@@ -0,0 +1,34 @@
+use bevy_utils::HashMap;
+use std::hash::Hash;
+
+/// The current value of analog inputs of type `T`, such as gamepad sticks
+pub struct Axis<T> {
+    axis_data: HashMap<T, f32>,
+}
+
+impl<T> Default for Axis<T> {
+    fn default() -> Self {
+        Axis {
+            axis_data: HashMap::default(),
+        }
+    }
+}
+
+impl<T> Axis<T>
+where
+    T: Copy + Eq + Hash,
+{
+    /// Sets the value of `axis`, returning its previous value, if any
+    pub fn set(&mut self, axis: T, value: f32) -> Option<f32> {
+        self.axis_data.insert(axis, value)
+    }
+
+    /// The value of `axis`, or `None` if it hasn't been reported yet
+    pub fn get(&self, axis: T) -> Option<f32> {
+        self.axis_data.get(&axis).copied()
+    }
+
+    pub fn remove(&mut self, axis: T) -> Option<f32> {
+        self.axis_data.remove(&axis)
+    }
+}
@@ -0,0 +1,280 @@
+use super::keyboard::ElementState;
+use crate::{Axis, Input};
+use bevy_app::prelude::{EventReader, Events};
+use bevy_ecs::{Local, Res, ResMut};
+use bevy_utils::HashMap;
+
+/// A connected gamepad, identified by the order it was connected in
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gamepad(pub usize);
+
+/// A change in the connection state of a gamepad
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GamepadEventType {
+    Connected,
+    Disconnected,
+}
+
+/// A gamepad connection event
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GamepadEvent(pub Gamepad, pub GamepadEventType);
+
+/// A digital button on a gamepad, named after its position on the controller
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadButtonType {
+    South,
+    East,
+    North,
+    West,
+    C,
+    Z,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// A button on a specific gamepad
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct GamepadButton(pub Gamepad, pub GamepadButtonType);
+
+/// An analog stick or trigger axis on a gamepad
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadAxisType {
+    LeftStickX,
+    LeftStickY,
+    LeftZ,
+    RightStickX,
+    RightStickY,
+    RightZ,
+    DPadX,
+    DPadY,
+}
+
+/// An axis on a specific gamepad
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct GamepadAxis(pub Gamepad, pub GamepadAxisType);
+
+/// A gamepad button input event
+#[derive(Debug, Clone)]
+pub struct GamepadButtonInput {
+    pub button: GamepadButton,
+    pub state: ElementState,
+}
+
+/// A gamepad axis input event. `value` is in `-1.0..=1.0` for sticks and `0.0..=1.0` for triggers.
+#[derive(Debug, Clone)]
+pub struct GamepadAxisInput {
+    pub axis: GamepadAxis,
+    pub value: f32,
+}
+
+/// Deadzones applied to gamepad axes before they are written to `Axis<GamepadAxis>`
+///
+/// Axis values whose magnitude is below the deadzone read as `0.0`. Values outside of it are
+/// rescaled, so the axis still covers its full range.
+pub struct GamepadSettings {
+    pub default_deadzone: f32,
+    pub deadzones: HashMap<GamepadAxisType, f32>,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        GamepadSettings {
+            default_deadzone: 0.1,
+            deadzones: HashMap::default(),
+        }
+    }
+}
+
+impl GamepadSettings {
+    pub fn deadzone(&self, axis: GamepadAxisType) -> f32 {
+        self.deadzones
+            .get(&axis)
+            .copied()
+            .unwrap_or(self.default_deadzone)
+    }
+
+    pub fn apply_deadzone(&self, axis: GamepadAxisType, value: f32) -> f32 {
+        let deadzone = self.deadzone(axis);
+        if value.abs() <= deadzone {
+            0.0
+        } else {
+            value.signum() * (value.abs() - deadzone) / (1.0 - deadzone)
+        }
+    }
+}
+
+/// State used by the gamepad input systems
+#[derive(Default)]
+pub struct GamepadInputState {
+    gamepad_event_reader: EventReader<GamepadEvent>,
+    gamepad_button_input_event_reader: EventReader<GamepadButtonInput>,
+    gamepad_axis_input_event_reader: EventReader<GamepadAxisInput>,
+}
+
+/// Updates the Input<GamepadButton> and Axis<GamepadAxis> resources with the latest gamepad events
+///
+/// The buttons and axes of a disconnected gamepad are released and cleared.
+pub fn gamepad_input_system(
+    mut state: Local<GamepadInputState>,
+    mut button_input: ResMut<Input<GamepadButton>>,
+    mut axis: ResMut<Axis<GamepadAxis>>,
+    settings: Res<GamepadSettings>,
+    gamepad_events: Res<Events<GamepadEvent>>,
+    button_input_events: Res<Events<GamepadButtonInput>>,
+    axis_input_events: Res<Events<GamepadAxisInput>>,
+) {
+    button_input.update();
+    for event in state
+        .gamepad_button_input_event_reader
+        .iter(&button_input_events)
+    {
+        match event.state {
+            ElementState::Pressed => button_input.press(event.button),
+            ElementState::Released => button_input.release(event.button),
+        }
+    }
+
+    for event in state
+        .gamepad_axis_input_event_reader
+        .iter(&axis_input_events)
+    {
+        let GamepadAxis(_, axis_type) = event.axis;
+        axis.set(event.axis, settings.apply_deadzone(axis_type, event.value));
+    }
+
+    for GamepadEvent(gamepad, event_type) in state.gamepad_event_reader.iter(&gamepad_events) {
+        if let GamepadEventType::Disconnected = event_type {
+            let released = button_input
+                .get_pressed()
+                .filter(|GamepadButton(pressed_gamepad, _)| pressed_gamepad == gamepad)
+                .copied()
+                .collect::<Vec<_>>();
+            for button in released {
+                button_input.release(button);
+            }
+            for axis_type in ALL_AXIS_TYPES.iter() {
+                axis.remove(GamepadAxis(*gamepad, *axis_type));
+            }
+        }
+    }
+}
+
+const ALL_AXIS_TYPES: [GamepadAxisType; 8] = [
+    GamepadAxisType::LeftStickX,
+    GamepadAxisType::LeftStickY,
+    GamepadAxisType::LeftZ,
+    GamepadAxisType::RightStickX,
+    GamepadAxisType::RightStickY,
+    GamepadAxisType::RightZ,
+    GamepadAxisType::DPadX,
+    GamepadAxisType::DPadY,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+
+    #[test]
+    fn deadzone() {
+        let mut settings = GamepadSettings::default();
+        settings.deadzones.insert(GamepadAxisType::LeftZ, 0.5);
+
+        // inside the deadzone
+        assert_eq!(
+            settings.apply_deadzone(GamepadAxisType::LeftStickX, 0.05),
+            0.0
+        );
+        assert_eq!(
+            settings.apply_deadzone(GamepadAxisType::LeftStickX, -0.1),
+            0.0
+        );
+        assert_eq!(settings.apply_deadzone(GamepadAxisType::LeftZ, 0.4), 0.0);
+
+        // outside of it, rescaled to the full range
+        assert_eq!(
+            settings.apply_deadzone(GamepadAxisType::LeftStickX, 1.0),
+            1.0
+        );
+        assert_eq!(
+            settings.apply_deadzone(GamepadAxisType::LeftStickX, -1.0),
+            -1.0
+        );
+        assert_eq!(settings.apply_deadzone(GamepadAxisType::LeftZ, 0.75), 0.5);
+        assert_eq!(settings.apply_deadzone(GamepadAxisType::LeftZ, -0.75), -0.5);
+    }
+
+    #[test]
+    fn disconnecting_clears_gamepad_state() {
+        let mut resources = Resources::default();
+        resources.insert(Input::<GamepadButton>::default());
+        resources.insert(Axis::<GamepadAxis>::default());
+        resources.insert(GamepadSettings::default());
+        resources.insert(Events::<GamepadEvent>::default());
+        resources.insert(Events::<GamepadButtonInput>::default());
+        resources.insert(Events::<GamepadAxisInput>::default());
+
+        let mut world = World::default();
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", gamepad_input_system.system());
+        schedule.initialize(&mut resources);
+
+        let (first, second) = (Gamepad(0), Gamepad(1));
+        for gamepad in [first, second].iter() {
+            resources
+                .get_mut::<Events<GamepadButtonInput>>()
+                .unwrap()
+                .send(GamepadButtonInput {
+                    button: GamepadButton(*gamepad, GamepadButtonType::South),
+                    state: ElementState::Pressed,
+                });
+            resources
+                .get_mut::<Events<GamepadAxisInput>>()
+                .unwrap()
+                .send(GamepadAxisInput {
+                    axis: GamepadAxis(*gamepad, GamepadAxisType::LeftStickX),
+                    value: 1.0,
+                });
+        }
+        schedule.run(&mut world, &mut resources);
+
+        resources
+            .get_mut::<Events<GamepadEvent>>()
+            .unwrap()
+            .send(GamepadEvent(first, GamepadEventType::Disconnected));
+        schedule.run(&mut world, &mut resources);
+
+        let button_input = resources.get::<Input<GamepadButton>>().unwrap();
+        let axis = resources.get::<Axis<GamepadAxis>>().unwrap();
+        assert!(!button_input.pressed(GamepadButton(first, GamepadButtonType::South)));
+        assert!(button_input.just_released(GamepadButton(first, GamepadButtonType::South)));
+        assert_eq!(
+            axis.get(GamepadAxis(first, GamepadAxisType::LeftStickX)),
+            None
+        );
+
+        // other gamepads are left alone
+        assert!(button_input.pressed(GamepadButton(second, GamepadButtonType::South)));
+        assert_eq!(
+            axis.get(GamepadAxis(second, GamepadAxisType::LeftStickX)),
+            Some(1.0)
+        );
+    }
+}
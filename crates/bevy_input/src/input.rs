@@ -47,6 +47,10 @@ where
         self.just_released.contains(&input)
     }
 
+    pub fn get_pressed(&self) -> impl ExactSizeIterator<Item = &T> {
+        self.pressed.iter()
+    }
+
     pub fn update(&mut self) {
         self.just_pressed.clear();
         self.just_released.clear();
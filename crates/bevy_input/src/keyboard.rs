@@ -1,10 +1,13 @@
 use crate::Input;
 use bevy_app::prelude::*;
 use bevy_ecs::{Local, Res, ResMut};
+use bevy_window::WindowId;
 
 /// A key input event from a keyboard device
 #[derive(Debug, Clone)]
 pub struct KeyboardInput {
+    /// The window that had focus
+    pub window_id: WindowId,
     pub scan_code: u32,
     pub key_code: Option<KeyCode>,
     pub state: ElementState,
@@ -1,21 +1,41 @@
+pub mod action;
+mod axis;
+pub mod gamepad;
 mod input;
 pub mod keyboard;
 pub mod mouse;
 pub mod system;
+pub mod touch;
 
+pub use axis::*;
 pub use input::*;
 
 pub mod prelude {
-    pub use crate::{keyboard::KeyCode, mouse::MouseButton, Input};
+    pub use crate::{
+        action::{ActionMap, Actions, AddActions, Binding},
+        gamepad::{Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType},
+        keyboard::KeyCode,
+        mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseButton},
+        touch::{TouchInput, Touches},
+        Axis, Input,
+    };
 }
 
 use bevy_app::prelude::*;
+use gamepad::{
+    gamepad_input_system, GamepadAxis, GamepadAxisInput, GamepadButton, GamepadButtonInput,
+    GamepadEvent, GamepadSettings,
+};
 use keyboard::{keyboard_input_system, KeyCode, KeyboardInput};
-use mouse::{mouse_button_input_system, MouseButton, MouseButtonInput, MouseMotion, MouseWheel};
+use mouse::{
+    accumulate_mouse_system, mouse_button_input_system, AccumulatedMouseMotion,
+    AccumulatedMouseScroll, MouseButton, MouseButtonInput, MouseMotion, MouseWheel,
+};
+use touch::{touch_screen_input_system, TouchInput, Touches};
 
 use bevy_ecs::IntoQuerySystem;
 
-/// Adds keyboard and mouse input to an App
+/// Adds keyboard, mouse, gamepad and touch input to an App
 #[derive(Default)]
 pub struct InputPlugin;
 
@@ -34,6 +54,25 @@ impl Plugin for InputPlugin {
             .add_system_to_stage(
                 bevy_app::stage::EVENT_UPDATE,
                 mouse_button_input_system.system(),
+            )
+            .init_resource::<AccumulatedMouseMotion>()
+            .init_resource::<AccumulatedMouseScroll>()
+            .add_system_to_stage(
+                bevy_app::stage::EVENT_UPDATE,
+                accumulate_mouse_system.system(),
+            )
+            .add_event::<GamepadEvent>()
+            .add_event::<GamepadButtonInput>()
+            .add_event::<GamepadAxisInput>()
+            .init_resource::<GamepadSettings>()
+            .init_resource::<Input<GamepadButton>>()
+            .init_resource::<Axis<GamepadAxis>>()
+            .add_system_to_stage(bevy_app::stage::EVENT_UPDATE, gamepad_input_system.system())
+            .add_event::<TouchInput>()
+            .init_resource::<Touches>()
+            .add_system_to_stage(
+                bevy_app::stage::EVENT_UPDATE,
+                touch_screen_input_system.system(),
             );
     }
 }
@@ -3,10 +3,12 @@ use crate::Input;
 use bevy_app::prelude::{EventReader, Events};
 use bevy_ecs::{Local, Res, ResMut};
 use bevy_math::Vec2;
+use bevy_window::WindowId;
 
 /// A mouse button input event
 #[derive(Debug, Clone)]
 pub struct MouseButtonInput {
+    pub window_id: WindowId,
     pub button: MouseButton,
     pub state: ElementState,
 }
@@ -37,6 +39,7 @@ pub enum MouseScrollUnit {
 /// A mouse scroll wheel event, where x represents horizontal scroll and y represents vertical scroll.
 #[derive(Debug, Clone)]
 pub struct MouseWheel {
+    pub window_id: WindowId,
     pub unit: MouseScrollUnit,
     pub x: f32,
     pub y: f32,
@@ -65,3 +68,49 @@ pub fn mouse_button_input_system(
         }
     }
 }
+
+/// The sum of this frame's [MouseMotion] events, so deltas reported at a high rate add up rather
+/// than only the last one counting
+#[derive(Debug, Default, Clone)]
+pub struct AccumulatedMouseMotion {
+    pub delta: Vec2,
+}
+
+/// The sum of this frame's [MouseWheel] events, kept apart by [MouseScrollUnit]
+#[derive(Debug, Default, Clone)]
+pub struct AccumulatedMouseScroll {
+    pub line_delta: Vec2,
+    pub pixel_delta: Vec2,
+}
+
+/// State used by the mouse accumulation system
+#[derive(Default)]
+pub struct AccumulateMouseState {
+    mouse_motion_event_reader: EventReader<MouseMotion>,
+    mouse_wheel_event_reader: EventReader<MouseWheel>,
+}
+
+/// Resets the AccumulatedMouseMotion and AccumulatedMouseScroll resources and sums up the latest
+/// MouseMotion and MouseWheel events
+pub fn accumulate_mouse_system(
+    mut state: Local<AccumulateMouseState>,
+    mut mouse_motion: ResMut<AccumulatedMouseMotion>,
+    mut mouse_scroll: ResMut<AccumulatedMouseScroll>,
+    mouse_motion_events: Res<Events<MouseMotion>>,
+    mouse_wheel_events: Res<Events<MouseWheel>>,
+) {
+    mouse_motion.delta = Vec2::zero();
+    for event in state.mouse_motion_event_reader.iter(&mouse_motion_events) {
+        mouse_motion.delta += event.delta;
+    }
+
+    mouse_scroll.line_delta = Vec2::zero();
+    mouse_scroll.pixel_delta = Vec2::zero();
+    for event in state.mouse_wheel_event_reader.iter(&mouse_wheel_events) {
+        let delta = Vec2::new(event.x, event.y);
+        match event.unit {
+            MouseScrollUnit::Line => mouse_scroll.line_delta += delta,
+            MouseScrollUnit::Pixel => mouse_scroll.pixel_delta += delta,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use bevy_app::prelude::{EventReader, Events};
+use bevy_ecs::{Local, Res, ResMut};
+use bevy_math::Vec2;
+use bevy_utils::HashMap;
+use bevy_window::WindowId;
+
+/// The stage of a touch's lifetime
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    Ended,
+    /// The system cancelled the touch, e.g. because the window lost focus
+    Cancelled,
+}
+
+/// A touch input event
+///
+/// `id` identifies a touch from the event that started it to the event that ended it, and may be
+/// reused by later touches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchInput {
+    pub window_id: WindowId,
+    pub phase: TouchPhase,
+    pub position: Vec2,
+    pub id: u64,
+}
+
+/// The state of a single touch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Touch {
+    pub id: u64,
+    pub start_position: Vec2,
+    pub position: Vec2,
+    pub phase: TouchPhase,
+}
+
+impl Touch {
+    /// How far the touch has moved since it started
+    pub fn distance(&self) -> Vec2 {
+        self.position - self.start_position
+    }
+}
+
+/// The touches currently held down, and those that started, ended or were cancelled this update
+#[derive(Default)]
+pub struct Touches {
+    active: HashMap<u64, Touch>,
+    just_pressed: HashMap<u64, Touch>,
+    just_released: HashMap<u64, Touch>,
+    just_cancelled: HashMap<u64, Touch>,
+}
+
+impl Touches {
+    /// Iterates over the touches currently held down
+    pub fn iter(&self) -> impl Iterator<Item = &Touch> {
+        self.active.values()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Touch> {
+        self.active.get(&id)
+    }
+
+    pub fn just_pressed(&self, id: u64) -> bool {
+        self.just_pressed.contains_key(&id)
+    }
+
+    pub fn iter_just_pressed(&self) -> impl Iterator<Item = &Touch> {
+        self.just_pressed.values()
+    }
+
+    pub fn just_released(&self, id: u64) -> bool {
+        self.just_released.contains_key(&id)
+    }
+
+    pub fn iter_just_released(&self) -> impl Iterator<Item = &Touch> {
+        self.just_released.values()
+    }
+
+    pub fn just_cancelled(&self, id: u64) -> bool {
+        self.just_cancelled.contains_key(&id)
+    }
+
+    pub fn iter_just_cancelled(&self) -> impl Iterator<Item = &Touch> {
+        self.just_cancelled.values()
+    }
+
+    fn process_event(&mut self, event: &TouchInput) {
+        match event.phase {
+            TouchPhase::Started => {
+                let touch = Touch {
+                    id: event.id,
+                    start_position: event.position,
+                    position: event.position,
+                    phase: event.phase,
+                };
+                self.active.insert(event.id, touch);
+                self.just_pressed.insert(event.id, touch);
+            }
+            TouchPhase::Moved => {
+                if let Some(touch) = self.active.get_mut(&event.id) {
+                    touch.position = event.position;
+                    touch.phase = event.phase;
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                // a touch that started and ended within one update is still reported as pressed
+                if let Some(mut touch) = self.active.remove(&event.id) {
+                    touch.position = event.position;
+                    touch.phase = event.phase;
+                    if event.phase == TouchPhase::Ended {
+                        self.just_released.insert(event.id, touch);
+                    } else {
+                        self.just_cancelled.insert(event.id, touch);
+                    }
+                }
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+        self.just_cancelled.clear();
+    }
+}
+
+/// State used by the touch input system
+#[derive(Default)]
+pub struct TouchSystemState {
+    touch_event_reader: EventReader<TouchInput>,
+}
+
+/// Updates the Touches resource with the latest TouchInput events
+pub fn touch_screen_input_system(
+    mut state: Local<TouchSystemState>,
+    mut touches: ResMut<Touches>,
+    touch_input_events: Res<Events<TouchInput>>,
+) {
+    touches.update();
+    for event in state.touch_event_reader.iter(&touch_input_events) {
+        touches.process_event(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+
+    fn touch_input(phase: TouchPhase, x: f32, id: u64) -> TouchInput {
+        TouchInput {
+            window_id: WindowId::primary(),
+            phase,
+            position: Vec2::new(x, 0.0),
+            id,
+        }
+    }
+
+    #[test]
+    fn follow_drag() {
+        let mut touches = Touches::default();
+        touches.process_event(&touch_input(TouchPhase::Started, 1.0, 7));
+        touches.process_event(&touch_input(TouchPhase::Started, 5.0, 8));
+        assert!(touches.just_pressed(7) && touches.just_pressed(8));
+
+        touches.update();
+        touches.process_event(&touch_input(TouchPhase::Moved, 3.0, 7));
+        let touch = touches.get(7).unwrap();
+        assert_eq!(touch.start_position, Vec2::new(1.0, 0.0));
+        assert_eq!(touch.distance(), Vec2::new(2.0, 0.0));
+        assert!(!touches.just_pressed(7));
+
+        touches.update();
+        touches.process_event(&touch_input(TouchPhase::Ended, 4.0, 7));
+        touches.process_event(&touch_input(TouchPhase::Cancelled, 5.0, 8));
+        assert!(touches.just_released(7) && touches.just_cancelled(8));
+        assert_eq!(touches.iter().count(), 0);
+    }
+
+    #[test]
+    fn touches_follow_touch_input_events() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(Events::<TouchInput>::default());
+        resources.insert(Touches::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", touch_screen_input_system.system());
+        schedule.initialize(&mut resources);
+        let mut send_and_run = |resources: &mut Resources, phase, x| {
+            resources
+                .get_mut::<Events<TouchInput>>()
+                .unwrap()
+                .send(touch_input(phase, x, 3));
+            schedule.run(&mut world, resources);
+        };
+
+        send_and_run(&mut resources, TouchPhase::Started, 1.0);
+        {
+            let touches = resources.get::<Touches>().unwrap();
+            assert!(touches.just_pressed(3));
+            assert_eq!(touches.get(3).unwrap().position, Vec2::new(1.0, 0.0));
+        }
+
+        send_and_run(&mut resources, TouchPhase::Moved, 4.0);
+        {
+            let touches = resources.get::<Touches>().unwrap();
+            assert!(!touches.just_pressed(3));
+            assert_eq!(touches.get(3).unwrap().distance(), Vec2::new(3.0, 0.0));
+        }
+
+        send_and_run(&mut resources, TouchPhase::Ended, 4.0);
+        let touches = resources.get::<Touches>().unwrap();
+        assert!(touches.just_released(3));
+        assert_eq!(touches.iter().count(), 0);
+    }
+}
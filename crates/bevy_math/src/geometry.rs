@@ -1,4 +1,4 @@
-use glam::Vec2;
+use glam::{Vec2, Vec3};
 use std::ops::{Add, AddAssign};
 
 /// A two dimensional "size" as defined by a width and height
@@ -23,6 +23,22 @@ impl<T: Default> Default for Size<T> {
     }
 }
 
+/// A half-line starting at `origin` and extending along `direction`, e.g. from a camera through
+/// the cursor
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Ray {
+    pub origin: Vec3,
+    /// Normalized direction
+    pub direction: Vec3,
+}
+
+impl Ray {
+    /// The point `distance` units along the ray
+    pub fn point_at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+}
+
 /// A rect, as defined by its "side" locations
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Rect<T> {
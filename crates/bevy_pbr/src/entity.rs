@@ -1,4 +1,8 @@
-use crate::{light::Light, material::StandardMaterial, render_graph::FORWARD_PIPELINE_HANDLE};
+use crate::{
+    light::PointLight,
+    material::StandardMaterial,
+    render_graph::{FORWARD_PIPELINE_HANDLE, SHADOW_PIPELINE_HANDLE},
+};
 use bevy_asset::Handle;
 use bevy_ecs::Bundle;
 use bevy_render::{
@@ -26,24 +30,54 @@ pub struct PbrComponents {
 impl Default for PbrComponents {
     fn default() -> Self {
         Self {
-            render_pipelines: RenderPipelines::from_pipelines(vec![RenderPipeline::specialized(
-                FORWARD_PIPELINE_HANDLE,
-                PipelineSpecialization {
-                    dynamic_bindings: vec![
-                        // Transform
-                        DynamicBinding {
-                            bind_group: 2,
-                            binding: 0,
-                        },
-                        // StandardMaterial_albedo
-                        DynamicBinding {
-                            bind_group: 3,
-                            binding: 0,
-                        },
-                    ],
-                    ..Default::default()
-                },
-            )]),
+            render_pipelines: RenderPipelines::from_pipelines(vec![
+                RenderPipeline::specialized(
+                    FORWARD_PIPELINE_HANDLE,
+                    PipelineSpecialization {
+                        dynamic_bindings: vec![
+                            // Transform
+                            DynamicBinding {
+                                bind_group: 2,
+                                binding: 0,
+                            },
+                            // StandardMaterial_base_color
+                            DynamicBinding {
+                                bind_group: 3,
+                                binding: 0,
+                            },
+                            // StandardMaterial_metallic
+                            DynamicBinding {
+                                bind_group: 3,
+                                binding: 3,
+                            },
+                            // StandardMaterial_roughness
+                            DynamicBinding {
+                                bind_group: 3,
+                                binding: 4,
+                            },
+                            // StandardMaterial_emissive
+                            DynamicBinding {
+                                bind_group: 3,
+                                binding: 9,
+                            },
+                        ],
+                        ..Default::default()
+                    },
+                ),
+                RenderPipeline::specialized(
+                    SHADOW_PIPELINE_HANDLE,
+                    PipelineSpecialization {
+                        dynamic_bindings: vec![
+                            // Transform
+                            DynamicBinding {
+                                bind_group: 1,
+                                binding: 0,
+                            },
+                        ],
+                        ..Default::default()
+                    },
+                ),
+            ]),
             mesh: Default::default(),
             material: Default::default(),
             main_pass: Default::default(),
@@ -56,10 +90,10 @@ impl Default for PbrComponents {
     }
 }
 
-/// A component bundle for "light" entities
+/// A component bundle for "point light" entities
 #[derive(Bundle, Default)]
 pub struct LightComponents {
-    pub light: Light,
+    pub light: PointLight,
     pub transform: Transform,
     pub translation: Translation,
     pub rotation: Rotation,
@@ -3,21 +3,27 @@ pub mod render_graph;
 mod entity;
 mod light;
 mod material;
+mod shadow;
 
 pub use entity::*;
 pub use light::*;
 pub use material::*;
+pub use shadow::*;
 
 pub mod prelude {
-    pub use crate::{entity::*, light::Light, material::StandardMaterial};
+    pub use crate::{
+        entity::*,
+        light::{DirectionalLight, PointLight, ShadowBounds},
+        material::StandardMaterial,
+    };
 }
 
 use bevy_app::prelude::*;
-use bevy_asset::AddAsset;
+use bevy_asset::{AddAsset, Handle};
 use bevy_ecs::IntoQuerySystem;
-use bevy_render::{render_graph::RenderGraph, shader};
+use bevy_render::{mesh, render_graph::RenderGraph, shader};
 use bevy_type_registry::RegisterType;
-use light::Light;
+use light::{DirectionalLight, PointLight};
 use material::StandardMaterial;
 use render_graph::add_pbr_graph;
 
@@ -28,13 +34,30 @@ pub struct PbrPlugin;
 impl Plugin for PbrPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_asset::<StandardMaterial>()
-            .register_component::<Light>()
+            .register_component::<PointLight>()
+            .register_component::<DirectionalLight>()
             .add_system_to_stage(
                 stage::POST_UPDATE,
                 shader::asset_shader_defs_system::<StandardMaterial>.system(),
+            )
+            .add_system_to_stage(
+                stage::POST_UPDATE,
+                material::normal_map_tangents_system.system(),
+            )
+            .add_system_to_stage(
+                stage::POST_UPDATE,
+                shadow::directional_light_shadow_system.system(),
+            )
+            .add_system_to_stage(
+                stage::POST_UPDATE,
+                mesh::calculate_bounds_system::<Handle<StandardMaterial>>.system(),
             );
         let resources = app.resources();
         let mut render_graph = resources.get_mut::<RenderGraph>().unwrap();
         add_pbr_graph(&mut render_graph, resources);
     }
+
+    fn dependencies(&self) -> Vec<std::any::TypeId> {
+        vec![std::any::TypeId::of::<bevy_render::RenderPlugin>()]
+    }
 }
@@ -1,56 +1,119 @@
 use bevy_core::Byteable;
-use bevy_math::Mat4;
+use bevy_math::{Vec3, Vec4};
 use bevy_property::Properties;
-use bevy_render::{
-    camera::{CameraProjection, PerspectiveProjection},
-    color::Color,
-};
+use bevy_render::color::Color;
 use bevy_transform::components::Translation;
-use std::ops::Range;
 
-/// A point light
-#[derive(Properties)]
-pub struct Light {
+/// A light that shines in every direction from a point, like a light bulb
+#[derive(Debug, Clone, Copy, Properties)]
+pub struct PointLight {
     pub color: Color,
-    pub fov: f32,
-    pub depth: Range<f32>,
+    /// A surface facing the light from a distance `d` receives `intensity / d²` times its color
+    pub intensity: f32,
+    /// The distance at which the light stops having an effect. Its falloff is smoothed so that it
+    /// reaches zero here instead of cutting off abruptly.
+    pub range: f32,
 }
 
-impl Default for Light {
+impl Default for PointLight {
     fn default() -> Self {
-        Light {
+        PointLight {
             color: Color::rgb(1.0, 1.0, 1.0),
-            depth: 0.1..50.0,
-            fov: f32::to_radians(60.0),
+            intensity: 100.0,
+            range: 20.0,
         }
     }
 }
 
+/// A light that shines in a single direction from infinitely far away, like the sun
+#[derive(Debug, Clone, Copy, Properties)]
+pub struct DirectionalLight {
+    pub color: Color,
+    /// A surface facing the light receives `illuminance` times its color
+    pub illuminance: f32,
+    /// The direction the light travels in
+    pub direction: Vec3,
+    /// Whether this light casts shadows. Only the first directional light with shadows enabled
+    /// casts them.
+    pub shadows_enabled: bool,
+    /// The width and height of the shadow map, in texels
+    pub shadow_map_size: u32,
+    /// How far a surface has to be behind the closest occluder to count as shadowed. Too small a
+    /// bias causes "shadow acne" (stripes of shadow on lit surfaces), too large a bias makes shadows
+    /// detach from the objects casting them ("peter-panning").
+    pub shadow_bias: f32,
+    /// The region of the world that receives shadows
+    #[property(ignore)]
+    pub shadow_bounds: ShadowBounds,
+}
+
+impl Default for DirectionalLight {
+    fn default() -> Self {
+        DirectionalLight {
+            color: Color::rgb(1.0, 1.0, 1.0),
+            illuminance: 1.0,
+            direction: Vec3::new(0.0, -1.0, 0.0),
+            shadows_enabled: false,
+            shadow_map_size: 2048,
+            shadow_bias: 0.005,
+            shadow_bounds: ShadowBounds::default(),
+        }
+    }
+}
+
+/// The region of the world that a [DirectionalLight]'s shadow map covers. The smaller the region, the
+/// sharper the shadows.
+#[derive(Debug, Clone, Copy)]
+pub enum ShadowBounds {
+    /// The part of the 3d camera's view that is closer than `distance` to the camera
+    CameraView { distance: f32 },
+    /// A box of `half_extents` around `center`, in world space
+    Fixed { center: Vec3, half_extents: Vec3 },
+}
+
+impl Default for ShadowBounds {
+    fn default() -> Self {
+        ShadowBounds::CameraView { distance: 50.0 }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy)]
-pub(crate) struct LightRaw {
-    pub proj: [[f32; 4]; 4],
+pub(crate) struct PointLightRaw {
+    /// The light's position, with its range in `w`
     pub pos: [f32; 4],
+    /// The light's color, premultiplied by its intensity
     pub color: [f32; 4],
 }
 
-unsafe impl Byteable for LightRaw {}
+unsafe impl Byteable for PointLightRaw {}
 
-impl LightRaw {
-    pub fn from(light: &Light, transform: &Mat4, translation: &Translation) -> LightRaw {
-        let perspective = PerspectiveProjection {
-            fov: light.fov,
-            aspect_ratio: 1.0,
-            near: light.depth.start,
-            far: light.depth.end,
-        };
-
-        let proj = perspective.get_projection_matrix() * *transform;
+impl PointLightRaw {
+    pub fn from(light: &PointLight, translation: &Translation) -> PointLightRaw {
         let (x, y, z) = translation.0.into();
-        LightRaw {
-            proj: proj.to_cols_array_2d(),
-            pos: [x, y, z, 1.0],
-            color: light.color.into(),
+        PointLightRaw {
+            pos: [x, y, z, light.range],
+            color: (Vec4::from(light.color.as_linear_rgba_f32()) * light.intensity).into(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct DirectionalLightRaw {
+    pub direction: [f32; 4],
+    /// The light's color, premultiplied by its illuminance
+    pub color: [f32; 4],
+}
+
+unsafe impl Byteable for DirectionalLightRaw {}
+
+impl DirectionalLightRaw {
+    pub fn from(light: &DirectionalLight) -> DirectionalLightRaw {
+        let (x, y, z) = light.direction.normalize().into();
+        DirectionalLightRaw {
+            direction: [x, y, z, 0.0],
+            color: (Vec4::from(light.color.as_linear_rgba_f32()) * light.illuminance).into(),
         }
     }
 }
@@ -1,13 +1,43 @@
-use bevy_asset::{self, Handle};
-use bevy_render::{color::Color, renderer::RenderResources, shader::ShaderDefs, texture::Texture};
+use bevy_asset::{self, Assets, Handle};
+use bevy_ecs::{Query, Res, ResMut};
+use bevy_render::{
+    color::Color,
+    mesh::{Mesh, VertexAttribute},
+    pipeline::PrimitiveTopology,
+    renderer::RenderResources,
+    shader::ShaderDefs,
+    texture::Texture,
+};
 
-/// A material with "standard" properties used in PBR lighting
+/// A material with "standard" properties used in PBR lighting, following the metallic-roughness
+/// model of glTF. Each property can be given per pixel by a texture, which is multiplied by the
+/// property's value. Textures that haven't loaded yet are drawn as if they were white, or flat
+/// for normal maps.
 #[derive(RenderResources, ShaderDefs)]
 #[allow(clippy::manual_non_exhaustive)]
 pub struct StandardMaterial {
-    pub albedo: Color,
+    pub base_color: Color,
     #[shader_def]
-    pub albedo_texture: Option<Handle<Texture>>,
+    pub base_color_texture: Option<Handle<Texture>>,
+    /// How metal-like the surface is, from `0.0` (a dielectric like plastic) to `1.0`. Metals
+    /// have no diffuse color, and tint their reflections with their base color instead.
+    pub metallic: f32,
+    /// How rough the surface is, from `0.0` (mirror-like) to `1.0` (completely matte)
+    pub roughness: f32,
+    /// Multiplies the roughness with its green channel and the metallic with its blue channel
+    #[shader_def]
+    pub metallic_roughness_texture: Option<Handle<Texture>>,
+    /// A tangent space normal map, with +Y pointing up the image like in glTF. Normal maps hold
+    /// directions rather than colors, so they need a linear (not sRGB) format like
+    /// [TextureFormat::Rgba8Unorm](bevy_render::texture::TextureFormat::Rgba8Unorm). Meshes
+    /// without tangents get them computed, see [Mesh::compute_tangents].
+    #[render_resources(normal_map)]
+    #[shader_def]
+    pub normal_map_texture: Option<Handle<Texture>>,
+    /// Light given off by the surface regardless of lighting. Black by default.
+    pub emissive: Color,
+    #[shader_def]
+    pub emissive_texture: Option<Handle<Texture>>,
     #[render_resources(ignore)]
     #[shader_def]
     pub shaded: bool,
@@ -22,8 +52,14 @@ pub struct StandardMaterial {
 impl Default for StandardMaterial {
     fn default() -> Self {
         StandardMaterial {
-            albedo: Color::rgb(1.0, 1.0, 1.0),
-            albedo_texture: None,
+            base_color: Color::rgb(1.0, 1.0, 1.0),
+            base_color_texture: None,
+            metallic: 0.0,
+            roughness: 0.5,
+            metallic_roughness_texture: None,
+            normal_map_texture: None,
+            emissive: Color::BLACK,
+            emissive_texture: None,
             shaded: true,
             __non_exhaustive: (),
         }
@@ -33,7 +69,7 @@ impl Default for StandardMaterial {
 impl From<Color> for StandardMaterial {
     fn from(color: Color) -> Self {
         StandardMaterial {
-            albedo: color,
+            base_color: color,
             ..Default::default()
         }
     }
@@ -42,8 +78,36 @@ impl From<Color> for StandardMaterial {
 impl From<Handle<Texture>> for StandardMaterial {
     fn from(texture: Handle<Texture>) -> Self {
         StandardMaterial {
-            albedo_texture: Some(texture),
+            base_color_texture: Some(texture),
             ..Default::default()
         }
     }
 }
+
+/// Computes the tangents of meshes drawn with a normal mapped [StandardMaterial] that don't have
+/// any. Meshes without uvs are left alone, and are drawn without normal mapping.
+pub fn normal_map_tangents_system(
+    materials: Res<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query: Query<(&Handle<Mesh>, &Handle<StandardMaterial>)>,
+) {
+    for (mesh_handle, material_handle) in &mut query.iter() {
+        let normal_mapped = materials
+            .get(&material_handle)
+            .map_or(false, |material| material.normal_map_texture.is_some());
+        if !normal_mapped {
+            continue;
+        }
+
+        // check before borrowing mutably, which would mark the mesh as modified every frame
+        let needs_tangents = meshes.get(&mesh_handle).map_or(false, |mesh| {
+            mesh.primitive_topology == PrimitiveTopology::TriangleList
+                && mesh.get_attribute(VertexAttribute::TANGENT).is_none()
+                && mesh.get_attribute(VertexAttribute::UV).is_some()
+                && mesh.get_attribute(VertexAttribute::NORMAL).is_some()
+        });
+        if needs_tangents {
+            meshes.get_mut(&mesh_handle).unwrap().compute_tangents();
+        }
+    }
+}
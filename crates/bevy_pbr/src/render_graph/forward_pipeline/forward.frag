@@ -1,16 +1,30 @@
 #version 450
 
-const int MAX_LIGHTS = 10;
+const float PI = 3.141592653589793;
+const int MAX_POINT_LIGHTS = 10;
+const int MAX_DIRECTIONAL_LIGHTS = 4;
 
-struct Light {
-    mat4 proj;
+struct PointLight {
+    // xyz: position, w: range
     vec4 pos;
+    // premultiplied by intensity
+    vec4 color;
+};
+
+struct DirectionalLight {
+    vec4 direction;
+    // premultiplied by illuminance
     vec4 color;
 };
 
 layout(location = 0) in vec3 v_Position;
 layout(location = 1) in vec3 v_Normal;
 layout(location = 2) in vec2 v_Uv;
+# ifdef INSTANCED
+layout(location = 3) in vec4 v_Color;
+# endif
+layout(location = 4) flat in vec4 v_Camera;
+layout(location = 5) in vec4 v_Tangent;
 
 layout(location = 0) out vec4 o_Target;
 
@@ -19,43 +33,213 @@ layout(set = 0, binding = 0) uniform Camera {
 };
 
 layout(set = 1, binding = 0) uniform Lights {
+    // x: point lights, y: directional lights, z: whether a directional light casts shadows,
+    // w: the index of the directional light that casts shadows
     uvec4 NumLights;
-    Light SceneLights[MAX_LIGHTS];
+    mat4 DirectionalShadowViewProj;
+    // x: bias
+    vec4 DirectionalShadowParams;
+    PointLight PointLights[MAX_POINT_LIGHTS];
+    DirectionalLight DirectionalLights[MAX_DIRECTIONAL_LIGHTS];
+};
+layout(set = 1, binding = 1) uniform texture2D DirectionalShadowMap;
+layout(set = 1, binding = 2) uniform sampler DirectionalShadowMap_sampler;
+
+layout(set = 3, binding = 0) uniform StandardMaterial_base_color {
+    vec4 BaseColor;
 };
+# ifdef STANDARDMATERIAL_BASE_COLOR_TEXTURE
+layout(set = 3, binding = 1) uniform texture2D StandardMaterial_base_color_texture;
+layout(set = 3, binding = 2) uniform sampler StandardMaterial_base_color_texture_sampler;
+# endif
 
-layout(set = 3, binding = 0) uniform StandardMaterial_albedo {
-    vec4 Albedo;
+layout(set = 3, binding = 3) uniform StandardMaterial_metallic {
+    float Metallic;
+};
+layout(set = 3, binding = 4) uniform StandardMaterial_roughness {
+    float Roughness;
 };
+# ifdef STANDARDMATERIAL_METALLIC_ROUGHNESS_TEXTURE
+layout(set = 3, binding = 5) uniform texture2D StandardMaterial_metallic_roughness_texture;
+layout(set = 3, binding = 6) uniform sampler StandardMaterial_metallic_roughness_texture_sampler;
+# endif
 
-# ifdef STANDARDMATERIAL_ALBEDO_TEXTURE
-layout(set = 3, binding = 1) uniform texture2D StandardMaterial_albedo_texture;
-layout(set = 3, binding = 2) uniform sampler StandardMaterial_albedo_texture_sampler;
+# ifdef STANDARDMATERIAL_NORMAL_MAP_TEXTURE
+layout(set = 3, binding = 7) uniform texture2D StandardMaterial_normal_map_texture;
+layout(set = 3, binding = 8) uniform sampler StandardMaterial_normal_map_texture_sampler;
+# endif
+
+layout(set = 3, binding = 9) uniform StandardMaterial_emissive {
+    vec4 Emissive;
+};
+# ifdef STANDARDMATERIAL_EMISSIVE_TEXTURE
+layout(set = 3, binding = 10) uniform texture2D StandardMaterial_emissive_texture;
+layout(set = 3, binding = 11) uniform sampler StandardMaterial_emissive_texture_sampler;
+# endif
+
+// returns 0.0 where the shadow-casting directional light is occluded and 1.0 where it isn't
+float directional_shadow() {
+    vec4 light_clip = DirectionalShadowViewProj * vec4(v_Position, 1.0);
+    vec3 light_ndc = light_clip.xyz / light_clip.w;
+    // everything outside of the shadow map is lit
+    if (any(greaterThan(abs(light_ndc.xy), vec2(1.0))) || light_ndc.z > 1.0) {
+        return 1.0;
+    }
+    // ndc y points up, while texture v points down
+    vec2 uv = light_ndc.xy * vec2(0.5, -0.5) + 0.5;
+    float occluder_depth = texture(
+        sampler2D(DirectionalShadowMap, DirectionalShadowMap_sampler),
+        uv).r;
+    return light_ndc.z - DirectionalShadowParams.x > occluder_depth ? 0.0 : 1.0;
+}
+
+# ifdef STANDARDMATERIAL_NORMAL_MAP_TEXTURE
+// applies the normal map to the interpolated normal, in the tangent frame of the mesh
+vec3 mapped_normal(vec3 normal) {
+    // make the tangent perpendicular to the interpolated normal. vertices without tangents, like
+    // those of meshes without uvs, have zero tangents and aren't normal mapped
+    vec3 tangent = v_Tangent.xyz - normal * dot(normal, v_Tangent.xyz);
+    if (dot(tangent, tangent) < 1e-8) {
+        return normal;
+    }
+    tangent = normalize(tangent);
+    vec3 bitangent = cross(normal, tangent) * (v_Tangent.w < 0.0 ? -1.0 : 1.0);
+    vec3 mapped = texture(
+        sampler2D(StandardMaterial_normal_map_texture, StandardMaterial_normal_map_texture_sampler),
+        v_Uv).rgb * 2.0 - 1.0;
+    return normalize(mat3(tangent, bitangent, normal) * mapped);
+}
 # endif
 
+// the GGX (Trowbridge-Reitz) normal distribution function
+float distribution_ggx(float n_dot_h, float alpha) {
+    float alpha2 = alpha * alpha;
+    float f = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+    return alpha2 / (PI * f * f);
+}
+
+// the height-correlated Smith visibility term, which includes the specular BRDF's denominator
+float visibility_smith_ggx(float n_dot_v, float n_dot_l, float alpha) {
+    float alpha2 = alpha * alpha;
+    float ggx_l = n_dot_v * sqrt(n_dot_l * n_dot_l * (1.0 - alpha2) + alpha2);
+    float ggx_v = n_dot_l * sqrt(n_dot_v * n_dot_v * (1.0 - alpha2) + alpha2);
+    return 0.5 / (ggx_l + ggx_v);
+}
+
+vec3 fresnel_schlick(vec3 f0, float v_dot_h) {
+    return f0 + (1.0 - f0) * pow(1.0 - v_dot_h, 5.0);
+}
+
+// the light reflected towards the viewer by a light arriving from `to_light` with `color`.
+// lights are defined so that a white diffuse surface facing them reflects their color, so the
+// BRDF is scaled by PI
+vec3 shade(
+    vec3 normal,
+    vec3 to_view,
+    vec3 to_light,
+    vec3 color,
+    vec3 diffuse_color,
+    vec3 f0,
+    float alpha
+) {
+    float n_dot_l = max(dot(normal, to_light), 0.0);
+    if (n_dot_l <= 0.0) {
+        return vec3(0.0);
+    }
+    vec3 half_vector = normalize(to_light + to_view);
+    float n_dot_v = max(dot(normal, to_view), 1e-4);
+    float n_dot_h = max(dot(normal, half_vector), 0.0);
+    float v_dot_h = max(dot(to_view, half_vector), 0.0);
+
+    vec3 specular = distribution_ggx(n_dot_h, alpha)
+        * visibility_smith_ggx(n_dot_v, n_dot_l, alpha)
+        * fresnel_schlick(f0, v_dot_h);
+    return (diffuse_color + PI * specular) * color * n_dot_l;
+}
+
 void main() {
-    vec4 output_color = Albedo;
-# ifdef STANDARDMATERIAL_ALBEDO_TEXTURE
+    vec4 output_color = BaseColor;
+# ifdef STANDARDMATERIAL_BASE_COLOR_TEXTURE
     output_color *= texture(
-        sampler2D(StandardMaterial_albedo_texture, StandardMaterial_albedo_texture_sampler),
+        sampler2D(StandardMaterial_base_color_texture, StandardMaterial_base_color_texture_sampler),
         v_Uv);
 # endif
+# ifdef INSTANCED
+    output_color *= v_Color;
+# endif
+
+    vec3 emissive = Emissive.rgb;
+# ifdef STANDARDMATERIAL_EMISSIVE_TEXTURE
+    emissive *= texture(
+        sampler2D(StandardMaterial_emissive_texture, StandardMaterial_emissive_texture_sampler),
+        v_Uv).rgb;
+# endif
 
 # ifdef STANDARDMATERIAL_SHADED
+    float metallic = Metallic;
+    float roughness = Roughness;
+# ifdef STANDARDMATERIAL_METALLIC_ROUGHNESS_TEXTURE
+    vec4 metallic_roughness = texture(
+        sampler2D(
+            StandardMaterial_metallic_roughness_texture,
+            StandardMaterial_metallic_roughness_texture_sampler),
+        v_Uv);
+    metallic *= metallic_roughness.b;
+    roughness *= metallic_roughness.g;
+# endif
+    // very low roughness makes highlights too small to be sampled by a pixel
+    float alpha = max(roughness * roughness, 0.0064);
+    // dielectrics reflect about 4% of light head on, while metals reflect their color
+    vec3 f0 = mix(vec3(0.04), output_color.rgb, metallic);
+    vec3 diffuse_color = output_color.rgb * (1.0 - metallic);
+
     vec3 normal = normalize(v_Normal);
+# ifdef STANDARDMATERIAL_NORMAL_MAP_TEXTURE
+    normal = mapped_normal(normal);
+# endif
+    vec3 to_view = v_Camera.w == 0.0 ? v_Camera.xyz : normalize(v_Camera.xyz - v_Position);
+
+    // a crude stand-in for light bouncing around the scene
     vec3 ambient = vec3(0.05, 0.05, 0.05);
     // accumulate color
-    vec3 color = ambient;
-    for (int i=0; i<int(NumLights.x) && i<MAX_LIGHTS; ++i) {
-        Light light = SceneLights[i];
-        // compute Lambertian diffuse term
-        vec3 light_dir = normalize(light.pos.xyz - v_Position);
-        float diffuse = max(0.0, dot(normal, light_dir));
+    vec3 color = ambient * (diffuse_color + f0);
+    for (int i=0; i<int(NumLights.x) && i<MAX_POINT_LIGHTS; ++i) {
+        PointLight light = PointLights[i];
+        vec3 to_light = light.pos.xyz - v_Position;
+        float distance_squared = max(dot(to_light, to_light), 0.0001);
+        // inverse-square falloff, windowed so that it smoothly reaches zero at the light's range
+        float range = light.pos.w;
+        float falloff = distance_squared / (range * range);
+        float window = clamp(1.0 - falloff * falloff, 0.0, 1.0);
+        float attenuation = window * window / distance_squared;
         // add light contribution
-        color += diffuse * light.color.xyz;
+        color += shade(
+            normal,
+            to_view,
+            normalize(to_light),
+            attenuation * light.color.xyz,
+            diffuse_color,
+            f0,
+            alpha);
+    }
+    for (int i=0; i<int(NumLights.y) && i<MAX_DIRECTIONAL_LIGHTS; ++i) {
+        DirectionalLight light = DirectionalLights[i];
+        vec3 light_color = light.color.xyz;
+        if (NumLights.z != 0u && i == int(NumLights.w)) {
+            light_color *= directional_shadow();
+        }
+        color += shade(
+            normal,
+            to_view,
+            -light.direction.xyz,
+            light_color,
+            diffuse_color,
+            f0,
+            alpha);
     }
-    output_color.xyz *= color;
+    output_color.rgb = color;
 # endif
 
-    // multiply the light by material color
+    output_color.rgb += emissive;
     o_Target = output_color;
 }
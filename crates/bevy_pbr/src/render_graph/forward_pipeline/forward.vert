@@ -3,10 +3,26 @@
 layout(location = 0) in vec3 Vertex_Position;
 layout(location = 1) in vec3 Vertex_Normal;
 layout(location = 2) in vec2 Vertex_Uv;
+layout(location = 3) in vec4 Vertex_Tangent;
+
+# ifdef INSTANCED
+layout(location = 4) in vec4 I_MeshInstance_Model_0;
+layout(location = 5) in vec4 I_MeshInstance_Model_1;
+layout(location = 6) in vec4 I_MeshInstance_Model_2;
+layout(location = 7) in vec4 I_MeshInstance_Model_3;
+layout(location = 8) in vec4 I_MeshInstance_Color;
+# endif
 
 layout(location = 0) out vec3 v_Position;
 layout(location = 1) out vec3 v_Normal;
 layout(location = 2) out vec2 v_Uv;
+# ifdef INSTANCED
+layout(location = 3) out vec4 v_Color;
+# endif
+// xyz: the camera's position, w: 1.0. orthographic cameras have no position, so for them xyz is
+// the direction towards the camera and w is 0.0
+layout(location = 4) flat out vec4 v_Camera;
+layout(location = 5) out vec4 v_Tangent;
 
 layout(set = 0, binding = 0) uniform Camera {
     mat4 ViewProj;
@@ -17,9 +33,29 @@ layout(set = 2, binding = 0) uniform Transform {
 };
 
 void main() {
-    v_Normal = (Model * vec4(Vertex_Normal, 1.0)).xyz;
-    v_Normal = mat3(Model) * Vertex_Normal;
-    v_Position = (Model * vec4(Vertex_Position, 1.0)).xyz;
+# ifdef INSTANCED
+    // instance transforms are relative to the transform of the instanced mesh
+    mat4 model = Model * mat4(
+        I_MeshInstance_Model_0,
+        I_MeshInstance_Model_1,
+        I_MeshInstance_Model_2,
+        I_MeshInstance_Model_3);
+    v_Color = I_MeshInstance_Color;
+# else
+    mat4 model = Model;
+# endif
+    v_Normal = mat3(model) * Vertex_Normal;
+    v_Position = (model * vec4(Vertex_Position, 1.0)).xyz;
     v_Uv = Vertex_Uv;
+    v_Tangent = vec4(mat3(model) * Vertex_Tangent.xyz, Vertex_Tangent.w);
     gl_Position = ViewProj * vec4(v_Position, 1.0);
+
+    // the camera is the point that projects to infinity, where w is 0.0
+    vec4 camera = inverse(ViewProj) * vec4(0.0, 0.0, 1.0, 0.0);
+    if (abs(camera.w) > 1e-6) {
+        v_Camera = vec4(camera.xyz / camera.w, 1.0);
+    } else {
+        // cameras look down -z, and depth increases away from them
+        v_Camera = vec4(-normalize(camera.xyz), 0.0);
+    }
 }
@@ -1,44 +1,75 @@
 use crate::{
-    light::{Light, LightRaw},
-    render_graph::uniform,
+    light::{DirectionalLight, DirectionalLightRaw, PointLight, PointLightRaw},
+    render_graph::{camera, uniform},
 };
-use bevy_core::{AsBytes, Byteable};
+use bevy_core::{AsBytes, Byteable, FloatOrd};
 use bevy_ecs::{Commands, IntoQuerySystem, Local, Query, Res, ResMut, Resources, System, World};
 use bevy_render::{
-    render_graph::{CommandQueue, Node, ResourceSlots, SystemNode},
+    camera::{ActiveCameras, Camera},
+    render_graph::{base, CommandQueue, Node, ResourceSlotInfo, ResourceSlots, SystemNode},
     renderer::{
         BufferId, BufferInfo, BufferUsage, RenderContext, RenderResourceBinding,
-        RenderResourceBindings, RenderResourceContext,
+        RenderResourceBindings, RenderResourceContext, RenderResourceId, RenderResourceType,
+        SamplerId, TextureId,
+    },
+    texture::{
+        Extent3d, SamplerDescriptor, TextureDescriptor, TextureDimension, TextureFormat,
+        TextureUsage,
     },
 };
 use bevy_transform::prelude::*;
+use std::borrow::Cow;
 
 /// A Render Graph [Node] that write light data from the ECS to GPU buffers
+///
+/// When there are more point lights than fit in the buffer, the ones closest to the 3d camera are
+/// kept. The node also owns the depth texture that directional light shadows are rendered into,
+/// which it outputs for the shadow pass.
 #[derive(Default)]
 pub struct LightsNode {
     command_queue: CommandQueue,
-    max_lights: usize,
+    max_point_lights: usize,
+    max_directional_lights: usize,
 }
 
 impl LightsNode {
-    pub fn new(max_lights: usize) -> Self {
+    pub const OUT_SHADOW_MAP: &'static str = "shadow_map";
+
+    pub fn new(max_point_lights: usize, max_directional_lights: usize) -> Self {
         LightsNode {
-            max_lights,
+            max_point_lights,
+            max_directional_lights,
             command_queue: CommandQueue::default(),
         }
     }
 }
 
 impl Node for LightsNode {
+    fn output(&self) -> &[ResourceSlotInfo] {
+        static OUTPUT: &[ResourceSlotInfo] = &[ResourceSlotInfo {
+            name: Cow::Borrowed(LightsNode::OUT_SHADOW_MAP),
+            resource_type: RenderResourceType::Texture,
+        }];
+        OUTPUT
+    }
+
     fn update(
         &mut self,
         _world: &World,
-        _resources: &Resources,
+        resources: &Resources,
         render_context: &mut dyn RenderContext,
         _input: &ResourceSlots,
-        _output: &mut ResourceSlots,
+        output: &mut ResourceSlots,
     ) {
         self.command_queue.execute(render_context);
+
+        let render_resource_bindings = resources.get::<RenderResourceBindings>().unwrap();
+        if let Some(shadow_map) = render_resource_bindings
+            .get(uniform::DIRECTIONAL_SHADOW_MAP)
+            .and_then(|binding| binding.get_texture())
+        {
+            output.set(0, RenderResourceId::Texture(shadow_map));
+        }
     }
 }
 
@@ -50,6 +81,16 @@ struct LightCount {
 
 unsafe impl Byteable for LightCount {}
 
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct DirectionalShadowRaw {
+    pub view_proj: [[f32; 4]; 4],
+    /// x: bias
+    pub params: [f32; 4],
+}
+
+unsafe impl Byteable for DirectionalShadowRaw {}
+
 impl SystemNode for LightsNode {
     fn get_system(&self, commands: &mut Commands) -> Box<dyn System> {
         let system = lights_node_system.system();
@@ -57,9 +98,12 @@ impl SystemNode for LightsNode {
             system.id(),
             LightsNodeSystemState {
                 command_queue: self.command_queue.clone(),
-                max_lights: self.max_lights,
+                max_point_lights: self.max_point_lights,
+                max_directional_lights: self.max_directional_lights,
                 light_buffer: None,
                 staging_buffer: None,
+                shadow_map: None,
+                shadow_map_sampler: None,
             },
         );
         system
@@ -71,35 +115,118 @@ impl SystemNode for LightsNode {
 pub struct LightsNodeSystemState {
     light_buffer: Option<BufferId>,
     staging_buffer: Option<BufferId>,
+    /// The shadow map texture and its size
+    shadow_map: Option<(TextureId, u32)>,
+    shadow_map_sampler: Option<SamplerId>,
     command_queue: CommandQueue,
-    max_lights: usize,
+    max_point_lights: usize,
+    max_directional_lights: usize,
 }
 
 pub fn lights_node_system(
     mut state: Local<LightsNodeSystemState>,
     render_resource_context: Res<Box<dyn RenderResourceContext>>,
+    active_cameras: Res<ActiveCameras>,
     // TODO: this write on RenderResourceBindings will prevent this system from running in parallel with other systems that do the same
     mut render_resource_bindings: ResMut<RenderResourceBindings>,
-    mut query: Query<(&Light, &Transform, &Translation)>,
+    mut point_light_query: Query<(&PointLight, &Translation)>,
+    mut directional_light_query: Query<&DirectionalLight>,
+    camera_query: Query<(&Camera, &Transform)>,
 ) {
     let state = &mut state;
     let render_resource_context = &**render_resource_context;
 
-    let light_count = query.iter().iter().count();
-    let size = std::mem::size_of::<LightRaw>();
+    let point_light_size = std::mem::size_of::<PointLightRaw>();
+    let directional_light_size = std::mem::size_of::<DirectionalLightRaw>();
     let light_count_size = std::mem::size_of::<LightCount>();
-    let light_array_size = size * light_count;
-    let light_array_max_size = size * state.max_lights;
-    let current_light_uniform_size = light_count_size + light_array_size;
-    let max_light_uniform_size = light_count_size + light_array_max_size;
-
-    if let Some(staging_buffer) = state.staging_buffer {
-        if light_count == 0 {
-            return;
+    let directional_shadow_size = std::mem::size_of::<DirectionalShadowRaw>();
+    let point_lights_offset = light_count_size + directional_shadow_size;
+    let point_light_array_max_size = point_light_size * state.max_point_lights;
+    let directional_light_array_max_size = directional_light_size * state.max_directional_lights;
+    let max_light_uniform_size =
+        point_lights_offset + point_light_array_max_size + directional_light_array_max_size;
+
+    let mut point_lights = point_light_query
+        .iter()
+        .iter()
+        .map(|(light, translation)| (PointLightRaw::from(&light, &translation), translation.0))
+        .collect::<Vec<_>>();
+    if point_lights.len() > state.max_point_lights {
+        // cull the lights that are furthest from the camera
+        if let Some(camera_position) = active_cameras
+            .get(base::camera::CAMERA3D)
+            .and_then(|camera| camera_query.get::<Transform>(camera).ok())
+            .map(|transform| transform.value.w_axis().truncate())
+        {
+            point_lights.sort_by_key(|(_, position)| {
+                FloatOrd((*position - camera_position).length_squared())
+            });
+        }
+        point_lights.truncate(state.max_point_lights);
+    }
+
+    let mut shadowed_light = None;
+    let mut directional_lights = Vec::new();
+    for light in directional_light_query
+        .iter()
+        .iter()
+        .take(state.max_directional_lights)
+    {
+        if light.shadows_enabled && shadowed_light.is_none() {
+            shadowed_light = Some((directional_lights.len(), *light));
         }
+        directional_lights.push(DirectionalLightRaw::from(&light));
+    }
 
-        render_resource_context.map_buffer(staging_buffer);
-    } else {
+    // shadows are only drawn once the shadow camera has been spawned for the light
+    let shadow = shadowed_light.and_then(|(index, light)| {
+        let shadow_camera = active_cameras.get(camera::DIRECTIONAL_LIGHT_SHADOW)?;
+        let camera = camera_query.get::<Camera>(shadow_camera).ok()?;
+        let transform = camera_query.get::<Transform>(shadow_camera).ok()?;
+        let view_proj = camera.projection_matrix * transform.value.inverse();
+        Some((
+            index,
+            DirectionalShadowRaw {
+                view_proj: view_proj.to_cols_array_2d(),
+                params: [light.shadow_bias, 0.0, 0.0, 0.0],
+            },
+        ))
+    });
+
+    // the shader always samples a shadow map, so a placeholder is bound when no light casts shadows
+    let shadow_map_size = shadowed_light.map_or(1, |(_, light)| light.shadow_map_size.max(1));
+    if state.shadow_map.map(|(_, size)| size) != Some(shadow_map_size) {
+        if let Some((shadow_map, _)) = state.shadow_map.take() {
+            render_resource_context.remove_texture(shadow_map);
+        }
+        let shadow_map = render_resource_context.create_texture(TextureDescriptor {
+            size: Extent3d {
+                width: shadow_map_size,
+                height: shadow_map_size,
+                depth: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Depth32Float,
+            usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::SAMPLED,
+        });
+        render_resource_bindings.set(
+            uniform::DIRECTIONAL_SHADOW_MAP,
+            RenderResourceBinding::Texture(shadow_map),
+        );
+        state.shadow_map = Some((shadow_map, shadow_map_size));
+    }
+    if state.shadow_map_sampler.is_none() {
+        let sampler = render_resource_context.create_sampler(&SamplerDescriptor::default());
+        render_resource_bindings.set(
+            uniform::DIRECTIONAL_SHADOW_MAP_SAMPLER,
+            RenderResourceBinding::Sampler(sampler),
+        );
+        state.shadow_map_sampler = Some(sampler);
+    }
+
+    if state.staging_buffer.is_none() {
         let buffer = render_resource_context.create_buffer(BufferInfo {
             size: max_light_uniform_size,
             buffer_usage: BufferUsage::UNIFORM | BufferUsage::COPY_SRC | BufferUsage::COPY_DST,
@@ -121,25 +248,46 @@ pub fn lights_node_system(
             mapped_at_creation: true,
         });
         state.staging_buffer = Some(staging_buffer);
+    } else {
+        render_resource_context.map_buffer(state.staging_buffer.unwrap());
     }
 
     let staging_buffer = state.staging_buffer.unwrap();
+    // the directional lights start after the space reserved for point lights, as the shader's
+    // light arrays have a fixed size
+    let directional_lights_offset = point_lights_offset + point_light_array_max_size;
     render_resource_context.write_mapped_buffer(
         staging_buffer,
-        0..current_light_uniform_size as u64,
+        0..max_light_uniform_size as u64,
         &mut |data, _renderer| {
-            // light count
-            data[0..light_count_size].copy_from_slice([light_count as u32, 0, 0, 0].as_bytes());
-
-            // light array
-            for ((light, transform, translation), slot) in query
-                .iter()
-                .iter()
-                .zip(data[light_count_size..current_light_uniform_size].chunks_exact_mut(size))
-            {
-                slot.copy_from_slice(
-                    LightRaw::from(&light, &transform.value, &translation).as_bytes(),
-                );
+            // light counts
+            data[0..light_count_size].copy_from_slice(
+                [
+                    point_lights.len() as u32,
+                    directional_lights.len() as u32,
+                    shadow.is_some() as u32,
+                    shadow.as_ref().map_or(0, |(index, _)| *index as u32),
+                ]
+                .as_bytes(),
+            );
+
+            // directional light shadow
+            if let Some((_, shadow)) = shadow.as_ref() {
+                data[light_count_size..point_lights_offset].copy_from_slice(shadow.as_bytes());
+            }
+
+            // light arrays
+            for ((light, _), slot) in point_lights.iter().zip(
+                data[point_lights_offset..directional_lights_offset]
+                    .chunks_exact_mut(point_light_size),
+            ) {
+                slot.copy_from_slice(light.as_bytes());
+            }
+            for (light, slot) in directional_lights.iter().zip(
+                data[directional_lights_offset..max_light_uniform_size]
+                    .chunks_exact_mut(directional_light_size),
+            ) {
+                slot.copy_from_slice(light.as_bytes());
             }
         },
     );
@@ -1,27 +1,46 @@
 mod forward_pipeline;
 mod lights_node;
+mod shadow_pipeline;
 
 pub use forward_pipeline::*;
 pub use lights_node::*;
+pub use shadow_pipeline::*;
 
 /// the names of pbr graph nodes
 pub mod node {
     pub const TRANSFORM: &str = "transform";
     pub const STANDARD_MATERIAL: &str = "standard_material";
     pub const LIGHTS: &str = "lights";
+    pub const DIRECTIONAL_LIGHT_SHADOW_CAMERA: &str = "directional_light_shadow_camera";
+    pub const DIRECTIONAL_LIGHT_SHADOW_PASS: &str = "directional_light_shadow_pass";
+}
+
+/// the names of pbr cameras
+pub mod camera {
+    pub const DIRECTIONAL_LIGHT_SHADOW: &str = "DirectionalLightShadow";
 }
 
 /// the names of pbr uniforms
 pub mod uniform {
     pub const LIGHTS: &str = "Lights";
+    pub const DIRECTIONAL_SHADOW_MAP: &str = "DirectionalShadowMap";
+    pub const DIRECTIONAL_SHADOW_MAP_SAMPLER: &str = "DirectionalShadowMap_sampler";
 }
 
 use crate::prelude::StandardMaterial;
 use bevy_asset::Assets;
 use bevy_ecs::Resources;
 use bevy_render::{
+    camera::ActiveCameras,
+    pass::{
+        LoadOp, Operations, PassDescriptor, RenderPassDepthStencilAttachmentDescriptor,
+        TextureAttachment,
+    },
     pipeline::PipelineDescriptor,
-    render_graph::{base, AssetRenderResourcesNode, RenderGraph, RenderResourcesNode},
+    render_graph::{
+        base::{self, MainPass},
+        AssetRenderResourcesNode, CameraNode, PassNode, RenderGraph, RenderResourcesNode,
+    },
     shader::Shader,
 };
 use bevy_transform::prelude::Transform;
@@ -32,13 +51,38 @@ pub(crate) fn add_pbr_graph(graph: &mut RenderGraph, resources: &Resources) {
         node::STANDARD_MATERIAL,
         AssetRenderResourcesNode::<StandardMaterial>::new(true),
     );
-    graph.add_system_node(node::LIGHTS, LightsNode::new(10));
+    graph.add_system_node(node::LIGHTS, LightsNode::new(10, 4));
+    graph.add_system_node(
+        node::DIRECTIONAL_LIGHT_SHADOW_CAMERA,
+        CameraNode::new(camera::DIRECTIONAL_LIGHT_SHADOW),
+    );
+
+    let mut shadow_pass = PassNode::<&MainPass>::new(PassDescriptor {
+        color_attachments: Vec::new(),
+        depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+            attachment: TextureAttachment::Input("depth".to_string()),
+            depth_ops: Some(Operations {
+                load: LoadOp::Clear(1.0),
+                store: true,
+            }),
+            stencil_ops: None,
+        }),
+        sample_count: 1,
+    });
+    shadow_pass.add_camera(camera::DIRECTIONAL_LIGHT_SHADOW);
+    graph.add_node(node::DIRECTIONAL_LIGHT_SHADOW_PASS, shadow_pass);
+    resources
+        .get_mut::<ActiveCameras>()
+        .unwrap()
+        .add(camera::DIRECTIONAL_LIGHT_SHADOW);
+
     let mut shaders = resources.get_mut::<Assets<Shader>>().unwrap();
     let mut pipelines = resources.get_mut::<Assets<PipelineDescriptor>>().unwrap();
     pipelines.set(
         FORWARD_PIPELINE_HANDLE,
         build_forward_pipeline(&mut shaders),
     );
+    pipelines.set(SHADOW_PIPELINE_HANDLE, build_shadow_pipeline(&mut shaders));
 
     // TODO: replace these with "autowire" groups
     graph
@@ -50,4 +94,32 @@ pub(crate) fn add_pbr_graph(graph: &mut RenderGraph, resources: &Resources) {
     graph
         .add_node_edge(node::LIGHTS, base::node::MAIN_PASS)
         .unwrap();
+
+    graph
+        .add_slot_edge(
+            node::LIGHTS,
+            LightsNode::OUT_SHADOW_MAP,
+            node::DIRECTIONAL_LIGHT_SHADOW_PASS,
+            "depth",
+        )
+        .unwrap();
+    graph
+        .add_node_edge(
+            node::DIRECTIONAL_LIGHT_SHADOW_CAMERA,
+            node::DIRECTIONAL_LIGHT_SHADOW_PASS,
+        )
+        .unwrap();
+    graph
+        .add_node_edge(node::TRANSFORM, node::DIRECTIONAL_LIGHT_SHADOW_PASS)
+        .unwrap();
+    graph
+        .add_node_edge(
+            base::node::INSTANCED_MESHES,
+            node::DIRECTIONAL_LIGHT_SHADOW_PASS,
+        )
+        .unwrap();
+    // the main pass samples the shadow map
+    graph
+        .add_node_edge(node::DIRECTIONAL_LIGHT_SHADOW_PASS, base::node::MAIN_PASS)
+        .unwrap();
 }
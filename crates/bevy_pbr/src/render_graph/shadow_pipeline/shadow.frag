@@ -0,0 +1,10 @@
+#version 450
+
+// the shadow pass only writes depth. the camera is declared so that the pass can bind it for
+// both stages, like it does for other pipelines
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 ViewProj;
+};
+
+void main() {
+}
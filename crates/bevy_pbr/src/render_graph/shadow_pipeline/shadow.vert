@@ -0,0 +1,33 @@
+#version 450
+
+layout(location = 0) in vec3 Vertex_Position;
+layout(location = 1) in vec3 Vertex_Normal;
+layout(location = 2) in vec2 Vertex_Uv;
+
+# ifdef INSTANCED
+layout(location = 3) in vec4 I_MeshInstance_Model_0;
+layout(location = 4) in vec4 I_MeshInstance_Model_1;
+layout(location = 5) in vec4 I_MeshInstance_Model_2;
+layout(location = 6) in vec4 I_MeshInstance_Model_3;
+# endif
+
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 ViewProj;
+};
+
+layout(set = 1, binding = 0) uniform Transform {
+    mat4 Model;
+};
+
+void main() {
+# ifdef INSTANCED
+    mat4 model = Model * mat4(
+        I_MeshInstance_Model_0,
+        I_MeshInstance_Model_1,
+        I_MeshInstance_Model_2,
+        I_MeshInstance_Model_3);
+# else
+    mat4 model = Model;
+# endif
+    gl_Position = ViewProj * model * vec4(Vertex_Position, 1.0);
+}
@@ -0,0 +1,230 @@
+use crate::{
+    light::{DirectionalLight, ShadowBounds},
+    render_graph::camera,
+};
+use bevy_ecs::{Commands, Entity, Local, Query, ResMut};
+use bevy_math::{FaceToward, Mat4, Vec3, Vec4};
+use bevy_render::{
+    camera::{ActiveCameras, Camera, VisibleEntities},
+    render_graph::base,
+};
+use bevy_transform::prelude::Transform;
+
+/// Local "directional light shadow system" state
+#[derive(Default)]
+pub struct DirectionalLightShadowState {
+    shadow_camera: Option<Entity>,
+}
+
+/// Spawns a camera that renders the shadow map of the first [DirectionalLight] with shadows enabled,
+/// and fits its orthographic view to the light's [ShadowBounds]
+pub fn directional_light_shadow_system(
+    mut commands: Commands,
+    mut state: Local<DirectionalLightShadowState>,
+    mut active_cameras: ResMut<ActiveCameras>,
+    mut light_query: Query<&DirectionalLight>,
+    camera_query: Query<(&mut Camera, &mut Transform)>,
+) {
+    let light = light_query
+        .iter()
+        .iter()
+        .find(|light| light.shadows_enabled)
+        .cloned();
+    let light = match light {
+        Some(light) => light,
+        None => {
+            if let Some(shadow_camera) = state.shadow_camera.take() {
+                commands.despawn(shadow_camera);
+                // the pass node would otherwise keep drawing from the despawned camera
+                active_cameras.add(camera::DIRECTIONAL_LIGHT_SHADOW);
+            }
+            return;
+        }
+    };
+
+    let view_camera = active_cameras
+        .get(base::camera::CAMERA3D)
+        .and_then(|entity| {
+            let camera = camera_query.get::<Camera>(entity).ok()?;
+            let transform = camera_query.get::<Transform>(entity).ok()?;
+            Some(camera.projection_matrix * transform.value.inverse())
+        });
+    let (view, projection) = match shadow_view_projection(&light, view_camera) {
+        Some(view_projection) => view_projection,
+        None => return,
+    };
+
+    match state.shadow_camera {
+        Some(shadow_camera) => {
+            if let Ok(mut camera) = camera_query.get_mut::<Camera>(shadow_camera) {
+                camera.projection_matrix = projection;
+            }
+            if let Ok(mut transform) = camera_query.get_mut::<Transform>(shadow_camera) {
+                transform.value = view;
+            }
+        }
+        None => {
+            commands.spawn((
+                Camera {
+                    name: Some(camera::DIRECTIONAL_LIGHT_SHADOW.to_string()),
+                    projection_matrix: projection,
+                    ..Default::default()
+                },
+                Transform::new_sync_disabled(view),
+                VisibleEntities::default(),
+            ));
+            state.shadow_camera = commands.current_entity();
+        }
+    }
+}
+
+/// Returns the transform and orthographic projection of a camera looking along the light's direction
+/// that sees everything within the light's shadow bounds. `view_camera` is the view-projection of the
+/// camera the bounds are fit to, if they depend on it.
+fn shadow_view_projection(
+    light: &DirectionalLight,
+    view_camera: Option<Mat4>,
+) -> Option<(Mat4, Mat4)> {
+    let corners = match light.shadow_bounds {
+        ShadowBounds::CameraView { distance } => {
+            let inverse_view_camera = view_camera?.inverse();
+            let to_world = |x: f32, y: f32, z: f32| {
+                let position = inverse_view_camera * Vec4::new(x, y, z, 1.0);
+                position.truncate() / position.w()
+            };
+            let mut corners = Vec::with_capacity(8);
+            for &(x, y) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)].iter() {
+                let near = to_world(x, y, 0.0);
+                let far = to_world(x, y, 1.0);
+                // only cover the part of the view frustum that is closer than `distance`
+                let length = (far - near).length();
+                corners.push(near);
+                corners.push(near + (far - near) * (distance / length).min(1.0));
+            }
+            corners
+        }
+        ShadowBounds::Fixed {
+            center,
+            half_extents,
+        } => {
+            let mut corners = Vec::with_capacity(8);
+            for &x in [-1.0, 1.0].iter() {
+                for &y in [-1.0, 1.0].iter() {
+                    for &z in [-1.0, 1.0].iter() {
+                        corners.push(center + half_extents * Vec3::new(x, y, z));
+                    }
+                }
+            }
+            corners
+        }
+    };
+
+    let center = corners
+        .iter()
+        .fold(Vec3::zero(), |sum, corner| sum + *corner)
+        / 8.0;
+    let radius = corners
+        .iter()
+        .map(|corner| (*corner - center).length())
+        .fold(0.0, f32::max);
+    let direction = light.direction.normalize();
+    let up = if direction.y().abs() > 0.99 {
+        Vec3::unit_z()
+    } else {
+        Vec3::unit_y()
+    };
+    // back the camera up past the bounds, so that objects between them and the light still cast
+    // shadows into them
+    let eye = center - direction * radius * 2.0;
+    let view = Mat4::face_toward(eye, center, up);
+
+    let inverse_view = view.inverse();
+    let mut min = Vec3::splat(std::f32::MAX);
+    let mut max = Vec3::splat(std::f32::MIN);
+    for corner in corners.iter() {
+        let corner = (inverse_view * corner.extend(1.0)).truncate();
+        min = min.min(corner);
+        max = max.max(corner);
+    }
+    // the camera looks down -z, and everything from the camera to the far side of the bounds is kept
+    let projection = Mat4::orthographic_rh(min.x(), max.x(), min.y(), max.y(), 0.0, -min.z());
+    Some((view, projection))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::shadow_view_projection;
+    use crate::light::{DirectionalLight, ShadowBounds};
+    use bevy_math::{Mat4, Vec3};
+
+    fn assert_encloses(view: Mat4, projection: Mat4, point: Vec3) {
+        let clip = projection * view.inverse() * point.extend(1.0);
+        let ndc = clip.truncate() / clip.w();
+        let epsilon = 1e-4;
+        assert!(
+            ndc.x().abs() <= 1.0 + epsilon
+                && ndc.y().abs() <= 1.0 + epsilon
+                && ndc.z() >= -epsilon
+                && ndc.z() <= 1.0 + epsilon,
+            "{:?} is outside the shadow projection at {:?}",
+            point,
+            ndc
+        );
+    }
+
+    #[test]
+    fn encloses_fixed_bounds() {
+        let center = Vec3::new(10.0, 2.0, -5.0);
+        let half_extents = Vec3::new(4.0, 1.0, 3.0);
+        for direction in [
+            Vec3::new(1.0, -1.0, 0.5),
+            Vec3::new(0.0, -1.0, 0.0),
+            Vec3::new(-0.3, 0.2, 1.0),
+        ]
+        .iter()
+        {
+            let light = DirectionalLight {
+                direction: *direction,
+                shadow_bounds: ShadowBounds::Fixed {
+                    center,
+                    half_extents,
+                },
+                ..Default::default()
+            };
+            let (view, projection) = shadow_view_projection(&light, None).unwrap();
+            for &x in [-1.0, 1.0].iter() {
+                for &y in [-1.0, 1.0].iter() {
+                    for &z in [-1.0, 1.0].iter() {
+                        let corner = center + half_extents * Vec3::new(x, y, z);
+                        assert_encloses(view, projection, corner);
+                    }
+                }
+            }
+            assert_encloses(view, projection, center);
+        }
+    }
+
+    #[test]
+    fn encloses_the_near_part_of_the_camera_view() {
+        let light = DirectionalLight {
+            direction: Vec3::new(0.5, -1.0, -0.2),
+            shadow_bounds: ShadowBounds::CameraView { distance: 20.0 },
+            ..Default::default()
+        };
+        // looking down -z from the origin
+        let view_camera = Mat4::perspective_rh(std::f32::consts::PI / 4.0, 1.0, 1.0, 1000.0);
+        assert!(shadow_view_projection(&light, None).is_none());
+        let (view, projection) = shadow_view_projection(&light, Some(view_camera)).unwrap();
+
+        for point in [
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 0.0, -17.0),
+            Vec3::new(4.0, -4.0, -10.0),
+            Vec3::new(-7.0, 7.0, -17.5),
+        ]
+        .iter()
+        {
+            assert_encloses(view, projection, *point);
+        }
+    }
+}
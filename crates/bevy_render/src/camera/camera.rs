@@ -1,9 +1,10 @@
-use super::CameraProjection;
+use super::{CameraProjection, Frustum};
 use bevy_app::prelude::{EventReader, Events};
-use bevy_ecs::{Component, Local, Query, Res};
-use bevy_math::Mat4;
+use bevy_ecs::{Changed, Component, Entity, Local, Query, Res};
+use bevy_math::{Mat4, Ray, Vec2, Vec3};
 use bevy_property::Properties;
-use bevy_window::{WindowCreated, WindowId, WindowResized, Windows};
+use bevy_transform::prelude::Transform;
+use bevy_window::{Window, WindowCreated, WindowId, WindowResized, Windows};
 
 #[derive(Default, Debug, Properties)]
 pub struct Camera {
@@ -15,6 +16,63 @@ pub struct Camera {
     pub depth_calculation: DepthCalculation,
 }
 
+impl Camera {
+    /// Returns the volume the camera sees when it has the given transform
+    pub fn frustum(&self, transform: &Transform) -> Frustum {
+        Frustum::from_view_projection(&(self.projection_matrix * transform.value.inverse()))
+    }
+
+    /// Returns where `world_position` appears in `window` when the camera has the given transform,
+    /// in logical pixels from the bottom left corner like cursor positions. Returns `None` if the
+    /// point is behind the camera. Points outside the camera's view are projected off-screen.
+    pub fn world_to_screen(
+        &self,
+        transform: &Transform,
+        world_position: Vec3,
+        window: &Window,
+    ) -> Option<Vec2> {
+        let view_position = transform
+            .value
+            .inverse()
+            .mul_vec4(world_position.extend(1.0));
+        let clip_position = self.projection_matrix.mul_vec4(view_position);
+        // cameras look down -z, and perspective projections put the camera's depth in w
+        if view_position.z() > 0.0 || clip_position.w() <= 0.0 {
+            return None;
+        }
+
+        let ndc = clip_position.truncate().truncate() / clip_position.w();
+        let window_size = Vec2::new(window.width(), window.height());
+        Some((ndc + Vec2::one()) / 2.0 * window_size)
+    }
+
+    /// Returns the ray from the camera, with the given transform, through `screen_position` in
+    /// `window`. `screen_position` is in logical pixels from the bottom left corner, like cursor
+    /// positions. The ray starts on the near plane.
+    pub fn screen_to_world_ray(
+        &self,
+        transform: &Transform,
+        screen_position: Vec2,
+        window: &Window,
+    ) -> Ray {
+        let window_size = Vec2::new(window.width(), window.height());
+        let ndc = screen_position / window_size * 2.0 - Vec2::one();
+        let ndc_to_world = transform.value * self.projection_matrix.inverse();
+        let unproject = |depth: f32| {
+            let position = ndc_to_world.mul_vec4(ndc.extend(depth).extend(1.0));
+            position.truncate() / position.w()
+        };
+        // depth runs from 0 on the near plane to 1 on the far plane. Halfway is used rather than
+        // the far plane itself, which is at infinity for infinite projections.
+        let near = unproject(0.0);
+        let direction = (unproject(0.5) - near).normalize();
+        Ray {
+            origin: near,
+            direction,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum DepthCalculation {
     Distance,
@@ -38,7 +96,8 @@ pub fn camera_system<T: CameraProjection + Component>(
     window_resized_events: Res<Events<WindowResized>>,
     window_created_events: Res<Events<WindowCreated>>,
     windows: Res<Windows>,
-    mut query: Query<(&mut Camera, &mut T)>,
+    mut changed_query: Query<(Entity, Changed<T>)>,
+    mut query: Query<(Entity, &mut Camera, &mut T)>,
 ) {
     let mut changed_window_ids = Vec::new();
     // handle resize events. latest events are handled first because we only want to resize each window once
@@ -67,13 +126,81 @@ pub fn camera_system<T: CameraProjection + Component>(
         changed_window_ids.push(event.id);
     }
 
-    for (mut camera, mut camera_projection) in &mut query.iter() {
+    // new cameras and cameras whose projection settings changed also need their projection updated
+    let changed_projections = changed_query
+        .iter()
+        .iter()
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+
+    for (entity, mut camera, mut camera_projection) in &mut query.iter() {
         if let Some(window) = windows.get(camera.window) {
-            if changed_window_ids.contains(&window.id) {
-                camera_projection.update(window.width as usize, window.height as usize);
+            if changed_window_ids.contains(&window.id) || changed_projections.contains(&entity) {
+                camera_projection.update(window.width(), window.height());
                 camera.projection_matrix = camera_projection.get_projection_matrix();
                 camera.depth_calculation = camera_projection.depth_calculation();
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Camera;
+    use crate::camera::{CameraProjection, OrthographicProjection, PerspectiveProjection};
+    use bevy_math::{Mat4, Vec2, Vec3};
+    use bevy_transform::prelude::Transform;
+    use bevy_window::{Window, WindowDescriptor, WindowId};
+
+    fn window() -> Window {
+        Window::new(
+            WindowId::new(),
+            &WindowDescriptor {
+                width: 800.0,
+                height: 600.0,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn camera(projection: &mut impl CameraProjection, window: &Window) -> Camera {
+        projection.update(window.width(), window.height());
+        Camera {
+            projection_matrix: projection.get_projection_matrix(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_screen_round_trip() {
+        let window = window();
+        let transform = Transform::new(Mat4::from_translation(Vec3::new(1.0, 2.0, 10.0)));
+        let cameras = [
+            camera(&mut PerspectiveProjection::default(), &window),
+            camera(&mut OrthographicProjection::default(), &window),
+        ];
+        for camera in cameras.iter() {
+            let point = Vec3::new(3.0, 1.0, -20.0);
+            let screen_position = camera.world_to_screen(&transform, point, &window).unwrap();
+            let ray = camera.screen_to_world_ray(&transform, screen_position, &window);
+            let to_point = point - ray.origin;
+            let closest = ray.point_at(to_point.dot(ray.direction));
+            assert!((closest - point).length() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_world_to_screen() {
+        let window = window();
+        let transform = Transform::new(Mat4::from_translation(Vec3::new(0.0, 0.0, 10.0)));
+        let camera = camera(&mut PerspectiveProjection::default(), &window);
+        assert_eq!(
+            camera.world_to_screen(&transform, Vec3::zero(), &window),
+            Some(Vec2::new(400.0, 300.0))
+        );
+        assert_eq!(
+            camera.world_to_screen(&transform, Vec3::new(0.0, 0.0, 20.0), &window),
+            None
+        );
+    }
+}
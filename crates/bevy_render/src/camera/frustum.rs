@@ -0,0 +1,112 @@
+use crate::mesh::Aabb;
+use bevy_math::{Mat4, Vec4};
+
+/// The volume a camera can see, as six planes whose normals point inwards. Each plane is stored as
+/// `(normal, distance)`, so that a point `p` is in front of the plane when `normal.dot(p) + distance`
+/// is positive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    pub planes: [Vec4; 6],
+}
+
+impl Frustum {
+    /// Extracts the frustum planes from a view-projection matrix with a depth range of `0..1`
+    pub fn from_view_projection(view_projection: &Mat4) -> Self {
+        let rows = view_projection.transpose();
+        let (row_x, row_y, row_z, row_w) =
+            (rows.x_axis(), rows.y_axis(), rows.z_axis(), rows.w_axis());
+        let mut planes = [
+            // left, right
+            row_w + row_x,
+            row_w - row_x,
+            // bottom, top
+            row_w + row_y,
+            row_w - row_y,
+            // near, far
+            row_z,
+            row_w - row_z,
+        ];
+        for plane in planes.iter_mut() {
+            *plane /= plane.truncate().length();
+        }
+
+        Frustum { planes }
+    }
+
+    /// Returns whether any part of `aabb`, transformed by `model`, could be inside the frustum. This
+    /// errs on the side of returning `true`, so boxes that straddle the frustum are never culled.
+    pub fn intersects_aabb(&self, aabb: &Aabb, model: &Mat4) -> bool {
+        let world_aabb = aabb.transformed(model);
+        let center = world_aabb.center.extend(1.0);
+        for plane in self.planes.iter() {
+            let normal = plane.truncate();
+            // the distance from the center to the corner furthest in front of the plane
+            let radius = normal.abs().dot(world_aabb.half_extents);
+            if plane.dot(center) < -radius {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Frustum;
+    use crate::mesh::Aabb;
+    use bevy_math::{Mat4, Vec3};
+
+    fn frustum() -> Frustum {
+        // a camera at the origin looking down -z
+        let projection = Mat4::perspective_rh(std::f32::consts::PI / 2.0, 1.0, 1.0, 100.0);
+        Frustum::from_view_projection(&projection)
+    }
+
+    fn cube(center: Vec3, half_size: f32) -> Aabb {
+        Aabb {
+            center,
+            half_extents: Vec3::splat(half_size),
+        }
+    }
+
+    #[test]
+    fn test_inside() {
+        let aabb = cube(Vec3::new(0.0, 0.0, -10.0), 1.0);
+        assert!(frustum().intersects_aabb(&aabb, &Mat4::identity()));
+    }
+
+    #[test]
+    fn test_outside() {
+        let frustum = frustum();
+        for center in [
+            Vec3::new(0.0, 0.0, 10.0),
+            Vec3::new(0.0, 0.0, -200.0),
+            Vec3::new(50.0, 0.0, -10.0),
+            Vec3::new(0.0, -50.0, -10.0),
+        ]
+        .iter()
+        {
+            let aabb = cube(*center, 1.0);
+            assert!(!frustum.intersects_aabb(&aabb, &Mat4::identity()));
+        }
+    }
+
+    #[test]
+    fn test_large_aabb_straddling_frustum() {
+        // the center is behind the camera, but the box reaches far into the frustum
+        let aabb = cube(Vec3::new(0.0, 0.0, 40.0), 60.0);
+        assert!(frustum().intersects_aabb(&aabb, &Mat4::identity()));
+    }
+
+    #[test]
+    fn test_transformed_aabb() {
+        // closer to the camera than the near plane
+        let aabb = cube(Vec3::new(0.0, 0.0, -0.25), 0.5);
+        let frustum = frustum();
+        assert!(!frustum.intersects_aabb(&aabb, &Mat4::identity()));
+        assert!(frustum.intersects_aabb(&aabb, &Mat4::from_translation(Vec3::new(0.0, 0.0, -10.0))));
+        // scaling the box up makes it reach past the near plane
+        assert!(frustum.intersects_aabb(&aabb, &Mat4::from_scale(Vec3::splat(4.0))));
+    }
+}
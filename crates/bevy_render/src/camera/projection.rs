@@ -5,7 +5,8 @@ use serde::{Deserialize, Serialize};
 
 pub trait CameraProjection {
     fn get_projection_matrix(&self) -> Mat4;
-    fn update(&mut self, width: usize, height: usize);
+    /// Fits the projection to a window of the given logical size
+    fn update(&mut self, width: f32, height: f32);
     fn depth_calculation(&self) -> DepthCalculation;
 }
 
@@ -22,8 +23,8 @@ impl CameraProjection for PerspectiveProjection {
         Mat4::perspective_rh(self.fov, self.aspect_ratio, self.near, self.far)
     }
 
-    fn update(&mut self, width: usize, height: usize) {
-        self.aspect_ratio = width as f32 / height as f32;
+    fn update(&mut self, width: f32, height: f32) {
+        self.aspect_ratio = width / height;
     }
 
     fn depth_calculation(&self) -> DepthCalculation {
@@ -49,6 +50,19 @@ pub enum WindowOrigin {
     BottomLeft,
 }
 
+/// How an [OrthographicProjection] maps the window to world units
+#[derive(Debug, Clone, Property, Serialize, Deserialize)]
+pub enum ScalingMode {
+    /// One world unit is one logical pixel
+    WindowSize,
+    /// The window is this many world units wide, regardless of its size. The height follows the
+    /// window's aspect ratio.
+    FixedHorizontal(f32),
+    /// The window is this many world units tall, regardless of its size. The width follows the
+    /// window's aspect ratio.
+    FixedVertical(f32),
+}
+
 #[derive(Debug, Clone, Properties)]
 pub struct OrthographicProjection {
     pub left: f32,
@@ -58,6 +72,9 @@ pub struct OrthographicProjection {
     pub near: f32,
     pub far: f32,
     pub window_origin: WindowOrigin,
+    pub scaling_mode: ScalingMode,
+    /// Multiplies the size of the visible area. Values above 1.0 zoom out, values below zoom in.
+    pub scale: f32,
 }
 
 impl CameraProjection for OrthographicProjection {
@@ -72,11 +89,21 @@ impl CameraProjection for OrthographicProjection {
         )
     }
 
-    fn update(&mut self, width: usize, height: usize) {
+    fn update(&mut self, width: f32, height: f32) {
+        let (width, height) = match self.scaling_mode {
+            ScalingMode::WindowSize => (width, height),
+            ScalingMode::FixedHorizontal(world_width) => {
+                (world_width, world_width * height / width)
+            }
+            ScalingMode::FixedVertical(world_height) => {
+                (world_height * width / height, world_height)
+            }
+        };
+        let (width, height) = (width * self.scale, height * self.scale);
         match self.window_origin {
             WindowOrigin::Center => {
-                let half_width = width as f32 / 2.0;
-                let half_height = height as f32 / 2.0;
+                let half_width = width / 2.0;
+                let half_height = height / 2.0;
                 self.left = -half_width;
                 self.right = half_width;
                 self.top = half_height;
@@ -84,8 +111,8 @@ impl CameraProjection for OrthographicProjection {
             }
             WindowOrigin::BottomLeft => {
                 self.left = 0.0;
-                self.right = width as f32;
-                self.top = height as f32;
+                self.right = width;
+                self.top = height;
                 self.bottom = 0.0;
             }
         }
@@ -106,6 +133,45 @@ impl Default for OrthographicProjection {
             near: 0.0,
             far: 1000.0,
             window_origin: WindowOrigin::Center,
+            scaling_mode: ScalingMode::WindowSize,
+            scale: 1.0,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CameraProjection, OrthographicProjection, ScalingMode, WindowOrigin};
+
+    fn bounds(projection: &OrthographicProjection) -> (f32, f32, f32, f32) {
+        (
+            projection.left,
+            projection.right,
+            projection.bottom,
+            projection.top,
+        )
+    }
+
+    #[test]
+    fn test_orthographic_scaling_modes() {
+        let mut projection = OrthographicProjection::default();
+        projection.update(800.0, 600.0);
+        assert_eq!(bounds(&projection), (-400.0, 400.0, -300.0, 300.0));
+
+        projection.scale = 2.0;
+        projection.update(800.0, 600.0);
+        assert_eq!(bounds(&projection), (-800.0, 800.0, -600.0, 600.0));
+
+        projection.scale = 1.0;
+        projection.scaling_mode = ScalingMode::FixedVertical(10.0);
+        projection.update(800.0, 600.0);
+        let (left, right, bottom, top) = bounds(&projection);
+        assert_eq!((bottom, top), (-5.0, 5.0));
+        assert!((right - left - 40.0 / 3.0).abs() < 1e-5);
+
+        projection.scaling_mode = ScalingMode::FixedHorizontal(8.0);
+        projection.window_origin = WindowOrigin::BottomLeft;
+        projection.update(800.0, 600.0);
+        assert_eq!(bounds(&projection), (0.0, 8.0, 0.0, 6.0));
+    }
+}
@@ -1,5 +1,5 @@
 use super::{Camera, DepthCalculation};
-use crate::Draw;
+use crate::{mesh::Aabb, Draw};
 use bevy_core::FloatOrd;
 use bevy_ecs::{Entity, Query};
 use bevy_property::Properties;
@@ -27,10 +27,12 @@ pub fn visible_entities_system(
     mut camera_query: Query<(&Camera, &Transform, &mut VisibleEntities)>,
     mut draw_query: Query<(Entity, &Draw)>,
     draw_transform_query: Query<(&Draw, &Transform)>,
+    draw_aabb_query: Query<(&Draw, &Aabb)>,
 ) {
     for (camera, camera_transform, mut visible_entities) in &mut camera_query.iter() {
         visible_entities.value.clear();
         let camera_position = camera_transform.value.w_axis().truncate();
+        let frustum = camera.frustum(&camera_transform);
 
         let mut no_transform_order = 0.0;
         let mut transparent_entities = Vec::new();
@@ -40,6 +42,13 @@ pub fn visible_entities_system(
             }
 
             let order = if let Ok(transform) = draw_transform_query.get::<Transform>(entity) {
+                // entities without bounds are always drawn
+                if let Ok(aabb) = draw_aabb_query.get::<Aabb>(entity) {
+                    if !frustum.intersects_aabb(&aabb, &transform.value) {
+                        continue;
+                    }
+                }
+
                 let position = transform.value.w_axis().truncate();
                 // smaller distances are sorted to lower indices by using the distance from the camera
                 FloatOrd(match camera.depth_calculation {
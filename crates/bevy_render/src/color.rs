@@ -4,13 +4,21 @@ use crate::{
     renderer::{RenderResource, RenderResourceType},
 };
 use bevy_asset::Handle;
-use bevy_core::{Byteable, Bytes};
+use bevy_core::{AsBytes, Bytes};
 use bevy_math::{Vec3, Vec4};
 use bevy_property::Property;
 use serde::{Deserialize, Serialize};
 use std::ops::{Add, AddAssign, Mul, MulAssign};
 
 /// A RGBA color
+///
+/// The components are in sRGB space, which is how colors are usually picked, and how hex codes
+/// and image files store them. They are converted to linear space when sent to the GPU, so colors
+/// match across sprites, text and materials. Use the `_linear` constructors and accessors when
+/// working with linear values.
+///
+/// Multiplying two colors tints in linear space. All other arithmetic operators work directly on
+/// the sRGB components.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Property)]
 pub struct Color {
@@ -20,8 +28,6 @@ pub struct Color {
     pub a: f32,
 }
 
-unsafe impl Byteable for Color {}
-
 impl Color {
     pub const BLACK: Color = Color::rgb(0.0, 0.0, 0.0);
     pub const BLUE: Color = Color::rgb(0.0, 0.0, 1.0);
@@ -38,8 +44,43 @@ impl Color {
         Color { r, g, b, a }
     }
 
+    /// Creates a color from linear RGB components
+    pub fn rgb_linear(r: f32, g: f32, b: f32) -> Color {
+        Color::rgba_linear(r, g, b, 1.0)
+    }
+
+    /// Creates a color from linear RGB components and alpha
+    pub fn rgba_linear(r: f32, g: f32, b: f32, a: f32) -> Color {
+        Color::rgba(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), a)
+    }
+
+    /// Creates a color from hue in degrees, and saturation and lightness between 0.0 and 1.0
+    pub fn hsl(hue: f32, saturation: f32, lightness: f32) -> Color {
+        Color::hsla(hue, saturation, lightness, 1.0)
+    }
+
+    /// Creates a color from hue in degrees, and saturation, lightness and alpha between 0.0 and 1.0
+    pub fn hsla(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Color {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = lightness - chroma / 2.0;
+        Color::rgba(r + m, g + m, b + m, alpha)
+    }
+
+    /// Parses a hex color code like `"#ff8800"`. The `#` is optional, and the short `RGB` and
+    /// `RGBA` forms and an alpha component are supported.
     pub fn hex<T: AsRef<str>>(hex: T) -> Result<Color, HexColorError> {
         let hex = hex.as_ref();
+        let hex = if hex.starts_with('#') { &hex[1..] } else { hex };
 
         // RGB
         if hex.len() == 3 {
@@ -88,6 +129,48 @@ impl Color {
             a as f32 / u8::MAX as f32,
         )
     }
+
+    /// Returns the sRGB components and alpha
+    pub fn as_rgba_f32(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    /// Returns the linear components and alpha, as shaders expect them
+    pub fn as_linear_rgba_f32(self) -> [f32; 4] {
+        [
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+            self.a,
+        ]
+    }
+
+    /// Blends from `self` at `t == 0.0` to `other` at `t == 1.0`. Blending happens in linear
+    /// space, like light mixing, so halfway between two colors isn't darker than either.
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let from = Vec4::from(self.as_linear_rgba_f32());
+        let to = Vec4::from(other.as_linear_rgba_f32());
+        let blended = from + (to - from) * t;
+        Color::rgba_linear(blended.x(), blended.y(), blended.z(), blended.w())
+    }
+}
+
+/// Converts an sRGB component to linear space
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear component to sRGB space
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
 }
 
 impl Default for Color {
@@ -97,6 +180,7 @@ impl Default for Color {
 }
 
 impl AddAssign<Color> for Color {
+    /// Adds the sRGB components and alpha
     fn add_assign(&mut self, rhs: Color) {
         *self = Color {
             r: self.r + rhs.r,
@@ -110,6 +194,7 @@ impl AddAssign<Color> for Color {
 impl Add<Color> for Color {
     type Output = Color;
 
+    /// Adds the sRGB components and alpha
     fn add(self, rhs: Color) -> Self::Output {
         Color {
             r: self.r + rhs.r,
@@ -123,6 +208,7 @@ impl Add<Color> for Color {
 impl Add<Vec4> for Color {
     type Output = Color;
 
+    /// Adds `rhs` to the sRGB components and alpha
     fn add(self, rhs: Vec4) -> Self::Output {
         Color {
             r: self.r + rhs.x(),
@@ -146,12 +232,26 @@ impl From<Vec4> for Color {
 
 impl Into<[f32; 4]> for Color {
     fn into(self) -> [f32; 4] {
-        [self.r, self.g, self.b, self.a]
+        self.as_rgba_f32()
     }
 }
+
+impl Mul<Color> for Color {
+    type Output = Color;
+
+    /// Tints `self` by `rhs`, multiplying component-wise in linear space, like light reflected
+    /// off a colored surface
+    fn mul(self, rhs: Color) -> Self::Output {
+        let tinted = Vec4::from(self.as_linear_rgba_f32()) * Vec4::from(rhs.as_linear_rgba_f32());
+        Color::rgba_linear(tinted.x(), tinted.y(), tinted.z(), tinted.w())
+    }
+}
+
 impl Mul<f32> for Color {
     type Output = Color;
 
+    /// Scales the sRGB components and alpha. Unlike tinting, this isn't a linear change in
+    /// brightness.
     fn mul(self, rhs: f32) -> Self::Output {
         Color {
             r: self.r * rhs,
@@ -163,6 +263,7 @@ impl Mul<f32> for Color {
 }
 
 impl MulAssign<f32> for Color {
+    /// Scales the sRGB components and alpha
     fn mul_assign(&mut self, rhs: f32) {
         self.r *= rhs;
         self.g *= rhs;
@@ -174,6 +275,7 @@ impl MulAssign<f32> for Color {
 impl Mul<Vec4> for Color {
     type Output = Color;
 
+    /// Scales the sRGB components and alpha by the components of `rhs`
     fn mul(self, rhs: Vec4) -> Self::Output {
         Color {
             r: self.r * rhs.x(),
@@ -185,6 +287,7 @@ impl Mul<Vec4> for Color {
 }
 
 impl MulAssign<Vec4> for Color {
+    /// Scales the sRGB components and alpha by the components of `rhs`
     fn mul_assign(&mut self, rhs: Vec4) {
         self.r *= rhs.x();
         self.g *= rhs.y();
@@ -196,6 +299,7 @@ impl MulAssign<Vec4> for Color {
 impl Mul<Vec3> for Color {
     type Output = Color;
 
+    /// Scales the sRGB components by the components of `rhs`, leaving alpha unchanged
     fn mul(self, rhs: Vec3) -> Self::Output {
         Color {
             r: self.r * rhs.x(),
@@ -207,6 +311,7 @@ impl Mul<Vec3> for Color {
 }
 
 impl MulAssign<Vec3> for Color {
+    /// Scales the sRGB components by the components of `rhs`, leaving alpha unchanged
     fn mul_assign(&mut self, rhs: Vec3) {
         self.r *= rhs.x();
         self.g *= rhs.y();
@@ -214,6 +319,18 @@ impl MulAssign<Vec3> for Color {
     }
 }
 
+impl Bytes for Color {
+    fn write_bytes(&self, buffer: &mut [u8]) {
+        // shaders work in linear space
+        let linear = self.as_linear_rgba_f32();
+        buffer[0..self.byte_len()].copy_from_slice(linear.as_bytes());
+    }
+
+    fn byte_len(&self) -> usize {
+        std::mem::size_of::<[f32; 4]>()
+    }
+}
+
 impl Bytes for ColorSource {
     fn write_bytes(&self, buffer: &mut [u8]) {
         match *self {
@@ -315,3 +432,81 @@ fn test_hex_color() {
 
     assert!(Color::hex("1234567890").is_err());
 }
+
+#[test]
+fn test_hex_color_prefix() {
+    assert_eq!(
+        Color::hex("#FF0000").unwrap(),
+        Color::hex("FF0000").unwrap()
+    );
+    assert_eq!(Color::hex("#ff8800").unwrap(), Color::rgb_u8(255, 136, 0));
+    assert!(Color::hex("#").is_err());
+}
+
+#[test]
+fn test_hsl_color() {
+    assert_eq!(Color::hsl(0.0, 1.0, 0.5), Color::RED);
+    assert_eq!(Color::hsl(120.0, 1.0, 0.5), Color::GREEN);
+    assert_eq!(Color::hsl(240.0, 1.0, 0.5), Color::BLUE);
+    assert_eq!(Color::hsl(-120.0, 1.0, 0.5), Color::BLUE);
+    assert_eq!(Color::hsl(0.0, 0.0, 1.0), Color::WHITE);
+    assert_eq!(
+        Color::hsla(60.0, 1.0, 0.5, 0.5),
+        Color::rgba(1.0, 1.0, 0.0, 0.5)
+    );
+}
+
+#[test]
+fn test_linear_color() {
+    assert_eq!(Color::WHITE.as_linear_rgba_f32(), [1.0, 1.0, 1.0, 1.0]);
+    assert_eq!(Color::BLACK.as_linear_rgba_f32(), [0.0, 0.0, 0.0, 1.0]);
+    let [r, _, _, _] = Color::rgb(0.5, 0.0, 0.0).as_linear_rgba_f32();
+    assert!((r - 0.214).abs() < 1e-3);
+
+    let color = Color::rgba(0.2, 0.5, 0.8, 0.4);
+    let [r, g, b, a] = color.as_linear_rgba_f32();
+    let round_trip = Color::rgba_linear(r, g, b, a);
+    for (x, y) in color
+        .as_rgba_f32()
+        .iter()
+        .zip(round_trip.as_rgba_f32().iter())
+    {
+        assert!((x - y).abs() < 1e-5);
+    }
+
+    let mut bytes = [0; 16];
+    color.write_bytes(&mut bytes);
+    assert_eq!(&bytes[..], color.as_linear_rgba_f32().as_bytes());
+
+    let halfway = Color::BLACK.lerp(Color::WHITE, 0.5);
+    assert!((halfway.as_linear_rgba_f32()[0] - 0.5).abs() < 1e-5);
+}
+
+#[test]
+fn test_tinting_multiplies_in_linear_space() {
+    let color = Color::rgba(0.5, 0.8, 0.2, 0.5);
+    let tinted = color * Color::rgba(0.5, 1.0, 0.0, 0.5);
+    let [r, g, b, a] = tinted.as_linear_rgba_f32();
+    let linear = color.as_linear_rgba_f32();
+    assert!((r - linear[0] * srgb_to_linear(0.5)).abs() < 1e-6);
+    assert!((g - linear[1]).abs() < 1e-6);
+    assert_eq!(b, 0.0);
+    assert_eq!(a, 0.25);
+
+    // white leaves colors unchanged
+    let unchanged = color * Color::WHITE;
+    for (x, y) in unchanged
+        .as_rgba_f32()
+        .iter()
+        .zip(color.as_rgba_f32().iter())
+    {
+        assert!((x - y).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_scaling_works_on_srgb_components() {
+    let color = Color::rgba(0.2, 0.4, 0.6, 0.8);
+    assert_eq!(color * 0.5, Color::rgba(0.1, 0.2, 0.3, 0.4));
+    assert_eq!(color + color, Color::rgba(0.4, 0.8, 1.2, 1.6));
+}
@@ -1,7 +1,7 @@
 use crate::{
     pipeline::{
-        PipelineCompiler, PipelineDescriptor, PipelineLayout, PipelineSpecialization,
-        VertexBufferDescriptors,
+        InputStepMode, PipelineCompiler, PipelineDescriptor, PipelineLayout,
+        PipelineSpecialization, VertexBufferDescriptors,
     },
     renderer::{
         BindGroup, BindGroupId, BufferId, BufferUsage, RenderResource, RenderResourceBinding,
@@ -105,12 +105,52 @@ impl Draw {
         });
     }
 
+    pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) {
+        self.render_command(RenderCommand::Draw {
+            vertices,
+            instances,
+        });
+    }
+
+    pub fn draw_vertex_range(&mut self, vertex_range: VertexRange, instances: Range<u32>) {
+        match vertex_range {
+            VertexRange::Indexed(indices) => self.draw_indexed(indices, 0, instances),
+            VertexRange::Vertices(vertices) => self.draw(vertices, instances),
+        }
+    }
+
     #[inline]
     pub fn render_command(&mut self, render_command: RenderCommand) {
         self.render_commands.push(render_command);
     }
 }
 
+/// The vertices to draw with the buffers set by [DrawContext::set_vertex_buffers_from_bindings]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum VertexRange {
+    /// Indices into the vertex buffers, read from the index buffer
+    Indexed(Range<u32>),
+    /// No index buffer is set, so vertices are drawn in order
+    Vertices(Range<u32>),
+}
+
+impl VertexRange {
+    /// The range covering a whole vertex buffer of `vertex_buffer_size` bytes with vertices
+    /// `stride` bytes apart, or the whole `u16` index buffer of `index_buffer_size` bytes if there is
+    /// one
+    pub fn from_buffer_sizes(
+        vertex_buffer_size: usize,
+        stride: u64,
+        index_buffer_size: Option<usize>,
+    ) -> Self {
+        match index_buffer_size {
+            Some(index_buffer_size) => VertexRange::Indexed(0..(index_buffer_size / 2) as u32),
+            None if stride == 0 => VertexRange::Vertices(0..0),
+            None => VertexRange::Vertices(0..(vertex_buffer_size as u64 / stride) as u32),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum DrawError {
     #[error("Pipeline does not exist.")]
@@ -119,6 +159,8 @@ pub enum DrawError {
     NoPipelineSet,
     #[error("Pipeline has no layout")]
     PipelineHasNoLayout,
+    #[error("The pipeline's shaders haven't been loaded yet")]
+    ShadersNotLoaded,
     #[error("Failed to get a buffer for the given RenderResource.")]
     BufferAllocationFailure,
 }
@@ -245,6 +287,20 @@ impl<'a> DrawContext<'a> {
         {
             specialized_pipeline
         } else {
+            let source_descriptor = self
+                .pipelines
+                .get(&pipeline_handle)
+                .ok_or(DrawError::NonExistentPipeline)?;
+            let shader_stages = &source_descriptor.shader_stages;
+            // shaders loaded by the asset server become available a few frames later
+            if self.shaders.get(&shader_stages.vertex).is_none()
+                || shader_stages
+                    .fragment
+                    .map_or(false, |fragment| self.shaders.get(&fragment).is_none())
+            {
+                return Err(DrawError::ShadersNotLoaded);
+            }
+
             self.pipeline_compiler.compile_pipeline(
                 &**self.render_resource_context,
                 &mut self.pipelines,
@@ -331,8 +387,8 @@ impl<'a> DrawContext<'a> {
         &self,
         draw: &mut Draw,
         render_resource_bindings: &[&RenderResourceBindings],
-    ) -> Result<Option<Range<u32>>, DrawError> {
-        let mut indices = None;
+    ) -> Result<Option<VertexRange>, DrawError> {
+        let mut vertex_range = None;
         let pipeline = self
             .current_pipeline
             .ok_or_else(|| DrawError::NoPipelineSet)?;
@@ -350,15 +406,29 @@ impl<'a> DrawContext<'a> {
                     bindings.get_vertex_buffer(&vertex_buffer_descriptor.name)
                 {
                     draw.set_vertex_buffer(slot as u32, vertex_buffer, 0);
-                    if let Some(index_buffer) = index_buffer {
-                        if let Some(buffer_info) =
-                            self.render_resource_context.get_buffer_info(index_buffer)
-                        {
-                            indices = Some(0..(buffer_info.size / 2) as u32);
-                        } else {
-                            panic!("expected buffer type");
-                        }
+                    let index_buffer_size = index_buffer.map(|index_buffer| {
                         draw.set_index_buffer(index_buffer, 0);
+                        self.render_resource_context
+                            .get_buffer_info(index_buffer)
+                            .expect("expected buffer type")
+                            .size
+                    });
+
+                    // meshes without indices are drawn by vertex count, taken from the first
+                    // per-vertex buffer
+                    if index_buffer_size.is_some()
+                        || (vertex_range.is_none()
+                            && vertex_buffer_descriptor.step_mode == InputStepMode::Vertex)
+                    {
+                        let vertex_buffer_size = self
+                            .render_resource_context
+                            .get_buffer_info(vertex_buffer)
+                            .map_or(0, |buffer_info| buffer_info.size);
+                        vertex_range = Some(VertexRange::from_buffer_sizes(
+                            vertex_buffer_size,
+                            vertex_buffer_descriptor.stride,
+                            index_buffer_size,
+                        ));
                     }
 
                     break;
@@ -366,7 +436,7 @@ impl<'a> DrawContext<'a> {
             }
         }
 
-        Ok(indices)
+        Ok(vertex_range)
     }
 }
 
@@ -379,3 +449,43 @@ pub fn clear_draw_system(mut query: Query<&mut Draw>) {
         draw.clear_render_commands();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertex_range_falls_back_to_vertex_count_without_indices() {
+        // 6 u16 indices
+        assert_eq!(
+            VertexRange::from_buffer_sizes(4 * 32, 32, Some(12)),
+            VertexRange::Indexed(0..6)
+        );
+        assert_eq!(
+            VertexRange::from_buffer_sizes(3 * 32, 32, None),
+            VertexRange::Vertices(0..3)
+        );
+        assert_eq!(
+            VertexRange::from_buffer_sizes(0, 0, None),
+            VertexRange::Vertices(0..0)
+        );
+
+        let mut draw = Draw::default();
+        draw.draw_vertex_range(VertexRange::from_buffer_sizes(3 * 32, 32, None), 0..2);
+        draw.draw_vertex_range(VertexRange::from_buffer_sizes(4 * 32, 32, Some(12)), 0..1);
+        assert_eq!(
+            draw.render_commands,
+            vec![
+                RenderCommand::Draw {
+                    vertices: 0..3,
+                    instances: 0..2,
+                },
+                RenderCommand::DrawIndexed {
+                    indices: 0..6,
+                    base_vertex: 0,
+                    instances: 0..1,
+                },
+            ]
+        );
+    }
+}
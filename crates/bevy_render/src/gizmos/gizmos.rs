@@ -0,0 +1,332 @@
+use super::{GIZMOS_ON_TOP_PIPELINE_HANDLE, GIZMOS_PIPELINE_HANDLE};
+use crate::{
+    color::Color,
+    draw::{Draw, DrawContext, DrawError, RenderCommand},
+    pipeline::{
+        AsVertexBufferDescriptor, PipelineDescriptor, PipelineSpecialization, PrimitiveTopology,
+    },
+    render_graph::base::Msaa,
+    renderer::{BufferUsage, RenderResourceBinding, RenderResourceBindings},
+};
+use bevy_asset::Handle;
+use bevy_core::Byteable;
+use bevy_ecs::{Query, Res, ResMut};
+use bevy_math::Vec3;
+use bevy_transform::prelude::Transform;
+use std::{
+    f32::consts::PI,
+    ops::{Deref, DerefMut},
+};
+
+/// A vertex of a gizmo line. Shaders declare it as `GizmoVertex_Position` and
+/// `GizmoVertex_Color`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, AsVertexBufferDescriptor)]
+#[as_crate(bevy_render)]
+pub struct GizmoVertex {
+    /// World space, so lines are drawn without any per line uniforms
+    pub position: [f32; 3],
+    /// Linear RGBA
+    pub color: [f32; 4],
+}
+
+// SAFE: GizmoVertex is repr(C) containing only f32s
+unsafe impl Byteable for GizmoVertex {}
+
+/// The number of line segments used to approximate a circle
+const CIRCLE_SEGMENTS: usize = 32;
+
+/// Draws lines for one frame, for debugging. Nothing drawn here needs an entity.
+///
+/// Lines are depth tested, so other geometry hides them. Lines drawn through [Gizmos::on_top]
+/// are drawn over everything instead.
+#[derive(Default, Debug)]
+pub struct Gizmos {
+    depth_tested: GizmoLines,
+    on_top: GizmoLines,
+}
+
+impl Gizmos {
+    /// Returns the lines that are drawn over all other geometry
+    pub fn on_top(&mut self) -> &mut GizmoLines {
+        &mut self.on_top
+    }
+
+    pub fn clear(&mut self) {
+        self.depth_tested.clear();
+        self.on_top.clear();
+    }
+}
+
+impl Deref for Gizmos {
+    type Target = GizmoLines;
+
+    fn deref(&self) -> &GizmoLines {
+        &self.depth_tested
+    }
+}
+
+impl DerefMut for Gizmos {
+    fn deref_mut(&mut self) -> &mut GizmoLines {
+        &mut self.depth_tested
+    }
+}
+
+/// The lines buffered by [Gizmos] this frame. Every line has two vertices.
+#[derive(Default, Debug)]
+pub struct GizmoLines {
+    vertices: Vec<GizmoVertex>,
+}
+
+impl GizmoLines {
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Color) {
+        let color = color.as_linear_rgba_f32();
+        self.vertices.push(GizmoVertex {
+            position: start.into(),
+            color,
+        });
+        self.vertices.push(GizmoVertex {
+            position: end.into(),
+            color,
+        });
+    }
+
+    /// Draws the edges of the unit cube centered on the origin, moved, rotated and scaled by
+    /// `transform`
+    pub fn cuboid(&mut self, transform: &Transform, color: Color) {
+        let corner = |x: f32, y: f32, z: f32| {
+            transform
+                .value
+                .mul_vec4(Vec3::new(x, y, z).extend(1.0))
+                .truncate()
+        };
+        let bottom = [
+            corner(-0.5, -0.5, -0.5),
+            corner(0.5, -0.5, -0.5),
+            corner(0.5, -0.5, 0.5),
+            corner(-0.5, -0.5, 0.5),
+        ];
+        let top = [
+            corner(-0.5, 0.5, -0.5),
+            corner(0.5, 0.5, -0.5),
+            corner(0.5, 0.5, 0.5),
+            corner(-0.5, 0.5, 0.5),
+        ];
+        for i in 0..4 {
+            let next = (i + 1) % 4;
+            self.line(bottom[i], bottom[next], color);
+            self.line(top[i], top[next], color);
+            self.line(bottom[i], top[i], color);
+        }
+    }
+
+    /// Draws a sphere as three circles, one around each axis
+    pub fn sphere(&mut self, center: Vec3, radius: f32, color: Color) {
+        self.circle(
+            center,
+            Vec3::unit_x() * radius,
+            Vec3::unit_y() * radius,
+            color,
+        );
+        self.circle(
+            center,
+            Vec3::unit_y() * radius,
+            Vec3::unit_z() * radius,
+            color,
+        );
+        self.circle(
+            center,
+            Vec3::unit_z() * radius,
+            Vec3::unit_x() * radius,
+            color,
+        );
+    }
+
+    /// Draws the circle through `center + x` and `center + y`, where `x` and `y` are
+    /// perpendicular and the same length
+    fn circle(&mut self, center: Vec3, x: Vec3, y: Vec3, color: Color) {
+        let point = |i: usize| {
+            let angle = i as f32 / CIRCLE_SEGMENTS as f32 * 2.0 * PI;
+            center + x * angle.cos() + y * angle.sin()
+        };
+        for i in 0..CIRCLE_SEGMENTS {
+            self.line(point(i), point(i + 1), color);
+        }
+    }
+
+    /// Returns the vertices buffered, two per line
+    pub fn vertices(&self) -> &[GizmoVertex] {
+        &self.vertices
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+}
+
+/// Marks the entity that draws the [Gizmos] lines
+pub(crate) struct GizmosDraw;
+
+/// Draws the lines buffered in [Gizmos] this frame from a vertex buffer, then clears them. The
+/// depth tested lines are drawn first, so the lines drawn on top cover them.
+pub(crate) fn draw_gizmos_system(
+    mut draw_context: DrawContext,
+    msaa: Res<Msaa>,
+    mut render_resource_bindings: ResMut<RenderResourceBindings>,
+    mut gizmos: ResMut<Gizmos>,
+    mut query: Query<(&GizmosDraw, &mut Draw)>,
+) {
+    for (_, mut draw) in &mut query.iter() {
+        for (lines, pipeline) in [
+            (&gizmos.depth_tested, GIZMOS_PIPELINE_HANDLE),
+            (&gizmos.on_top, GIZMOS_ON_TOP_PIPELINE_HANDLE),
+        ]
+        .iter()
+        {
+            // empty buffers can't be created, and there is nothing to draw anyway
+            if lines.is_empty() {
+                continue;
+            }
+
+            draw_lines(
+                lines,
+                *pipeline,
+                &mut draw,
+                &mut draw_context,
+                &msaa,
+                &mut render_resource_bindings,
+            )
+            .unwrap();
+        }
+    }
+
+    gizmos.clear();
+}
+
+fn draw_lines(
+    lines: &GizmoLines,
+    pipeline: Handle<PipelineDescriptor>,
+    draw: &mut Draw,
+    context: &mut DrawContext,
+    msaa: &Msaa,
+    render_resource_bindings: &mut RenderResourceBindings,
+) -> Result<(), DrawError> {
+    let specialization = PipelineSpecialization {
+        sample_count: msaa.samples,
+        primitive_topology: PrimitiveTopology::LineList,
+        ..Default::default()
+    };
+    context.set_pipeline(draw, pipeline, &specialization)?;
+    context.set_bind_groups_from_bindings(draw, &mut [render_resource_bindings])?;
+
+    if let RenderResourceBinding::Buffer { buffer, .. } =
+        context.get_buffer(&lines.vertices, BufferUsage::VERTEX)?
+    {
+        draw.set_vertex_buffer(0, buffer, 0);
+    }
+    draw.render_command(RenderCommand::Draw {
+        vertices: 0..lines.vertices.len() as u32,
+        instances: 0..1,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GizmoVertex, Gizmos, CIRCLE_SEGMENTS};
+    use crate::{color::Color, pipeline::AsVertexBufferDescriptor};
+    use bevy_math::{Mat4, Vec3};
+    use bevy_transform::prelude::Transform;
+
+    #[test]
+    fn buffers_lines() {
+        let mut gizmos = Gizmos::default();
+        gizmos.line(Vec3::zero(), Vec3::one(), Color::RED);
+        gizmos.on_top().sphere(Vec3::zero(), 1.0, Color::GREEN);
+        gizmos.cuboid(&Transform::identity(), Color::BLUE);
+
+        assert_eq!(gizmos.vertices().len(), 2 + 12 * 2);
+        assert_eq!(gizmos.on_top().vertices().len(), 3 * CIRCLE_SEGMENTS * 2);
+
+        gizmos.clear();
+        assert!(gizmos.is_empty());
+        assert!(gizmos.on_top().is_empty());
+    }
+
+    #[test]
+    fn cuboid_edges() {
+        let mut gizmos = Gizmos::default();
+        let transform = Transform::new(Mat4::from_scale_rotation_translation(
+            Vec3::new(2.0, 4.0, 6.0),
+            Default::default(),
+            Vec3::new(1.0, 0.0, 0.0),
+        ));
+        gizmos.cuboid(&transform, Color::WHITE);
+
+        for vertex in gizmos.vertices().iter() {
+            assert!(vertex.position[0] == 0.0 || vertex.position[0] == 2.0);
+            assert_eq!(vertex.position[1].abs(), 2.0);
+            assert_eq!(vertex.position[2].abs(), 3.0);
+        }
+        // each edge is parallel to an axis
+        for line in gizmos.vertices().chunks_exact(2) {
+            let differences = (0..3)
+                .filter(|i| line[0].position[*i] != line[1].position[*i])
+                .count();
+            assert_eq!(differences, 1);
+        }
+    }
+
+    #[test]
+    fn sphere_points_are_on_sphere() {
+        let mut gizmos = Gizmos::default();
+        let center = Vec3::new(1.0, 2.0, 3.0);
+        gizmos.sphere(center, 2.0, Color::WHITE);
+
+        for vertex in gizmos.vertices().iter() {
+            let distance = (Vec3::from(vertex.position) - center).length();
+            assert!((distance - 2.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn vertices_store_linear_colors() {
+        let mut gizmos = Gizmos::default();
+        let color = Color::rgba(0.25, 0.5, 0.75, 0.5);
+        gizmos.line(Vec3::zero(), Vec3::one(), color);
+
+        let color = color.as_linear_rgba_f32();
+        assert_eq!(
+            gizmos.vertices(),
+            &[
+                GizmoVertex {
+                    position: [0.0, 0.0, 0.0],
+                    color,
+                },
+                GizmoVertex {
+                    position: [1.0, 1.0, 1.0],
+                    color,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn gizmo_vertex_buffer_descriptor() {
+        let descriptor = GizmoVertex::as_vertex_buffer_descriptor();
+        assert_eq!(descriptor.name, "GizmoVertex");
+        assert_eq!(descriptor.stride, std::mem::size_of::<GizmoVertex>() as u64);
+        assert_eq!(
+            descriptor
+                .attributes
+                .iter()
+                .map(|attribute| attribute.name.as_ref())
+                .collect::<Vec<_>>(),
+            vec!["GizmoVertex_Position", "GizmoVertex_Color"]
+        );
+    }
+}
@@ -0,0 +1,16 @@
+#version 450
+
+layout(location = 0) in vec3 GizmoVertex_Position;
+layout(location = 1) in vec4 GizmoVertex_Color;
+
+layout(location = 0) out vec4 v_Color;
+
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 ViewProj;
+};
+
+void main() {
+    v_Color = GizmoVertex_Color;
+    // gizmo lines are already in world space
+    gl_Position = ViewProj * vec4(GizmoVertex_Position, 1.0);
+}
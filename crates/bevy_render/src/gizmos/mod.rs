@@ -0,0 +1,98 @@
+#[allow(clippy::module_inception)]
+mod gizmos;
+
+pub(crate) use gizmos::{draw_gizmos_system, GizmosDraw};
+pub use gizmos::{GizmoLines, GizmoVertex, Gizmos};
+
+use crate::{
+    draw::Draw,
+    pipeline::{
+        BlendDescriptor, BlendFactor, BlendOperation, ColorStateDescriptor, ColorWrite,
+        CompareFunction, CullMode, DepthStencilStateDescriptor, FrontFace, PipelineDescriptor,
+        RasterizationStateDescriptor, StencilStateDescriptor, StencilStateFaceDescriptor,
+    },
+    render_graph::base::GizmosPass,
+    shader::{Shader, ShaderStage, ShaderStages},
+    texture::TextureFormat,
+};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{Commands, Resources};
+
+pub const GIZMOS_PIPELINE_HANDLE: Handle<PipelineDescriptor> =
+    Handle::from_u128(125024421507244165998445261225805921649);
+
+pub const GIZMOS_ON_TOP_PIPELINE_HANDLE: Handle<PipelineDescriptor> =
+    Handle::from_u128(145635264976264272533858151044196552562);
+
+/// Builds the pipeline that draws gizmo lines. Depth tested lines hide behind other geometry,
+/// while the others are drawn over everything and leave the depth buffer alone.
+pub fn build_gizmos_pipeline(shaders: &mut Assets<Shader>, depth_test: bool) -> PipelineDescriptor {
+    PipelineDescriptor {
+        rasterization_state: Some(RasterizationStateDescriptor {
+            front_face: FrontFace::Ccw,
+            cull_mode: CullMode::None,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+            clamp_depth: false,
+        }),
+        depth_stencil_state: Some(DepthStencilStateDescriptor {
+            format: TextureFormat::Depth32Float,
+            depth_write_enabled: depth_test,
+            depth_compare: if depth_test {
+                CompareFunction::LessEqual
+            } else {
+                CompareFunction::Always
+            },
+            stencil: StencilStateDescriptor {
+                front: StencilStateFaceDescriptor::IGNORE,
+                back: StencilStateFaceDescriptor::IGNORE,
+                read_mask: 0,
+                write_mask: 0,
+            },
+        }),
+        color_states: vec![ColorStateDescriptor {
+            format: TextureFormat::Bgra8UnormSrgb,
+            color_blend: BlendDescriptor {
+                src_factor: BlendFactor::SrcAlpha,
+                dst_factor: BlendFactor::OneMinusSrcAlpha,
+                operation: BlendOperation::Add,
+            },
+            alpha_blend: BlendDescriptor {
+                src_factor: BlendFactor::One,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+            write_mask: ColorWrite::ALL,
+        }],
+        ..PipelineDescriptor::new(ShaderStages {
+            vertex: shaders.add(Shader::from_glsl(
+                ShaderStage::Vertex,
+                include_str!("gizmos.vert"),
+            )),
+            fragment: Some(shaders.add(Shader::from_glsl(
+                ShaderStage::Fragment,
+                include_str!("gizmos.frag"),
+            ))),
+        })
+    }
+}
+
+pub(crate) fn add_gizmos_pipelines(resources: &Resources) {
+    let mut pipelines = resources.get_mut::<Assets<PipelineDescriptor>>().unwrap();
+    let mut shaders = resources.get_mut::<Assets<Shader>>().unwrap();
+    pipelines.set(
+        GIZMOS_PIPELINE_HANDLE,
+        build_gizmos_pipeline(&mut shaders, true),
+    );
+    pipelines.set(
+        GIZMOS_ON_TOP_PIPELINE_HANDLE,
+        build_gizmos_pipeline(&mut shaders, false),
+    );
+}
+
+/// Spawns the entity that draws all [Gizmos] lines. It is the only gizmo entity, no matter how
+/// many lines are drawn.
+pub(crate) fn setup_gizmos_system(mut commands: Commands) {
+    commands.spawn((GizmosDraw, GizmosPass, Draw::default()));
+}
@@ -2,9 +2,11 @@ pub mod batch;
 pub mod camera;
 pub mod color;
 pub mod draw;
+pub mod gizmos;
 pub mod mesh;
 pub mod pass;
 pub mod pipeline;
+pub mod raycast;
 pub mod render_graph;
 pub mod renderer;
 pub mod shader;
@@ -19,32 +21,37 @@ pub mod prelude {
         color::Color,
         draw::Draw,
         entity::*,
-        mesh::{shape, Mesh},
+        gizmos::Gizmos,
+        mesh::{shape, Instance, InstancedMesh, Mesh},
         pass::ClearColor,
         pipeline::RenderPipelines,
+        raycast::RaycastLayers,
         shader::Shader,
         texture::Texture,
     };
 }
 
 use crate::prelude::*;
-use base::{MainPass, Msaa};
+use base::{GizmosPass, MainPass, Msaa};
 use bevy_app::prelude::*;
-use bevy_asset::AddAsset;
+use bevy_asset::{AddAsset, Assets};
 use bevy_ecs::{IntoQuerySystem, IntoThreadLocalSystem};
 use bevy_type_registry::RegisterType;
 use camera::{
     ActiveCameras, Camera, OrthographicProjection, PerspectiveProjection, VisibleEntities,
 };
+use gizmos::GizmoVertex;
+use mesh::{Aabb, InstancedMesh, MeshInstance};
 use pipeline::{
-    DynamicBinding, PipelineCompiler, PipelineDescriptor, PipelineSpecialization,
-    PrimitiveTopology, ShaderSpecialization, VertexBufferDescriptors,
+    AsVertexBufferDescriptor, DynamicBinding, PipelineCompiler, PipelineDescriptor,
+    PipelineSpecialization, PrimitiveTopology, ShaderSpecialization, VertexBufferDescriptors,
 };
 use render_graph::{
     base::{self, BaseRenderGraphBuilder, BaseRenderGraphConfig},
     RenderGraph,
 };
 use renderer::{AssetRenderResourceBindings, RenderResourceBindings};
+use shader::{ShaderImport, ShaderImportLoader, ShaderLoader};
 use std::ops::Range;
 #[cfg(feature = "hdr")]
 use texture::HdrTextureLoader;
@@ -89,6 +96,9 @@ impl Plugin for RenderPlugin {
             app.add_asset_loader::<Texture, HdrTextureLoader>();
         }
 
+        app.add_asset_loader::<Shader, ShaderLoader>()
+            .add_asset_loader::<ShaderImport, ShaderImportLoader>();
+
         app.add_stage_after(bevy_asset::stage::ASSET_EVENTS, stage::RENDER_RESOURCE)
             .add_stage_after(stage::RENDER_RESOURCE, stage::RENDER_GRAPH_SYSTEMS)
             .add_stage_after(stage::RENDER_GRAPH_SYSTEMS, stage::DRAW)
@@ -97,6 +107,7 @@ impl Plugin for RenderPlugin {
             .add_asset::<Mesh>()
             .add_asset::<Texture>()
             .add_asset::<Shader>()
+            .add_asset::<ShaderImport>()
             .add_asset::<PipelineDescriptor>()
             .register_component::<Camera>()
             .register_component::<Draw>()
@@ -104,7 +115,11 @@ impl Plugin for RenderPlugin {
             .register_component::<OrthographicProjection>()
             .register_component::<PerspectiveProjection>()
             .register_component::<MainPass>()
+            .register_component::<GizmosPass>()
             .register_component::<VisibleEntities>()
+            .register_component::<InstancedMesh>()
+            .register_component::<Aabb>()
+            .register_component::<RaycastLayers>()
             .register_property::<Color>()
             .register_property::<Range<f32>>()
             .register_property::<ShaderSpecialization>()
@@ -118,6 +133,8 @@ impl Plugin for RenderPlugin {
             .init_resource::<TextureResourceSystemState>()
             .init_resource::<AssetRenderResourceBindings>()
             .init_resource::<ActiveCameras>()
+            .init_resource::<Gizmos>()
+            .add_startup_system(gizmos::setup_gizmos_system.system())
             .add_system_to_stage(
                 bevy_app::stage::PRE_UPDATE,
                 draw::clear_draw_system.system(),
@@ -134,11 +151,20 @@ impl Plugin for RenderPlugin {
                 bevy_app::stage::POST_UPDATE,
                 camera::camera_system::<PerspectiveProjection>.system(),
             )
+            .add_system_to_stage(
+                bevy_app::stage::POST_UPDATE,
+                shader::shader_defs_system::<InstancedMesh>.system(),
+            )
             // registration order matters here. this must come after all camera_system::<T> systems
             .add_system_to_stage(
                 bevy_app::stage::POST_UPDATE,
                 camera::visible_entities_system.system(),
             )
+            .add_system_to_stage(stage::RENDER_RESOURCE, base::msaa_system.system())
+            .add_system_to_stage(
+                stage::RENDER_RESOURCE,
+                shader::shader_hot_reload_system.system(),
+            )
             // TODO: turn these "resource systems" into graph nodes and remove the RENDER_RESOURCE stage
             .add_system_to_stage(
                 stage::RENDER_RESOURCE,
@@ -153,11 +179,24 @@ impl Plugin for RenderPlugin {
                 render_graph::render_graph_schedule_executor_system.thread_local_system(),
             )
             .add_system_to_stage(stage::DRAW, pipeline::draw_render_pipelines_system.system())
+            .add_system_to_stage(stage::DRAW, gizmos::draw_gizmos_system.system())
             .add_system_to_stage(
                 stage::POST_RENDER,
                 shader::clear_shader_defs_system.system(),
             );
 
+        app.resources()
+            .get_mut::<VertexBufferDescriptors>()
+            .unwrap()
+            .set(MeshInstance::as_vertex_buffer_descriptor().clone());
+        app.resources()
+            .get_mut::<VertexBufferDescriptors>()
+            .unwrap()
+            .set(GizmoVertex::as_vertex_buffer_descriptor().clone());
+
+        gizmos::add_gizmos_pipelines(app.resources());
+        Texture::add_fallback_textures(&mut app.resources().get_mut::<Assets<Texture>>().unwrap());
+
         if app.resources().get::<Msaa>().is_none() {
             app.init_resource::<Msaa>();
         }
@@ -177,4 +216,11 @@ impl Plugin for RenderPlugin {
             }
         }
     }
+
+    fn dependencies(&self) -> Vec<std::any::TypeId> {
+        vec![
+            std::any::TypeId::of::<bevy_asset::AssetPlugin>(),
+            std::any::TypeId::of::<bevy_window::WindowPlugin>(),
+        ]
+    }
 }
@@ -0,0 +1,150 @@
+use super::{InstancedMesh, Mesh};
+use bevy_app::prelude::{EventReader, Events};
+use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_ecs::{Commands, Component, Entity, Local, Query, Res};
+use bevy_math::{Mat4, Ray, Vec3};
+use bevy_property::Properties;
+use bevy_utils::HashMap;
+
+/// An axis-aligned bounding box, in the local space of its entity. Entities with bounds are culled
+/// when they are outside of a camera's frustum. Entities without bounds are always drawn.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Properties)]
+pub struct Aabb {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+}
+
+impl Aabb {
+    pub fn from_min_max(min: Vec3, max: Vec3) -> Self {
+        Aabb {
+            center: (min + max) / 2.0,
+            half_extents: (max - min) / 2.0,
+        }
+    }
+
+    pub fn min(&self) -> Vec3 {
+        self.center - self.half_extents
+    }
+
+    pub fn max(&self) -> Vec3 {
+        self.center + self.half_extents
+    }
+
+    /// Returns the smallest axis-aligned box in world space that contains this box transformed by
+    /// `model`
+    pub fn transformed(&self, model: &Mat4) -> Aabb {
+        let center = (*model * self.center.extend(1.0)).truncate();
+        // each world axis extent is the sum of the projections of the transformed local axes
+        let half_extents = model.x_axis().truncate().abs() * self.half_extents.x()
+            + model.y_axis().truncate().abs() * self.half_extents.y()
+            + model.z_axis().truncate().abs() * self.half_extents.z();
+        Aabb {
+            center,
+            half_extents,
+        }
+    }
+
+    /// Returns the distance along `ray` to where it enters this box transformed by `model`, or
+    /// `None` if it misses. Rays that start inside the box hit it at `0.0`. The world space box is
+    /// the one from [Aabb::transformed], so rotated boxes are hit slightly outside of their bounds.
+    pub fn ray_intersection(&self, ray: &Ray, model: &Mat4) -> Option<f32> {
+        let world_aabb = self.transformed(model);
+        let min: [f32; 3] = world_aabb.min().into();
+        let max: [f32; 3] = world_aabb.max().into();
+        let origin: [f32; 3] = ray.origin.into();
+        let direction: [f32; 3] = ray.direction.into();
+        let mut near = 0.0f32;
+        let mut far = f32::INFINITY;
+        for axis in 0..3 {
+            let (origin, direction) = (origin[axis], direction[axis]);
+            if direction == 0.0 {
+                // the ray is parallel to this axis' slab, so it's either always or never inside
+                if origin < min[axis] || origin > max[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let t1 = (min[axis] - origin) / direction;
+            let t2 = (max[axis] - origin) / direction;
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+            if near > far {
+                return None;
+            }
+        }
+
+        Some(near)
+    }
+}
+
+/// Local "calculate bounds system" state
+#[derive(Default)]
+pub struct CalculateBoundsState {
+    mesh_event_reader: EventReader<AssetEvent<Mesh>>,
+    mesh_bounds: HashMap<Handle<Mesh>, Option<Aabb>>,
+}
+
+/// Gives entities with a [Handle<Mesh>] and a `T` component the [Aabb] of their mesh, and keeps it up
+/// to date when the mesh changes. `T` selects the entities whose shaders draw their mesh as is. For
+/// example, sprites scale their mesh in the vertex shader, so their mesh bounds would be wrong.
+///
+/// [InstancedMesh] entities are skipped, as their instances are drawn outside of the mesh's bounds.
+pub fn calculate_bounds_system<T: Component>(
+    mut commands: Commands,
+    mut state: Local<CalculateBoundsState>,
+    meshes: Res<Assets<Mesh>>,
+    mesh_events: Res<Events<AssetEvent<Mesh>>>,
+    mut query: Query<(
+        Entity,
+        &Handle<Mesh>,
+        &T,
+        Option<&mut Aabb>,
+        Option<&InstancedMesh>,
+    )>,
+) {
+    let state = &mut *state;
+    for event in state.mesh_event_reader.iter(&mesh_events) {
+        match event {
+            AssetEvent::Created { handle }
+            | AssetEvent::Modified { handle }
+            | AssetEvent::Removed { handle } => {
+                state.mesh_bounds.remove(handle);
+            }
+        }
+    }
+
+    for (entity, mesh_handle, _, aabb, instanced_mesh) in &mut query.iter() {
+        if instanced_mesh.is_some() {
+            continue;
+        }
+
+        let mesh_bounds = match state.mesh_bounds.get(&mesh_handle) {
+            Some(mesh_bounds) => *mesh_bounds,
+            None => match meshes.get(&mesh_handle) {
+                Some(mesh) => {
+                    let mesh_bounds = mesh.compute_aabb();
+                    state.mesh_bounds.insert(*mesh_handle, mesh_bounds);
+                    mesh_bounds
+                }
+                // the mesh hasn't been loaded yet
+                None => continue,
+            },
+        };
+
+        match (aabb, mesh_bounds) {
+            (Some(mut aabb), Some(mesh_bounds)) => {
+                if *aabb != mesh_bounds {
+                    *aabb = mesh_bounds;
+                }
+            }
+            (None, Some(mesh_bounds)) => {
+                commands.insert_one(entity, mesh_bounds);
+            }
+            (Some(_), None) => {
+                commands.remove_one::<Aabb>(entity);
+            }
+            (None, None) => {}
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    pipeline::AsVertexBufferDescriptor,
+    shader::{ShaderDefIterator, ShaderDefs},
+    Color,
+};
+use bevy_core::Byteable;
+use bevy_ecs::Entity;
+use bevy_math::Mat4;
+use bevy_property::Properties;
+
+/// The shader def that is defined for the pipelines of entities with an [InstancedMesh]
+pub const INSTANCED_SHADER_DEF: &str = "INSTANCED";
+
+/// The data of a single instance of an [InstancedMesh], as read by shaders. Shaders declare it as
+/// `I_MeshInstance_Model_0` through `I_MeshInstance_Model_3` (the columns of the model matrix) and
+/// `I_MeshInstance_Color`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AsVertexBufferDescriptor)]
+#[as_crate(bevy_render)]
+pub struct MeshInstance {
+    #[vertex(instance)]
+    pub model: Mat4,
+    /// Linear RGBA, like colors in uniforms
+    #[vertex(instance)]
+    pub color: [f32; 4],
+}
+
+impl MeshInstance {
+    pub fn new(model: Mat4, color: Color) -> Self {
+        MeshInstance {
+            model,
+            color: color.as_linear_rgba_f32(),
+        }
+    }
+}
+
+// SAFE: MeshInstance is repr(C) containing a Mat4 (four Vec4s) and four f32s
+unsafe impl Byteable for MeshInstance {}
+
+/// A component that draws its entity's mesh once for every entity with an [Instance] that points
+/// to it, using a single instanced draw call. The transforms of the instances are relative to the
+/// entity's own transform.
+#[derive(Properties, Default, Clone)]
+pub struct InstancedMesh {
+    /// The number of instances written to the instance buffer this frame
+    #[property(ignore)]
+    pub instance_count: u32,
+}
+
+impl ShaderDefs for InstancedMesh {
+    fn shader_defs_len(&self) -> usize {
+        1
+    }
+
+    fn get_shader_def(&self, index: usize) -> Option<&str> {
+        match index {
+            0 => Some(INSTANCED_SHADER_DEF),
+            _ => None,
+        }
+    }
+
+    fn iter_shader_defs(&self) -> ShaderDefIterator {
+        ShaderDefIterator::new(self)
+    }
+}
+
+/// A component that draws its entity as an instance of the [InstancedMesh] on another entity
+#[derive(Debug, Clone, Copy)]
+pub struct Instance {
+    pub instanced_mesh: Entity,
+    pub color: Color,
+}
+
+impl Instance {
+    pub fn new(instanced_mesh: Entity) -> Self {
+        Instance {
+            instanced_mesh,
+            color: Color::WHITE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MeshInstance;
+    use crate::{
+        pipeline::{AsVertexBufferDescriptor, InputStepMode},
+        Color,
+    };
+    use bevy_math::Mat4;
+
+    #[test]
+    fn test_mesh_instance_color_is_linear() {
+        let color = Color::rgba(0.5, 0.25, 1.0, 0.5);
+        let instance = MeshInstance::new(Mat4::identity(), color);
+        assert_eq!(instance.color, color.as_linear_rgba_f32());
+        assert!(instance.color[0] < 0.5);
+        assert_eq!(instance.color[2], 1.0);
+        assert_eq!(instance.color[3], 0.5);
+    }
+
+    #[test]
+    fn test_mesh_instance_vertex_buffer_descriptor() {
+        let descriptor = MeshInstance::as_vertex_buffer_descriptor();
+        assert_eq!(descriptor.name, "MeshInstance");
+        assert_eq!(descriptor.step_mode, InputStepMode::Instance);
+        assert_eq!(
+            descriptor.stride,
+            std::mem::size_of::<MeshInstance>() as u64
+        );
+        assert_eq!(
+            descriptor
+                .attributes
+                .iter()
+                .map(|attribute| attribute.name.as_ref())
+                .collect::<Vec<_>>(),
+            vec![
+                "I_MeshInstance_Model_0",
+                "I_MeshInstance_Model_1",
+                "I_MeshInstance_Model_2",
+                "I_MeshInstance_Model_3",
+                "I_MeshInstance_Color",
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use super::Vertex;
+use super::{Aabb, Vertex};
 use crate::{
     pipeline::{
         AsVertexBufferDescriptor, IndexFormat, PrimitiveTopology, RenderPipelines,
@@ -39,6 +39,28 @@ impl VertexAttributeValues {
         self.len() == 0
     }
 
+    /// Returns the values at `indices`, in order
+    fn duplicated(&self, indices: &[u32]) -> Self {
+        fn duplicated<T: Copy>(values: &[T], indices: &[u32]) -> Vec<T> {
+            indices.iter().map(|i| values[*i as usize]).collect()
+        }
+
+        match self {
+            VertexAttributeValues::Float(values) => {
+                VertexAttributeValues::Float(duplicated(values, indices))
+            }
+            VertexAttributeValues::Float2(values) => {
+                VertexAttributeValues::Float2(duplicated(values, indices))
+            }
+            VertexAttributeValues::Float3(values) => {
+                VertexAttributeValues::Float3(duplicated(values, indices))
+            }
+            VertexAttributeValues::Float4(values) => {
+                VertexAttributeValues::Float4(duplicated(values, indices))
+            }
+        }
+    }
+
     // TODO: add vertex format as parameter here and perform type conversions
     pub fn get_bytes(&self) -> &[u8] {
         match self {
@@ -71,6 +93,10 @@ impl VertexAttribute {
     pub const NORMAL: &'static str = "Vertex_Normal";
     pub const POSITION: &'static str = "Vertex_Position";
     pub const UV: &'static str = "Vertex_Uv";
+    /// The direction of increasing u in xyz, and in w the sign to multiply `normal × tangent` by to
+    /// get the bitangent, which points up the texture (decreasing v) like in glTF. Vertices without
+    /// tangents get `[0.0; 4]`, which disables normal mapping for them.
+    pub const TANGENT: &'static str = "Vertex_Tangent";
 
     pub fn position(positions: Vec<[f32; 3]>) -> Self {
         VertexAttribute {
@@ -92,6 +118,13 @@ impl VertexAttribute {
             values: VertexAttributeValues::Float2(uvs),
         }
     }
+
+    pub fn tangent(tangents: Vec<[f32; 4]>) -> Self {
+        VertexAttribute {
+            name: Self::TANGENT.into(),
+            values: VertexAttributeValues::Float4(tangents),
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -104,6 +137,12 @@ pub enum MeshToVertexBufferError {
         descriptor_format: VertexFormat,
         mesh_format: VertexFormat,
     },
+    #[error("Mesh VertexAttribute {attribute_name} has {actual} vertices, but the Mesh's first VertexAttribute has {expected}.")]
+    MismatchedVertexCount {
+        attribute_name: Cow<'static, str>,
+        expected: usize,
+        actual: usize,
+    },
 }
 
 #[derive(Debug)]
@@ -122,11 +161,171 @@ impl Mesh {
         }
     }
 
+    /// Sets the values of the attribute called `name`, replacing any previous values
+    pub fn set_attribute(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        values: VertexAttributeValues,
+    ) {
+        let name = name.into();
+        match self
+            .attributes
+            .iter_mut()
+            .find(|attribute| attribute.name == name)
+        {
+            Some(attribute) => attribute.values = values,
+            None => self.attributes.push(VertexAttribute { name, values }),
+        }
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<&VertexAttributeValues> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| &attribute.values)
+    }
+
+    pub fn set_indices(&mut self, indices: Option<Vec<u32>>) {
+        self.indices = indices;
+    }
+
+    /// Returns the number of vertices, checking that every attribute has that many values
+    pub fn vertex_count(&self) -> Result<usize, MeshToVertexBufferError> {
+        let count = self.attributes.first().map(|a| a.values.len()).unwrap_or(0);
+        for attribute in self.attributes.iter() {
+            if attribute.values.len() != count {
+                return Err(MeshToVertexBufferError::MismatchedVertexCount {
+                    attribute_name: attribute.name.clone(),
+                    expected: count,
+                    actual: attribute.values.len(),
+                });
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Replaces the normals with ones perpendicular to each triangle, giving the mesh a faceted
+    /// look. Vertices shared between triangles are duplicated first, so each triangle gets its own.
+    ///
+    /// Panics if the mesh isn't a `TriangleList` or has no `Float3` positions.
+    pub fn compute_flat_normals(&mut self) {
+        assert!(
+            self.primitive_topology == PrimitiveTopology::TriangleList,
+            "flat normals can only be computed for TriangleList meshes"
+        );
+        if let Some(indices) = self.indices.take() {
+            for attribute in self.attributes.iter_mut() {
+                attribute.values = attribute.values.duplicated(&indices);
+            }
+            // meshes are only drawn indexed
+            self.indices = Some((0..indices.len() as u32).collect());
+        }
+
+        let positions = match self.get_attribute(VertexAttribute::POSITION) {
+            Some(VertexAttributeValues::Float3(positions)) => positions,
+            _ => panic!("flat normals can only be computed for meshes with Float3 positions"),
+        };
+        let normals = positions
+            .chunks_exact(3)
+            .flat_map(|triangle| {
+                let [a, b, c] = [
+                    Vec3::from(triangle[0]),
+                    Vec3::from(triangle[1]),
+                    Vec3::from(triangle[2]),
+                ];
+                let normal: [f32; 3] = (b - a).cross(c - a).normalize().into();
+                std::iter::repeat(normal).take(3)
+            })
+            .collect();
+        self.set_attribute(
+            VertexAttribute::NORMAL,
+            VertexAttributeValues::Float3(normals),
+        );
+    }
+
+    /// Computes tangents from the positions, normals and uvs, which normal mapping needs. Returns
+    /// `false` without computing them if the mesh isn't a `TriangleList` or lacks one of those
+    /// attributes. Vertices whose triangles have degenerate uvs get zero tangents, which the PBR
+    /// shader draws without normal mapping.
+    pub fn compute_tangents(&mut self) -> bool {
+        if self.primitive_topology != PrimitiveTopology::TriangleList {
+            return false;
+        }
+        let (positions, normals, uvs) = match (
+            self.get_attribute(VertexAttribute::POSITION),
+            self.get_attribute(VertexAttribute::NORMAL),
+            self.get_attribute(VertexAttribute::UV),
+        ) {
+            (
+                Some(VertexAttributeValues::Float3(positions)),
+                Some(VertexAttributeValues::Float3(normals)),
+                Some(VertexAttributeValues::Float2(uvs)),
+            ) if positions.len() == normals.len() && positions.len() == uvs.len() => {
+                (positions, normals, uvs)
+            }
+            _ => return false,
+        };
+
+        // sum the tangents and bitangents of the triangles around each vertex
+        let mut tangents = vec![Vec3::zero(); positions.len()];
+        let mut bitangents = vec![Vec3::zero(); positions.len()];
+        let triangles = match &self.indices {
+            Some(indices) => indices.iter().map(|i| *i as usize).collect::<Vec<_>>(),
+            None => (0..positions.len()).collect(),
+        };
+        for triangle in triangles.chunks_exact(3) {
+            let p0 = Vec3::from(positions[triangle[0]]);
+            let edge1 = Vec3::from(positions[triangle[1]]) - p0;
+            let edge2 = Vec3::from(positions[triangle[2]]) - p0;
+            let uv0 = Vec2::from(uvs[triangle[0]]);
+            let duv1 = Vec2::from(uvs[triangle[1]]) - uv0;
+            let duv2 = Vec2::from(uvs[triangle[2]]) - uv0;
+            let determinant = duv1.x() * duv2.y() - duv2.x() * duv1.y();
+            if determinant.abs() < std::f32::EPSILON {
+                continue;
+            }
+            let tangent = (edge1 * duv2.y() - edge2 * duv1.y()) / determinant;
+            let bitangent = (edge2 * duv1.x() - edge1 * duv2.x()) / determinant;
+            for i in triangle.iter() {
+                tangents[*i] += tangent;
+                bitangents[*i] += bitangent;
+            }
+        }
+
+        let tangents = tangents
+            .iter()
+            .zip(bitangents.iter())
+            .zip(normals.iter())
+            .map(|((tangent, bitangent), normal)| {
+                let normal = Vec3::from(*normal);
+                // make the tangent perpendicular to the normal
+                let tangent = *tangent - normal * normal.dot(*tangent);
+                if tangent.length_squared() < std::f32::EPSILON {
+                    return [0.0; 4];
+                }
+                let tangent = tangent.normalize();
+                // `bitangent` points towards increasing v, which is down the texture
+                let sign = if normal.cross(tangent).dot(*bitangent) > 0.0 {
+                    -1.0
+                } else {
+                    1.0
+                };
+                [tangent.x(), tangent.y(), tangent.z(), sign]
+            })
+            .collect();
+        self.set_attribute(
+            VertexAttribute::TANGENT,
+            VertexAttributeValues::Float4(tangents),
+        );
+        true
+    }
+
     pub fn get_vertex_buffer_bytes(
         &self,
         vertex_buffer_descriptor: &VertexBufferDescriptor,
     ) -> Result<Vec<u8>, MeshToVertexBufferError> {
-        let length = self.attributes.first().map(|a| a.values.len()).unwrap_or(0);
+        let length = self.vertex_count()?;
         let mut bytes = vec![0; vertex_buffer_descriptor.stride as usize * length];
 
         for vertex_attribute in vertex_buffer_descriptor.attributes.iter() {
@@ -145,6 +344,8 @@ impl Mesh {
                             .copy_from_slice(vertex_slice);
                     }
                 }
+                // tangents are only needed for normal mapping, so they are left zeroed
+                None if vertex_attribute.name == VertexAttribute::TANGENT => {}
                 None => {
                     return Err(MeshToVertexBufferError::MissingVertexAttribute {
                         attribute_name: vertex_attribute.name.clone(),
@@ -156,6 +357,28 @@ impl Mesh {
         Ok(bytes)
     }
 
+    /// Returns the bounds of the mesh's vertex positions, or `None` if it has no positions
+    pub fn compute_aabb(&self) -> Option<Aabb> {
+        let positions = self
+            .attributes
+            .iter()
+            .find(|attribute| attribute.name == VertexAttribute::POSITION)?;
+        let positions = match &positions.values {
+            VertexAttributeValues::Float3(positions) if !positions.is_empty() => positions,
+            _ => return None,
+        };
+
+        let mut min = Vec3::splat(std::f32::MAX);
+        let mut max = Vec3::splat(std::f32::MIN);
+        for position in positions.iter() {
+            let position = Vec3::from(*position);
+            min = min.min(position);
+            max = max.max(position);
+        }
+
+        Some(Aabb::from_min_max(min, max))
+    }
+
     pub fn get_index_buffer_bytes(&self, index_format: IndexFormat) -> Option<Vec<u8>> {
         self.indices.as_ref().map(|indices| match index_format {
             IndexFormat::Uint16 => indices
@@ -193,36 +416,36 @@ pub mod shape {
         fn from(cube: Cube) -> Self {
             let size = cube.size;
             let vertices = &[
-                // top (0., 0., size)
-                ([-size, -size, size], [0., 0., size], [0., 0.]),
-                ([size, -size, size], [0., 0., size], [size, 0.]),
-                ([size, size, size], [0., 0., size], [size, size]),
-                ([-size, size, size], [0., 0., size], [0., size]),
-                // bottom (0., 0., -size)
-                ([-size, size, -size], [0., 0., -size], [size, 0.]),
-                ([size, size, -size], [0., 0., -size], [0., 0.]),
-                ([size, -size, -size], [0., 0., -size], [0., size]),
-                ([-size, -size, -size], [0., 0., -size], [size, size]),
-                // right (size, 0., 0.)
-                ([size, -size, -size], [size, 0., 0.], [0., 0.]),
-                ([size, size, -size], [size, 0., 0.], [size, 0.]),
-                ([size, size, size], [size, 0., 0.], [size, size]),
-                ([size, -size, size], [size, 0., 0.], [0., size]),
-                // left (-size, 0., 0.)
-                ([-size, -size, size], [-size, 0., 0.], [size, 0.]),
-                ([-size, size, size], [-size, 0., 0.], [0., 0.]),
-                ([-size, size, -size], [-size, 0., 0.], [0., size]),
-                ([-size, -size, -size], [-size, 0., 0.], [size, size]),
-                // front (0., size, 0.)
-                ([size, size, -size], [0., size, 0.], [size, 0.]),
-                ([-size, size, -size], [0., size, 0.], [0., 0.]),
-                ([-size, size, size], [0., size, 0.], [0., size]),
-                ([size, size, size], [0., size, 0.], [size, size]),
-                // back (0., -size, 0.)
-                ([size, -size, size], [0., -size, 0.], [0., 0.]),
-                ([-size, -size, size], [0., -size, 0.], [size, 0.]),
-                ([-size, -size, -size], [0., -size, 0.], [size, size]),
-                ([size, -size, -size], [0., -size, 0.], [0., size]),
+                // top (0., 0., 1.)
+                ([-size, -size, size], [0., 0., 1.], [0., 0.]),
+                ([size, -size, size], [0., 0., 1.], [1., 0.]),
+                ([size, size, size], [0., 0., 1.], [1., 1.]),
+                ([-size, size, size], [0., 0., 1.], [0., 1.]),
+                // bottom (0., 0., -1.)
+                ([-size, size, -size], [0., 0., -1.], [1., 0.]),
+                ([size, size, -size], [0., 0., -1.], [0., 0.]),
+                ([size, -size, -size], [0., 0., -1.], [0., 1.]),
+                ([-size, -size, -size], [0., 0., -1.], [1., 1.]),
+                // right (1., 0., 0.)
+                ([size, -size, -size], [1., 0., 0.], [0., 0.]),
+                ([size, size, -size], [1., 0., 0.], [1., 0.]),
+                ([size, size, size], [1., 0., 0.], [1., 1.]),
+                ([size, -size, size], [1., 0., 0.], [0., 1.]),
+                // left (-1., 0., 0.)
+                ([-size, -size, size], [-1., 0., 0.], [1., 0.]),
+                ([-size, size, size], [-1., 0., 0.], [0., 0.]),
+                ([-size, size, -size], [-1., 0., 0.], [0., 1.]),
+                ([-size, -size, -size], [-1., 0., 0.], [1., 1.]),
+                // front (0., 1., 0.)
+                ([size, size, -size], [0., 1., 0.], [1., 0.]),
+                ([-size, size, -size], [0., 1., 0.], [0., 0.]),
+                ([-size, size, size], [0., 1., 0.], [0., 1.]),
+                ([size, size, size], [0., 1., 0.], [1., 1.]),
+                // back (0., -1., 0.)
+                ([size, -size, size], [0., -1., 0.], [0., 0.]),
+                ([-size, -size, size], [0., -1., 0.], [1., 0.]),
+                ([-size, -size, -size], [0., -1., 0.], [1., 1.]),
+                ([size, -size, -size], [0., -1., 0.], [0., 1.]),
             ];
 
             let mut positions = Vec::new();
@@ -259,7 +482,7 @@ pub mod shape {
     pub struct Quad {
         /// Full width and height of the rectangle.
         pub size: Vec2,
-        /// Flips the texture coords of the resulting vertices.
+        /// Flips the texture coords of the resulting vertices horizontally.
         pub flip: bool,
     }
 
@@ -277,58 +500,14 @@ pub mod shape {
         fn from(quad: Quad) -> Self {
             let extent_x = quad.size.x() / 2.0;
             let extent_y = quad.size.y() / 2.0;
+            let (u_left, u_right) = if quad.flip { (1.0, 0.0) } else { (0.0, 1.0) };
 
-            let north_west = vec2(-extent_x, extent_y);
-            let north_east = vec2(extent_x, extent_y);
-            let south_west = vec2(-extent_x, -extent_y);
-            let south_east = vec2(extent_x, -extent_y);
-            let vertices = if quad.flip {
-                [
-                    (
-                        [south_east.x(), south_east.y(), 0.0],
-                        [0.0, 0.0, 1.0],
-                        [1.0, 1.0],
-                    ),
-                    (
-                        [north_east.x(), north_east.y(), 0.0],
-                        [0.0, 0.0, 1.0],
-                        [1.0, 0.0],
-                    ),
-                    (
-                        [north_west.x(), north_west.y(), 0.0],
-                        [0.0, 0.0, 1.0],
-                        [0.0, 0.0],
-                    ),
-                    (
-                        [south_west.x(), south_west.y(), 0.0],
-                        [0.0, 0.0, 1.0],
-                        [0.0, 1.0],
-                    ),
-                ]
-            } else {
-                [
-                    (
-                        [south_west.x(), south_west.y(), 0.0],
-                        [0.0, 0.0, 1.0],
-                        [0.0, 1.0],
-                    ),
-                    (
-                        [north_west.x(), north_west.y(), 0.0],
-                        [0.0, 0.0, 1.0],
-                        [0.0, 0.0],
-                    ),
-                    (
-                        [north_east.x(), north_east.y(), 0.0],
-                        [0.0, 0.0, 1.0],
-                        [1.0, 0.0],
-                    ),
-                    (
-                        [south_east.x(), south_east.y(), 0.0],
-                        [0.0, 0.0, 1.0],
-                        [1.0, 1.0],
-                    ),
-                ]
-            };
+            let vertices = [
+                ([-extent_x, -extent_y, 0.0], [0.0, 0.0, 1.0], [u_left, 1.0]),
+                ([-extent_x, extent_y, 0.0], [0.0, 0.0, 1.0], [u_left, 0.0]),
+                ([extent_x, extent_y, 0.0], [0.0, 0.0, 1.0], [u_right, 0.0]),
+                ([extent_x, -extent_y, 0.0], [0.0, 0.0, 1.0], [u_right, 1.0]),
+            ];
 
             let indices = vec![0, 2, 1, 0, 3, 2];
 
@@ -515,8 +694,8 @@ pub fn mesh_resource_provider_system(
                 changed_meshes.insert(*handle);
             }
             AssetEvent::Modified { handle } => {
+                // the current buffers are only replaced once the modified mesh is known to be valid
                 changed_meshes.insert(*handle);
-                remove_current_mesh_resources(render_resource_context, *handle);
             }
             AssetEvent::Removed { handle } => {
                 remove_current_mesh_resources(render_resource_context, *handle);
@@ -529,9 +708,19 @@ pub fn mesh_resource_provider_system(
 
     for changed_mesh_handle in changed_meshes.iter() {
         if let Some(mesh) = meshes.get(changed_mesh_handle) {
-            let vertex_bytes = mesh
-                .get_vertex_buffer_bytes(&vertex_buffer_descriptor)
-                .unwrap();
+            let vertex_bytes = match mesh.get_vertex_buffer_bytes(&vertex_buffer_descriptor) {
+                Ok(vertex_bytes) => vertex_bytes,
+                Err(err) => {
+                    // entities keep drawing the last valid version of the mesh, if there was one
+                    log::error!(
+                        "Failed to create buffers for mesh {:?}: {}",
+                        changed_mesh_handle,
+                        err
+                    );
+                    continue;
+                }
+            };
+            remove_current_mesh_resources(render_resource_context, *changed_mesh_handle);
             // TODO: use a staging buffer here
             let vertex_buffer = render_resource_context.create_buffer_with_data(
                 BufferInfo {
@@ -541,25 +730,26 @@ pub fn mesh_resource_provider_system(
                 &vertex_bytes,
             );
 
-            let index_bytes = mesh.get_index_buffer_bytes(IndexFormat::Uint16).unwrap();
-            let index_buffer = render_resource_context.create_buffer_with_data(
-                BufferInfo {
-                    buffer_usage: BufferUsage::INDEX,
-                    ..Default::default()
-                },
-                &index_bytes,
-            );
-
             render_resource_context.set_asset_resource(
                 *changed_mesh_handle,
                 RenderResourceId::Buffer(vertex_buffer),
                 VERTEX_BUFFER_ASSET_INDEX,
             );
-            render_resource_context.set_asset_resource(
-                *changed_mesh_handle,
-                RenderResourceId::Buffer(index_buffer),
-                INDEX_BUFFER_ASSET_INDEX,
-            );
+
+            if let Some(index_bytes) = mesh.get_index_buffer_bytes(IndexFormat::Uint16) {
+                let index_buffer = render_resource_context.create_buffer_with_data(
+                    BufferInfo {
+                        buffer_usage: BufferUsage::INDEX,
+                        ..Default::default()
+                    },
+                    &index_bytes,
+                );
+                render_resource_context.set_asset_resource(
+                    *changed_mesh_handle,
+                    RenderResourceId::Buffer(index_buffer),
+                    INDEX_BUFFER_ASSET_INDEX,
+                );
+            }
         }
     }
 
@@ -593,9 +783,20 @@ pub fn mesh_resource_provider_system(
 
 #[cfg(test)]
 mod tests {
-    use super::{AsVertexBufferDescriptor, Mesh, VertexAttribute};
-    use crate::{mesh::Vertex, pipeline::PrimitiveTopology};
+    use super::{
+        mesh_resource_provider_system, shape, AsVertexBufferDescriptor, Mesh,
+        MeshToVertexBufferError, VertexAttribute, VertexAttributeValues, VERTEX_BUFFER_ASSET_INDEX,
+    };
+    use crate::{
+        mesh::Vertex,
+        pipeline::{PrimitiveTopology, RenderPipelines, VertexBufferDescriptors},
+        renderer::{HeadlessRenderResourceContext, RenderResourceContext, RenderResourceId},
+    };
+    use bevy_app::prelude::Events;
+    use bevy_asset::{AssetEvent, Assets};
     use bevy_core::AsBytes;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+    use bevy_math::{Vec2, Vec3};
 
     #[test]
     fn test_get_vertex_bytes() {
@@ -629,16 +830,19 @@ mod tests {
                 position: [0., 0., 0.],
                 normal: [1., 1., 1.],
                 uv: [2., 2.],
+                tangent: [0., 0., 0., 0.],
             },
             Vertex {
                 position: [3., 3., 3.],
                 normal: [4., 4., 4.],
                 uv: [5., 5.],
+                tangent: [0., 0., 0., 0.],
             },
             Vertex {
                 position: [6., 6., 6.],
                 normal: [7., 7., 7.],
                 uv: [8., 8.],
+                tangent: [0., 0., 0., 0.],
             },
         ];
 
@@ -649,4 +853,254 @@ mod tests {
             "buffer bytes are equal"
         );
     }
+
+    #[test]
+    fn test_compute_aabb() {
+        let aabb = Mesh::from(shape::Cube { size: 2.0 })
+            .compute_aabb()
+            .unwrap();
+        assert_eq!(aabb.center, Vec3::zero());
+        assert_eq!(aabb.half_extents, Vec3::splat(2.0));
+
+        let empty = Mesh::new(PrimitiveTopology::TriangleList);
+        assert!(empty.compute_aabb().is_none());
+    }
+
+    fn float3s<'a>(mesh: &'a Mesh, name: &str) -> &'a [[f32; 3]] {
+        match mesh.get_attribute(name) {
+            Some(VertexAttributeValues::Float3(values)) => values,
+            _ => panic!("expected Float3 {}", name),
+        }
+    }
+
+    #[test]
+    fn test_shapes() {
+        let meshes = [
+            Mesh::from(shape::Cube { size: 2.0 }),
+            Mesh::from(shape::Plane { size: 3.0 }),
+            Mesh::from(shape::Quad::new(Vec2::new(2.0, 1.0))),
+            Mesh::from(shape::Quad::flipped(Vec2::new(2.0, 1.0))),
+        ];
+        for mesh in meshes.iter() {
+            let vertex_count = mesh.vertex_count().unwrap();
+            let positions = float3s(mesh, VertexAttribute::POSITION);
+            let normals = float3s(mesh, VertexAttribute::NORMAL);
+            for triangle in mesh.indices.as_ref().unwrap().chunks_exact(3) {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+                assert!(a.max(b).max(c) < vertex_count as u32);
+                let [a, b, c] = [a as usize, b as usize, c as usize];
+                // triangles are wound counter-clockwise when seen from the side their normals face
+                let face_normal = (Vec3::from(positions[b]) - Vec3::from(positions[a]))
+                    .cross(Vec3::from(positions[c]) - Vec3::from(positions[a]));
+                assert!(face_normal.dot(Vec3::from(normals[a])) > 0.0);
+            }
+            for normal in normals.iter() {
+                assert!((Vec3::from(*normal).length() - 1.0).abs() < 1e-5);
+            }
+        }
+
+        let uvs = |mesh: &Mesh| match mesh.get_attribute(VertexAttribute::UV) {
+            Some(VertexAttributeValues::Float2(uvs)) => uvs.clone(),
+            _ => panic!("expected Float2 uvs"),
+        };
+        let quad = uvs(&meshes[2]);
+        let flipped = uvs(&meshes[3]);
+        for (uv, flipped_uv) in quad.iter().zip(flipped.iter()) {
+            assert_eq!([1.0 - uv[0], uv[1]], *flipped_uv);
+        }
+
+        let sphere = Mesh::from(shape::Icosphere {
+            radius: 2.0,
+            subdivisions: 3,
+        });
+        let vertex_count = sphere.vertex_count().unwrap();
+        assert!(sphere
+            .indices
+            .as_ref()
+            .unwrap()
+            .iter()
+            .all(|i| (*i as usize) < vertex_count));
+        let positions = float3s(&sphere, VertexAttribute::POSITION);
+        let normals = float3s(&sphere, VertexAttribute::NORMAL);
+        for (position, normal) in positions.iter().zip(normals.iter()) {
+            assert!((Vec3::from(*position).length() - 2.0).abs() < 1e-4);
+            assert!((Vec3::from(*position) / 2.0 - Vec3::from(*normal)).length() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_compute_tangents() {
+        let mut quad = Mesh::from(shape::Quad::new(Vec2::new(2.0, 1.0)));
+        assert!(quad.compute_tangents());
+        match quad.get_attribute(VertexAttribute::TANGENT) {
+            // u increases to the right, and v down, so the bitangent is +y
+            Some(VertexAttributeValues::Float4(tangents)) => {
+                assert_eq!(tangents, &vec![[1.0, 0.0, 0.0, 1.0]; 4])
+            }
+            _ => panic!("expected Float4 tangents"),
+        }
+
+        let mut cube = Mesh::from(shape::Cube { size: 1.0 });
+        assert!(cube.compute_tangents());
+        let normals = float3s(&cube, VertexAttribute::NORMAL).to_vec();
+        match cube.get_attribute(VertexAttribute::TANGENT) {
+            Some(VertexAttributeValues::Float4(tangents)) => {
+                for (tangent, normal) in tangents.iter().zip(normals.iter()) {
+                    let xyz = Vec3::new(tangent[0], tangent[1], tangent[2]);
+                    assert!((xyz.length() - 1.0).abs() < 1e-5);
+                    assert!(xyz.dot(Vec3::from(*normal)).abs() < 1e-5);
+                    assert!(tangent[3] == 1.0 || tangent[3] == -1.0);
+                }
+            }
+            _ => panic!("expected Float4 tangents"),
+        }
+
+        // without uvs there are no tangents, and the mesh can still be drawn
+        let mut no_uvs = Mesh::new(PrimitiveTopology::TriangleList);
+        no_uvs.set_attribute(
+            VertexAttribute::POSITION,
+            VertexAttributeValues::Float3(vec![[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]),
+        );
+        no_uvs.set_attribute(
+            VertexAttribute::NORMAL,
+            VertexAttributeValues::Float3(vec![[0., 0., 1.]; 3]),
+        );
+        assert!(!no_uvs.compute_tangents());
+        assert!(no_uvs.get_attribute(VertexAttribute::TANGENT).is_none());
+
+        // degenerate uvs give zero tangents rather than NaNs
+        no_uvs.set_attribute(
+            VertexAttribute::UV,
+            VertexAttributeValues::Float2(vec![[0., 0.]; 3]),
+        );
+        assert!(no_uvs.compute_tangents());
+        match no_uvs.get_attribute(VertexAttribute::TANGENT) {
+            Some(VertexAttributeValues::Float4(tangents)) => {
+                assert_eq!(tangents, &vec![[0.0; 4]; 3])
+            }
+            _ => panic!("expected Float4 tangents"),
+        }
+    }
+
+    #[test]
+    fn test_mismatched_vertex_count() {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.set_attribute(
+            VertexAttribute::POSITION,
+            VertexAttributeValues::Float3(vec![[0., 0., 0.]; 3]),
+        );
+        mesh.set_attribute(
+            VertexAttribute::UV,
+            VertexAttributeValues::Float2(vec![[0., 0.]; 2]),
+        );
+        match mesh.get_vertex_buffer_bytes(Vertex::as_vertex_buffer_descriptor()) {
+            Err(MeshToVertexBufferError::MismatchedVertexCount {
+                attribute_name,
+                expected: 3,
+                actual: 2,
+            }) => assert_eq!(attribute_name, VertexAttribute::UV),
+            _ => panic!("expected a vertex count mismatch"),
+        }
+        assert_eq!(
+            mesh.get_vertex_buffer_bytes(Vertex::as_vertex_buffer_descriptor())
+                .unwrap_err()
+                .to_string(),
+            "Mesh VertexAttribute Vertex_Uv has 2 vertices, but the Mesh's first VertexAttribute has 3."
+        );
+    }
+
+    #[test]
+    fn test_invalid_modified_mesh_keeps_its_buffers() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert::<Box<dyn RenderResourceContext>>(Box::new(
+            HeadlessRenderResourceContext::default(),
+        ));
+        resources.insert(Assets::<Mesh>::default());
+        resources.insert(Events::<AssetEvent<Mesh>>::default());
+        resources.insert(VertexBufferDescriptors::default());
+
+        let handle = resources
+            .get_mut::<Assets<Mesh>>()
+            .unwrap()
+            .add(Mesh::from(shape::Cube { size: 1.0 }));
+        let entity = world.spawn((handle, RenderPipelines::default()));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", Assets::<Mesh>::asset_event_system.system());
+        schedule.add_system_to_stage("update", mesh_resource_provider_system.system());
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        let vertex_buffer = |resources: &Resources| match resources
+            .get::<Box<dyn RenderResourceContext>>()
+            .unwrap()
+            .get_asset_resource(handle, VERTEX_BUFFER_ASSET_INDEX)
+        {
+            Some(RenderResourceId::Buffer(buffer)) => buffer,
+            _ => panic!("expected a vertex buffer"),
+        };
+        let buffer = vertex_buffer(&resources);
+
+        resources
+            .get_mut::<Assets<Mesh>>()
+            .unwrap()
+            .get_mut(&handle)
+            .unwrap()
+            .set_attribute(
+                VertexAttribute::UV,
+                VertexAttributeValues::Float2(vec![[0., 0.]; 2]),
+            );
+        schedule.run(&mut world, &mut resources);
+
+        // the old buffers are still alive and bound
+        assert_eq!(vertex_buffer(&resources), buffer);
+        assert!(resources
+            .get::<Box<dyn RenderResourceContext>>()
+            .unwrap()
+            .get_buffer_info(buffer)
+            .is_some());
+        let render_pipelines = world.get::<RenderPipelines>(entity).unwrap();
+        assert_eq!(
+            render_pipelines
+                .bindings
+                .get_vertex_buffer("Vertex")
+                .map(|(vertex_buffer, _)| vertex_buffer),
+            Some(buffer)
+        );
+    }
+
+    #[test]
+    fn test_compute_flat_normals() {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.set_attribute(
+            VertexAttribute::POSITION,
+            VertexAttributeValues::Float3(vec![
+                [0., 0., 0.],
+                [1., 0., 0.],
+                [0., 1., 0.],
+                [0., 0., -1.],
+            ]),
+        );
+        mesh.set_indices(Some(vec![0, 1, 2, 0, 3, 1]));
+        mesh.compute_flat_normals();
+
+        assert_eq!(mesh.vertex_count().unwrap(), 6);
+        assert_eq!(mesh.indices, Some(vec![0, 1, 2, 3, 4, 5]));
+        match mesh.get_attribute(VertexAttribute::NORMAL) {
+            Some(VertexAttributeValues::Float3(normals)) => assert_eq!(
+                normals,
+                &[
+                    [0., 0., 1.],
+                    [0., 0., 1.],
+                    [0., 0., 1.],
+                    [0., -1., 0.],
+                    [0., -1., 0.],
+                    [0., -1., 0.],
+                ]
+            ),
+            _ => panic!("expected Float3 normals"),
+        }
+    }
 }
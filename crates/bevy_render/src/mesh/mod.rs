@@ -1,6 +1,10 @@
+mod bounds;
+mod instanced_mesh;
 #[allow(clippy::module_inception)]
 mod mesh;
 mod vertex;
 
+pub use bounds::*;
+pub use instanced_mesh::*;
 pub use mesh::*;
 pub use vertex::*;
@@ -181,6 +181,22 @@ impl PipelineCompiler {
         specialized_pipeline_handle
     }
 
+    /// Forgets the compiled versions of `shader` and of the pipelines that use it, so that they are
+    /// compiled again from the shader's current source the next time they are used
+    pub fn invalidate_shader(
+        &mut self,
+        pipelines: &Assets<PipelineDescriptor>,
+        shader: Handle<Shader>,
+    ) {
+        self.specialized_shaders.remove(&shader);
+        self.specialized_pipelines.retain(|source_pipeline, _| {
+            pipelines.get(source_pipeline).map_or(true, |descriptor| {
+                descriptor.shader_stages.vertex != shader
+                    && descriptor.shader_stages.fragment != Some(shader)
+            })
+        });
+    }
+
     pub fn iter_compiled_pipelines(
         &self,
         pipeline_handle: Handle<PipelineDescriptor>,
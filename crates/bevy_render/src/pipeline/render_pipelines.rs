@@ -1,6 +1,7 @@
 use super::{PipelineDescriptor, PipelineSpecialization};
 use crate::{
-    draw::{Draw, DrawContext},
+    draw::{Draw, DrawContext, DrawError},
+    mesh::InstancedMesh,
     prelude::Msaa,
     renderer::RenderResourceBindings,
 };
@@ -75,25 +76,38 @@ pub fn draw_render_pipelines_system(
     mut draw_context: DrawContext,
     mut render_resource_bindings: ResMut<RenderResourceBindings>,
     msaa: Res<Msaa>,
-    mut query: Query<(&mut Draw, &mut RenderPipelines)>,
+    mut query: Query<(&mut Draw, &mut RenderPipelines, Option<&InstancedMesh>)>,
 ) {
-    for (mut draw, mut render_pipelines) in &mut query.iter() {
+    for (mut draw, mut render_pipelines, instanced_mesh) in &mut query.iter() {
         if !draw.is_visible {
             continue;
         }
+        let instances = match instanced_mesh {
+            // instanced meshes have no instance buffer to draw from until they have instances
+            Some(instanced_mesh) if instanced_mesh.instance_count == 0 => continue,
+            Some(instanced_mesh) => 0..instanced_mesh.instance_count,
+            None => 0..1,
+        };
         let render_pipelines = &mut *render_pipelines;
         for pipeline in render_pipelines.pipelines.iter_mut() {
-            pipeline.specialization.sample_count = msaa.samples;
+            // depth-only pipelines, like shadow pipelines, draw into passes that aren't multisampled
+            let has_color_states = draw_context
+                .pipelines
+                .get(&pipeline.pipeline)
+                .map_or(true, |descriptor| !descriptor.color_states.is_empty());
+            pipeline.specialization.sample_count = if has_color_states { msaa.samples } else { 1 };
         }
 
         for render_pipeline in render_pipelines.pipelines.iter() {
-            draw_context
-                .set_pipeline(
-                    &mut draw,
-                    render_pipeline.pipeline,
-                    &render_pipeline.specialization,
-                )
-                .unwrap();
+            let result = draw_context.set_pipeline(
+                &mut draw,
+                render_pipeline.pipeline,
+                &render_pipeline.specialization,
+            );
+            if let Err(DrawError::ShadersNotLoaded) = result {
+                continue;
+            }
+            result.unwrap();
             draw_context
                 .set_bind_groups_from_bindings(
                     &mut draw,
@@ -103,11 +117,11 @@ pub fn draw_render_pipelines_system(
                     ],
                 )
                 .unwrap();
-            let indices = draw_context
+            let vertex_range = draw_context
                 .set_vertex_buffers_from_bindings(&mut draw, &[&render_pipelines.bindings])
                 .unwrap();
-            if let Some(indices) = indices {
-                draw.draw_indexed(indices, 0, 0..1);
+            if let Some(vertex_range) = vertex_range {
+                draw.draw_vertex_range(vertex_range, instances.clone());
             }
         }
     }
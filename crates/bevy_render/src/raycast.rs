@@ -0,0 +1,202 @@
+use crate::mesh::Aabb;
+use bevy_core::FloatOrd;
+use bevy_ecs::Entity;
+use bevy_math::Ray;
+use bevy_property::Properties;
+use bevy_transform::prelude::Transform;
+
+/// The layers an entity is on, one per bit. Raycasts only hit entities on at least one of the
+/// layers they cast against. Entities without this component are on layer 0 only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Properties)]
+pub struct RaycastLayers {
+    pub mask: u32,
+}
+
+impl RaycastLayers {
+    pub const ALL: RaycastLayers = RaycastLayers { mask: u32::MAX };
+    pub const NONE: RaycastLayers = RaycastLayers { mask: 0 };
+
+    /// Only the given layer, which must be less than 32
+    pub const fn layer(layer: u32) -> Self {
+        RaycastLayers { mask: 1 << layer }
+    }
+
+    /// These layers plus the given layer, which must be less than 32
+    pub const fn with(self, layer: u32) -> Self {
+        RaycastLayers {
+            mask: self.mask | 1 << layer,
+        }
+    }
+
+    pub fn intersects(&self, other: &RaycastLayers) -> bool {
+        self.mask & other.mask != 0
+    }
+}
+
+impl Default for RaycastLayers {
+    fn default() -> Self {
+        RaycastLayers::layer(0)
+    }
+}
+
+/// An entity that raycasts can hit, as yielded by a
+/// `Query<(Entity, &Aabb, &Transform, Option<&RaycastLayers>)>`
+pub type RaycastCandidate<'a> = (Entity, &'a Aabb, &'a Transform, Option<&'a RaycastLayers>);
+
+/// Returns the entity whose [Aabb], transformed into world space, `ray` hits first, along with
+/// the distance to the hit. Only `candidates` on one of `layers` are tested.
+pub fn raycast<'a>(
+    ray: &Ray,
+    layers: RaycastLayers,
+    candidates: impl IntoIterator<Item = RaycastCandidate<'a>>,
+) -> Option<(Entity, f32)> {
+    hits(ray, layers, candidates).min_by_key(|(_, distance)| FloatOrd(*distance))
+}
+
+/// Like [raycast], but returns every entity hit, nearest first
+pub fn intersect_all<'a>(
+    ray: &Ray,
+    layers: RaycastLayers,
+    candidates: impl IntoIterator<Item = RaycastCandidate<'a>>,
+) -> Vec<(Entity, f32)> {
+    let mut hits = hits(ray, layers, candidates).collect::<Vec<_>>();
+    hits.sort_by_key(|(_, distance)| FloatOrd(*distance));
+    hits
+}
+
+fn hits<'a>(
+    ray: &Ray,
+    layers: RaycastLayers,
+    candidates: impl IntoIterator<Item = RaycastCandidate<'a>>,
+) -> impl Iterator<Item = (Entity, f32)> {
+    let ray = *ray;
+    candidates
+        .into_iter()
+        .filter(move |(_, _, _, entity_layers)| {
+            entity_layers
+                .copied()
+                .unwrap_or_default()
+                .intersects(&layers)
+        })
+        .filter_map(move |(entity, aabb, transform, _)| {
+            aabb.ray_intersection(&ray, &transform.value)
+                .map(|distance| (entity, distance))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{intersect_all, raycast, RaycastCandidate, RaycastLayers};
+    use crate::mesh::Aabb;
+    use bevy_ecs::World;
+    use bevy_math::{Mat4, Quat, Ray, Vec3};
+    use bevy_transform::prelude::Transform;
+
+    fn unit_box() -> Aabb {
+        Aabb {
+            center: Vec3::zero(),
+            half_extents: Vec3::splat(0.5),
+        }
+    }
+
+    fn at(x: f32, y: f32, z: f32) -> Transform {
+        Transform::new(Mat4::from_translation(Vec3::new(x, y, z)))
+    }
+
+    fn down_z() -> Ray {
+        Ray {
+            origin: Vec3::new(0.0, 0.0, 10.0),
+            direction: -Vec3::unit_z(),
+        }
+    }
+
+    #[test]
+    fn ray_intersection() {
+        let aabb = unit_box();
+        let identity = Mat4::identity();
+        assert_eq!(aabb.ray_intersection(&down_z(), &identity), Some(9.5));
+
+        // starting inside
+        let inside = Ray {
+            origin: Vec3::zero(),
+            direction: Vec3::unit_x(),
+        };
+        assert_eq!(aabb.ray_intersection(&inside, &identity), Some(0.0));
+
+        // pointing away
+        let away = Ray {
+            origin: Vec3::new(0.0, 0.0, 10.0),
+            direction: Vec3::unit_z(),
+        };
+        assert_eq!(aabb.ray_intersection(&away, &identity), None);
+
+        // parallel to a face, just outside of it
+        let parallel = Ray {
+            origin: Vec3::new(0.6, 0.0, 10.0),
+            direction: -Vec3::unit_z(),
+        };
+        assert_eq!(aabb.ray_intersection(&parallel, &identity), None);
+
+        // scaled and moved
+        let model = Mat4::from_scale_rotation_translation(
+            Vec3::splat(2.0),
+            Quat::identity(),
+            Vec3::new(0.6, 0.0, 1.0),
+        );
+        assert_eq!(aabb.ray_intersection(&parallel, &model), Some(8.0));
+    }
+
+    #[test]
+    fn nearest_hit() {
+        let mut world = World::default();
+        let far = world.spawn((unit_box(), at(0.0, 0.0, -5.0)));
+        let near = world.spawn((unit_box(), at(0.0, 0.0, 0.0)));
+        world.spawn((unit_box(), at(5.0, 0.0, 0.0)));
+
+        let mut query = world.query::<RaycastCandidate>();
+        assert_eq!(
+            raycast(&down_z(), RaycastLayers::ALL, &mut query),
+            Some((near, 9.5))
+        );
+        assert_eq!(
+            intersect_all(&down_z(), RaycastLayers::ALL, &mut query),
+            vec![(near, 9.5), (far, 14.5)]
+        );
+
+        let miss = Ray {
+            origin: Vec3::new(0.0, 0.0, 10.0),
+            direction: Vec3::unit_y(),
+        };
+        assert_eq!(raycast(&miss, RaycastLayers::ALL, &mut query), None);
+    }
+
+    #[test]
+    fn layers() {
+        let mut world = World::default();
+        // entities without layers are on layer 0
+        let default_layer = world.spawn((unit_box(), at(0.0, 0.0, 0.0)));
+        let other_layers = world.spawn((
+            unit_box(),
+            at(0.0, 0.0, 2.0),
+            RaycastLayers::layer(1).with(2),
+        ));
+
+        let mut query = world.query::<RaycastCandidate>();
+        assert_eq!(
+            raycast(&down_z(), RaycastLayers::layer(0), &mut query),
+            Some((default_layer, 9.5))
+        );
+        assert_eq!(
+            raycast(&down_z(), RaycastLayers::layer(2), &mut query),
+            Some((other_layers, 7.5))
+        );
+        assert_eq!(
+            raycast(&down_z(), RaycastLayers::layer(3), &mut query),
+            None
+        );
+        assert_eq!(
+            intersect_all(&down_z(), RaycastLayers::NONE, &mut query),
+            vec![]
+        );
+    }
+}
@@ -1,15 +1,17 @@
 use super::{
-    CameraNode, PassNode, RenderGraph, SharedBuffersNode, TextureCopyNode, WindowSwapChainNode,
-    WindowTextureNode,
+    CameraNode, InstancedMeshNode, PassNode, RenderGraph, SharedBuffersNode, TextureCopyNode,
+    WindowSwapChainNode, WindowTextureNode,
 };
 use crate::{
     pass::{
         LoadOp, Operations, PassDescriptor, RenderPassColorAttachmentDescriptor,
         RenderPassDepthStencilAttachmentDescriptor, TextureAttachment,
     },
+    renderer::RenderResourceContext,
     texture::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsage},
     Color,
 };
+use bevy_ecs::{Res, ResMut};
 use bevy_property::Properties;
 use bevy_window::WindowId;
 
@@ -17,17 +19,45 @@ use bevy_window::WindowId;
 #[derive(Default, Properties)]
 pub struct MainPass;
 
+/// A component that indicates that an entity should be drawn in the "gizmos pass", which draws
+/// over the main pass' color and depth after it
+#[derive(Default, Properties)]
+pub struct GizmosPass;
+
+/// Configures multi-sample anti-aliasing (MSAA) for the base render graph's passes
+///
+/// The sample count can be changed at runtime: the multisampled render targets are recreated and
+/// pipelines are specialized for the new count.
 pub struct Msaa {
     pub samples: u32,
 }
 
 impl Default for Msaa {
     fn default() -> Self {
-        Self { samples: 1 }
+        // MSAA is cheap enough to be on by default on desktop GPUs
+        #[cfg(not(target_arch = "wasm32"))]
+        let samples = 4;
+        #[cfg(target_arch = "wasm32")]
+        let samples = 1;
+        Self { samples }
     }
 }
 
 impl Msaa {
+    /// The sample counts the main pass attachments can be created with, which both its color and
+    /// depth formats have to support
+    pub fn supported_sample_counts(
+        render_resource_context: &dyn RenderResourceContext,
+    ) -> Vec<u32> {
+        let depth_sample_counts =
+            render_resource_context.supported_sample_counts(TextureFormat::Depth32Float);
+        render_resource_context
+            .supported_sample_counts(TextureFormat::Bgra8UnormSrgb)
+            .into_iter()
+            .filter(|samples| depth_sample_counts.contains(samples))
+            .collect()
+    }
+
     pub fn color_attachment_descriptor(
         &self,
         attachment: TextureAttachment,
@@ -55,6 +85,7 @@ pub struct BaseRenderGraphConfig {
     pub add_3d_camera: bool,
     pub add_main_depth_texture: bool,
     pub add_main_pass: bool,
+    pub add_gizmos_pass: bool,
     pub connect_main_pass_to_swapchain: bool,
     pub connect_main_pass_to_main_depth_texture: bool,
 }
@@ -67,7 +98,9 @@ pub mod node {
     pub const MAIN_DEPTH_TEXTURE: &str = "main_pass_depth_texture";
     pub const MAIN_SAMPLED_COLOR_ATTACHMENT: &str = "main_pass_sampled_color_attachment";
     pub const MAIN_PASS: &str = "main_pass";
+    pub const GIZMOS_PASS: &str = "gizmos_pass";
     pub const SHARED_BUFFERS: &str = "shared_buffers";
+    pub const INSTANCED_MESHES: &str = "instanced_meshes";
 }
 
 pub mod camera {
@@ -81,12 +114,42 @@ impl Default for BaseRenderGraphConfig {
             add_2d_camera: true,
             add_3d_camera: true,
             add_main_pass: true,
+            add_gizmos_pass: true,
             add_main_depth_texture: true,
             connect_main_pass_to_swapchain: true,
             connect_main_pass_to_main_depth_texture: true,
         }
     }
 }
+/// Replaces an [Msaa] sample count the renderer can't create the main pass attachments with by the
+/// largest supported count below it
+pub fn msaa_system(
+    render_resource_context: Res<Box<dyn RenderResourceContext>>,
+    mut msaa: ResMut<Msaa>,
+) {
+    let supported = Msaa::supported_sample_counts(&**render_resource_context);
+    let samples = clamp_sample_count(msaa.samples, &supported);
+    if samples == msaa.samples {
+        return;
+    }
+
+    log::warn!(
+        "MSAA sample count {} is not supported, using {} instead",
+        msaa.samples,
+        samples
+    );
+    msaa.samples = samples;
+}
+
+fn clamp_sample_count(samples: u32, supported: &[u32]) -> u32 {
+    supported
+        .iter()
+        .cloned()
+        .filter(|supported| *supported <= samples)
+        .max()
+        .unwrap_or(1)
+}
+
 /// The "base render graph" provides a core set of render graph nodes which can be used to build any graph.
 /// By itself this graph doesn't do much, but it allows Render plugins to interop with each other by having a common
 /// set of nodes. It can be customized using `BaseRenderGraphConfig`.
@@ -106,37 +169,41 @@ impl BaseRenderGraphBuilder for RenderGraph {
         }
 
         self.add_node(node::SHARED_BUFFERS, SharedBuffersNode::default());
+        self.add_system_node(node::INSTANCED_MESHES, InstancedMeshNode::default());
         if config.add_main_depth_texture {
-            self.add_node(
-                node::MAIN_DEPTH_TEXTURE,
-                WindowTextureNode::new(
-                    WindowId::primary(),
-                    TextureDescriptor {
-                        size: Extent3d {
-                            depth: 1,
-                            width: 1,
-                            height: 1,
-                        },
-                        mip_level_count: 1,
-                        sample_count: msaa.samples,
-                        dimension: TextureDimension::D2,
-                        format: TextureFormat::Depth32Float, // PERF: vulkan docs recommend using 24 bit depth for better performance
-                        usage: TextureUsage::OUTPUT_ATTACHMENT,
+            let mut main_depth_texture_node = WindowTextureNode::new(
+                WindowId::primary(),
+                TextureDescriptor {
+                    size: Extent3d {
+                        depth: 1,
+                        width: 1,
+                        height: 1,
                     },
-                ),
+                    mip_level_count: 1,
+                    sample_count: msaa.samples,
+                    dimension: TextureDimension::D2,
+                    format: TextureFormat::Depth32Float, // PERF: vulkan docs recommend using 24 bit depth for better performance
+                    usage: TextureUsage::OUTPUT_ATTACHMENT,
+                },
             );
+            main_depth_texture_node.use_msaa_sample_count();
+            self.add_node(node::MAIN_DEPTH_TEXTURE, main_depth_texture_node);
         }
 
         if config.add_main_pass {
+            // the resolve target is always connected, so that msaa can be turned on and off at
+            // runtime. the pass draws straight into it while msaa is off.
             let mut main_pass_node = PassNode::<&MainPass>::new(PassDescriptor {
-                color_attachments: vec![msaa.color_attachment_descriptor(
-                    TextureAttachment::Input("color_attachment".to_string()),
-                    TextureAttachment::Input("color_resolve_target".to_string()),
-                    Operations {
+                color_attachments: vec![RenderPassColorAttachmentDescriptor {
+                    attachment: TextureAttachment::Input("color_attachment".to_string()),
+                    resolve_target: Some(TextureAttachment::Input(
+                        "color_resolve_target".to_string(),
+                    )),
+                    ops: Operations {
                         load: LoadOp::Clear(Color::rgb(0.1, 0.1, 0.1)),
                         store: true,
                     },
-                )],
+                }],
                 depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
                     attachment: TextureAttachment::Input("depth".to_string()),
                     depth_ops: Some(Operations {
@@ -149,6 +216,7 @@ impl BaseRenderGraphBuilder for RenderGraph {
             });
 
             main_pass_node.use_default_clear_color(0);
+            main_pass_node.use_msaa_sample_count();
 
             if config.add_3d_camera {
                 main_pass_node.add_camera(camera::CAMERA3D);
@@ -164,6 +232,8 @@ impl BaseRenderGraphBuilder for RenderGraph {
                 .unwrap();
             self.add_node_edge(node::SHARED_BUFFERS, node::MAIN_PASS)
                 .unwrap();
+            self.add_node_edge(node::INSTANCED_MESHES, node::MAIN_PASS)
+                .unwrap();
 
             if config.add_3d_camera {
                 self.add_node_edge(node::CAMERA3D, node::MAIN_PASS).unwrap();
@@ -174,6 +244,61 @@ impl BaseRenderGraphBuilder for RenderGraph {
             }
         }
 
+        if config.add_gizmos_pass {
+            // loads what the main pass drew, so gizmos are depth tested against it
+            let mut gizmos_pass_node = PassNode::<&GizmosPass>::new(PassDescriptor {
+                color_attachments: vec![RenderPassColorAttachmentDescriptor {
+                    attachment: TextureAttachment::Input("color_attachment".to_string()),
+                    resolve_target: Some(TextureAttachment::Input(
+                        "color_resolve_target".to_string(),
+                    )),
+                    ops: Operations {
+                        load: LoadOp::Load,
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+                    attachment: TextureAttachment::Input("depth".to_string()),
+                    depth_ops: Some(Operations {
+                        load: LoadOp::Load,
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+                sample_count: msaa.samples,
+            });
+
+            gizmos_pass_node.use_msaa_sample_count();
+
+            if config.add_3d_camera {
+                gizmos_pass_node.add_camera(camera::CAMERA3D);
+            }
+
+            if config.add_2d_camera {
+                gizmos_pass_node.add_camera(camera::CAMERA2D);
+            }
+
+            self.add_node(node::GIZMOS_PASS, gizmos_pass_node);
+
+            self.add_node_edge(node::SHARED_BUFFERS, node::GIZMOS_PASS)
+                .unwrap();
+
+            if config.add_main_pass {
+                self.add_node_edge(node::MAIN_PASS, node::GIZMOS_PASS)
+                    .unwrap();
+            }
+
+            if config.add_3d_camera {
+                self.add_node_edge(node::CAMERA3D, node::GIZMOS_PASS)
+                    .unwrap();
+            }
+
+            if config.add_2d_camera {
+                self.add_node_edge(node::CAMERA2D, node::GIZMOS_PASS)
+                    .unwrap();
+            }
+        }
+
         self.add_node(
             node::PRIMARY_SWAP_CHAIN,
             WindowSwapChainNode::new(WindowId::primary()),
@@ -184,35 +309,43 @@ impl BaseRenderGraphBuilder for RenderGraph {
                 node::PRIMARY_SWAP_CHAIN,
                 WindowSwapChainNode::OUT_TEXTURE,
                 node::MAIN_PASS,
-                if msaa.samples > 1 {
-                    "color_resolve_target"
-                } else {
-                    "color_attachment"
-                },
+                "color_resolve_target",
             )
             .unwrap();
+
+            if config.add_gizmos_pass {
+                self.add_slot_edge(
+                    node::PRIMARY_SWAP_CHAIN,
+                    WindowSwapChainNode::OUT_TEXTURE,
+                    node::GIZMOS_PASS,
+                    "color_resolve_target",
+                )
+                .unwrap();
+            }
         }
 
-        if msaa.samples > 1 {
-            self.add_node(
-                node::MAIN_SAMPLED_COLOR_ATTACHMENT,
-                WindowTextureNode::new(
-                    WindowId::primary(),
-                    TextureDescriptor {
-                        size: Extent3d {
-                            depth: 1,
-                            width: 1,
-                            height: 1,
-                        },
-                        mip_level_count: 1,
-                        sample_count: msaa.samples,
-                        dimension: TextureDimension::D2,
-                        format: TextureFormat::Bgra8UnormSrgb,
-                        usage: TextureUsage::OUTPUT_ATTACHMENT,
-                    },
-                ),
-            );
+        let mut main_sampled_color_attachment_node = WindowTextureNode::new(
+            WindowId::primary(),
+            TextureDescriptor {
+                size: Extent3d {
+                    depth: 1,
+                    width: 1,
+                    height: 1,
+                },
+                mip_level_count: 1,
+                sample_count: msaa.samples,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Bgra8UnormSrgb,
+                usage: TextureUsage::OUTPUT_ATTACHMENT,
+            },
+        );
+        main_sampled_color_attachment_node.use_msaa_sample_count();
+        self.add_node(
+            node::MAIN_SAMPLED_COLOR_ATTACHMENT,
+            main_sampled_color_attachment_node,
+        );
 
+        if config.add_main_pass {
             self.add_slot_edge(
                 node::MAIN_SAMPLED_COLOR_ATTACHMENT,
                 WindowSwapChainNode::OUT_TEXTURE,
@@ -222,6 +355,16 @@ impl BaseRenderGraphBuilder for RenderGraph {
             .unwrap();
         }
 
+        if config.add_gizmos_pass {
+            self.add_slot_edge(
+                node::MAIN_SAMPLED_COLOR_ATTACHMENT,
+                WindowSwapChainNode::OUT_TEXTURE,
+                node::GIZMOS_PASS,
+                "color_attachment",
+            )
+            .unwrap();
+        }
+
         if config.connect_main_pass_to_main_depth_texture {
             self.add_slot_edge(
                 node::MAIN_DEPTH_TEXTURE,
@@ -230,8 +373,55 @@ impl BaseRenderGraphBuilder for RenderGraph {
                 "depth",
             )
             .unwrap();
+
+            if config.add_gizmos_pass {
+                self.add_slot_edge(
+                    node::MAIN_DEPTH_TEXTURE,
+                    WindowTextureNode::OUT_TEXTURE,
+                    node::GIZMOS_PASS,
+                    "depth",
+                )
+                .unwrap();
+            }
         }
 
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::HeadlessRenderResourceContext;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+
+    #[test]
+    fn clamp_to_supported_sample_count() {
+        assert_eq!(clamp_sample_count(4, &[1, 2, 4, 8]), 4);
+        assert_eq!(clamp_sample_count(16, &[1, 2, 4, 8]), 8);
+        assert_eq!(clamp_sample_count(8, &[1, 4]), 4);
+        assert_eq!(clamp_sample_count(3, &[1, 4]), 1);
+        assert_eq!(clamp_sample_count(0, &[1, 4]), 1);
+    }
+
+    #[test]
+    fn msaa_is_clamped_to_render_context_sample_counts() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert::<Box<dyn RenderResourceContext>>(Box::new(
+            HeadlessRenderResourceContext::default(),
+        ));
+        resources.insert(Msaa { samples: 8 });
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", msaa_system.system());
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(resources.get::<Msaa>().unwrap().samples, 4);
+
+        resources.get_mut::<Msaa>().unwrap().samples = 1;
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(resources.get::<Msaa>().unwrap().samples, 1);
+    }
+}
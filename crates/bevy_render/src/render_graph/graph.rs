@@ -1,6 +1,6 @@
 use super::{Edge, Node, NodeId, NodeLabel, NodeState, RenderGraphError, SlotLabel, SystemNode};
 use bevy_ecs::{Commands, Schedule};
-use bevy_utils::HashMap;
+use bevy_utils::{HashMap, HashSet};
 use std::{borrow::Cow, fmt::Debug};
 pub struct RenderGraph {
     nodes: HashMap<NodeId, NodeState>,
@@ -166,6 +166,11 @@ impl RenderGraph {
             return Err(RenderGraphError::EdgeAlreadyExists(edge.clone()));
         }
 
+        // nodes run after the nodes they depend on, which isn't possible in a cycle
+        if self.has_path(edge.get_input_node(), edge.get_output_node()) {
+            return Err(RenderGraphError::EdgeCreatesCycle(edge.clone()));
+        }
+
         match *edge {
             Edge::SlotEdge {
                 output_node,
@@ -231,6 +236,33 @@ impl RenderGraph {
         false
     }
 
+    /// Returns whether `to` can be reached from `from` by following output edges
+    fn has_path(&self, from: NodeId, to: NodeId) -> bool {
+        let mut visited = HashSet::default();
+        let mut stack = vec![from];
+        while let Some(node) = stack.pop() {
+            if node == to {
+                return true;
+            }
+
+            if !visited.insert(node) {
+                continue;
+            }
+
+            if let Ok(node_state) = self.get_node_state(node) {
+                stack.extend(
+                    node_state
+                        .edges
+                        .output_edges
+                        .iter()
+                        .map(|edge| edge.get_input_node()),
+                );
+            }
+        }
+
+        false
+    }
+
     pub fn take_schedule(&mut self) -> Option<Schedule> {
         self.system_node_schedule.take()
     }
@@ -476,4 +508,36 @@ mod tests {
             "Adding to a duplicate edge should return an error"
         );
     }
+
+    #[test]
+    pub fn test_cycle_rejected() {
+        let mut graph = RenderGraph::default();
+
+        graph.add_node("A", TestNode::new(0, 1));
+        graph.add_node("B", TestNode::new(1, 1));
+        graph.add_node("C", TestNode::new(1, 0));
+
+        graph.add_slot_edge("A", 0, "B", 0).unwrap();
+        graph.add_slot_edge("B", 0, "C", 0).unwrap();
+        assert_eq!(
+            graph.add_node_edge("C", "A"),
+            Err(RenderGraphError::EdgeCreatesCycle(Edge::NodeEdge {
+                output_node: graph.get_node_id("C").unwrap(),
+                input_node: graph.get_node_id("A").unwrap(),
+            })),
+            "Adding an edge that closes a loop should return an error"
+        );
+        assert_eq!(
+            graph.add_node_edge("B", "B"),
+            Err(RenderGraphError::EdgeCreatesCycle(Edge::NodeEdge {
+                output_node: graph.get_node_id("B").unwrap(),
+                input_node: graph.get_node_id("B").unwrap(),
+            })),
+            "A node can't depend on itself"
+        );
+        assert!(
+            graph.add_node_edge("A", "C").is_ok(),
+            "Edges that skip ahead don't create cycles"
+        );
+    }
 }
@@ -36,6 +36,8 @@ pub enum RenderGraphError {
     },
     #[error("Attempted to add an edge that already exists")]
     EdgeAlreadyExists(Edge),
+    #[error("Attempted to add an edge that would create a cycle")]
+    EdgeCreatesCycle(Edge),
     #[error("Node has an unconnected input slot.")]
     UnconnectedNodeInputSlot { node: NodeId, input_slot: usize },
     #[error("Node has an unconnected output slot.")]
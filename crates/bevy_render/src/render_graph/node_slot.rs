@@ -49,6 +49,12 @@ impl ResourceSlots {
         slot.resource = Some(resource);
     }
 
+    /// Clears the slot, returning the resource it held
+    pub fn remove(&mut self, label: impl Into<SlotLabel>) -> Option<RenderResourceId> {
+        let slot = self.get_slot_mut(label).unwrap();
+        slot.resource.take()
+    }
+
     pub fn get(&self, label: impl Into<SlotLabel>) -> Option<RenderResourceId> {
         let slot = self.get_slot(label).unwrap();
         slot.resource.clone()
@@ -0,0 +1,160 @@
+use crate::{
+    mesh::{Instance, InstancedMesh, MeshInstance},
+    pipeline::{AsVertexBufferDescriptor, RenderPipelines},
+    render_graph::{CommandQueue, Node, ResourceSlots, SystemNode},
+    renderer::{BufferId, BufferInfo, BufferUsage, RenderContext, RenderResourceContext},
+};
+use bevy_core::AsBytes;
+use bevy_ecs::{Commands, Entity, IntoQuerySystem, Local, Query, Res, Resources, System, World};
+use bevy_transform::prelude::*;
+use bevy_utils::{HashMap, HashSet};
+
+/// A Render Graph [Node] that writes the transforms and colors of [Instance] entities to the
+/// instance buffers of the [InstancedMesh]es they belong to
+#[derive(Default)]
+pub struct InstancedMeshNode {
+    command_queue: CommandQueue,
+}
+
+impl Node for InstancedMeshNode {
+    fn update(
+        &mut self,
+        _world: &World,
+        _resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        _input: &ResourceSlots,
+        _output: &mut ResourceSlots,
+    ) {
+        self.command_queue.execute(render_context);
+    }
+}
+
+impl SystemNode for InstancedMeshNode {
+    fn get_system(&self, commands: &mut Commands) -> Box<dyn System> {
+        let system = instanced_mesh_node_system.system();
+        commands.insert_local_resource(
+            system.id(),
+            InstancedMeshNodeState {
+                command_queue: self.command_queue.clone(),
+                instance_buffers: HashMap::default(),
+            },
+        );
+        system
+    }
+}
+
+/// The instance buffer of an [InstancedMesh] and the staging buffer used to write to it
+struct InstanceBuffers {
+    buffer: BufferId,
+    staging_buffer: BufferId,
+    /// The number of instances the buffers have room for
+    capacity: usize,
+}
+
+/// Local "instanced mesh node system" state
+#[derive(Default)]
+pub struct InstancedMeshNodeState {
+    command_queue: CommandQueue,
+    instance_buffers: HashMap<Entity, InstanceBuffers>,
+}
+
+pub fn instanced_mesh_node_system(
+    mut state: Local<InstancedMeshNodeState>,
+    render_resource_context: Res<Box<dyn RenderResourceContext>>,
+    mut instance_query: Query<(&Instance, &Transform)>,
+    mut instanced_mesh_query: Query<(Entity, &mut InstancedMesh, &mut RenderPipelines)>,
+) {
+    let state = &mut *state;
+    let render_resource_context = &**render_resource_context;
+    let instance_size = std::mem::size_of::<MeshInstance>();
+
+    let mut instances = HashMap::<Entity, Vec<MeshInstance>>::default();
+    for (instance, transform) in &mut instance_query.iter() {
+        instances
+            .entry(instance.instanced_mesh)
+            .or_insert_with(Vec::new)
+            .push(MeshInstance::new(transform.value, instance.color));
+    }
+
+    let mut instanced_meshes = HashSet::default();
+    for (entity, mut instanced_mesh, mut render_pipelines) in &mut instanced_mesh_query.iter() {
+        instanced_meshes.insert(entity);
+        let instances = instances.remove(&entity).unwrap_or_default();
+        instanced_mesh.instance_count = instances.len() as u32;
+        if instances.is_empty() {
+            continue;
+        }
+
+        let has_capacity = state
+            .instance_buffers
+            .get(&entity)
+            .map_or(false, |buffers| buffers.capacity >= instances.len());
+        if has_capacity {
+            render_resource_context.map_buffer(state.instance_buffers[&entity].staging_buffer);
+        } else {
+            if let Some(buffers) = state.instance_buffers.remove(&entity) {
+                render_resource_context.remove_buffer(buffers.buffer);
+                render_resource_context.remove_buffer(buffers.staging_buffer);
+            }
+
+            // growing to the next power of two keeps the buffers from being reallocated every time
+            // a few instances are added
+            let capacity = instances.len().next_power_of_two();
+            let size = capacity * instance_size;
+            let buffer = render_resource_context.create_buffer(BufferInfo {
+                size,
+                buffer_usage: BufferUsage::VERTEX | BufferUsage::COPY_DST,
+                ..Default::default()
+            });
+            let staging_buffer = render_resource_context.create_buffer(BufferInfo {
+                size,
+                buffer_usage: BufferUsage::COPY_SRC | BufferUsage::MAP_WRITE,
+                mapped_at_creation: true,
+            });
+            render_pipelines.bindings.set_vertex_buffer(
+                &MeshInstance::as_vertex_buffer_descriptor().name,
+                buffer,
+                None,
+            );
+            state.instance_buffers.insert(
+                entity,
+                InstanceBuffers {
+                    buffer,
+                    staging_buffer,
+                    capacity,
+                },
+            );
+        }
+
+        let buffers = &state.instance_buffers[&entity];
+        let size = instances.len() * instance_size;
+        render_resource_context.write_mapped_buffer(
+            buffers.staging_buffer,
+            0..size as u64,
+            &mut |data, _renderer| {
+                data[0..size].copy_from_slice(instances.as_slice().as_bytes());
+            },
+        );
+        render_resource_context.unmap_buffer(buffers.staging_buffer);
+        state.command_queue.copy_buffer_to_buffer(
+            buffers.staging_buffer,
+            0,
+            buffers.buffer,
+            0,
+            size as u64,
+        );
+    }
+
+    // free the buffers of instanced meshes that were despawned
+    let removed = state
+        .instance_buffers
+        .keys()
+        .filter(|entity| !instanced_meshes.contains(*entity))
+        .cloned()
+        .collect::<Vec<_>>();
+    for entity in removed {
+        let buffers = state.instance_buffers.remove(&entity).unwrap();
+        render_resource_context.remove_buffer(buffers.buffer);
+        render_resource_context.remove_buffer(buffers.staging_buffer);
+    }
+}
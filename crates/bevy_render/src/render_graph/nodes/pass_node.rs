@@ -6,7 +6,7 @@ use crate::{
         BindGroupDescriptor, BindType, BindingDescriptor, BindingShaderStage, PipelineDescriptor,
         UniformProperty,
     },
-    render_graph::{Node, ResourceSlotInfo, ResourceSlots},
+    render_graph::{base::Msaa, Node, ResourceSlotInfo, ResourceSlots},
     renderer::{
         BindGroup, BindGroupId, BufferId, RenderContext, RenderResourceBindings, RenderResourceType,
     },
@@ -28,6 +28,7 @@ pub struct PassNode<Q: HecsQuery> {
     color_resolve_target_indices: Vec<Option<usize>>,
     depth_stencil_attachment_input_index: Option<usize>,
     default_clear_color_inputs: Vec<usize>,
+    use_msaa_sample_count: bool,
     camera_bind_group_descriptor: BindGroupDescriptor,
     _marker: PhantomData<Q>,
 }
@@ -91,6 +92,7 @@ impl<Q: HecsQuery> PassNode<Q> {
             color_resolve_target_indices,
             depth_stencil_attachment_input_index,
             default_clear_color_inputs: Vec::new(),
+            use_msaa_sample_count: false,
             camera_bind_group_descriptor,
             _marker: PhantomData::default(),
         }
@@ -106,6 +108,12 @@ impl<Q: HecsQuery> PassNode<Q> {
     pub fn use_default_clear_color(&mut self, color_attachment_index: usize) {
         self.default_clear_color_inputs.push(color_attachment_index);
     }
+
+    /// Makes the pass's sample count follow the [Msaa] resource. While msaa is off, color
+    /// attachments that have a resolve target draw straight into it instead.
+    pub fn use_msaa_sample_count(&mut self) {
+        self.use_msaa_sample_count = true;
+    }
 }
 
 impl<Q: HecsQuery + Send + Sync + 'static> Node for PassNode<Q> {
@@ -142,6 +150,17 @@ impl<Q: HecsQuery + Send + Sync + 'static> Node for PassNode<Q> {
             }
         }
 
+        if self.use_msaa_sample_count {
+            self.descriptor.sample_count = resources.get::<Msaa>().unwrap().samples;
+            if self.descriptor.sample_count == 1 {
+                for color_attachment in self.descriptor.color_attachments.iter_mut() {
+                    if let Some(resolve_target) = color_attachment.resolve_target.take() {
+                        color_attachment.attachment = resolve_target;
+                    }
+                }
+            }
+        }
+
         if let Some(input_index) = self.depth_stencil_attachment_input_index {
             self.descriptor
                 .depth_stencil_attachment
@@ -208,10 +227,21 @@ impl<Q: HecsQuery + Send + Sync + 'static> Node for PassNode<Q> {
                         }
 
                         // each Draw component contains an ordered list of render commands. we turn those into actual render commands here
+                        let mut pipeline_is_compatible = true;
                         for render_command in draw.render_commands.iter() {
+                            if let RenderCommand::SetPipeline { pipeline } = render_command {
+                                // skip the commands of pipelines that draw into other kinds of passes
+                                pipeline_is_compatible = is_pipeline_compatible(
+                                    &self.descriptor,
+                                    pipelines.get(pipeline).unwrap(),
+                                );
+                            }
+                            if !pipeline_is_compatible {
+                                continue;
+                            }
+
                             match render_command {
                                 RenderCommand::SetPipeline { pipeline } => {
-                                    // TODO: Filter pipelines
                                     render_pass.set_pipeline(*pipeline);
                                     let descriptor = pipelines.get(pipeline).unwrap();
                                     draw_state.set_pipeline(*pipeline, descriptor);
@@ -291,6 +321,15 @@ impl<Q: HecsQuery + Send + Sync + 'static> Node for PassNode<Q> {
     }
 }
 
+/// Whether `pipeline` can draw into the attachments of `pass`. An entity can have pipelines for
+/// several passes, e.g. a forward pipeline for the main pass and a depth-only pipeline for a shadow
+/// pass.
+fn is_pipeline_compatible(pass: &PassDescriptor, pipeline: &PipelineDescriptor) -> bool {
+    pipeline.color_states.len() == pass.color_attachments.len()
+        && pipeline.depth_stencil_state.is_some() == pass.depth_stencil_attachment.is_some()
+        && pipeline.sample_count == pass.sample_count
+}
+
 /// Tracks the current pipeline state to ensure draw calls are valid.
 #[derive(Default)]
 struct DrawState {
@@ -664,6 +664,25 @@ fn setup_uniform_texture_resources<T>(
             let render_resource_name = uniforms.get_render_resource_name(i).unwrap();
             let sampler_name = format!("{}_sampler", render_resource_name);
             if let Some(texture_handle) = render_resource.texture() {
+                let is_loaded = |handle| {
+                    render_resource_context
+                        .get_asset_resource(handle, texture::TEXTURE_ASSET_INDEX)
+                        .is_some()
+                };
+                // bind a fallback until the texture loads, so it isn't drawn with a missing or
+                // stale texture
+                let texture_handle = if is_loaded(texture_handle) {
+                    texture_handle
+                } else if uniforms
+                    .get_render_resource_hints(i)
+                    .map_or(false, |hints| {
+                        hints.contains(RenderResourceHints::NORMAL_MAP)
+                    })
+                {
+                    texture::FLAT_NORMAL_TEXTURE_HANDLE
+                } else {
+                    texture::WHITE_TEXTURE_HANDLE
+                };
                 if let Some(texture_resource) = render_resource_context
                     .get_asset_resource(texture_handle, texture::TEXTURE_ASSET_INDEX)
                 {
@@ -685,3 +704,104 @@ fn setup_uniform_texture_resources<T>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::setup_uniform_texture_resources;
+    use crate::{
+        renderer::{
+            HeadlessRenderResourceContext, RenderResource, RenderResourceBinding,
+            RenderResourceBindings, RenderResourceContext, RenderResourceHints, RenderResourceId,
+            RenderResourceIterator, RenderResources, SamplerId, TextureId,
+        },
+        texture::{
+            Texture, FLAT_NORMAL_TEXTURE_HANDLE, SAMPLER_ASSET_INDEX, TEXTURE_ASSET_INDEX,
+            WHITE_TEXTURE_HANDLE,
+        },
+    };
+    use bevy_asset::Handle;
+
+    struct TestMaterial {
+        color_texture: Option<Handle<Texture>>,
+        normal_map: Option<Handle<Texture>>,
+    }
+
+    impl RenderResources for TestMaterial {
+        fn render_resources_len(&self) -> usize {
+            2
+        }
+
+        fn get_render_resource(&self, index: usize) -> Option<&dyn RenderResource> {
+            match index {
+                0 => Some(&self.color_texture),
+                1 => Some(&self.normal_map),
+                _ => None,
+            }
+        }
+
+        fn get_render_resource_name(&self, index: usize) -> Option<&str> {
+            ["TestMaterial_color_texture", "TestMaterial_normal_map"]
+                .get(index)
+                .copied()
+        }
+
+        fn get_render_resource_hints(&self, index: usize) -> Option<RenderResourceHints> {
+            if index == 1 {
+                Some(RenderResourceHints::NORMAL_MAP)
+            } else {
+                None
+            }
+        }
+
+        fn iter(&self) -> RenderResourceIterator {
+            RenderResourceIterator::new(self)
+        }
+    }
+
+    fn load(context: &dyn RenderResourceContext, handle: Handle<Texture>) -> TextureId {
+        let texture = TextureId::new();
+        context.set_asset_resource(
+            handle,
+            RenderResourceId::Texture(texture),
+            TEXTURE_ASSET_INDEX,
+        );
+        context.set_asset_resource(
+            handle,
+            RenderResourceId::Sampler(SamplerId::new()),
+            SAMPLER_ASSET_INDEX,
+        );
+        texture
+    }
+
+    #[test]
+    fn unloaded_textures_use_fallbacks() {
+        let context = HeadlessRenderResourceContext::default();
+        let white = load(&context, WHITE_TEXTURE_HANDLE);
+        let flat_normal = load(&context, FLAT_NORMAL_TEXTURE_HANDLE);
+
+        let color_texture = Handle::from_u128(1);
+        let material = TestMaterial {
+            color_texture: Some(color_texture),
+            normal_map: Some(Handle::from_u128(2)),
+        };
+        let mut bindings = RenderResourceBindings::default();
+        setup_uniform_texture_resources(&material, &context, &mut bindings);
+        assert_eq!(
+            bindings.get("TestMaterial_color_texture"),
+            Some(&RenderResourceBinding::Texture(white))
+        );
+        assert_eq!(
+            bindings.get("TestMaterial_normal_map"),
+            Some(&RenderResourceBinding::Texture(flat_normal))
+        );
+        assert!(bindings.get("TestMaterial_normal_map_sampler").is_some());
+
+        // the real texture replaces the fallback once it loads
+        let loaded = load(&context, color_texture);
+        setup_uniform_texture_resources(&material, &context, &mut bindings);
+        assert_eq!(
+            bindings.get("TestMaterial_color_texture"),
+            Some(&RenderResourceBinding::Texture(loaded))
+        );
+    }
+}
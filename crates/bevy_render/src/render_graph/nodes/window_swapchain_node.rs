@@ -47,9 +47,14 @@ impl Node for WindowSwapChainNode {
         let window_resized_events = resources.get::<Events<WindowResized>>().unwrap();
         let windows = resources.get::<Windows>().unwrap();
 
-        let window = windows
-            .get(self.window_id)
-            .expect("Received window resized event for non-existent window");
+        let window = match windows.get(self.window_id) {
+            Some(window) => window,
+            None => {
+                // the window was closed, so nodes that draw to it are skipped
+                output.remove(WINDOW_TEXTURE);
+                return;
+            }
+        };
 
         let render_resource_context = render_context.resources_mut();
 
@@ -1,5 +1,5 @@
 use crate::{
-    render_graph::{Node, ResourceSlotInfo, ResourceSlots},
+    render_graph::{base::Msaa, Node, ResourceSlotInfo, ResourceSlots},
     renderer::{RenderContext, RenderResourceId, RenderResourceType},
     texture::TextureDescriptor,
 };
@@ -11,6 +11,7 @@ use std::borrow::Cow;
 pub struct WindowTextureNode {
     window_id: WindowId,
     descriptor: TextureDescriptor,
+    use_msaa_sample_count: bool,
     window_created_event_reader: EventReader<WindowCreated>,
     window_resized_event_reader: EventReader<WindowResized>,
 }
@@ -22,10 +23,17 @@ impl WindowTextureNode {
         WindowTextureNode {
             window_id,
             descriptor,
+            use_msaa_sample_count: false,
             window_created_event_reader: Default::default(),
             window_resized_event_reader: Default::default(),
         }
     }
+
+    /// Makes the texture's sample count follow the [Msaa] resource, recreating the texture when it
+    /// changes
+    pub fn use_msaa_sample_count(&mut self) {
+        self.use_msaa_sample_count = true;
+    }
 }
 
 impl Node for WindowTextureNode {
@@ -50,9 +58,26 @@ impl Node for WindowTextureNode {
         let window_resized_events = resources.get::<Events<WindowResized>>().unwrap();
         let windows = resources.get::<Windows>().unwrap();
 
-        let window = windows
-            .get(self.window_id)
-            .expect("Received window resized event for non-existent window");
+        let window = match windows.get(self.window_id) {
+            Some(window) => window,
+            None => {
+                // the window was closed, so nodes that draw to it are skipped
+                if let Some(RenderResourceId::Texture(old_texture)) = output.remove(WINDOW_TEXTURE)
+                {
+                    render_context.resources_mut().remove_texture(old_texture);
+                }
+                return;
+            }
+        };
+
+        let mut sample_count_changed = false;
+        if self.use_msaa_sample_count {
+            let msaa = resources.get::<Msaa>().unwrap();
+            if self.descriptor.sample_count != msaa.samples {
+                self.descriptor.sample_count = msaa.samples;
+                sample_count_changed = true;
+            }
+        }
 
         if self
             .window_created_event_reader
@@ -62,14 +87,15 @@ impl Node for WindowTextureNode {
                 .window_resized_event_reader
                 .find_latest(&window_resized_events, |e| e.id == window.id)
                 .is_some()
+            || sample_count_changed
         {
             let render_resource_context = render_context.resources_mut();
             if let Some(RenderResourceId::Texture(old_texture)) = output.get(WINDOW_TEXTURE) {
                 render_resource_context.remove_texture(old_texture);
             }
 
-            self.descriptor.size.width = window.width;
-            self.descriptor.size.height = window.height;
+            self.descriptor.size.width = window.physical_width;
+            self.descriptor.size.height = window.physical_height;
             let texture_resource = render_resource_context.create_texture(self.descriptor);
             output.set(WINDOW_TEXTURE, RenderResourceId::Texture(texture_resource));
         }
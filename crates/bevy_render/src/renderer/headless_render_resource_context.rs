@@ -3,7 +3,7 @@ use crate::{
     pipeline::{BindGroupDescriptorId, PipelineDescriptor},
     renderer::{BindGroup, BufferId, BufferInfo, RenderResourceId, SamplerId, TextureId},
     shader::Shader,
-    texture::{SamplerDescriptor, TextureDescriptor},
+    texture::{SamplerDescriptor, TextureDescriptor, TextureFormat},
 };
 use bevy_asset::{Assets, Handle, HandleUntyped};
 use bevy_utils::HashMap;
@@ -130,6 +130,11 @@ impl RenderResourceContext for HeadlessRenderResourceContext {
 
     fn clear_bind_groups(&self) {}
 
+    fn supported_sample_counts(&self, _format: TextureFormat) -> Vec<u32> {
+        // the sample counts every WebGPU implementation is required to support
+        vec![1, 4]
+    }
+
     fn get_buffer_info(&self, buffer: BufferId) -> Option<BufferInfo> {
         self.buffer_info.read().get(&buffer).cloned()
     }
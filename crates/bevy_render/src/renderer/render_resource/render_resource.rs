@@ -68,6 +68,9 @@ bitflags::bitflags! {
     #[repr(transparent)]
     pub struct RenderResourceHints: u32 {
         const BUFFER = 1;
+        /// A normal map texture. While it loads, a flat normal map is bound in its place instead of
+        /// the usual white texture.
+        const NORMAL_MAP = 2;
     }
 }
 
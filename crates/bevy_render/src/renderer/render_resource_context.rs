@@ -2,7 +2,7 @@ use crate::{
     pipeline::{BindGroupDescriptorId, PipelineDescriptor},
     renderer::{BindGroup, BufferId, BufferInfo, RenderResourceId, SamplerId, TextureId},
     shader::Shader,
-    texture::{SamplerDescriptor, TextureDescriptor},
+    texture::{SamplerDescriptor, TextureDescriptor, TextureFormat},
 };
 use bevy_asset::{Assets, Handle, HandleUntyped};
 use bevy_window::Window;
@@ -60,6 +60,10 @@ pub trait RenderResourceContext: Downcast + Send + Sync + 'static {
         bind_group: &BindGroup,
     );
     fn clear_bind_groups(&self);
+    /// The sample counts that render targets of the given format can be created with. Backends
+    /// that can't ask the device return the counts they know the format supports, so this might
+    /// leave out counts the device could handle.
+    fn supported_sample_counts(&self, format: TextureFormat) -> Vec<u32>;
 }
 
 impl dyn RenderResourceContext {
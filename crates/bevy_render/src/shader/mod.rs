@@ -1,8 +1,10 @@
 #[allow(clippy::module_inception)]
 mod shader;
 mod shader_defs;
+mod shader_loader;
 mod shader_reflect;
 
 pub use shader::*;
 pub use shader_defs::*;
+pub use shader_loader::*;
 pub use shader_reflect::*;
@@ -1,6 +1,7 @@
 use super::ShaderLayout;
 use bevy_asset::Handle;
-use std::marker::Copy;
+use std::{marker::Copy, path::PathBuf};
+use thiserror::Error;
 
 /// The stage of a shader
 #[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
@@ -21,18 +22,26 @@ impl Into<bevy_glsl_to_spirv::ShaderType> for ShaderStage {
     }
 }
 
+/// An error that occurred while compiling a shader
+#[derive(Error, Debug)]
+pub enum ShaderError {
+    #[error("Shader compilation error:\n{0}")]
+    Compilation(String),
+}
+
 #[cfg(not(target_os = "ios"))]
 fn glsl_to_spirv(
     glsl_source: &str,
     stage: ShaderStage,
     shader_defs: Option<&[String]>,
-) -> Vec<u32> {
+) -> Result<Vec<u32>, ShaderError> {
     use std::io::Read;
 
-    let mut output = bevy_glsl_to_spirv::compile(glsl_source, stage.into(), shader_defs).unwrap();
+    let mut output = bevy_glsl_to_spirv::compile(glsl_source, stage.into(), shader_defs)
+        .map_err(ShaderError::Compilation)?;
     let mut spv_bytes = Vec::new();
     output.read_to_end(&mut spv_bytes).unwrap();
-    bytes_to_words(&spv_bytes)
+    Ok(bytes_to_words(&spv_bytes))
 }
 
 #[cfg(target_os = "ios")]
@@ -51,7 +60,7 @@ fn glsl_to_spirv(
     glsl_source: &str,
     stage: ShaderStage,
     shader_defs: Option<&[String]>,
-) -> Vec<u32> {
+) -> Result<Vec<u32>, ShaderError> {
     let mut compiler = shaderc::Compiler::new().unwrap();
     let mut options = shaderc::CompileOptions::new().unwrap();
     if let Some(shader_defs) = shader_defs {
@@ -68,9 +77,9 @@ fn glsl_to_spirv(
             "main",
             Some(&options),
         )
-        .unwrap();
+        .map_err(|err| ShaderError::Compilation(err.to_string()))?;
 
-    binary_result.as_binary().to_vec()
+    Ok(binary_result.as_binary().to_vec())
 }
 
 fn bytes_to_words(bytes: &[u8]) -> Vec<u32> {
@@ -102,32 +111,37 @@ impl ShaderSource {
 pub struct Shader {
     pub source: ShaderSource,
     pub stage: ShaderStage,
+    /// The files whose contents were `#import`ed into the source. The shader is reloaded when they
+    /// change.
+    pub imports: Vec<PathBuf>,
 }
 
 impl Shader {
     pub fn new(stage: ShaderStage, source: ShaderSource) -> Shader {
-        Shader { stage, source }
-    }
-
-    pub fn from_glsl(stage: ShaderStage, glsl: &str) -> Shader {
         Shader {
-            source: ShaderSource::Glsl(glsl.to_string()),
             stage,
+            source,
+            imports: Vec::new(),
         }
     }
 
+    pub fn from_glsl(stage: ShaderStage, glsl: &str) -> Shader {
+        Shader::new(stage, ShaderSource::Glsl(glsl.to_string()))
+    }
+
     pub fn get_spirv(&self, macros: Option<&[String]>) -> Vec<u32> {
+        self.try_get_spirv(macros).unwrap()
+    }
+
+    pub fn try_get_spirv(&self, macros: Option<&[String]>) -> Result<Vec<u32>, ShaderError> {
         match self.source {
-            ShaderSource::Spirv(ref bytes) => bytes.clone(),
+            ShaderSource::Spirv(ref bytes) => Ok(bytes.clone()),
             ShaderSource::Glsl(ref source) => glsl_to_spirv(&source, self.stage, macros),
         }
     }
 
     pub fn get_spirv_shader(&self, macros: Option<&[String]>) -> Shader {
-        Shader {
-            source: ShaderSource::Spirv(self.get_spirv(macros)),
-            stage: self.stage,
-        }
+        Shader::new(self.stage, ShaderSource::Spirv(self.get_spirv(macros)))
     }
 
     pub fn reflect_layout(&self, enforce_bevy_conventions: bool) -> Option<ShaderLayout> {
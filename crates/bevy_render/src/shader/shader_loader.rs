@@ -0,0 +1,327 @@
+use super::{Shader, ShaderSource, ShaderStage};
+use crate::pipeline::{PipelineCompiler, PipelineDescriptor};
+use anyhow::Result;
+use bevy_app::prelude::{EventReader, Events};
+use bevy_asset::{AssetEvent, AssetLoader, AssetServer, Assets, Handle};
+use bevy_ecs::{Local, Res, ResMut};
+use bevy_utils::{HashMap, HashSet};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Loads GLSL shaders as [Shader] assets. The stage is determined by the file extension: `.vert`,
+/// `.frag` or `.comp`.
+///
+/// A line of the form `#import "path"` is replaced with the contents of the file at `path`, relative
+/// to the file that imports it. Imported files can import other files.
+#[derive(Clone, Default)]
+pub struct ShaderLoader;
+
+impl AssetLoader<Shader> for ShaderLoader {
+    fn from_bytes(&self, asset_path: &Path, bytes: Vec<u8>) -> Result<Shader> {
+        let stage = match asset_path
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("vert") => ShaderStage::Vertex,
+            Some("frag") => ShaderStage::Fragment,
+            Some("comp") => ShaderStage::Compute,
+            _ => return Err(ShaderLoadError::UnknownStage(asset_path.to_owned()).into()),
+        };
+
+        let source = String::from_utf8(bytes)?;
+        let processed = preprocess(asset_path, &source, &|path| fs::read_to_string(path))?;
+        let mut shader = Shader::new(stage, ShaderSource::Glsl(processed.source));
+
+        // compiling here means that a shader with errors never replaces a working version of it
+        if let Err(err) = shader.try_get_spirv(None) {
+            return Err(
+                ShaderLoadError::Compilation(processed.map_error_lines(&err.to_string())).into(),
+            );
+        }
+
+        shader.imports = processed.imports;
+        Ok(shader)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        static EXTENSIONS: &[&str] = &["vert", "frag", "comp"];
+        EXTENSIONS
+    }
+}
+
+/// A file that shaders can `#import`. Imports are loaded through the [AssetServer](bevy_asset::AssetServer)
+/// so that changes to them are picked up.
+#[derive(Debug, Clone)]
+pub struct ShaderImport {
+    pub source: String,
+}
+
+/// Loads `.glsl` files as [ShaderImport] assets
+#[derive(Clone, Default)]
+pub struct ShaderImportLoader;
+
+impl AssetLoader<ShaderImport> for ShaderImportLoader {
+    fn from_bytes(&self, _asset_path: &Path, bytes: Vec<u8>) -> Result<ShaderImport> {
+        Ok(ShaderImport {
+            source: String::from_utf8(bytes)?,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        static EXTENSIONS: &[&str] = &["glsl"];
+        EXTENSIONS
+    }
+}
+
+/// Local "shader hot reload system" state
+#[derive(Default)]
+pub struct ShaderHotReloadState {
+    shader_event_reader: EventReader<AssetEvent<Shader>>,
+    import_event_reader: EventReader<AssetEvent<ShaderImport>>,
+    importers: HashMap<Handle<ShaderImport>, HashSet<Handle<Shader>>>,
+}
+
+/// Recompiles the pipelines that use a shader when it changes, and reloads shaders when a file they
+/// import changes
+pub fn shader_hot_reload_system(
+    mut state: Local<ShaderHotReloadState>,
+    asset_server: Res<AssetServer>,
+    shader_events: Res<Events<AssetEvent<Shader>>>,
+    import_events: Res<Events<AssetEvent<ShaderImport>>>,
+    shaders: Res<Assets<Shader>>,
+    pipelines: Res<Assets<PipelineDescriptor>>,
+    mut pipeline_compiler: ResMut<PipelineCompiler>,
+) {
+    let state = &mut *state;
+    for event in state.shader_event_reader.iter(&shader_events) {
+        let handle = match event {
+            AssetEvent::Created { handle } => *handle,
+            AssetEvent::Modified { handle } => {
+                pipeline_compiler.invalidate_shader(&pipelines, *handle);
+                *handle
+            }
+            AssetEvent::Removed { handle } => {
+                pipeline_compiler.invalidate_shader(&pipelines, *handle);
+                continue;
+            }
+        };
+
+        if let Some(shader) = shaders.get(&handle) {
+            for import in shader.imports.iter() {
+                // loading imports through the asset server makes it watch them for changes
+                let import_handle = match asset_server.get_handle(import) {
+                    Some(import_handle) => import_handle,
+                    None => match asset_server.load(import) {
+                        Ok(import_handle) => import_handle,
+                        Err(err) => {
+                            log::warn!(
+                                "Changes to shader import {:?} won't be reloaded: {:?}",
+                                import,
+                                err
+                            );
+                            continue;
+                        }
+                    },
+                };
+                state
+                    .importers
+                    .entry(import_handle)
+                    .or_insert_with(HashSet::default)
+                    .insert(handle);
+            }
+        }
+    }
+
+    for event in state.import_event_reader.iter(&import_events) {
+        if let AssetEvent::Modified { handle } = event {
+            for importer in state.importers.get(handle).into_iter().flatten() {
+                if let Some(path) = asset_server.get_handle_path(*importer) {
+                    if let Err(err) = asset_server.load_untyped(&path) {
+                        log::error!("Failed to reload shader {:?}: {:?}", path, err);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// An error that occurred while loading a shader
+#[derive(Error, Debug)]
+pub enum ShaderLoadError {
+    #[error("Can't determine the shader stage of {0:?} from its extension")]
+    UnknownStage(PathBuf),
+    #[error("Failed to read {path:?}, imported by {importer:?}")]
+    Io {
+        path: PathBuf,
+        importer: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{0:?} imports itself, through {1:?}")]
+    Cycle(PathBuf, Vec<PathBuf>),
+    #[error("Malformed import in {0:?} on line {1}. Imports look like: #import \"path\"")]
+    MalformedImport(PathBuf, usize),
+    #[error("{0}")]
+    Compilation(String),
+}
+
+/// Shader source with its imports inlined
+#[derive(Debug, Default)]
+struct ProcessedSource {
+    source: String,
+    /// Every imported file, in the order they were first imported
+    imports: Vec<PathBuf>,
+    /// The file and line number (starting at 1) each line of `source` came from
+    source_map: Vec<(PathBuf, usize)>,
+}
+
+impl ProcessedSource {
+    /// Replaces the line numbers in compiler messages like `ERROR: <file>:<line>: <message>` with
+    /// the file and line the error is in before imports were inlined
+    fn map_error_lines(&self, message: &str) -> String {
+        message
+            .lines()
+            .map(|line| {
+                let parts = line.splitn(4, ':').collect::<Vec<_>>();
+                let source_location = parts
+                    .get(2)
+                    .and_then(|line_number| line_number.trim().parse::<usize>().ok())
+                    .and_then(|line_number| self.source_map.get(line_number.checked_sub(1)?));
+                match (source_location, parts.get(3)) {
+                    (Some((path, line_number)), Some(rest)) => {
+                        format!("{}: {}:{}:{}", parts[0], path.display(), line_number, rest)
+                    }
+                    _ => line.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn preprocess(
+    path: &Path,
+    source: &str,
+    read: &dyn Fn(&Path) -> std::io::Result<String>,
+) -> Result<ProcessedSource, ShaderLoadError> {
+    let mut processed = ProcessedSource::default();
+    let mut import_stack = vec![path.to_owned()];
+    inline_imports(path, source, read, &mut import_stack, &mut processed)?;
+    Ok(processed)
+}
+
+fn inline_imports(
+    path: &Path,
+    source: &str,
+    read: &dyn Fn(&Path) -> std::io::Result<String>,
+    import_stack: &mut Vec<PathBuf>,
+    processed: &mut ProcessedSource,
+) -> Result<(), ShaderLoadError> {
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let import = match line.trim_start().strip_prefix("#import") {
+            Some(import) => import.trim(),
+            None => {
+                processed.source.push_str(line);
+                processed.source.push('\n');
+                processed.source_map.push((path.to_owned(), line_number));
+                continue;
+            }
+        };
+
+        if import.len() < 2 || !import.starts_with('"') || !import.ends_with('"') {
+            return Err(ShaderLoadError::MalformedImport(
+                path.to_owned(),
+                line_number,
+            ));
+        }
+
+        let import_path = path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(&import[1..import.len() - 1]);
+        if import_stack.contains(&import_path) {
+            return Err(ShaderLoadError::Cycle(import_path, import_stack.clone()));
+        }
+
+        let import_source = read(&import_path).map_err(|source| ShaderLoadError::Io {
+            path: import_path.clone(),
+            importer: path.to_owned(),
+            source,
+        })?;
+        if !processed.imports.contains(&import_path) {
+            processed.imports.push(import_path.clone());
+        }
+
+        import_stack.push(import_path.clone());
+        inline_imports(&import_path, &import_source, read, import_stack, processed)?;
+        import_stack.pop();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{preprocess, ShaderLoadError};
+    use std::{
+        io,
+        path::{Path, PathBuf},
+    };
+
+    fn read(path: &Path) -> io::Result<String> {
+        match path.to_str().unwrap() {
+            "shaders/lighting.glsl" => Ok("#import \"common.glsl\"\nvec3 light();\n".to_string()),
+            "shaders/common.glsl" => Ok("const float PI = 3.14;\n".to_string()),
+            "shaders/a.glsl" => Ok("#import \"b.glsl\"\n".to_string()),
+            "shaders/b.glsl" => Ok("#import \"a.glsl\"\n".to_string()),
+            _ => Err(io::Error::new(io::ErrorKind::NotFound, "not found")),
+        }
+    }
+
+    #[test]
+    fn test_nested_imports() {
+        let source = "#version 450\n#import \"lighting.glsl\"\nvoid main() {}\n";
+        let processed = preprocess(Path::new("shaders/shader.frag"), source, &read).unwrap();
+        assert_eq!(
+            processed.source,
+            "#version 450\nconst float PI = 3.14;\nvec3 light();\nvoid main() {}\n"
+        );
+        assert_eq!(
+            processed.imports,
+            vec![
+                PathBuf::from("shaders/lighting.glsl"),
+                PathBuf::from("shaders/common.glsl")
+            ]
+        );
+        assert_eq!(
+            processed.map_error_lines("ERROR: 0:3: 'light' : no matching overloaded function"),
+            "ERROR: shaders/lighting.glsl:2: 'light' : no matching overloaded function"
+        );
+        assert_eq!(
+            processed.map_error_lines("ERROR: 0:4: 'x' : undeclared identifier"),
+            "ERROR: shaders/shader.frag:3: 'x' : undeclared identifier"
+        );
+    }
+
+    #[test]
+    fn test_import_cycle() {
+        let source = "#version 450\n#import \"a.glsl\"\n";
+        let result = preprocess(Path::new("shaders/shader.frag"), source, &read);
+        assert!(
+            matches!(result, Err(ShaderLoadError::Cycle(path, _)) if path == Path::new("shaders/a.glsl"))
+        );
+    }
+
+    #[test]
+    fn test_malformed_import() {
+        let source = "#version 450\n#import lighting.glsl\n";
+        let result = preprocess(Path::new("shaders/shader.frag"), source, &read);
+        assert!(matches!(
+            result,
+            Err(ShaderLoadError::MalformedImport(_, 2))
+        ));
+    }
+}
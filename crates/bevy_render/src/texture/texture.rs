@@ -11,6 +11,15 @@ use bevy_utils::HashSet;
 pub const TEXTURE_ASSET_INDEX: usize = 0;
 pub const SAMPLER_ASSET_INDEX: usize = 1;
 
+/// A 1x1 white texture, bound in place of textures that haven't loaded yet
+pub const WHITE_TEXTURE_HANDLE: Handle<Texture> =
+    Handle::from_u128(223497164063458519219843617263180236917);
+
+/// A 1x1 normal map whose normal points straight out of the surface, bound in place of normal
+/// maps that haven't loaded yet
+pub const FLAT_NORMAL_TEXTURE_HANDLE: Handle<Texture> =
+    Handle::from_u128(84627018403751932874610738165212574053);
+
 #[derive(Clone)]
 pub struct Texture {
     pub data: Vec<u8>,
@@ -59,6 +68,26 @@ impl Texture {
         value
     }
 
+    /// Adds the textures that are bound in place of textures that haven't loaded yet
+    pub(crate) fn add_fallback_textures(textures: &mut Assets<Texture>) {
+        textures.set(
+            WHITE_TEXTURE_HANDLE,
+            Texture::new_fill(
+                Vec2::one(),
+                &[255, 255, 255, 255],
+                TextureFormat::Rgba8UnormSrgb,
+            ),
+        );
+        textures.set(
+            FLAT_NORMAL_TEXTURE_HANDLE,
+            Texture::new_fill(
+                Vec2::one(),
+                &[128, 128, 255, 255],
+                TextureFormat::Rgba8Unorm,
+            ),
+        );
+    }
+
     pub fn aspect(&self) -> f32 {
         self.size.y() / self.size.x()
     }
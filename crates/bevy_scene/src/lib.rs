@@ -2,10 +2,14 @@ mod loaded_scenes;
 mod scene;
 mod scene_spawner;
 pub mod serde;
+mod world_diff;
+mod world_serde;
 
 pub use loaded_scenes::*;
 pub use scene::*;
 pub use scene_spawner::*;
+pub use world_diff::*;
+pub use world_serde::*;
 
 pub mod prelude {
     pub use crate::{Scene, SceneSpawner};
@@ -26,6 +30,15 @@ impl Plugin for ScenePlugin {
             .add_asset_loader::<Scene, SceneLoader>()
             .init_resource::<SceneSpawner>()
             .add_stage_after(stage::EVENT_UPDATE, SCENE_STAGE)
+            // patch existing instances before new ones are spawned from the modified scene
+            .add_system_to_stage(SCENE_STAGE, scene_reload_system.thread_local_system())
             .add_system_to_stage(SCENE_STAGE, scene_spawner_system.thread_local_system());
     }
+
+    fn dependencies(&self) -> Vec<std::any::TypeId> {
+        vec![
+            std::any::TypeId::of::<bevy_asset::AssetPlugin>(),
+            std::any::TypeId::of::<bevy_type_registry::TypeRegistryPlugin>(),
+        ]
+    }
 }
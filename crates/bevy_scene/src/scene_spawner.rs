@@ -1,16 +1,23 @@
-use crate::Scene;
+use crate::{serialize_ron, Scene};
 use bevy_app::prelude::*;
 use bevy_asset::{AssetEvent, Assets, Handle};
-use bevy_ecs::{Resources, World};
+use bevy_ecs::{Entity, EntityMap, Resources, World};
+use bevy_property::property_serde::DynamicPropertiesSerializer;
 use bevy_type_registry::TypeRegistry;
 use bevy_utils::{HashMap, HashSet};
 use thiserror::Error;
 use uuid::Uuid;
 
 struct InstanceInfo {
-    entity_map: HashMap<u128, bevy_ecs::Entity>,
+    entity_map: EntityMap,
+    /// The version of the scene this instance was spawned from or last patched to
+    snapshot: SceneSnapshot,
 }
 
+/// The serialized component values of a scene, by scene entity id and component type name.
+/// Kept for spawned instances so a reload can tell which components changed.
+type SceneSnapshot = HashMap<u128, HashMap<String, Option<String>>>;
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 struct InstanceId(Uuid);
 
@@ -26,6 +33,7 @@ pub struct SceneSpawner {
     spawned_scenes: HashMap<Handle<Scene>, Vec<InstanceId>>,
     spawned_instances: HashMap<InstanceId, InstanceInfo>,
     scene_asset_event_reader: EventReader<AssetEvent<Scene>>,
+    scene_reload_event_reader: EventReader<AssetEvent<Scene>>,
     scenes_to_spawn: Vec<Handle<Scene>>,
     scenes_to_load: Vec<Handle<Scene>>,
 }
@@ -58,24 +66,31 @@ impl SceneSpawner {
         Ok(())
     }
 
-    pub fn spawn_sync(
+    /// Spawns a new copy of the scene into `world`, giving each of its entities a fresh id
+    ///
+    /// Returns the map from the ids in the scene to the spawned entities. Components registered
+    /// with `register_map_entities` are remapped through it, so references between the scene's
+    /// entities point at the same copy.
+    pub fn spawn(
         &mut self,
         world: &mut World,
         resources: &Resources,
         scene_handle: Handle<Scene>,
-    ) -> Result<(), SceneSpawnError> {
+    ) -> Result<EntityMap, SceneSpawnError> {
         let instance_id = InstanceId::new();
         let mut instance_info = InstanceInfo {
-            entity_map: HashMap::default(),
+            entity_map: EntityMap::default(),
+            snapshot: Self::snapshot(resources, scene_handle)?,
         };
         Self::load_internal(world, resources, scene_handle, Some(&mut instance_info))?;
+        let entity_map = instance_info.entity_map.clone();
         self.spawned_instances.insert(instance_id, instance_info);
         let spawned = self
             .spawned_scenes
             .entry(scene_handle)
             .or_insert_with(Vec::new);
         spawned.push(instance_id);
-        Ok(())
+        Ok(entity_map)
     }
 
     fn load_internal(
@@ -93,14 +108,23 @@ impl SceneSpawner {
                 handle: scene_handle,
             })?;
 
+        if let Some(ref mut instance_info) = instance_info {
+            // every scene entity needs an id before components referring to each other are mapped
+            for scene_entity in scene.entities.iter() {
+                let scene_id = Entity::from_id(scene_entity.entity);
+                if instance_info.entity_map.get(scene_id).is_err() {
+                    instance_info
+                        .entity_map
+                        .insert(scene_id, world.entity_generator().generate());
+                }
+            }
+        }
+
         for scene_entity in scene.entities.iter() {
-            let entity = if let Some(ref mut instance_info) = instance_info {
-                *instance_info
-                    .entity_map
-                    .entry(scene_entity.entity)
-                    .or_insert_with(bevy_ecs::Entity::new)
-            } else {
-                bevy_ecs::Entity::from_id(scene_entity.entity)
+            let scene_id = Entity::from_id(scene_entity.entity);
+            let entity = match instance_info {
+                Some(ref instance_info) => instance_info.entity_map.get(scene_id).unwrap(),
+                None => scene_id,
             };
             if world.contains(entity) {
                 for component in scene_entity.components.iter() {
@@ -126,9 +150,32 @@ impl SceneSpawner {
                 }
             }
         }
+
+        if let Some(instance_info) = instance_info {
+            for scene_entity in scene.entities.iter() {
+                let entity = instance_info
+                    .entity_map
+                    .get(Entity::from_id(scene_entity.entity))
+                    .unwrap();
+                for component in scene_entity.components.iter() {
+                    // components were looked up above, so they are registered
+                    component_registry
+                        .get_with_name(&component.type_name)
+                        .unwrap()
+                        .map_component_entities(world, entity, &instance_info.entity_map);
+                }
+            }
+        }
         Ok(())
     }
 
+    /// Brings the spawned instances of each scene up to date with its current version
+    ///
+    /// Only the differences from the version each instance was last brought to are applied, so
+    /// instances spawned from different versions are all patched correctly: changed and added
+    /// components are inserted, removed components are removed, and entities added to or removed
+    /// from the scene are spawned or despawned. Components that aren't part of the scene are left
+    /// untouched.
     pub fn update_spawned_scenes(
         &mut self,
         world: &mut World,
@@ -136,17 +183,146 @@ impl SceneSpawner {
         scene_handles: &[Handle<Scene>],
     ) -> Result<(), SceneSpawnError> {
         for scene_handle in scene_handles {
+            let snapshot = Self::snapshot(resources, *scene_handle)?;
             if let Some(spawned_instances) = self.spawned_scenes.get(scene_handle) {
                 for instance_id in spawned_instances.iter() {
                     if let Some(instance_info) = self.spawned_instances.get_mut(instance_id) {
-                        Self::load_internal(world, resources, *scene_handle, Some(instance_info))?;
+                        let previous =
+                            std::mem::replace(&mut instance_info.snapshot, snapshot.clone());
+                        Self::patch_internal(
+                            world,
+                            resources,
+                            *scene_handle,
+                            &previous,
+                            &snapshot,
+                            instance_info,
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn patch_internal(
+        world: &mut World,
+        resources: &Resources,
+        scene_handle: Handle<Scene>,
+        previous: &SceneSnapshot,
+        current: &SceneSnapshot,
+        instance_info: &mut InstanceInfo,
+    ) -> Result<(), SceneSpawnError> {
+        let type_registry = resources.get::<TypeRegistry>().unwrap();
+        let component_registry = type_registry.component.read();
+        let scenes = resources.get::<Assets<Scene>>().unwrap();
+        let scene = scenes
+            .get(&scene_handle)
+            .ok_or_else(|| SceneSpawnError::NonExistentScene {
+                handle: scene_handle,
+            })?;
+
+        for scene_id in previous.keys() {
+            if !current.contains_key(scene_id) {
+                if let Some(entity) = instance_info.entity_map.remove(Entity::from_id(*scene_id)) {
+                    // the entity may already have been despawned at runtime
+                    let _ = world.despawn(entity);
+                }
+            }
+        }
+
+        for scene_entity in scene.entities.iter() {
+            let scene_id = Entity::from_id(scene_entity.entity);
+            if instance_info.entity_map.get(scene_id).is_err() {
+                instance_info
+                    .entity_map
+                    .insert(scene_id, world.entity_generator().generate());
+            }
+        }
+
+        let no_components = HashMap::default();
+        let mut patched = Vec::new();
+        for scene_entity in scene.entities.iter() {
+            let entity = instance_info
+                .entity_map
+                .get(Entity::from_id(scene_entity.entity))
+                .unwrap();
+            if !world.contains(entity) {
+                if previous.contains_key(&scene_entity.entity) {
+                    // despawned at runtime, so it stays despawned
+                    continue;
+                }
+                world.spawn_as_entity(entity, ());
+            }
+
+            let previous_components = previous.get(&scene_entity.entity).unwrap_or(&no_components);
+            let current_components = &current[&scene_entity.entity];
+            for type_name in previous_components.keys() {
+                if !current_components.contains_key(type_name) {
+                    if let Some(component_registration) =
+                        component_registry.get_with_name(type_name)
+                    {
+                        component_registration.remove_component_from_entity(world, entity);
                     }
                 }
             }
+
+            for component in scene_entity.components.iter() {
+                let previous_value = previous_components.get(&component.type_name);
+                let value = &current_components[&component.type_name];
+                if value.is_some() && previous_value == Some(value) {
+                    continue;
+                }
+                let component_registration = component_registry
+                    .get_with_name(&component.type_name)
+                    .ok_or_else(|| SceneSpawnError::UnregisteredComponent {
+                        type_name: component.type_name.to_string(),
+                    })?;
+                component_registration.add_component_to_entity(world, resources, entity, component);
+                patched.push((entity, component_registration));
+            }
+        }
+
+        for (entity, component_registration) in patched {
+            component_registration.map_component_entities(world, entity, &instance_info.entity_map);
         }
         Ok(())
     }
 
+    fn snapshot(
+        resources: &Resources,
+        scene_handle: Handle<Scene>,
+    ) -> Result<SceneSnapshot, SceneSpawnError> {
+        let type_registry = resources.get::<TypeRegistry>().unwrap();
+        let property_type_registry = type_registry.property.read();
+        let scenes = resources.get::<Assets<Scene>>().unwrap();
+        let scene = scenes
+            .get(&scene_handle)
+            .ok_or_else(|| SceneSpawnError::NonExistentScene {
+                handle: scene_handle,
+            })?;
+
+        Ok(scene
+            .entities
+            .iter()
+            .map(|scene_entity| {
+                let components = scene_entity
+                    .components
+                    .iter()
+                    .map(|component| {
+                        // a component that fails to serialize is always treated as changed
+                        let value = serialize_ron(DynamicPropertiesSerializer::new(
+                            component,
+                            &property_type_registry,
+                        ))
+                        .ok();
+                        (component.type_name.clone(), value)
+                    })
+                    .collect();
+                (scene_entity.entity, components)
+            })
+            .collect())
+    }
+
     pub fn load_queued_scenes(
         &mut self,
         world: &mut World,
@@ -176,7 +352,7 @@ impl SceneSpawner {
         let scenes_to_spawn = self.scenes_to_spawn.drain(..).collect::<Vec<_>>();
         let mut non_existent_scenes = Vec::new();
         for scene_handle in scenes_to_spawn {
-            match self.spawn_sync(world, resources, scene_handle) {
+            match self.spawn(world, resources, scene_handle) {
                 Ok(_) => {}
                 Err(SceneSpawnError::NonExistentScene { .. }) => {
                     non_existent_scenes.push(scene_handle)
@@ -194,7 +370,6 @@ pub fn scene_spawner_system(world: &mut World, resources: &mut Resources) {
     let mut scene_spawner = resources.get_mut::<SceneSpawner>().unwrap();
     let scene_asset_events = resources.get::<Events<AssetEvent<Scene>>>().unwrap();
 
-    let mut updated_spawned_scenes = Vec::new();
     for event in scene_spawner
         .scene_asset_event_reader
         .iter(&scene_asset_events)
@@ -203,15 +378,223 @@ pub fn scene_spawner_system(world: &mut World, resources: &mut Resources) {
             if scene_spawner.loaded_scenes.contains(handle) {
                 scene_spawner.load(*handle);
             }
+        }
+    }
+
+    scene_spawner.load_queued_scenes(world, resources).unwrap();
+    scene_spawner.spawn_queued_scenes(world, resources).unwrap();
+}
+
+/// Patches the spawned instances of modified scenes in place. See
+/// [SceneSpawner::update_spawned_scenes].
+pub fn scene_reload_system(world: &mut World, resources: &mut Resources) {
+    let mut scene_spawner = resources.get_mut::<SceneSpawner>().unwrap();
+    let scene_asset_events = resources.get::<Events<AssetEvent<Scene>>>().unwrap();
+
+    let mut updated_spawned_scenes = Vec::new();
+    for event in scene_spawner
+        .scene_reload_event_reader
+        .iter(&scene_asset_events)
+    {
+        if let AssetEvent::Modified { handle } = event {
             if scene_spawner.spawned_scenes.contains_key(handle) {
                 updated_spawned_scenes.push(*handle);
             }
         }
     }
 
-    scene_spawner.load_queued_scenes(world, resources).unwrap();
-    scene_spawner.spawn_queued_scenes(world, resources).unwrap();
     scene_spawner
         .update_spawned_scenes(world, resources, &updated_spawned_scenes)
         .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{IntoQuerySystem, IntoThreadLocalSystem, MapEntities, NoSuchEntity, Schedule};
+    use bevy_property::{Properties, PropertiesVal};
+
+    #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Properties)]
+    struct Value(u32);
+
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Properties)]
+    struct Target(Entity);
+
+    impl Default for Target {
+        fn default() -> Self {
+            Target(Entity::from_id(u128::MAX))
+        }
+    }
+
+    impl MapEntities for Target {
+        fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), NoSuchEntity> {
+            self.0.map_entities(entity_map)
+        }
+    }
+
+    #[test]
+    fn spawned_instances_reference_their_own_entities() {
+        let type_registry = TypeRegistry::default();
+        {
+            let mut component_registry = type_registry.component.write();
+            component_registry.register::<Target>();
+            component_registry.register_map_entities::<Target>();
+        }
+
+        // two entities pointing at each other
+        let mut source = World::new();
+        let a = source.spawn((Target::default(),));
+        let b = source.spawn((Target(a),));
+        source.insert_one(a, Target(b)).unwrap();
+        let scene = Scene::from_world(&source, &type_registry.component.read());
+
+        let mut resources = Resources::default();
+        let mut scenes = Assets::<Scene>::default();
+        let scene_handle = scenes.add(scene);
+        resources.insert(scenes);
+        resources.insert(type_registry);
+
+        let mut world = World::new();
+        let mut scene_spawner = SceneSpawner::default();
+        let first = scene_spawner
+            .spawn(&mut world, &resources, scene_handle)
+            .unwrap();
+        let second = scene_spawner
+            .spawn(&mut world, &resources, scene_handle)
+            .unwrap();
+
+        let mut spawned = Vec::new();
+        for entity_map in [&first, &second].iter() {
+            let new_a = entity_map.get(a).unwrap();
+            let new_b = entity_map.get(b).unwrap();
+            assert_eq!(*world.get::<Target>(new_a).unwrap(), Target(new_b));
+            assert_eq!(*world.get::<Target>(new_b).unwrap(), Target(new_a));
+            spawned.push(new_a);
+            spawned.push(new_b);
+        }
+        let distinct = spawned.iter().collect::<HashSet<_>>();
+        assert_eq!(distinct.len(), 4);
+        assert!(!distinct.contains(&a) && !distinct.contains(&b));
+    }
+
+    #[test]
+    fn reloading_a_scene_patches_spawned_instances() {
+        let type_registry = TypeRegistry::default();
+        type_registry.component.write().register::<Value>();
+
+        let mut source = World::new();
+        let source_entity = source.spawn((Value(1),));
+        let scene = Scene::from_world(&source, &type_registry.component.read());
+
+        let mut resources = Resources::default();
+        let mut scenes = Assets::<Scene>::default();
+        let scene_handle = scenes.add(scene);
+        resources.insert(scenes);
+        resources.insert(type_registry);
+        resources.insert(Events::<AssetEvent<Scene>>::default());
+
+        let mut world = World::new();
+        let mut scene_spawner = SceneSpawner::default();
+        let entity_map = scene_spawner
+            .spawn(&mut world, &resources, scene_handle)
+            .unwrap();
+        resources.insert(scene_spawner);
+        let entity = entity_map.get(source_entity).unwrap();
+        assert_eq!(*world.get::<Value>(entity).unwrap(), Value(1));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", Assets::<Scene>::asset_event_system.system());
+        schedule.add_system_to_stage("update", scene_reload_system.thread_local_system());
+        schedule.run(&mut world, &mut resources);
+        let mut event_reader = resources
+            .get::<Events<AssetEvent<Scene>>>()
+            .unwrap()
+            .get_reader_current();
+
+        // reload the scene under the same handle
+        source.get_mut::<Value>(source_entity).unwrap().0 = 2;
+        let reloaded = Scene::from_world(
+            &source,
+            &resources.get::<TypeRegistry>().unwrap().component.read(),
+        );
+        resources
+            .get_mut::<Assets<Scene>>()
+            .unwrap()
+            .set(scene_handle, reloaded);
+        schedule.run(&mut world, &mut resources);
+
+        let scene_events = resources.get::<Events<AssetEvent<Scene>>>().unwrap();
+        assert!(event_reader.iter(&scene_events).any(
+            |event| matches!(event, AssetEvent::Modified { handle } if *handle == scene_handle)
+        ));
+        let scenes = resources.get::<Assets<Scene>>().unwrap();
+        let value = &scenes.get(&scene_handle).unwrap().entities[0].components[0];
+        assert_eq!(value.prop_val::<u32>("0"), Some(&2));
+        // the spawned entity was patched in place rather than respawned
+        assert_eq!(*world.get::<Value>(entity).unwrap(), Value(2));
+        assert_eq!(world.entity_count(), 1);
+    }
+
+    #[test]
+    fn instances_spawned_from_different_versions_are_patched_against_their_own() {
+        let type_registry = TypeRegistry::default();
+        type_registry.component.write().register::<Value>();
+
+        let mut source = World::new();
+        let source_entity = source.spawn((Value(1),));
+        let scene = Scene::from_world(&source, &type_registry.component.read());
+
+        let mut resources = Resources::default();
+        let mut scenes = Assets::<Scene>::default();
+        let scene_handle = scenes.add(scene);
+        resources.insert(scenes);
+        resources.insert(type_registry);
+
+        let mut world = World::new();
+        let mut scene_spawner = SceneSpawner::default();
+        let first = scene_spawner
+            .spawn(&mut world, &resources, scene_handle)
+            .unwrap()
+            .get(source_entity)
+            .unwrap();
+
+        // a second instance is spawned from the modified scene before the reload is handled
+        let set_value = |resources: &Resources, source: &mut World, value| {
+            source.get_mut::<Value>(source_entity).unwrap().0 = value;
+            let scene = Scene::from_world(
+                source,
+                &resources.get::<TypeRegistry>().unwrap().component.read(),
+            );
+            resources
+                .get_mut::<Assets<Scene>>()
+                .unwrap()
+                .set(scene_handle, scene);
+        };
+        set_value(&resources, &mut source, 2);
+        let second = scene_spawner
+            .spawn(&mut world, &resources, scene_handle)
+            .unwrap()
+            .get(source_entity)
+            .unwrap();
+        scene_spawner
+            .update_spawned_scenes(&mut world, &resources, &[scene_handle])
+            .unwrap();
+        assert_eq!(*world.get::<Value>(first).unwrap(), Value(2));
+        assert_eq!(*world.get::<Value>(second).unwrap(), Value(2));
+
+        // a runtime change to a value the scene didn't change survives the next reload
+        world.get_mut::<Value>(second).unwrap().0 = 5;
+        scene_spawner
+            .update_spawned_scenes(&mut world, &resources, &[scene_handle])
+            .unwrap();
+        assert_eq!(*world.get::<Value>(second).unwrap(), Value(5));
+
+        set_value(&resources, &mut source, 3);
+        scene_spawner
+            .update_spawned_scenes(&mut world, &resources, &[scene_handle])
+            .unwrap();
+        assert_eq!(*world.get::<Value>(first).unwrap(), Value(3));
+        assert_eq!(*world.get::<Value>(second).unwrap(), Value(3));
+    }
+}
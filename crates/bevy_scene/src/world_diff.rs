@@ -0,0 +1,269 @@
+use crate::{serialize_ron, Scene, SceneSpawnError};
+use bevy_ecs::{Entity, Resources, World};
+use bevy_property::{property_serde::DynamicPropertiesSerializer, DynamicProperties};
+use bevy_type_registry::TypeRegistry;
+use bevy_utils::HashMap;
+
+/// The registered components of every entity in a world at one point in time, to compute
+/// [WorldDiff]s against
+///
+/// Components whose types aren't in the component registry are left out, but their entities are
+/// still tracked.
+#[derive(Default)]
+pub struct WorldSnapshot {
+    entities: HashMap<u128, HashMap<String, SnapshotComponent>>,
+}
+
+struct SnapshotComponent {
+    properties: DynamicProperties,
+    /// The serialized value of the component. Change trackers are cleared every frame, so they
+    /// can't tell whether a component changed since an older snapshot, but comparing values can.
+    /// A component that fails to serialize is always treated as changed.
+    value: Option<String>,
+}
+
+impl WorldSnapshot {
+    pub fn from_world(world: &World, type_registry: &TypeRegistry) -> Self {
+        let component_registry = type_registry.component.read();
+        let property_type_registry = type_registry.property.read();
+        let scene = Scene::from_world(world, &component_registry);
+        let entities = scene
+            .entities
+            .into_iter()
+            .map(|scene_entity| {
+                let components = scene_entity
+                    .components
+                    .into_iter()
+                    .map(|properties| {
+                        let value = serialize_ron(DynamicPropertiesSerializer::new(
+                            &properties,
+                            &property_type_registry,
+                        ))
+                        .ok();
+                        (
+                            properties.type_name.clone(),
+                            SnapshotComponent { properties, value },
+                        )
+                    })
+                    .collect();
+                (scene_entity.entity, components)
+            })
+            .collect();
+        WorldSnapshot { entities }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains_key(&entity.id())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Computes the changes that turn the world this snapshot was taken of into `world`
+    pub fn diff(&self, world: &World, type_registry: &TypeRegistry) -> WorldDiff {
+        let current = WorldSnapshot::from_world(world, type_registry);
+        let mut diff = WorldDiff::default();
+        for id in self.entities.keys() {
+            if !current.entities.contains_key(id) {
+                diff.despawned.push(Entity::from_id(*id));
+            }
+        }
+
+        let no_components = HashMap::default();
+        for (id, components) in current.entities {
+            let entity = Entity::from_id(id);
+            let previous_components = match self.entities.get(&id) {
+                Some(previous_components) => previous_components,
+                None => {
+                    diff.spawned.push(entity);
+                    &no_components
+                }
+            };
+            for type_name in previous_components.keys() {
+                if !components.contains_key(type_name) {
+                    diff.removed.push((entity, type_name.clone()));
+                }
+            }
+            for (type_name, component) in components {
+                match previous_components.get(&type_name) {
+                    None => diff.added.push((entity, component.properties)),
+                    Some(previous)
+                        if component.value.is_none() || previous.value != component.value =>
+                    {
+                        diff.changed.push((entity, component.properties))
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        // snapshots are hash maps, sorting makes diffs of the same worlds identical
+        diff.spawned.sort_by_key(|entity| entity.id());
+        diff.despawned.sort_by_key(|entity| entity.id());
+        diff.added.sort_by(|(a, a_properties), (b, b_properties)| {
+            (a.id(), &a_properties.type_name).cmp(&(b.id(), &b_properties.type_name))
+        });
+        diff.changed
+            .sort_by(|(a, a_properties), (b, b_properties)| {
+                (a.id(), &a_properties.type_name).cmp(&(b.id(), &b_properties.type_name))
+            });
+        diff.removed.sort_by(|(a, a_type_name), (b, b_type_name)| {
+            (a.id(), a_type_name).cmp(&(b.id(), b_type_name))
+        });
+        diff
+    }
+}
+
+/// The changes to the registered components of a world between a [WorldSnapshot] and a later
+/// state of the world
+///
+/// Applying the diff to a world that matches the snapshot, e.g. one restored from it, makes its
+/// registered components match the later state exactly. Entity ids are preserved.
+#[derive(Default)]
+pub struct WorldDiff {
+    /// Entities that didn't exist in the snapshot. Their components are in `added`.
+    pub spawned: Vec<Entity>,
+    pub despawned: Vec<Entity>,
+    /// Components that entities didn't have in the snapshot
+    pub added: Vec<(Entity, DynamicProperties)>,
+    /// Components whose value differs from the snapshot
+    pub changed: Vec<(Entity, DynamicProperties)>,
+    /// Components that entities had in the snapshot, by type name
+    pub removed: Vec<(Entity, String)>,
+}
+
+impl WorldDiff {
+    pub fn is_empty(&self) -> bool {
+        self.spawned.is_empty()
+            && self.despawned.is_empty()
+            && self.added.is_empty()
+            && self.changed.is_empty()
+            && self.removed.is_empty()
+    }
+
+    /// Applies the changes to `world`. `resources` are used to construct components before their
+    /// properties are applied.
+    pub fn apply(
+        &self,
+        world: &mut World,
+        type_registry: &TypeRegistry,
+        resources: &Resources,
+    ) -> Result<(), SceneSpawnError> {
+        let component_registry = type_registry.component.read();
+        for entity in self.despawned.iter() {
+            // despawning twice is harmless, so diffs can be reapplied
+            let _ = world.despawn(*entity);
+        }
+        for entity in self.spawned.iter() {
+            if !world.contains(*entity) {
+                world.spawn_as_entity(*entity, ());
+            }
+        }
+
+        for (entity, type_name) in self.removed.iter() {
+            let component_registration =
+                component_registry.get_with_name(type_name).ok_or_else(|| {
+                    SceneSpawnError::UnregisteredComponent {
+                        type_name: type_name.to_string(),
+                    }
+                })?;
+            component_registration.remove_component_from_entity(world, *entity);
+        }
+
+        // inserting replaces the whole component, so fields reset to their defaults in the later
+        // state are reset here too
+        for (entity, properties) in self.added.iter().chain(self.changed.iter()) {
+            let component_registration = component_registry
+                .get_with_name(&properties.type_name)
+                .ok_or_else(|| SceneSpawnError::UnregisteredComponent {
+                    type_name: properties.type_name.to_string(),
+                })?;
+            component_registration.add_component_to_entity(world, resources, *entity, properties);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_property::Properties;
+
+    #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Properties)]
+    struct Value(u32);
+
+    #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Properties)]
+    struct Label(u32);
+
+    fn baseline() -> World {
+        let mut world = World::new();
+        world.spawn_as_entity(Entity::from_id(1), (Value(1),));
+        world.spawn_as_entity(Entity::from_id(2), (Value(2), Label(20)));
+        world.spawn_as_entity(Entity::from_id(3), (Value(3),));
+        world
+    }
+
+    #[test]
+    fn applying_a_diff_to_the_baseline_reproduces_the_world() {
+        let type_registry = TypeRegistry::default();
+        {
+            let mut component_registry = type_registry.component.write();
+            component_registry.register::<Value>();
+            component_registry.register::<Label>();
+        }
+        let (first, second, third) = (Entity::from_id(1), Entity::from_id(2), Entity::from_id(3));
+
+        let mut world = baseline();
+        let snapshot = WorldSnapshot::from_world(&world, &type_registry);
+        assert_eq!(snapshot.len(), 3);
+        assert!(snapshot.diff(&world, &type_registry).is_empty());
+
+        let spawned = world.spawn((Value(4), Label(40)));
+        world.despawn(third).unwrap();
+        world.insert_one(first, Label(10)).unwrap();
+        *world.get_mut::<Value>(second).unwrap() = Value(22);
+        world.remove_one::<Label>(second).unwrap();
+
+        let diff = snapshot.diff(&world, &type_registry);
+        assert_eq!(diff.spawned, vec![spawned]);
+        assert_eq!(diff.despawned, vec![third]);
+        assert_eq!(diff.added.len(), 3);
+        assert_eq!(
+            diff.changed
+                .iter()
+                .map(|(entity, properties)| (*entity, properties.type_name.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(second, std::any::type_name::<Value>())]
+        );
+        assert_eq!(
+            diff.removed,
+            vec![(second, std::any::type_name::<Label>().to_string())]
+        );
+
+        let mut restored = baseline();
+        diff.apply(&mut restored, &type_registry, &Resources::default())
+            .unwrap();
+        assert!(WorldSnapshot::from_world(&restored, &type_registry)
+            .diff(&world, &type_registry)
+            .is_empty());
+
+        assert!(!restored.contains(third));
+        assert_eq!(*restored.get::<Value>(first).unwrap(), Value(1));
+        assert_eq!(*restored.get::<Label>(first).unwrap(), Label(10));
+        assert_eq!(*restored.get::<Value>(second).unwrap(), Value(22));
+        assert!(restored.get::<Label>(second).is_err());
+        assert_eq!(*restored.get::<Value>(spawned).unwrap(), Value(4));
+        assert_eq!(*restored.get::<Label>(spawned).unwrap(), Label(40));
+        assert_eq!(restored.iter().count(), 3);
+
+        // diffs can be reapplied
+        diff.apply(&mut restored, &type_registry, &Resources::default())
+            .unwrap();
+        assert_eq!(restored.iter().count(), 3);
+    }
+}
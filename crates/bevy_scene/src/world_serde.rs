@@ -0,0 +1,114 @@
+use crate::{serde::SceneDeserializer, Scene};
+use bevy_ecs::{Entity, Resources, World};
+use bevy_type_registry::TypeRegistry;
+use serde::de::DeserializeSeed;
+
+/// Snapshots every registered component of every entity in `world` as RON
+///
+/// Components whose types aren't in the component registry are left out.
+pub fn serialize_world(
+    world: &World,
+    type_registry: &TypeRegistry,
+) -> Result<String, bevy_ron::Error> {
+    let component_registry = type_registry.component.read();
+    let property_type_registry = type_registry.property.read();
+    Scene::from_world(world, &component_registry).serialize_ron(&property_type_registry)
+}
+
+/// Rebuilds a world from the output of [serialize_world], preserving entity ids
+///
+/// Components whose types aren't in the component registry are skipped with a warning.
+/// `resources` are used to construct components before their serialized properties are applied.
+pub fn deserialize_world(
+    input: &str,
+    type_registry: &TypeRegistry,
+    resources: &Resources,
+) -> Result<World, bevy_ron::Error> {
+    let scene = {
+        let property_type_registry = type_registry.property.read();
+        let mut deserializer = bevy_ron::de::Deserializer::from_str(input)?;
+        let scene_deserializer = SceneDeserializer {
+            property_type_registry: &property_type_registry,
+        };
+        scene_deserializer.deserialize(&mut deserializer)?
+    };
+
+    let component_registry = type_registry.component.read();
+    let mut world = World::new();
+    for scene_entity in scene.entities.iter() {
+        let entity = Entity::from_id(scene_entity.entity);
+        world.spawn_as_entity(entity, ());
+        for component in scene_entity.components.iter() {
+            match component_registry.get_with_name(&component.type_name) {
+                Some(component_registration) => component_registration
+                    .add_component_to_entity(&mut world, resources, entity, component),
+                None => log::warn!(
+                    "skipping unregistered component {} of entity {}",
+                    component.type_name,
+                    scene_entity.entity
+                ),
+            }
+        }
+    }
+
+    Ok(world)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deserialize_world, serialize_world};
+    use bevy_ecs::{Resources, World};
+    use bevy_property::Properties;
+    use bevy_type_registry::TypeRegistry;
+
+    #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Properties)]
+    struct Value(u32);
+
+    #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Properties)]
+    struct Other(u32);
+
+    fn type_registry(with_other: bool) -> TypeRegistry {
+        let type_registry = TypeRegistry::default();
+        {
+            let mut component_registry = type_registry.component.write();
+            let mut property_registry = type_registry.property.write();
+            property_registry.register::<u32>();
+            component_registry.register::<Value>();
+            property_registry.register::<Value>();
+            if with_other {
+                component_registry.register::<Other>();
+                property_registry.register::<Other>();
+            }
+        }
+        type_registry
+    }
+
+    #[test]
+    fn round_trip() {
+        let type_registry = type_registry(false);
+        let mut world = World::new();
+        let a = world.spawn((Value(1),));
+        let b = world.spawn((Value(2),));
+
+        let serialized = serialize_world(&world, &type_registry).unwrap();
+        let loaded = deserialize_world(&serialized, &type_registry, &Resources::default()).unwrap();
+
+        assert_eq!(*loaded.get::<Value>(a).unwrap(), Value(1));
+        assert_eq!(*loaded.get::<Value>(b).unwrap(), Value(2));
+        assert_eq!(loaded.iter().count(), 2);
+    }
+
+    #[test]
+    fn skips_unregistered_components() {
+        let mut world = World::new();
+        let a = world.spawn((Value(1), Other(2)));
+        let serialized = serialize_world(&world, &type_registry(true)).unwrap();
+
+        // `Other` is in the save, but the loading registry doesn't know it
+        let loaded =
+            deserialize_world(&serialized, &type_registry(false), &Resources::default()).unwrap();
+
+        assert_eq!(*loaded.get::<Value>(a).unwrap(), Value(1));
+        assert!(loaded.get::<Other>(a).is_err());
+    }
+}
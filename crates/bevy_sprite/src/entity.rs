@@ -1,17 +1,13 @@
-use crate::{
-    render::SPRITE_PIPELINE_HANDLE, sprite::Sprite, ColorMaterial, TextureAtlas,
-    TextureAtlasSprite, QUAD_HANDLE, SPRITE_SHEET_PIPELINE_HANDLE,
-};
+use crate::{sprite::Sprite, ColorMaterial, TextureAtlas, TextureAtlasSprite, QUAD_HANDLE};
 use bevy_asset::Handle;
 use bevy_ecs::Bundle;
-use bevy_render::{
-    mesh::Mesh,
-    pipeline::{DynamicBinding, PipelineSpecialization, RenderPipeline, RenderPipelines},
-    prelude::Draw,
-    render_graph::base::MainPass,
-};
+use bevy_render::{mesh::Mesh, prelude::Draw, render_graph::base::MainPass};
 use bevy_transform::prelude::{Rotation, Scale, Transform, Translation};
 
+/// A Bundle of components for drawing a single sprite. Sprites are drawn in batches, see
+/// [draw_sprite_batches_system](crate::draw_sprite_batches_system). Adding
+/// [RenderPipelines](bevy_render::pipeline::RenderPipelines) draws the sprite on its own instead,
+/// for example with [SPRITE_PIPELINE_HANDLE](crate::SPRITE_PIPELINE_HANDLE).
 #[derive(Bundle)]
 pub struct SpriteComponents {
     pub sprite: Sprite,
@@ -19,7 +15,6 @@ pub struct SpriteComponents {
     pub material: Handle<ColorMaterial>,
     pub main_pass: MainPass,
     pub draw: Draw,
-    pub render_pipelines: RenderPipelines,
     pub transform: Transform,
     pub translation: Translation,
     pub rotation: Rotation,
@@ -30,24 +25,6 @@ impl Default for SpriteComponents {
     fn default() -> Self {
         Self {
             mesh: QUAD_HANDLE,
-            render_pipelines: RenderPipelines::from_pipelines(vec![RenderPipeline::specialized(
-                SPRITE_PIPELINE_HANDLE,
-                PipelineSpecialization {
-                    dynamic_bindings: vec![
-                        // Transform
-                        DynamicBinding {
-                            bind_group: 2,
-                            binding: 0,
-                        },
-                        // Sprite
-                        DynamicBinding {
-                            bind_group: 2,
-                            binding: 1,
-                        },
-                    ],
-                    ..Default::default()
-                },
-            )]),
             draw: Draw {
                 is_transparent: true,
                 ..Default::default()
@@ -64,7 +41,8 @@ impl Default for SpriteComponents {
 }
 
 /// A Bundle of components for drawing a single sprite from a sprite sheet (also referred
-/// to as a `TextureAtlas`)
+/// to as a `TextureAtlas`). Like [SpriteComponents], these are drawn in batches unless given
+/// [RenderPipelines](bevy_render::pipeline::RenderPipelines).
 #[derive(Bundle)]
 pub struct SpriteSheetComponents {
     /// The specific sprite from the texture atlas to be drawn
@@ -73,7 +51,6 @@ pub struct SpriteSheetComponents {
     pub texture_atlas: Handle<TextureAtlas>,
     /// Data pertaining to how the sprite is drawn on the screen
     pub draw: Draw,
-    pub render_pipelines: RenderPipelines,
     pub main_pass: MainPass,
     pub mesh: Handle<Mesh>, // TODO: maybe abstract this out
     pub transform: Transform,
@@ -85,24 +62,6 @@ pub struct SpriteSheetComponents {
 impl Default for SpriteSheetComponents {
     fn default() -> Self {
         Self {
-            render_pipelines: RenderPipelines::from_pipelines(vec![RenderPipeline::specialized(
-                SPRITE_SHEET_PIPELINE_HANDLE,
-                PipelineSpecialization {
-                    dynamic_bindings: vec![
-                        // Transform
-                        DynamicBinding {
-                            bind_group: 2,
-                            binding: 0,
-                        },
-                        // TextureAtlasSprite
-                        DynamicBinding {
-                            bind_group: 2,
-                            binding: 1,
-                        },
-                    ],
-                    ..Default::default()
-                },
-            )]),
             draw: Draw {
                 is_transparent: true,
                 ..Default::default()
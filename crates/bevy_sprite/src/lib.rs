@@ -48,6 +48,10 @@ impl Plugin for SpritePlugin {
             .add_system_to_stage(
                 stage::POST_UPDATE,
                 asset_shader_defs_system::<ColorMaterial>.system(),
+            )
+            .add_system_to_stage(
+                bevy_render::stage::DRAW,
+                render::draw_sprite_batches_system.system(),
             );
 
         let resources = app.resources();
@@ -64,4 +68,8 @@ impl Plugin for SpritePlugin {
         let mut color_materials = resources.get_mut::<Assets<ColorMaterial>>().unwrap();
         color_materials.add_default(ColorMaterial::default());
     }
+
+    fn dependencies(&self) -> Vec<std::any::TypeId> {
+        vec![std::any::TypeId::of::<bevy_render::RenderPlugin>()]
+    }
 }
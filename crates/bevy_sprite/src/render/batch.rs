@@ -0,0 +1,501 @@
+use super::SPRITE_BATCH_PIPELINE_HANDLE;
+use crate::{ColorMaterial, Sprite, TextureAtlas, TextureAtlasSprite};
+use bevy_asset::{Assets, Handle};
+use bevy_core::{Byteable, FloatOrd};
+use bevy_ecs::{Entity, Query, Res, ResMut, With, Without};
+use bevy_math::{Vec2, Vec3};
+use bevy_render::{
+    color::Color,
+    draw::{Draw, DrawContext, DrawError, RenderCommand},
+    pipeline::{AsVertexBufferDescriptor, PipelineSpecialization, RenderPipelines},
+    prelude::Msaa,
+    renderer::{
+        AssetRenderResourceBindings, BufferUsage, RenderResourceBinding, RenderResourceBindings,
+    },
+    texture::Texture,
+};
+use bevy_transform::prelude::Transform;
+use bevy_utils::HashMap;
+
+/// A vertex of a batched sprite. Positions are in world space, so a whole batch is drawn without
+/// any per sprite uniforms.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, AsVertexBufferDescriptor)]
+pub struct SpriteVertex {
+    pub position: [f32; 3],
+    pub uv: [f32; 2],
+    /// The linear color the texture is multiplied by
+    pub color: [f32; 4],
+}
+
+// SAFE: SpriteVertex is repr(C) containing only f32s
+unsafe impl Byteable for SpriteVertex {}
+
+/// The corners of the unit quad, in the order of [shape::Quad](bevy_render::mesh::shape::Quad):
+/// bottom left, top left, top right, bottom right
+const QUAD_CORNERS: [(f32, f32); 4] = [(-0.5, -0.5), (-0.5, 0.5), (0.5, 0.5), (0.5, -0.5)];
+
+/// The two triangles of a quad, as indices into [QUAD_CORNERS]
+const QUAD_INDICES: [usize; 6] = [0, 2, 1, 0, 3, 2];
+
+/// What the sprites of a batch sample. Sprites can only share a batch if they sample the same
+/// texture through the same kind of asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BatchTexture {
+    None,
+    Material(Handle<Texture>),
+    Atlas(Handle<Texture>),
+}
+
+/// The asset whose bindings hold a batch's texture
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BatchBindings {
+    None,
+    Material(Handle<ColorMaterial>),
+    Atlas(Handle<TextureAtlas>),
+}
+
+#[derive(Debug)]
+struct BatchedSprite {
+    entity: Entity,
+    z: f32,
+    texture: BatchTexture,
+    bindings: BatchBindings,
+    quad: [SpriteVertex; 4],
+}
+
+/// Sprites drawn with a single draw call, by the first of them
+#[derive(Debug)]
+struct SpriteBatch {
+    carrier: Entity,
+    first_z: f32,
+    /// Whether the batch starts at the same z as the one before it, which limits it to that z
+    pinned: bool,
+    texture: BatchTexture,
+    bindings: BatchBindings,
+    vertices: Vec<SpriteVertex>,
+}
+
+/// Groups sprites into batches, drawn back to front. Only consecutive sprites share a batch, so
+/// batching never changes which sprites are blended over which. Sprites at the same z are
+/// grouped by texture, as their order is arbitrary.
+///
+/// Each batch is drawn by its first sprite, so it is sorted among other transparent entities by
+/// that sprite's z. A batch starting at the same z as the one before it might be drawn before
+/// it, so it only takes sprites at that z. For the same reason a batch never reaches past
+/// `transparent_zs`, the z of each transparent entity drawn on its own, as that entity would be
+/// drawn over the batch's sprites in front of it.
+fn batch_sprites(mut sprites: Vec<BatchedSprite>, transparent_zs: &[f32]) -> Vec<SpriteBatch> {
+    let mut texture_order = HashMap::default();
+    for sprite in sprites.iter() {
+        let next = texture_order.len();
+        texture_order.entry(sprite.texture).or_insert(next);
+    }
+    sprites.sort_by_key(|sprite| (FloatOrd(sprite.z), texture_order[&sprite.texture]));
+
+    // sprites are sorted, so no sprite is behind the first sprite of the batch before it
+    let mut batches: Vec<SpriteBatch> = Vec::new();
+    for sprite in sprites {
+        let vertices = QUAD_INDICES.iter().map(|i| sprite.quad[*i]);
+        if let Some(batch) = batches.last_mut() {
+            let crosses_entity = transparent_zs
+                .iter()
+                .any(|z| batch.first_z <= *z && *z < sprite.z);
+            if batch.texture == sprite.texture
+                && (!batch.pinned || sprite.z <= batch.first_z)
+                && !crosses_entity
+            {
+                batch.vertices.extend(vertices);
+                continue;
+            }
+        }
+
+        let pinned = batches
+            .last()
+            .map_or(false, |batch| sprite.z <= batch.first_z);
+        batches.push(SpriteBatch {
+            carrier: sprite.entity,
+            first_z: sprite.z,
+            pinned,
+            texture: sprite.texture,
+            bindings: sprite.bindings,
+            vertices: vertices.collect(),
+        });
+    }
+    batches
+}
+
+/// Returns the corners of `sprite`, placed by `transform`
+fn sprite_quad(sprite: &Sprite, transform: &Transform, color: Color) -> [SpriteVertex; 4] {
+    let anchor = sprite.anchor.as_vec();
+    let color = color.as_linear_rgba_f32();
+    let mut quad = [SpriteVertex {
+        position: [0.0; 3],
+        uv: [0.0; 2],
+        color,
+    }; 4];
+    for (vertex, (x, y)) in quad.iter_mut().zip(QUAD_CORNERS.iter()) {
+        let position = (Vec2::new(*x, *y) - anchor) * sprite.size;
+        vertex.position = world_position(transform, position);
+        // v points down the texture
+        let u = if sprite.flip_x { 0.5 - x } else { 0.5 + x };
+        let v = if sprite.flip_y { 0.5 + y } else { 0.5 - y };
+        vertex.uv = [u, v];
+    }
+    quad
+}
+
+/// Returns the corners of the atlas `sprite`, placed by `transform`. Like `sprite_sheet.vert`,
+/// this rounds the corners to whole pixels and nudges the uvs into the texture's pixels.
+fn atlas_sprite_quad(
+    sprite: &TextureAtlasSprite,
+    atlas: &TextureAtlas,
+    transform: &Transform,
+) -> Option<[SpriteVertex; 4]> {
+    let rect = atlas.textures.get(sprite.index as usize)?;
+    let size = rect.max - rect.min;
+    let (left, right) = if sprite.flip_x {
+        (rect.max.x(), rect.min.x())
+    } else {
+        (rect.min.x(), rect.max.x())
+    };
+    let color = sprite.color.as_linear_rgba_f32();
+    let mut quad = [SpriteVertex {
+        position: [0.0; 3],
+        uv: [0.0; 2],
+        color,
+    }; 4];
+    for (vertex, (x, y)) in quad.iter_mut().zip(QUAD_CORNERS.iter()) {
+        let position = Vec2::new(*x, *y) * size;
+        let position = Vec2::new(position.x().ceil(), position.y().ceil());
+        vertex.position = world_position(transform, position);
+        let u = if *x < 0.0 { left } else { right };
+        let v = if *y < 0.0 { rect.max.y() } else { rect.min.y() };
+        vertex.uv = ((Vec2::new(u, v) + Vec2::new(0.01, 0.01)) / atlas.size).into();
+    }
+    Some(quad)
+}
+
+fn world_position(transform: &Transform, position: Vec2) -> [f32; 3] {
+    let position = transform.value.mul_vec4(position.extend(0.0).extend(1.0));
+    Vec3::from(position.truncate()).into()
+}
+
+/// Draws all [Sprite] and [TextureAtlasSprite] entities in as few draw calls as possible, by
+/// drawing each batch of sprites that share a texture from one vertex buffer. Sprites are sorted
+/// by z, like the 2d camera sorts them.
+///
+/// Sprites with [RenderPipelines] aren't batched, and draw themselves like any other entity.
+/// Batches are split around transparent entities with [RenderPipelines], so they are still
+/// blended in z order with the sprites.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+pub fn draw_sprite_batches_system(
+    mut draw_context: DrawContext,
+    msaa: Res<Msaa>,
+    materials: Res<Assets<ColorMaterial>>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    mut render_resource_bindings: ResMut<RenderResourceBindings>,
+    mut asset_render_resource_bindings: ResMut<AssetRenderResourceBindings>,
+    mut sprite_query: Query<
+        Without<
+            RenderPipelines,
+            (
+                Entity,
+                &mut Draw,
+                &Sprite,
+                &Handle<ColorMaterial>,
+                &Transform,
+            ),
+        >,
+    >,
+    mut atlas_sprite_query: Query<
+        Without<
+            RenderPipelines,
+            (
+                Entity,
+                &mut Draw,
+                &TextureAtlasSprite,
+                &Handle<TextureAtlas>,
+                &Transform,
+            ),
+        >,
+    >,
+    mut transparent_query: Query<With<RenderPipelines, (&Draw, &Transform)>>,
+) {
+    let mut transparent_zs = Vec::new();
+    for (draw, transform) in &mut transparent_query.iter() {
+        if draw.is_visible && draw.is_transparent {
+            transparent_zs.push(transform.value.w_axis().z());
+        }
+    }
+
+    let mut sprites = Vec::new();
+    for (entity, draw, sprite, material_handle, transform) in &mut sprite_query.iter() {
+        if !draw.is_visible {
+            continue;
+        }
+        let material = match materials.get(&material_handle) {
+            Some(material) => material,
+            None => continue,
+        };
+        let (texture, bindings) = match material.texture {
+            Some(texture) => (
+                BatchTexture::Material(texture),
+                BatchBindings::Material(*material_handle),
+            ),
+            None => (BatchTexture::None, BatchBindings::None),
+        };
+        sprites.push(BatchedSprite {
+            entity,
+            z: transform.value.w_axis().z(),
+            texture,
+            bindings,
+            quad: sprite_quad(&sprite, &transform, material.color),
+        });
+    }
+
+    for (entity, draw, sprite, atlas_handle, transform) in &mut atlas_sprite_query.iter() {
+        if !draw.is_visible {
+            continue;
+        }
+        let atlas = match texture_atlases.get(&atlas_handle) {
+            Some(atlas) => atlas,
+            None => continue,
+        };
+        if let Some(quad) = atlas_sprite_quad(&sprite, atlas, &transform) {
+            sprites.push(BatchedSprite {
+                entity,
+                z: transform.value.w_axis().z(),
+                texture: BatchTexture::Atlas(atlas.texture),
+                bindings: BatchBindings::Atlas(*atlas_handle),
+                quad,
+            });
+        }
+    }
+
+    for batch in batch_sprites(sprites, &transparent_zs) {
+        let asset_bindings = match batch.bindings {
+            BatchBindings::None => None,
+            BatchBindings::Material(handle) => Some(asset_render_resource_bindings.get_mut(handle)),
+            BatchBindings::Atlas(handle) => Some(asset_render_resource_bindings.get_mut(handle)),
+        };
+        // the batch's asset might not have its render resources yet
+        if let Some(None) = asset_bindings {
+            continue;
+        }
+
+        let mut draw = match sprite_query.get_mut::<Draw>(batch.carrier) {
+            Ok(draw) => draw,
+            Err(_) => atlas_sprite_query.get_mut::<Draw>(batch.carrier).unwrap(),
+        };
+        draw_batch(
+            &batch,
+            &mut draw,
+            &mut draw_context,
+            &msaa,
+            &mut render_resource_bindings,
+            asset_bindings.flatten(),
+        )
+        .unwrap();
+    }
+}
+
+fn draw_batch(
+    batch: &SpriteBatch,
+    draw: &mut Draw,
+    context: &mut DrawContext,
+    msaa: &Msaa,
+    render_resource_bindings: &mut RenderResourceBindings,
+    asset_bindings: Option<&mut RenderResourceBindings>,
+) -> Result<(), DrawError> {
+    let mut specialization = PipelineSpecialization {
+        sample_count: msaa.samples,
+        ..Default::default()
+    };
+    let shader_def = match batch.texture {
+        BatchTexture::None => None,
+        BatchTexture::Material(_) => Some("COLORMATERIAL_TEXTURE"),
+        BatchTexture::Atlas(_) => Some("TEXTUREATLAS_TEXTURE"),
+    };
+    if let Some(shader_def) = shader_def {
+        specialization
+            .shader_specialization
+            .shader_defs
+            .insert(shader_def.to_string());
+    }
+    context.set_pipeline(draw, SPRITE_BATCH_PIPELINE_HANDLE, &specialization)?;
+
+    match asset_bindings {
+        Some(asset_bindings) => context
+            .set_bind_groups_from_bindings(draw, &mut [render_resource_bindings, asset_bindings])?,
+        None => context.set_bind_groups_from_bindings(draw, &mut [render_resource_bindings])?,
+    }
+
+    if let RenderResourceBinding::Buffer { buffer, .. } =
+        context.get_buffer(&batch.vertices, BufferUsage::VERTEX)?
+    {
+        draw.set_vertex_buffer(0, buffer, 0);
+    }
+    draw.render_command(RenderCommand::Draw {
+        vertices: 0..batch.vertices.len() as u32,
+        instances: 0..1,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        atlas_sprite_quad, batch_sprites, sprite_quad, BatchBindings, BatchTexture, BatchedSprite,
+        SpriteVertex,
+    };
+    use crate::{Anchor, Rect, Sprite, TextureAtlas, TextureAtlasSprite};
+    use bevy_asset::Handle;
+    use bevy_ecs::{Entity, World};
+    use bevy_math::{Mat4, Vec2, Vec3};
+    use bevy_render::{color::Color, texture::Texture};
+    use bevy_transform::prelude::Transform;
+
+    fn sprite(entity: Entity, z: f32, texture: BatchTexture) -> BatchedSprite {
+        BatchedSprite {
+            entity,
+            z,
+            texture,
+            bindings: BatchBindings::None,
+            quad: [SpriteVertex {
+                position: [0.0, 0.0, z],
+                uv: [0.0; 2],
+                color: [1.0; 4],
+            }; 4],
+        }
+    }
+
+    fn entities(count: usize) -> Vec<Entity> {
+        let mut world = World::default();
+        (0..count).map(|_| world.spawn(())).collect()
+    }
+
+    #[test]
+    fn batches_consecutive_sprites() {
+        let e = entities(5);
+        let a = BatchTexture::Material(Handle::<Texture>::from_u128(1));
+        let b = BatchTexture::Atlas(Handle::<Texture>::from_u128(1));
+        let batches = batch_sprites(
+            vec![
+                sprite(e[0], 3.0, a),
+                sprite(e[1], 0.0, a),
+                sprite(e[2], 1.0, b),
+                sprite(e[3], 2.0, a),
+                sprite(e[4], 2.5, a),
+            ],
+            &[],
+        );
+
+        // back to front, splitting where another texture is drawn between
+        let batches = batches
+            .iter()
+            .map(|batch| (batch.carrier, batch.texture, batch.vertices.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(batches, vec![(e[1], a, 6), (e[2], b, 6), (e[3], a, 18)]);
+    }
+
+    #[test]
+    fn groups_sprites_at_the_same_z_by_texture() {
+        let e = entities(6);
+        let a = BatchTexture::None;
+        let b = BatchTexture::Material(Handle::<Texture>::from_u128(2));
+        let batches = batch_sprites(
+            vec![
+                sprite(e[0], 0.0, a),
+                sprite(e[1], 0.0, b),
+                sprite(e[2], 0.0, a),
+                sprite(e[3], 0.0, b),
+                sprite(e[4], 1.0, b),
+                sprite(e[5], 2.0, b),
+            ],
+            &[],
+        );
+
+        let batches = batches
+            .iter()
+            .map(|batch| (batch.carrier, batch.vertices.len()))
+            .collect::<Vec<_>>();
+        // the second batch starts at the same z as the first, so it might be drawn first. it
+        // can't hold the sprites in front of the first batch.
+        assert_eq!(batches, vec![(e[0], 12), (e[1], 12), (e[4], 12)]);
+    }
+
+    #[test]
+    fn splits_batches_around_transparent_entities() {
+        let e = entities(4);
+        let a = BatchTexture::None;
+        let batches = batch_sprites(
+            vec![
+                sprite(e[0], 0.0, a),
+                sprite(e[1], 1.0, a),
+                sprite(e[2], 2.0, a),
+                sprite(e[3], 3.0, a),
+            ],
+            &[1.5, 3.0],
+        );
+
+        // the entity at 1.5 is drawn between the two batches. the one at 3.0 is drawn at the same
+        // z as the sprite there, so it doesn't split the second batch.
+        let batches = batches
+            .iter()
+            .map(|batch| (batch.carrier, batch.vertices.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(batches, vec![(e[0], 12), (e[2], 12)]);
+    }
+
+    #[test]
+    fn sprite_quad_corners() {
+        let sprite = Sprite {
+            size: Vec2::new(2.0, 4.0),
+            anchor: Anchor::BottomLeft,
+            flip_x: true,
+            flip_y: false,
+        };
+        let transform = Transform::new(Mat4::from_translation(Vec3::new(10.0, 0.0, 1.0)));
+        let quad = sprite_quad(&sprite, &transform, Color::rgba(1.0, 1.0, 1.0, 0.5));
+
+        let positions = quad.iter().map(|v| v.position).collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            vec![
+                [10.0, 0.0, 1.0],
+                [10.0, 4.0, 1.0],
+                [12.0, 4.0, 1.0],
+                [12.0, 0.0, 1.0]
+            ]
+        );
+        let uvs = quad.iter().map(|v| v.uv).collect::<Vec<_>>();
+        assert_eq!(uvs, vec![[1.0, 1.0], [1.0, 0.0], [0.0, 0.0], [0.0, 1.0]]);
+        assert_eq!(quad[0].color, [1.0, 1.0, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn atlas_sprite_quad_uvs() {
+        let mut atlas = TextureAtlas::new_empty(Handle::default(), Vec2::new(100.0, 100.0));
+        atlas.textures.push(Rect {
+            min: Vec2::new(10.0, 20.0),
+            max: Vec2::new(30.0, 60.0),
+        });
+        let quad =
+            atlas_sprite_quad(&TextureAtlasSprite::new(0), &atlas, &Transform::identity()).unwrap();
+
+        assert_eq!(quad[0].position, [-10.0, -20.0, 0.0]);
+        assert_eq!(quad[2].position, [10.0, 20.0, 0.0]);
+        let expected_uvs = [[0.1, 0.6], [0.1, 0.2], [0.3, 0.2], [0.3, 0.6]];
+        for (vertex, expected) in quad.iter().zip(expected_uvs.iter()) {
+            let uv = Vec2::from(vertex.uv);
+            assert!((uv - Vec2::from(*expected)).length() < 1e-3);
+        }
+
+        assert!(
+            atlas_sprite_quad(&TextureAtlasSprite::new(1), &atlas, &Transform::identity())
+                .is_none()
+        );
+    }
+}
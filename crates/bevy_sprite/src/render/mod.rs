@@ -1,11 +1,16 @@
+mod batch;
+
+pub use batch::*;
+
 use crate::{ColorMaterial, Sprite, TextureAtlas, TextureAtlasSprite};
 use bevy_asset::{Assets, Handle};
 use bevy_ecs::Resources;
 use bevy_render::{
     pipeline::{
-        BlendDescriptor, BlendFactor, BlendOperation, ColorStateDescriptor, ColorWrite,
-        CompareFunction, CullMode, DepthStencilStateDescriptor, FrontFace, PipelineDescriptor,
-        RasterizationStateDescriptor, StencilStateDescriptor, StencilStateFaceDescriptor,
+        AsVertexBufferDescriptor, BlendDescriptor, BlendFactor, BlendOperation,
+        ColorStateDescriptor, ColorWrite, CompareFunction, CullMode, DepthStencilStateDescriptor,
+        FrontFace, PipelineDescriptor, RasterizationStateDescriptor, StencilStateDescriptor,
+        StencilStateFaceDescriptor, VertexBufferDescriptors,
     },
     render_graph::{base, AssetRenderResourcesNode, RenderGraph, RenderResourcesNode},
     shader::{Shader, ShaderStage, ShaderStages},
@@ -18,6 +23,9 @@ pub const SPRITE_PIPELINE_HANDLE: Handle<PipelineDescriptor> =
 pub const SPRITE_SHEET_PIPELINE_HANDLE: Handle<PipelineDescriptor> =
     Handle::from_u128(90168858051802816124217444474933884151);
 
+pub const SPRITE_BATCH_PIPELINE_HANDLE: Handle<PipelineDescriptor> =
+    Handle::from_u128(208734180390584711593062917290374627307);
+
 pub fn build_sprite_sheet_pipeline(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
     PipelineDescriptor {
         rasterization_state: Some(RasterizationStateDescriptor {
@@ -114,6 +122,55 @@ pub fn build_sprite_pipeline(shaders: &mut Assets<Shader>) -> PipelineDescriptor
     }
 }
 
+/// Builds the pipeline that draws batches of [SpriteVertex]es, see [draw_sprite_batches_system]
+pub fn build_sprite_batch_pipeline(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
+    PipelineDescriptor {
+        rasterization_state: Some(RasterizationStateDescriptor {
+            front_face: FrontFace::Ccw,
+            cull_mode: CullMode::None,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+            clamp_depth: false,
+        }),
+        depth_stencil_state: Some(DepthStencilStateDescriptor {
+            format: TextureFormat::Depth32Float,
+            depth_write_enabled: true,
+            depth_compare: CompareFunction::LessEqual,
+            stencil: StencilStateDescriptor {
+                front: StencilStateFaceDescriptor::IGNORE,
+                back: StencilStateFaceDescriptor::IGNORE,
+                read_mask: 0,
+                write_mask: 0,
+            },
+        }),
+        color_states: vec![ColorStateDescriptor {
+            format: TextureFormat::Bgra8UnormSrgb,
+            color_blend: BlendDescriptor {
+                src_factor: BlendFactor::SrcAlpha,
+                dst_factor: BlendFactor::OneMinusSrcAlpha,
+                operation: BlendOperation::Add,
+            },
+            alpha_blend: BlendDescriptor {
+                src_factor: BlendFactor::One,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+            write_mask: ColorWrite::ALL,
+        }],
+        ..PipelineDescriptor::new(ShaderStages {
+            vertex: shaders.add(Shader::from_glsl(
+                ShaderStage::Vertex,
+                include_str!("sprite_batch.vert"),
+            )),
+            fragment: Some(shaders.add(Shader::from_glsl(
+                ShaderStage::Fragment,
+                include_str!("sprite_batch.frag"),
+            ))),
+        })
+    }
+}
+
 pub mod node {
     pub const COLOR_MATERIAL: &str = "color_material";
     pub const SPRITE: &str = "sprite";
@@ -155,6 +212,15 @@ impl SpriteRenderGraphBuilder for RenderGraph {
             SPRITE_SHEET_PIPELINE_HANDLE,
             build_sprite_sheet_pipeline(&mut shaders),
         );
+        pipelines.set(
+            SPRITE_BATCH_PIPELINE_HANDLE,
+            build_sprite_batch_pipeline(&mut shaders),
+        );
+
+        resources
+            .get_mut::<VertexBufferDescriptors>()
+            .unwrap()
+            .set(SpriteVertex::as_vertex_buffer_descriptor().clone());
         self
     }
 }
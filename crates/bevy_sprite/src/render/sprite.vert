@@ -15,10 +15,20 @@ layout(set = 2, binding = 0) uniform Transform {
 };
 layout(set = 2, binding = 1) uniform Sprite {
     vec2 Sprite_size;
+    vec2 Sprite_anchor;
+    uint Sprite_flip_x;
+    uint Sprite_flip_y;
 };
 
 void main() {
     v_Uv = Vertex_Uv;
-    vec3 position = Vertex_Position * vec3(Sprite_size, 1.0);
+    if (Sprite_flip_x != 0) {
+        v_Uv.x = 1.0 - v_Uv.x;
+    }
+    if (Sprite_flip_y != 0) {
+        v_Uv.y = 1.0 - v_Uv.y;
+    }
+    // the unit quad is centered on the origin, so shift it to put the anchor there instead
+    vec3 position = vec3((Vertex_Position.xy - Sprite_anchor) * Sprite_size, Vertex_Position.z);
     gl_Position = ViewProj * Model * vec4(position, 1.0);
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+#version 450
+
+layout(location = 0) in vec3 SpriteVertex_Position;
+layout(location = 1) in vec2 SpriteVertex_Uv;
+layout(location = 2) in vec4 SpriteVertex_Color;
+
+layout(location = 0) out vec2 v_Uv;
+layout(location = 1) out vec4 v_Color;
+
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 ViewProj;
+};
+
+void main() {
+    v_Uv = SpriteVertex_Uv;
+    v_Color = SpriteVertex_Color;
+    // batched sprites are already in world space
+    gl_Position = ViewProj * vec4(SpriteVertex_Position, 1.0);
+}
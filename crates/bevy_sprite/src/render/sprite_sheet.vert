@@ -33,17 +33,25 @@ layout(set = 2, binding = 0) uniform Transform {
 layout(set = 2, binding = 1) uniform TextureAtlasSprite {
     vec4 TextureAtlasSprite_color;
     uint TextureAtlasSprite_index;
+    uint TextureAtlasSprite_flip_x;
 };
 
 void main() {
     Rect sprite_rect = Textures[TextureAtlasSprite_index];
     vec2 sprite_dimensions = sprite_rect.end - sprite_rect.begin;
     vec3 vertex_position = vec3(Vertex_Position.xy * sprite_dimensions, 0.0);
+    // flipping swaps the left and right edges of the sampled rect
+    float left = sprite_rect.begin.x;
+    float right = sprite_rect.end.x;
+    if (TextureAtlasSprite_flip_x != 0) {
+        left = sprite_rect.end.x;
+        right = sprite_rect.begin.x;
+    }
     vec2 atlas_positions[4] = vec2[](
-        vec2(sprite_rect.begin.x, sprite_rect.end.y),
-        sprite_rect.begin,
-        vec2(sprite_rect.end.x, sprite_rect.begin.y), 
-        sprite_rect.end
+        vec2(left, sprite_rect.end.y),
+        vec2(left, sprite_rect.begin.y),
+        vec2(right, sprite_rect.begin.y), 
+        vec2(right, sprite_rect.end.y)
     );
     v_Uv = (atlas_positions[gl_VertexIndex] + vec2(0.01, 0.01)) / AtlasSize;
     v_Color = TextureAtlasSprite_color;
@@ -1,6 +1,6 @@
 use crate::ColorMaterial;
 use bevy_asset::{Assets, Handle};
-use bevy_core::Byteable;
+use bevy_core::Bytes;
 use bevy_ecs::{Query, Res};
 use bevy_math::Vec2;
 use bevy_render::{
@@ -8,15 +8,70 @@ use bevy_render::{
     texture::Texture,
 };
 
-#[repr(C)]
-#[derive(Default, RenderResources, RenderResource)]
+#[derive(Default, Bytes, RenderResources, RenderResource)]
 #[render_resources(from_self)]
 pub struct Sprite {
     pub size: Vec2,
+    /// The point of the sprite that sits at its transform's translation
+    pub anchor: Anchor,
+    /// Mirror the sprite horizontally
+    pub flip_x: bool,
+    /// Mirror the sprite vertically
+    pub flip_y: bool,
 }
 
-// SAFE: sprite is repr(C) and only consists of byteables
-unsafe impl Byteable for Sprite {}
+impl Sprite {
+    pub fn new(size: Vec2) -> Self {
+        Sprite {
+            size,
+            ..Default::default()
+        }
+    }
+}
+
+/// The pivot point of a [Sprite], which is placed at the sprite's position and which it rotates
+/// and scales around
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Anchor {
+    Center,
+    BottomLeft,
+    BottomRight,
+    TopLeft,
+    TopRight,
+    /// A point relative to the sprite's size, from (-0.5, -0.5) at the bottom left corner to
+    /// (0.5, 0.5) at the top right one
+    Custom(Vec2),
+}
+
+impl Anchor {
+    /// The anchor point relative to the sprite's size, with the center at the origin
+    pub fn as_vec(&self) -> Vec2 {
+        match *self {
+            Anchor::Center => Vec2::new(0.0, 0.0),
+            Anchor::BottomLeft => Vec2::new(-0.5, -0.5),
+            Anchor::BottomRight => Vec2::new(0.5, -0.5),
+            Anchor::TopLeft => Vec2::new(-0.5, 0.5),
+            Anchor::TopRight => Vec2::new(0.5, 0.5),
+            Anchor::Custom(point) => point,
+        }
+    }
+}
+
+impl Default for Anchor {
+    fn default() -> Self {
+        Anchor::Center
+    }
+}
+
+impl Bytes for Anchor {
+    fn write_bytes(&self, buffer: &mut [u8]) {
+        self.as_vec().write_bytes(buffer);
+    }
+
+    fn byte_len(&self) -> usize {
+        std::mem::size_of::<Vec2>()
+    }
+}
 
 pub fn sprite_system(
     materials: Res<Assets<ColorMaterial>>,
@@ -30,6 +30,8 @@ pub struct TextureAtlas {
 pub struct TextureAtlasSprite {
     pub color: Color,
     pub index: u32,
+    /// Mirror the sprite horizontally, e.g. to turn a walk cycle around
+    pub flip_x: bool,
 }
 
 impl Default for TextureAtlasSprite {
@@ -37,6 +39,7 @@ impl Default for TextureAtlasSprite {
         Self {
             index: 0,
             color: Color::WHITE,
+            flip_x: false,
         }
     }
 }
@@ -1,5 +1,5 @@
-use crate::{Font, FontAtlasSet};
-use ab_glyph::{Glyph, PxScale, ScaleFont};
+use crate::{Font, FontAtlasSet, TextAlignment, TextLayout};
+use ab_glyph::{PxScale, ScaleFont};
 use bevy_asset::Assets;
 use bevy_math::{Mat4, Vec2, Vec3};
 use bevy_render::{
@@ -19,6 +19,9 @@ use bevy_sprite::{TextureAtlas, TextureAtlasSprite};
 pub struct TextStyle {
     pub font_size: f32,
     pub color: Color,
+    pub alignment: TextAlignment,
+    /// Wrap lines at word boundaries to fit within this width
+    pub max_width: Option<f32>,
 }
 
 impl Default for TextStyle {
@@ -26,6 +29,8 @@ impl Default for TextStyle {
         Self {
             color: Color::WHITE,
             font_size: 12.0,
+            alignment: Default::default(),
+            max_width: None,
         }
     }
 }
@@ -39,7 +44,7 @@ pub struct DrawableText<'a> {
     pub position: Vec3,
     pub container_size: Vec2,
     pub style: &'a TextStyle,
-    pub text: &'a str,
+    pub layout: &'a TextLayout,
     pub msaa: &'a Msaa,
 }
 
@@ -79,29 +84,29 @@ impl<'a> Drawable for DrawableText<'a> {
         let font = &self.font.font;
         let scale = PxScale::from(self.style.font_size);
         let scaled_font = ab_glyph::Font::as_scaled(&font, scale);
-        let mut caret = self.position;
-        let mut last_glyph: Option<Glyph> = None;
+        let container_top = self.position.y() + self.container_size.y();
 
         // set local per-character bindings
-        for character in self.text.chars() {
-            if character.is_control() {
-                if character == '\n' {
-                    caret.set_x(self.position.x());
-                    // TODO: Necessary to also calculate scaled_font.line_gap() in here?
-                    caret.set_y(caret.y() - scaled_font.height());
-                }
-                continue;
-            }
+        for positioned_glyph in self.layout.glyphs.iter() {
+            let offset = self.style.alignment.offset(
+                self.layout,
+                positioned_glyph.line,
+                self.container_size,
+            );
+            // the caret sits at the bottom left of the glyph's line
+            let caret = Vec3::new(
+                self.position.x() + offset.x() + positioned_glyph.position.x(),
+                container_top - offset.y() - positioned_glyph.position.y() - scaled_font.height(),
+                self.position.z(),
+            );
 
+            let character = positioned_glyph.character;
             let glyph = scaled_font.scaled_glyph(character);
-            if let Some(last_glyph) = last_glyph.take() {
-                caret.set_x(caret.x() + scaled_font.kern(last_glyph.id, glyph.id));
-            }
             if let Some(glyph_atlas_info) = self
                 .font_atlas_set
                 .get_glyph_atlas_info(self.style.font_size, character)
             {
-                if let Some(outlined) = scaled_font.outline_glyph(glyph.clone()) {
+                if let Some(outlined) = scaled_font.outline_glyph(glyph) {
                     let texture_atlas = self
                         .texture_atlases
                         .get(&glyph_atlas_info.texture_atlas)
@@ -131,6 +136,7 @@ impl<'a> Drawable for DrawableText<'a> {
                     let sprite = TextureAtlasSprite {
                         index: glyph_atlas_info.char_index,
                         color: self.style.color,
+                        flip_x: false,
                     };
 
                     let transform_buffer = context
@@ -150,8 +156,6 @@ impl<'a> Drawable for DrawableText<'a> {
                     draw.draw_indexed(indices.clone(), 0, 0..1);
                 }
             }
-            caret.set_x(caret.x() + scaled_font.h_advance(glyph.id));
-            last_glyph = Some(glyph);
         }
         Ok(())
     }
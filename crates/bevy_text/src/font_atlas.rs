@@ -4,6 +4,7 @@ use bevy_render::texture::{Texture, TextureFormat};
 use bevy_sprite::{DynamicTextureAtlasBuilder, TextureAtlas};
 use bevy_utils::HashMap;
 
+/// One page of glyphs rasterized at a single font size
 pub struct FontAtlas {
     pub dynamic_texture_atlas_builder: DynamicTextureAtlasBuilder,
     pub glyph_to_index: HashMap<char, u32>,
@@ -33,21 +34,23 @@ impl FontAtlas {
         self.glyph_to_index.get(&character).cloned()
     }
 
+    /// Packs the glyph `texture` into this atlas, returning false if there isn't room for it
     pub fn add_char(
         &mut self,
         textures: &mut Assets<Texture>,
         texture_atlases: &mut Assets<TextureAtlas>,
         character: char,
         texture: &Texture,
-    ) {
+    ) -> bool {
         let texture_atlas = texture_atlases.get_mut(&self.texture_atlas).unwrap();
         if let Some(index) =
             self.dynamic_texture_atlas_builder
                 .add_texture(texture_atlas, textures, texture)
         {
             self.glyph_to_index.insert(character, index);
+            true
         } else {
-            panic!("ran out of space in font atlas");
+            false
         }
     }
 }
@@ -10,10 +10,18 @@ use bevy_utils::HashMap;
 // work around rust's f32 order/hash limitations
 type FontSizeKey = FloatOrd;
 
+/// The size of a newly allocated font atlas page, unless a glyph needs a larger one
+const FONT_ATLAS_PAGE_SIZE: f32 = 512.0;
+
+/// The glyphs of one font rasterized so far, shared by all text that uses the font
+///
+/// Glyphs are rasterized on demand, separately for each font size, and packed into pages of
+/// [FontAtlas]es. A new page is allocated when the existing ones are full. Glyphs are never evicted,
+/// so text that is being drawn can always find its glyphs.
 #[derive(Default)]
 pub struct FontAtlasSet {
     font: Handle<Font>,
-    font_atlases: HashMap<FontSizeKey, FontAtlas>,
+    font_atlases: HashMap<FontSizeKey, Vec<FontAtlas>>,
 }
 
 #[derive(Debug)]
@@ -30,18 +38,26 @@ impl FontAtlasSet {
         }
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (&FontSizeKey, &FontAtlas)> {
+    /// Iterates over the pages of glyphs rasterized at each font size
+    pub fn iter(&self) -> impl Iterator<Item = (&FontSizeKey, &Vec<FontAtlas>)> {
         self.font_atlases.iter()
     }
 
     pub fn has_char(&self, character: char, font_size: f32) -> bool {
         self.font_atlases
             .get(&FloatOrd(font_size))
-            .map_or(false, |font_atlas| {
-                font_atlas.get_char_index(character).is_some()
+            .map_or(false, |font_atlases| {
+                font_atlases
+                    .iter()
+                    .any(|font_atlas| font_atlas.get_char_index(character).is_some())
             })
     }
 
+    /// Rasterizes the glyphs of `text` that aren't in the atlas yet, returning the width of the text
+    /// on a single line
+    ///
+    /// This is called for every UI `Text` as it changes, but can also be called ahead of time to avoid
+    /// rasterizing glyphs in the frame that new text first appears.
     pub fn add_glyphs_to_atlas(
         &mut self,
         fonts: &Assets<Font>,
@@ -52,10 +68,10 @@ impl FontAtlasSet {
     ) -> f32 {
         let font = fonts.get(&self.font).unwrap();
         let scaled_font = ab_glyph::Font::as_scaled(&font.font, font_size);
-        let font_atlas = self
+        let font_atlases = self
             .font_atlases
             .entry(FloatOrd(font_size))
-            .or_insert_with(|| FontAtlas::new(textures, texture_atlases, Vec2::new(512.0, 512.0)));
+            .or_insert_with(Vec::new);
 
         let mut last_glyph: Option<Glyph> = None;
         let mut width = 0.0;
@@ -67,10 +83,20 @@ impl FontAtlasSet {
             if let Some(last_glyph) = last_glyph.take() {
                 width += scaled_font.kern(last_glyph.id, glyph.id);
             }
-            if font_atlas.get_char_index(character).is_none() {
+            let has_char = font_atlases
+                .iter()
+                .any(|font_atlas| font_atlas.get_char_index(character).is_some());
+            if !has_char {
                 if let Some(outlined_glyph) = scaled_font.outline_glyph(glyph.clone()) {
                     let glyph_texture = Font::get_outlined_glyph_texture(outlined_glyph);
-                    font_atlas.add_char(textures, texture_atlases, character, &glyph_texture);
+                    add_char_to_pages(
+                        font_atlases,
+                        textures,
+                        texture_atlases,
+                        character,
+                        &glyph_texture,
+                        FONT_ATLAS_PAGE_SIZE,
+                    );
                 }
             }
             width += scaled_font.h_advance(glyph.id);
@@ -83,13 +109,107 @@ impl FontAtlasSet {
     pub fn get_glyph_atlas_info(&self, font_size: f32, character: char) -> Option<GlyphAtlasInfo> {
         self.font_atlases
             .get(&FloatOrd(font_size))
-            .and_then(|font_atlas| {
-                font_atlas
-                    .get_char_index(character)
-                    .map(|char_index| GlyphAtlasInfo {
-                        texture_atlas: font_atlas.texture_atlas,
-                        char_index,
-                    })
+            .and_then(|font_atlases| {
+                font_atlases.iter().find_map(|font_atlas| {
+                    font_atlas
+                        .get_char_index(character)
+                        .map(|char_index| GlyphAtlasInfo {
+                            texture_atlas: font_atlas.texture_atlas,
+                            char_index,
+                        })
+                })
             })
     }
 }
+
+/// Packs the glyph into the first page with room for it, allocating a new page of at least
+/// `page_size` if there is none
+fn add_char_to_pages(
+    font_atlases: &mut Vec<FontAtlas>,
+    textures: &mut Assets<Texture>,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    character: char,
+    glyph_texture: &Texture,
+    page_size: f32,
+) {
+    if font_atlases
+        .iter_mut()
+        .any(|font_atlas| font_atlas.add_char(textures, texture_atlases, character, glyph_texture))
+    {
+        return;
+    }
+
+    // every page is full, so start a new one that is large enough for the glyph and its padding
+    let page_size = Vec2::new(
+        page_size.max(glyph_texture.size.x() + 2.0),
+        page_size.max(glyph_texture.size.y() + 2.0),
+    );
+    let mut font_atlas = FontAtlas::new(textures, texture_atlases, page_size);
+    if !font_atlas.add_char(textures, texture_atlases, character, glyph_texture) {
+        panic!("glyph does not fit in an empty font atlas");
+    }
+    font_atlases.push(font_atlas);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::add_char_to_pages;
+    use crate::FontAtlas;
+    use bevy_asset::Assets;
+    use bevy_math::Vec2;
+    use bevy_render::texture::{Texture, TextureFormat};
+    use bevy_sprite::TextureAtlas;
+
+    fn glyph(size: f32) -> Texture {
+        Texture::new_fill(
+            Vec2::new(size, size),
+            &[255, 255, 255, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    #[test]
+    fn full_pages_overflow_into_new_ones() {
+        let mut textures = Assets::<Texture>::default();
+        let mut texture_atlases = Assets::<TextureAtlas>::default();
+        let mut font_atlases = Vec::new();
+        let mut add = |font_atlases: &mut Vec<FontAtlas>, character, size| {
+            add_char_to_pages(
+                font_atlases,
+                &mut textures,
+                &mut texture_atlases,
+                character,
+                &glyph(size),
+                8.0,
+            )
+        };
+
+        // with their padding, these glyphs don't both fit in a page
+        add(&mut font_atlases, 'a', 3.0);
+        add(&mut font_atlases, 'b', 6.0);
+        assert_eq!(font_atlases.len(), 2);
+        // glyphs larger than a page get a page of their own
+        add(&mut font_atlases, 'c', 10.0);
+        assert_eq!(font_atlases.len(), 3);
+        // small glyphs still fill the gaps in earlier pages
+        add(&mut font_atlases, 'd', 3.0);
+        assert_eq!(font_atlases.len(), 3);
+
+        let page_of = |character| {
+            font_atlases
+                .iter()
+                .position(|font_atlas| font_atlas.get_char_index(character).is_some())
+        };
+        assert_eq!(page_of('a'), Some(0));
+        assert_eq!(page_of('b'), Some(1));
+        assert_eq!(page_of('c'), Some(2));
+        assert_eq!(page_of('d'), Some(0));
+        assert_eq!(
+            texture_atlases
+                .get(&font_atlases[2].texture_atlas)
+                .unwrap()
+                .size,
+            Vec2::new(12.0, 12.0)
+        );
+    }
+}
@@ -0,0 +1,379 @@
+use crate::Font;
+use ab_glyph::{GlyphId, PxScale, PxScaleFont, ScaleFont};
+use bevy_asset::Handle;
+use bevy_math::Vec2;
+
+/// Where each line of text is placed horizontally within its container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HorizontalAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl Default for HorizontalAlign {
+    fn default() -> Self {
+        HorizontalAlign::Left
+    }
+}
+
+/// Where a block of text is placed vertically within its container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalAlign {
+    Top,
+    Center,
+    Bottom,
+}
+
+impl Default for VerticalAlign {
+    fn default() -> Self {
+        VerticalAlign::Top
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TextAlignment {
+    pub horizontal: HorizontalAlign,
+    pub vertical: VerticalAlign,
+}
+
+impl TextAlignment {
+    /// How far `line` of `layout` is moved right, and the whole text is moved down, from the top
+    /// left corner of a container of `container_size`
+    pub fn offset(&self, layout: &TextLayout, line: usize, container_size: Vec2) -> Vec2 {
+        let line_width = layout.line_widths[line];
+        let x = match self.horizontal {
+            HorizontalAlign::Left => 0.0,
+            HorizontalAlign::Center => (container_size.x() - line_width) / 2.0,
+            HorizontalAlign::Right => container_size.x() - line_width,
+        };
+        let y = match self.vertical {
+            VerticalAlign::Top => 0.0,
+            VerticalAlign::Center => (container_size.y() - layout.size.y()) / 2.0,
+            VerticalAlign::Bottom => container_size.y() - layout.size.y(),
+        };
+        Vec2::new(x, y)
+    }
+}
+
+/// A glyph placed by a [TextLayout]
+#[derive(Debug, Clone)]
+pub struct PositionedGlyph {
+    pub character: char,
+    /// The index of the line this glyph is on
+    pub line: usize,
+    /// The glyph's caret position, measured right from the start of its line and down from the top
+    /// of the text
+    pub position: Vec2,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LayoutSource {
+    font: Handle<Font>,
+    font_size: f32,
+    max_width: Option<f32>,
+    text: String,
+}
+
+/// The glyphs of a string broken into lines, with every line starting at x = 0
+///
+/// Alignment is applied when the text is drawn, because it depends on the size of the container.
+#[derive(Debug, Default, Clone)]
+pub struct TextLayout {
+    pub glyphs: Vec<PositionedGlyph>,
+    pub line_widths: Vec<f32>,
+    pub line_height: f32,
+    pub size: Vec2,
+    source: Option<LayoutSource>,
+}
+
+impl TextLayout {
+    /// Lays out `text` with `font`, starting a new line at every `\n` and, if `max_width` is set,
+    /// wrapping lines at word boundaries to fit within it
+    ///
+    /// Words that are wider than `max_width` on their own are broken between characters. Every line
+    /// holds at least one glyph, so text always fits on a finite number of lines.
+    pub fn new(font: &Font, font_size: f32, max_width: Option<f32>, text: &str) -> Self {
+        let scaled_font = ab_glyph::Font::as_scaled(&font.font, PxScale::from(font_size));
+        layout_text(&scaled_font, max_width, text)
+    }
+
+    /// Lays out `text` again, unless it was already laid out with the same font, size and bounds.
+    /// Returns whether the layout changed.
+    pub fn update(
+        &mut self,
+        font_handle: Handle<Font>,
+        font: &Font,
+        font_size: f32,
+        max_width: Option<f32>,
+        text: &str,
+    ) -> bool {
+        let source = LayoutSource {
+            font: font_handle,
+            font_size,
+            max_width,
+            text: text.to_string(),
+        };
+        if self.source.as_ref() == Some(&source) {
+            return false;
+        }
+
+        *self = TextLayout::new(font, font_size, max_width, text);
+        self.source = Some(source);
+        true
+    }
+}
+
+/// The font measurements text layout depends on
+trait GlyphMetrics {
+    fn glyph_id(&self, character: char) -> GlyphId;
+    fn h_advance(&self, glyph_id: GlyphId) -> f32;
+    fn kern(&self, first: GlyphId, second: GlyphId) -> f32;
+    fn height(&self) -> f32;
+    fn line_gap(&self) -> f32;
+}
+
+impl<F: ab_glyph::Font> GlyphMetrics for PxScaleFont<F> {
+    fn glyph_id(&self, character: char) -> GlyphId {
+        ScaleFont::glyph_id(self, character)
+    }
+
+    fn h_advance(&self, glyph_id: GlyphId) -> f32 {
+        ScaleFont::h_advance(self, glyph_id)
+    }
+
+    fn kern(&self, first: GlyphId, second: GlyphId) -> f32 {
+        ScaleFont::kern(self, first, second)
+    }
+
+    fn height(&self) -> f32 {
+        ScaleFont::height(self)
+    }
+
+    fn line_gap(&self) -> f32 {
+        ScaleFont::line_gap(self)
+    }
+}
+
+/// See [TextLayout::new]
+fn layout_text(metrics: &impl GlyphMetrics, max_width: Option<f32>, text: &str) -> TextLayout {
+    let mut builder = LayoutBuilder {
+        layout: TextLayout {
+            line_height: metrics.height() + metrics.line_gap(),
+            ..Default::default()
+        },
+        caret: 0.0,
+        content_width: 0.0,
+        last_glyph: None,
+    };
+
+    for paragraph in text.split('\n') {
+        for word in split_words(paragraph) {
+            let is_whitespace = word.starts_with(char::is_whitespace);
+            if let Some(max_width) = max_width {
+                if !is_whitespace
+                    && builder.content_width > 0.0
+                    && builder.caret + builder.measure(metrics, word) > max_width
+                {
+                    builder.break_line();
+                }
+            }
+
+            for character in word.chars().filter(|character| !character.is_control()) {
+                let glyph_id = metrics.glyph_id(character);
+                let mut x = builder.caret;
+                if let Some(last_glyph) = builder.last_glyph {
+                    x += metrics.kern(last_glyph, glyph_id);
+                }
+                let advance = metrics.h_advance(glyph_id);
+                if let Some(max_width) = max_width {
+                    // only reached by words that don't fit on a line of their own
+                    if !is_whitespace && builder.content_width > 0.0 && x + advance > max_width {
+                        builder.break_line();
+                        x = 0.0;
+                    }
+                }
+
+                builder.caret = x + advance;
+                builder.last_glyph = Some(glyph_id);
+                if !is_whitespace {
+                    builder.content_width = builder.caret;
+                    let line = builder.layout.line_widths.len();
+                    builder.layout.glyphs.push(PositionedGlyph {
+                        character,
+                        line,
+                        position: Vec2::new(x, line as f32 * builder.layout.line_height),
+                    });
+                }
+            }
+        }
+        builder.break_line();
+    }
+
+    let mut layout = builder.layout;
+    let line_count = layout.line_widths.len() as f32;
+    let width = layout.line_widths.iter().cloned().fold(0.0, f32::max);
+    let height = metrics.height() + (line_count - 1.0) * layout.line_height;
+    layout.size = Vec2::new(width, height);
+    layout
+}
+
+struct LayoutBuilder {
+    layout: TextLayout,
+    caret: f32,
+    /// The width of the current line, excluding trailing whitespace
+    content_width: f32,
+    last_glyph: Option<GlyphId>,
+}
+
+impl LayoutBuilder {
+    fn measure(&self, metrics: &impl GlyphMetrics, word: &str) -> f32 {
+        let mut last_glyph = self.last_glyph;
+        let mut width = 0.0;
+        for character in word.chars().filter(|character| !character.is_control()) {
+            let glyph_id = metrics.glyph_id(character);
+            if let Some(last_glyph) = last_glyph {
+                width += metrics.kern(last_glyph, glyph_id);
+            }
+            width += metrics.h_advance(glyph_id);
+            last_glyph = Some(glyph_id);
+        }
+        width
+    }
+
+    fn break_line(&mut self) {
+        self.layout.line_widths.push(self.content_width);
+        self.caret = 0.0;
+        self.content_width = 0.0;
+        self.last_glyph = None;
+    }
+}
+
+/// Splits `text` into alternating runs of whitespace and non-whitespace characters
+fn split_words(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let is_whitespace = first.is_whitespace();
+        let end = rest
+            .find(|character: char| character.is_whitespace() != is_whitespace)
+            .unwrap_or(rest.len());
+        let (word, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(word)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every glyph is 10 wide, lines are 12 high with a gap of 3, and "AV" kerns by -2
+    struct FixedMetrics;
+
+    impl GlyphMetrics for FixedMetrics {
+        fn glyph_id(&self, character: char) -> GlyphId {
+            GlyphId(character as u16)
+        }
+
+        fn h_advance(&self, _glyph_id: GlyphId) -> f32 {
+            10.0
+        }
+
+        fn kern(&self, first: GlyphId, second: GlyphId) -> f32 {
+            if (first, second) == (GlyphId('A' as u16), GlyphId('V' as u16)) {
+                -2.0
+            } else {
+                0.0
+            }
+        }
+
+        fn height(&self) -> f32 {
+            12.0
+        }
+
+        fn line_gap(&self) -> f32 {
+            3.0
+        }
+    }
+
+    fn lines(layout: &TextLayout) -> Vec<String> {
+        let mut lines = vec![String::new(); layout.line_widths.len()];
+        for glyph in layout.glyphs.iter() {
+            lines[glyph.line].push(glyph.character);
+        }
+        lines
+    }
+
+    #[test]
+    fn wraps_at_word_boundaries() {
+        let layout = layout_text(&FixedMetrics, Some(100.0), "hello world foo");
+        assert_eq!(lines(&layout), vec!["hello", "worldfoo"]);
+        assert_eq!(layout.line_widths, vec![50.0, 90.0]);
+        assert_eq!(layout.size, Vec2::new(90.0, 27.0));
+
+        let w = layout.glyphs.iter().find(|glyph| glyph.character == 'w');
+        assert_eq!(w.unwrap().position, Vec2::new(0.0, 15.0));
+        let f = layout.glyphs.iter().find(|glyph| glyph.character == 'f');
+        assert_eq!(f.unwrap().position, Vec2::new(60.0, 15.0));
+    }
+
+    #[test]
+    fn newlines_start_new_lines() {
+        let layout = layout_text(&FixedMetrics, None, "ab\n\ncd");
+        assert_eq!(lines(&layout), vec!["ab", "", "cd"]);
+        assert_eq!(layout.size, Vec2::new(20.0, 42.0));
+    }
+
+    #[test]
+    fn long_words_are_broken() {
+        let layout = layout_text(&FixedMetrics, Some(45.0), "abcdefghij");
+        assert_eq!(lines(&layout), vec!["abcd", "efgh", "ij"]);
+
+        // a bound narrower than a single glyph still places one glyph per line
+        let layout = layout_text(&FixedMetrics, Some(5.0), "abc");
+        assert_eq!(lines(&layout), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn kerning_moves_glyphs() {
+        let layout = layout_text(&FixedMetrics, None, "AVA");
+        let positions = layout
+            .glyphs
+            .iter()
+            .map(|glyph| glyph.position.x())
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![0.0, 8.0, 18.0]);
+        assert_eq!(layout.line_widths, vec![28.0]);
+    }
+
+    #[test]
+    fn alignment_offsets() {
+        let layout = layout_text(&FixedMetrics, Some(100.0), "hello world foo");
+        let container_size = Vec2::new(110.0, 47.0);
+        let alignment = |horizontal, vertical| TextAlignment {
+            horizontal,
+            vertical,
+        };
+
+        let top_left = alignment(HorizontalAlign::Left, VerticalAlign::Top);
+        assert_eq!(
+            top_left.offset(&layout, 1, container_size),
+            Vec2::new(0.0, 0.0)
+        );
+        let centered = alignment(HorizontalAlign::Center, VerticalAlign::Center);
+        assert_eq!(
+            centered.offset(&layout, 0, container_size),
+            Vec2::new(30.0, 10.0)
+        );
+        assert_eq!(
+            centered.offset(&layout, 1, container_size),
+            Vec2::new(10.0, 10.0)
+        );
+        let bottom_right = alignment(HorizontalAlign::Right, VerticalAlign::Bottom);
+        assert_eq!(
+            bottom_right.offset(&layout, 1, container_size),
+            Vec2::new(20.0, 20.0)
+        );
+    }
+}
@@ -3,15 +3,17 @@ mod font;
 mod font_atlas;
 mod font_atlas_set;
 mod font_loader;
+mod layout;
 
 pub use draw::*;
 pub use font::*;
 pub use font_atlas::*;
 pub use font_atlas_set::*;
 pub use font_loader::*;
+pub use layout::*;
 
 pub mod prelude {
-    pub use crate::{Font, TextStyle};
+    pub use crate::{Font, HorizontalAlign, TextAlignment, TextStyle, VerticalAlign};
 }
 
 use bevy_app::prelude::*;
@@ -26,4 +28,8 @@ impl Plugin for TextPlugin {
             .add_asset::<FontAtlasSet>()
             .add_asset_loader::<Font, FontLoader>();
     }
+
+    fn dependencies(&self) -> Vec<std::any::TypeId> {
+        vec![std::any::TypeId::of::<bevy_sprite::SpritePlugin>()]
+    }
 }
@@ -1,4 +1,4 @@
-use bevy_ecs::Entity;
+use bevy_ecs::{Entity, EntityMap, MapEntities, NoSuchEntity};
 use bevy_property::Properties;
 use smallvec::SmallVec;
 use std::ops::{Deref, DerefMut};
@@ -12,6 +12,15 @@ impl Children {
     }
 }
 
+impl MapEntities for Children {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), NoSuchEntity> {
+        for entity in self.0.iter_mut() {
+            entity.map_entities(entity_map)?;
+        }
+        Ok(())
+    }
+}
+
 impl Deref for Children {
     type Target = SmallVec<[Entity; 8]>;
 
@@ -1,4 +1,4 @@
-use bevy_ecs::{Entity, FromResources};
+use bevy_ecs::{Entity, EntityMap, FromResources, MapEntities, NoSuchEntity};
 use bevy_property::Properties;
 use std::ops::{Deref, DerefMut};
 
@@ -15,6 +15,12 @@ impl FromResources for Parent {
     }
 }
 
+impl MapEntities for Parent {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), NoSuchEntity> {
+        self.0.map_entities(entity_map)
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct PreviousParent(pub Option<Entity>);
 
@@ -82,7 +82,8 @@ impl WorldWriter for PushChildren {
 
 impl<'a> ChildBuilder<'a> {
     pub fn spawn(&mut self, components: impl DynamicBundle + Send + Sync + 'static) -> &mut Self {
-        self.spawn_as_entity(Entity::new(), components)
+        let entity = self.commands.generate_entity();
+        self.spawn_as_entity(entity, components)
     }
 
     pub fn spawn_as_entity(
@@ -45,8 +45,8 @@ pub struct DespawnRecursive {
 
 fn despawn_with_children_recursive(world: &mut World, entity: Entity) {
     // first, make the entity's own parent forget about it
-    if let Ok(parent) = world.get::<Parent>(entity) {
-        if let Ok(mut children) = world.get_mut::<Children>(parent.0) {
+    if let Ok(parent) = world.get::<Parent>(entity).map(|parent| parent.0) {
+        if let Ok(mut children) = world.get_mut::<Children>(parent) {
             children.retain(|c| *c != entity);
         }
     }
@@ -56,17 +56,18 @@ fn despawn_with_children_recursive(world: &mut World, entity: Entity) {
 
 // Should only be called by `despawn_with_children_recursive`!
 fn despawn_with_children_recursive_inner(world: &mut World, entity: Entity) {
-    if let Some(children) = world
-        .get::<Children>(entity)
-        .ok()
-        .map(|children| children.0.iter().cloned().collect::<Vec<Entity>>())
-    {
-        for e in children {
-            despawn_with_children_recursive(world, e);
+    // Taking the children out before descending means a malformed hierarchy that lists an ancestor
+    // as a child can't send us around in circles.
+    if let Ok(children) = world.remove_one::<Children>(entity) {
+        for e in children.0 {
+            despawn_with_children_recursive_inner(world, e);
         }
     }
 
-    world.despawn(entity).unwrap();
+    // Children listed twice, or already despawned, are simply skipped
+    if world.despawn(entity).is_err() {
+        log::debug!("Failed to despawn non-existent entity {:?}", entity);
+    }
 }
 
 impl WorldWriter for DespawnRecursive {
@@ -87,10 +88,21 @@ impl DespawnRecursiveExt for Commands {
     }
 }
 
+impl DespawnRecursiveExt for World {
+    /// Despawns the provided entity and its children.
+    fn despawn_recursive(&mut self, entity: Entity) -> &mut Self {
+        despawn_with_children_recursive(self, entity);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::DespawnRecursiveExt;
-    use crate::{components::Children, hierarchy::BuildChildren};
+    use crate::{
+        components::{Children, Parent},
+        hierarchy::BuildChildren,
+    };
     use bevy_ecs::{Commands, Resources, World};
 
     #[test]
@@ -150,4 +162,28 @@ mod tests {
             vec![(0u32, 0u64), (0u32, 0u64), (0u32, 0u64), (1u32, 1u64)]
         );
     }
+
+    #[test]
+    fn despawn_recursive_malformed() {
+        let mut world = World::default();
+        let parent = world.spawn((0u32,));
+        let child = world.spawn((1u32, Parent(parent)));
+        let despawned = world.spawn((2u32,));
+        world.despawn(despawned).unwrap();
+        world
+            .insert_one(parent, Children::with(&[child, child, despawned]))
+            .unwrap();
+        // a cycle back up to the parent
+        world.insert_one(child, Children::with(&[parent])).unwrap();
+        world.clear_trackers();
+
+        world.despawn_recursive(parent);
+
+        assert_eq!(world.query::<&u32>().iter().count(), 0);
+        let mut removed = world.removed::<u32>().to_vec();
+        removed.sort_by_key(|entity| entity.id());
+        let mut expected = vec![parent, child];
+        expected.sort_by_key(|entity| entity.id());
+        assert_eq!(removed, expected);
+    }
 }
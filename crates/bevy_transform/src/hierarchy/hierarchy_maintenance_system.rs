@@ -1,5 +1,5 @@
 use crate::components::*;
-use bevy_ecs::{Commands, Entity, IntoQuerySystem, Query, System, Without};
+use bevy_ecs::{Commands, Entity, IntoQuerySystem, Query, QueryError, System, Without};
 use bevy_utils::HashMap;
 use smallvec::SmallVec;
 
@@ -98,8 +98,29 @@ pub fn parent_update_system(
     });
 }
 
+pub fn orphaned_children_system(
+    mut commands: Commands,
+    mut query: Query<(Entity, &Parent)>,
+    entities: Query<Entity>,
+) {
+    // Entities whose parent was despawned out from under them are detached, which makes them
+    // roots of their own hierarchy.
+    for (entity, parent) in &mut query.iter() {
+        if let Err(QueryError::NoSuchEntity) = entities.entity(parent.0) {
+            log::trace!(
+                "Detaching {:?} from its despawned parent {:?}",
+                entity,
+                parent.0
+            );
+            commands.remove_one::<Parent>(entity);
+            commands.remove_one::<PreviousParent>(entity);
+        }
+    }
+}
+
 pub fn hierarchy_maintenance_systems() -> Vec<Box<dyn System>> {
     vec![
+        orphaned_children_system.system(),
         missing_previous_parent_system.system(),
         parent_update_system.system(),
     ]
@@ -190,4 +211,37 @@ mod test {
             vec![children[1]]
         );
     }
+
+    #[test]
+    fn detach_orphans() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        for system in transform_systems() {
+            schedule.add_system_to_stage("update", system);
+        }
+
+        let mut commands = Commands::default();
+        let mut parent = None;
+        let mut child = None;
+        commands
+            .spawn((Translation::new(1.0, 0.0, 0.0), Transform::identity()))
+            .for_current_entity(|entity| parent = Some(entity))
+            .with_children(|parent| {
+                parent
+                    .spawn((Translation::new(0.0, 2.0, 0.0), Transform::identity()))
+                    .for_current_entity(|entity| child = Some(entity));
+            });
+        let (parent, child) = (parent.unwrap(), child.unwrap());
+        commands.apply(&mut world, &mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        world.despawn(parent).unwrap();
+        schedule.run(&mut world, &mut resources);
+
+        assert!(world.get::<Parent>(child).is_err());
+        assert!(world.get::<PreviousParent>(child).is_err());
+    }
 }
@@ -8,7 +8,7 @@ pub mod prelude {
     pub use crate::{components::*, hierarchy::*, TransformPlugin};
 }
 
-use bevy_app::prelude::*;
+use bevy_app::{prelude::*, startup_stage};
 use bevy_ecs::prelude::*;
 use bevy_type_registry::RegisterType;
 use prelude::{
@@ -33,14 +33,17 @@ impl Plugin for TransformPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.register_component::<Children>()
             .register_component::<Parent>()
+            .register_map_entities::<Children>()
+            .register_map_entities::<Parent>()
             .register_component::<LocalTransform>()
             .register_component::<Transform>()
             .register_component::<Translation>()
             .register_component::<Rotation>()
             .register_component::<Scale>()
             .register_component::<NonUniformScale>()
-            // add transform systems to startup so the first update is "correct"
-            .add_startup_systems(transform_systems())
+            // add transform systems to startup so the first update is "correct". they run after
+            // STARTUP so entities spawned there are still marked as changed
+            .add_startup_systems_to_stage(startup_stage::POST_STARTUP, transform_systems())
             .add_systems_to_stage(stage::POST_UPDATE, transform_systems());
     }
 }
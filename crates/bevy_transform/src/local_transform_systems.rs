@@ -2,7 +2,8 @@ use crate::components::*;
 use bevy_ecs::prelude::*;
 use bevy_math::{Mat4, Quat, Vec3};
 
-// TODO: "on changed" for all of these systems
+// These only write `LocalTransform` when it actually differs, so `Changed<LocalTransform>` is a
+// reliable signal for transform propagation
 pub fn local_transform_translation_system(
     mut query: Query<
         Without<
@@ -12,7 +13,10 @@ pub fn local_transform_translation_system(
     >,
 ) {
     for (mut local, translation) in &mut query.iter() {
-        *local = LocalTransform(Mat4::from_translation(translation.0));
+        let local_transform = LocalTransform(Mat4::from_translation(translation.0));
+        if *local != local_transform {
+            *local = local_transform;
+        }
     }
 }
 
@@ -25,7 +29,10 @@ pub fn local_transform_rotation_system(
     >,
 ) {
     for (mut local, rotation) in &mut query.iter() {
-        *local = LocalTransform(Mat4::from_quat(rotation.0));
+        let local_transform = LocalTransform(Mat4::from_quat(rotation.0));
+        if *local != local_transform {
+            *local = local_transform;
+        }
     }
 }
 
@@ -38,7 +45,11 @@ pub fn local_transform_scale_system(
     >,
 ) {
     for (mut local, scale) in &mut query.iter() {
-        *local = LocalTransform(Mat4::from_scale(Vec3::new(scale.0, scale.0, scale.0)));
+        let local_transform =
+            LocalTransform(Mat4::from_scale(Vec3::new(scale.0, scale.0, scale.0)));
+        if *local != local_transform {
+            *local = local_transform;
+        }
     }
 }
 
@@ -51,7 +62,10 @@ pub fn local_transform_non_uniform_scale_system(
     >,
 ) {
     for (mut local, non_uniform_scale) in &mut query.iter() {
-        *local = LocalTransform(Mat4::from_scale(non_uniform_scale.0));
+        let local_transform = LocalTransform(Mat4::from_scale(non_uniform_scale.0));
+        if *local != local_transform {
+            *local = local_transform;
+        }
     }
 }
 
@@ -61,7 +75,11 @@ pub fn local_transform_translation_rotation_system(
     >,
 ) {
     for (mut local, translation, rotation) in &mut query.iter() {
-        *local = LocalTransform(Mat4::from_rotation_translation(rotation.0, translation.0));
+        let local_transform =
+            LocalTransform(Mat4::from_rotation_translation(rotation.0, translation.0));
+        if *local != local_transform {
+            *local = local_transform;
+        }
     }
 }
 
@@ -71,11 +89,14 @@ pub fn local_transform_translation_scale_system(
     >,
 ) {
     for (mut local, translation, scale) in &mut query.iter() {
-        *local = LocalTransform(Mat4::from_scale_rotation_translation(
+        let local_transform = LocalTransform(Mat4::from_scale_rotation_translation(
             Vec3::new(scale.0, scale.0, scale.0),
             Quat::default(),
             translation.0,
         ));
+        if *local != local_transform {
+            *local = local_transform;
+        }
     }
 }
 
@@ -85,11 +106,14 @@ pub fn local_transform_translation_non_uniform_scale_system(
     >,
 ) {
     for (mut local, translation, non_uniform_scale) in &mut query.iter() {
-        *local = LocalTransform(Mat4::from_scale_rotation_translation(
+        let local_transform = LocalTransform(Mat4::from_scale_rotation_translation(
             non_uniform_scale.0,
             Quat::default(),
             translation.0,
         ));
+        if *local != local_transform {
+            *local = local_transform;
+        }
     }
 }
 
@@ -99,11 +123,14 @@ pub fn local_transform_rotation_scale_system(
     >,
 ) {
     for (mut local, rotation, scale) in &mut query.iter() {
-        *local = LocalTransform(Mat4::from_scale_rotation_translation(
+        let local_transform = LocalTransform(Mat4::from_scale_rotation_translation(
             Vec3::new(scale.0, scale.0, scale.0),
             rotation.0,
             Vec3::default(),
         ));
+        if *local != local_transform {
+            *local = local_transform;
+        }
     }
 }
 
@@ -113,11 +140,14 @@ pub fn local_transform_rotation_non_uniform_scale_system(
     >,
 ) {
     for (mut local, rotation, non_uniform_scale) in &mut query.iter() {
-        *local = LocalTransform(Mat4::from_scale_rotation_translation(
+        let local_transform = LocalTransform(Mat4::from_scale_rotation_translation(
             non_uniform_scale.0,
             rotation.0,
             Vec3::default(),
         ));
+        if *local != local_transform {
+            *local = local_transform;
+        }
     }
 }
 
@@ -127,11 +157,14 @@ pub fn local_transform_translation_rotation_scale_system(
     >,
 ) {
     for (mut local, translation, rotation, scale) in &mut query.iter() {
-        *local = LocalTransform(Mat4::from_scale_rotation_translation(
+        let local_transform = LocalTransform(Mat4::from_scale_rotation_translation(
             Vec3::new(scale.0, scale.0, scale.0),
             rotation.0,
             translation.0,
         ));
+        if *local != local_transform {
+            *local = local_transform;
+        }
     }
 }
 
@@ -149,11 +182,14 @@ pub fn local_transform_translation_rotation_non_uniform_scale_system(
     >,
 ) {
     for (mut local, translation, rotation, non_uniform_scale) in &mut query.iter() {
-        *local = LocalTransform(Mat4::from_scale_rotation_translation(
+        let local_transform = LocalTransform(Mat4::from_scale_rotation_translation(
             non_uniform_scale.0,
             rotation.0,
             translation.0,
         ));
+        if *local != local_transform {
+            *local = local_transform;
+        }
     }
 }
 
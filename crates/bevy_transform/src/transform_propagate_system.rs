@@ -1,30 +1,145 @@
 use crate::components::*;
 use bevy_ecs::prelude::*;
+use bevy_utils::HashSet;
 
+/// Recomputes `Transform` for the parts of the hierarchy that changed this frame.
+///
+/// A subtree is recomputed when its root's `Transform` or `LocalTransform` changed, or when any
+/// entity in it had its `LocalTransform` or `Parent` changed. Everything else keeps the value
+/// from an earlier frame.
 pub fn transform_propagate_system(
     mut root_query: Query<
-        Without<Parent, (Option<&Children>, &mut Transform, Option<&LocalTransform>)>,
+        Without<
+            Parent,
+            (
+                Entity,
+                Option<&Children>,
+                &mut Transform,
+                Option<&LocalTransform>,
+            ),
+        >,
     >,
     mut local_transform_query: Query<(&mut Transform, &LocalTransform, Option<&Children>)>,
+    parent_query: Query<&Parent>,
+    mut changed_root_query: Query<Without<Parent, (Entity, Changed<Transform>)>>,
+    mut changed_local_transform_query: Query<(Entity, Changed<LocalTransform>)>,
+    mut changed_parent_query: Query<(Entity, Changed<Parent>)>,
 ) {
-    for (children, mut transform, local_transform) in &mut root_query.iter() {
+    let mut changed = HashSet::default();
+    for (entity, _) in &mut changed_root_query.iter() {
+        changed.insert(entity);
+    }
+    for (entity, _) in &mut changed_local_transform_query.iter() {
+        changed.insert(entity);
+    }
+    for (entity, _) in &mut changed_parent_query.iter() {
+        changed.insert(entity);
+    }
+
+    let mut visited = HashSet::default();
+    for (entity, children, mut transform, local_transform) in &mut root_query.iter() {
+        // Entities that just lost their `Parent` keep their old global transform, so roots are
+        // also refreshed whenever they disagree with their local transform
+        let stale = local_transform.map_or(false, |local| transform.value != local.0);
+        if !stale && !changed.contains(&entity) {
+            continue;
+        }
+
         if let Some(local_transform) = local_transform {
             transform.value = local_transform.0;
         }
 
+        visited.insert(entity);
         if let Some(children) = children {
             for child in children.0.iter() {
-                propagate_recursive(*transform, &mut local_transform_query, *child);
+                propagate_recursive(
+                    *transform,
+                    &mut local_transform_query,
+                    &parent_query,
+                    entity,
+                    *child,
+                    &mut visited,
+                );
+            }
+        }
+    }
+
+    // Changed entities below an unchanged root weren't reached above. This also covers children
+    // whose parent doesn't list them in `Children` yet. Start from the topmost changed ancestor
+    // so every affected subtree is recomputed once, on top of its parent's current transform.
+    for entity in changed.iter() {
+        if visited.contains(entity) {
+            continue;
+        }
+
+        let mut top = *entity;
+        let mut walked = HashSet::default();
+        walked.insert(top);
+        let mut parent = match parent_query.get::<Parent>(top) {
+            Ok(parent) => parent.0,
+            Err(_) => continue,
+        };
+        while changed.contains(&parent) && !visited.contains(&parent) && walked.insert(parent) {
+            match parent_query.get::<Parent>(parent) {
+                Ok(grandparent) => {
+                    top = parent;
+                    parent = grandparent.0;
+                }
+                Err(_) => break,
             }
         }
+
+        let parent_transform = match root_query
+            .get::<Transform>(parent)
+            .map(|transform| *transform)
+            .or_else(|_| {
+                local_transform_query
+                    .get::<Transform>(parent)
+                    .map(|transform| *transform)
+            }) {
+            Ok(parent_transform) => parent_transform,
+            Err(_) => continue,
+        };
+        propagate_recursive(
+            parent_transform,
+            &mut local_transform_query,
+            &parent_query,
+            parent,
+            top,
+            &mut visited,
+        );
     }
 }
 
 fn propagate_recursive(
     parent_local_to_world: Transform,
     local_transform_query: &mut Query<(&mut Transform, &LocalTransform, Option<&Children>)>,
+    parent_query: &Query<&Parent>,
+    expected_parent: Entity,
     entity: Entity,
+    visited: &mut HashSet<Entity>,
 ) {
+    // Only descend into children that agree on who their parent is, and never visit an entity
+    // twice, so malformed `Children` lists and `Parent` cycles can't send us around in circles.
+    match parent_query.get::<Parent>(entity) {
+        Ok(parent) if parent.0 == expected_parent => {}
+        _ => {
+            log::warn!(
+                "Entity {:?} is listed as a child of {:?}, but its Parent doesn't match",
+                entity,
+                expected_parent
+            );
+            return;
+        }
+    }
+    if !visited.insert(entity) {
+        log::warn!(
+            "Entity {:?} was reached twice while propagating transforms, its hierarchy has a cycle",
+            entity
+        );
+        return;
+    }
+
     log::trace!("Updating Transform for {:?}", entity);
     let local_transform = {
         if let Ok(local_transform) = local_transform_query.get::<LocalTransform>(entity) {
@@ -55,7 +170,14 @@ fn propagate_recursive(
         .unwrap_or_default();
 
     for child in children {
-        propagate_recursive(new_transform, local_transform_query, child);
+        propagate_recursive(
+            new_transform,
+            local_transform_query,
+            parent_query,
+            entity,
+            child,
+            visited,
+        );
     }
 }
 
@@ -153,4 +275,83 @@ mod test {
                 * Mat4::from_translation(Vec3::new(0.0, 0.0, 3.0))
         );
     }
+
+    #[test]
+    fn only_changed_subtrees_are_recomputed() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        for system in transform_systems() {
+            schedule.add_system_to_stage("update", system);
+        }
+
+        let parent = world.spawn((Translation::new(1.0, 0.0, 0.0), Transform::identity()));
+        let child = world.spawn((
+            Translation::new(0.0, 2.0, 0.0),
+            LocalTransform::identity(),
+            Transform::identity(),
+            Parent(parent),
+        ));
+        schedule.run(&mut world, &mut resources);
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(
+            world.get::<Transform>(child).unwrap().value,
+            Mat4::from_translation(Vec3::new(1.0, 2.0, 0.0))
+        );
+
+        // nothing in the child's hierarchy changed, so a planted value survives the next update
+        let planted = Mat4::from_translation(Vec3::new(9.0, 9.0, 9.0));
+        world.get_mut::<Transform>(child).unwrap().value = planted;
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(world.get::<Transform>(child).unwrap().value, planted);
+
+        // moving the parent recomputes its subtree
+        world.get_mut::<Translation>(parent).unwrap().0 = Vec3::new(3.0, 0.0, 0.0);
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(
+            world.get::<Transform>(child).unwrap().value,
+            Mat4::from_translation(Vec3::new(3.0, 2.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn parent_cycles_terminate() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        for system in transform_systems() {
+            schedule.add_system_to_stage("update", system);
+        }
+
+        let a = world.spawn((
+            Translation::new(1.0, 0.0, 0.0),
+            LocalTransform::identity(),
+            Transform::identity(),
+        ));
+        let b = world.spawn((
+            Translation::new(0.0, 2.0, 0.0),
+            LocalTransform::identity(),
+            Transform::identity(),
+            Parent(a),
+        ));
+        world.insert_one(a, Parent(b)).unwrap();
+        for _ in 0..3 {
+            schedule.run(&mut world, &mut resources);
+        }
+        assert_eq!(world.get::<Children>(a).unwrap().0.as_slice(), &[b]);
+        assert_eq!(world.get::<Children>(b).unwrap().0.as_slice(), &[a]);
+
+        // changing a local transform inside the cycle walks it through both `Children` lists,
+        // which has to stop once it comes back around
+        world.get_mut::<Translation>(a).unwrap().0 = Vec3::new(5.0, 0.0, 0.0);
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(
+            world.get::<LocalTransform>(a).unwrap().0,
+            Mat4::from_translation(Vec3::new(5.0, 0.0, 0.0))
+        );
+    }
 }
@@ -2,7 +2,8 @@ use crate::components::*;
 use bevy_ecs::prelude::*;
 use bevy_math::{Mat4, Quat, Vec3};
 
-// TODO: on changed for all of these systems
+// These only write `Transform` when it actually differs, so `Changed<Transform>` on a root is a
+// reliable signal for transform propagation
 pub fn transform_translation_system(
     mut query: Query<
         Without<
@@ -19,7 +20,10 @@ pub fn transform_translation_system(
             continue;
         }
 
-        *transform = Transform::new(Mat4::from_translation(translation.0));
+        let new_transform = Transform::new(Mat4::from_translation(translation.0));
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
     }
 }
 
@@ -39,7 +43,10 @@ pub fn transform_rotation_system(
             continue;
         }
 
-        *transform = Transform::new(Mat4::from_quat(rotation.0));
+        let new_transform = Transform::new(Mat4::from_quat(rotation.0));
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
     }
 }
 
@@ -59,7 +66,10 @@ pub fn transform_scale_system(
             continue;
         }
 
-        *transform = Transform::new(Mat4::from_scale(Vec3::new(scale.0, scale.0, scale.0)));
+        let new_transform = Transform::new(Mat4::from_scale(Vec3::new(scale.0, scale.0, scale.0)));
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
     }
 }
 
@@ -79,7 +89,10 @@ pub fn transform_non_uniform_scale_system(
             continue;
         }
 
-        *transform = Transform::new(Mat4::from_scale(non_uniform_scale.0));
+        let new_transform = Transform::new(Mat4::from_scale(non_uniform_scale.0));
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
     }
 }
 
@@ -96,7 +109,11 @@ pub fn transform_translation_rotation_system(
             continue;
         }
 
-        *transform = Transform::new(Mat4::from_rotation_translation(rotation.0, translation.0));
+        let new_transform =
+            Transform::new(Mat4::from_rotation_translation(rotation.0, translation.0));
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
     }
 }
 
@@ -113,11 +130,14 @@ pub fn transform_translation_scale_system(
             continue;
         }
 
-        *transform = Transform::new(Mat4::from_scale_rotation_translation(
+        let new_transform = Transform::new(Mat4::from_scale_rotation_translation(
             Vec3::new(scale.0, scale.0, scale.0),
             Quat::default(),
             translation.0,
         ));
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
     }
 }
 
@@ -134,11 +154,14 @@ pub fn transform_translation_non_uniform_scale_system(
             continue;
         }
 
-        *transform = Transform::new(Mat4::from_scale_rotation_translation(
+        let new_transform = Transform::new(Mat4::from_scale_rotation_translation(
             non_uniform_scale.0,
             Quat::default(),
             translation.0,
         ));
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
     }
 }
 
@@ -155,11 +178,14 @@ pub fn transform_rotation_scale_system(
             continue;
         }
 
-        *transform = Transform::new(Mat4::from_scale_rotation_translation(
+        let new_transform = Transform::new(Mat4::from_scale_rotation_translation(
             Vec3::new(scale.0, scale.0, scale.0),
             rotation.0,
             Vec3::default(),
         ));
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
     }
 }
 
@@ -176,11 +202,14 @@ pub fn transform_rotation_non_uniform_scale_system(
             continue;
         }
 
-        *transform = Transform::new(Mat4::from_scale_rotation_translation(
+        let new_transform = Transform::new(Mat4::from_scale_rotation_translation(
             non_uniform_scale.0,
             rotation.0,
             Vec3::default(),
         ));
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
     }
 }
 
@@ -197,11 +226,14 @@ pub fn transform_translation_rotation_scale_system(
             continue;
         }
 
-        *transform = Transform::new(Mat4::from_scale_rotation_translation(
+        let new_transform = Transform::new(Mat4::from_scale_rotation_translation(
             Vec3::new(scale.0, scale.0, scale.0),
             rotation.0,
             translation.0,
         ));
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
     }
 }
 
@@ -218,11 +250,14 @@ pub fn transform_translation_rotation_non_uniform_scale_system(
             continue;
         }
 
-        *transform = Transform::new(Mat4::from_scale_rotation_translation(
+        let new_transform = Transform::new(Mat4::from_scale_rotation_translation(
             non_uniform_scale.0,
             rotation.0,
             translation.0,
         ));
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
     }
 }
 
@@ -0,0 +1,131 @@
+use bevy_ecs::{DynamicBundle, TypeInfo};
+use bevy_property::Property;
+use std::{alloc::dealloc, any::TypeId, fmt};
+
+/// A bundle of boxed components whose types are only known at runtime, e.g. because they were
+/// loaded from data. It can be passed to `World::spawn` like any other bundle.
+///
+/// Components are usually added through [ComponentRegistration::add_component_to_bundle](crate::ComponentRegistration::add_component_to_bundle),
+/// which builds them from (possibly dynamic) properties.
+#[derive(Default)]
+pub struct ComponentBundle {
+    /// Sorted the way [DynamicBundle] expects: by descending alignment, then id
+    components: Vec<(TypeInfo, Box<dyn Property>)>,
+}
+
+impl ComponentBundle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `component`, whose type is described by `ty`. Each component type can only be added
+    /// once.
+    ///
+    /// Panics if `component` isn't a value of the type described by `ty`.
+    pub fn add(
+        &mut self,
+        ty: TypeInfo,
+        component: Box<dyn Property>,
+    ) -> Result<&mut Self, DuplicateComponent> {
+        assert!(
+            component.any().type_id() == ty.id(),
+            "ComponentBundle component {} does not match its TypeInfo",
+            component.type_name()
+        );
+        match self
+            .components
+            .binary_search_by(|(other, _)| other.cmp(&ty))
+        {
+            Ok(_) => Err(DuplicateComponent {
+                type_name: component.type_name().to_string(),
+            }),
+            Err(index) => {
+                self.components.insert(index, (ty, component));
+                Ok(self)
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+impl DynamicBundle for ComponentBundle {
+    fn with_ids<T>(&self, f: impl FnOnce(&[TypeId]) -> T) -> T {
+        let ids = self
+            .components
+            .iter()
+            .map(|(ty, _)| ty.id())
+            .collect::<Vec<_>>();
+        f(&ids)
+    }
+
+    fn type_info(&self) -> Vec<TypeInfo> {
+        self.components.iter().map(|(ty, _)| *ty).collect()
+    }
+
+    unsafe fn put(self, mut f: impl FnMut(*mut u8, TypeId, usize) -> bool) {
+        for (ty, component) in self.components {
+            let component = Box::into_raw(component);
+            let layout = ty.layout();
+            if !f(component.cast::<u8>(), ty.id(), layout.size()) {
+                drop(Box::from_raw(component));
+            } else if layout.size() != 0 {
+                // the value was moved out, only its allocation is left to free
+                dealloc(component.cast::<u8>(), layout);
+            }
+        }
+    }
+}
+
+/// Error returned when a component type is added to a [ComponentBundle] twice
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DuplicateComponent {
+    pub type_name: String,
+}
+
+impl fmt::Display for DuplicateComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "component {} was added more than once", self.type_name)
+    }
+}
+
+impl std::error::Error for DuplicateComponent {}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComponentBundle, DuplicateComponent};
+    use bevy_ecs::{TypeInfo, World};
+
+    #[test]
+    fn spawn_component_bundle() {
+        let mut bundle = ComponentBundle::new();
+        bundle
+            .add(TypeInfo::of::<u8>(), Box::new(1u8))
+            .unwrap()
+            .add(TypeInfo::of::<String>(), Box::new("abc".to_string()))
+            .unwrap()
+            .add(TypeInfo::of::<u64>(), Box::new(2u64))
+            .unwrap();
+        assert_eq!(
+            bundle
+                .add(TypeInfo::of::<u8>(), Box::new(3u8))
+                .err()
+                .unwrap(),
+            DuplicateComponent {
+                type_name: "u8".to_string()
+            }
+        );
+
+        let mut world = World::new();
+        let entity = world.spawn(bundle);
+        assert_eq!(*world.get::<u8>(entity).unwrap(), 1);
+        assert_eq!(*world.get::<u64>(entity).unwrap(), 2);
+        assert_eq!(*world.get::<String>(entity).unwrap(), "abc");
+    }
+}
@@ -1,6 +1,8 @@
+mod component_bundle;
 mod register_type;
 mod type_registry;
 
+pub use component_bundle::*;
 pub use register_type::*;
 pub use type_registry::*;
 
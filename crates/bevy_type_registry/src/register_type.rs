@@ -1,12 +1,15 @@
 use crate::TypeRegistry;
 use bevy_app::AppBuilder;
-use bevy_ecs::{Component, FromResources};
+use bevy_ecs::{Component, FromResources, MapEntities};
 use bevy_property::{DeserializeProperty, Properties, Property};
 
 pub trait RegisterType {
     fn register_component<T>(&mut self) -> &mut Self
     where
         T: Properties + DeserializeProperty + Component + FromResources;
+    fn register_map_entities<T>(&mut self) -> &mut Self
+    where
+        T: MapEntities + Component;
     fn register_properties<T>(&mut self) -> &mut Self
     where
         T: Properties + DeserializeProperty + FromResources;
@@ -28,6 +31,17 @@ impl RegisterType for AppBuilder {
         self
     }
 
+    fn register_map_entities<T>(&mut self) -> &mut Self
+    where
+        T: MapEntities + Component,
+    {
+        {
+            let type_registry = self.app.resources.get::<TypeRegistry>().unwrap();
+            type_registry.component.write().register_map_entities::<T>();
+        }
+        self
+    }
+
     fn register_properties<T>(&mut self) -> &mut Self
     where
         T: Properties + DeserializeProperty + Component + FromResources,
@@ -1,4 +1,7 @@
-use bevy_ecs::{Archetype, Component, Entity, FromResources, Resources, World};
+use crate::{ComponentBundle, DuplicateComponent};
+use bevy_ecs::{
+    Archetype, Component, Entity, EntityMap, FromResources, MapEntities, Resources, TypeInfo, World,
+};
 use bevy_property::{Properties, Property, PropertyTypeRegistration, PropertyTypeRegistry};
 use bevy_utils::{HashMap, HashSet};
 use parking_lot::RwLock;
@@ -37,6 +40,32 @@ impl ComponentRegistry {
         self.registrations.insert(registration.ty, registration);
     }
 
+    /// Lets scenes remap the entities referenced by `T` components when they are spawned
+    ///
+    /// Panics if `T` hasn't been registered as a component.
+    pub fn register_map_entities<T>(&mut self)
+    where
+        T: MapEntities + Component,
+    {
+        let registration = self
+            .registrations
+            .get_mut(&TypeId::of::<T>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Component is not registered: {}",
+                    std::any::type_name::<T>()
+                )
+            });
+        registration.component_map_entities_fn = Some(|world, entity, entity_map| {
+            let mapped = world
+                .get_mut::<T>(entity)
+                .map(|mut component| component.map_entities(entity_map).is_ok());
+            if let Ok(false) = mapped {
+                world.remove_one::<T>(entity).unwrap();
+            }
+        });
+    }
+
     pub fn get(&self, type_id: &TypeId) -> Option<&ComponentRegistration> {
         self.registrations.get(type_id)
     }
@@ -69,8 +98,12 @@ impl ComponentRegistry {
 pub struct ComponentRegistration {
     pub ty: TypeId,
     component_add_fn: fn(&mut World, resources: &Resources, Entity, &dyn Property),
+    component_bundle_fn:
+        fn(&mut ComponentBundle, &Resources, &dyn Property) -> Result<(), DuplicateComponent>,
     component_apply_fn: fn(&mut World, Entity, &dyn Property),
+    component_remove_fn: fn(&mut World, Entity),
     component_properties_fn: fn(&Archetype, usize) -> &dyn Properties,
+    component_map_entities_fn: Option<fn(&mut World, Entity, &EntityMap)>,
     pub short_name: String,
     pub long_name: &'static str,
 }
@@ -88,10 +121,23 @@ impl ComponentRegistration {
                 component.apply(property);
                 world.insert_one(entity, component).unwrap();
             },
+            component_bundle_fn: |bundle: &mut ComponentBundle,
+                                  resources: &Resources,
+                                  property: &dyn Property| {
+                let mut component = T::from_resources(resources);
+                component.apply(property);
+                bundle
+                    .add(TypeInfo::of::<T>(), Box::new(component))
+                    .map(|_| ())
+            },
             component_apply_fn: |world: &mut World, entity: Entity, property: &dyn Property| {
                 let mut component = world.get_mut::<T>(entity).unwrap();
                 component.apply(property);
             },
+            component_remove_fn: |world: &mut World, entity: Entity| {
+                // removing a component the entity doesn't have is a no-op
+                let _ = world.remove_one::<T>(entity);
+            },
             component_properties_fn: |archetype: &Archetype, index: usize| {
                 // the type has been looked up by the caller, so this is safe
                 unsafe {
@@ -99,6 +145,7 @@ impl ComponentRegistration {
                     ptr.as_ref().unwrap()
                 }
             },
+            component_map_entities_fn: None,
             short_name: PropertyTypeRegistration::get_short_name(std::any::type_name::<T>()),
             long_name: std::any::type_name::<T>(),
         }
@@ -114,6 +161,17 @@ impl ComponentRegistration {
         (self.component_add_fn)(world, resources, entity, property);
     }
 
+    /// Builds the component from `property`, like [ComponentRegistration::add_component_to_entity],
+    /// and adds it to `bundle`
+    pub fn add_component_to_bundle(
+        &self,
+        bundle: &mut ComponentBundle,
+        resources: &Resources,
+        property: &dyn Property,
+    ) -> Result<(), DuplicateComponent> {
+        (self.component_bundle_fn)(bundle, resources, property)
+    }
+
     pub fn apply_component_to_entity(
         &self,
         world: &mut World,
@@ -123,6 +181,25 @@ impl ComponentRegistration {
         (self.component_apply_fn)(world, entity, property);
     }
 
+    pub fn remove_component_from_entity(&self, world: &mut World, entity: Entity) {
+        (self.component_remove_fn)(world, entity);
+    }
+
+    /// Rewrites the entity references of `entity`'s component through `entity_map`, if the
+    /// component was registered with [ComponentRegistry::register_map_entities]
+    ///
+    /// A component referring to an entity missing from the map is removed from `entity`.
+    pub fn map_component_entities(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        entity_map: &EntityMap,
+    ) {
+        if let Some(map_entities_fn) = self.component_map_entities_fn {
+            map_entities_fn(world, entity, entity_map);
+        }
+    }
+
     pub fn get_component_properties<'a>(
         &self,
         archetype: &'a Archetype,
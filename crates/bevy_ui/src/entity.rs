@@ -13,6 +13,7 @@ use bevy_render::{
     pipeline::{DynamicBinding, PipelineSpecialization, RenderPipeline, RenderPipelines},
 };
 use bevy_sprite::{ColorMaterial, QUAD_HANDLE};
+use bevy_text::TextLayout;
 use bevy_transform::{
     components::LocalTransform,
     prelude::{Rotation, Scale, Transform, Translation},
@@ -116,6 +117,7 @@ pub struct TextComponents {
     pub style: Style,
     pub draw: Draw,
     pub text: Text,
+    pub text_layout: TextLayout,
     pub calculated_size: CalculatedSize,
     pub focus_policy: FocusPolicy,
     pub transform: Transform,
@@ -131,6 +133,7 @@ impl Default for TextComponents {
                 ..Default::default()
             },
             text: Default::default(),
+            text_layout: Default::default(),
             node: Default::default(),
             calculated_size: Default::default(),
             style: Default::default(),
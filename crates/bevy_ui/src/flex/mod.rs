@@ -106,8 +106,8 @@ impl FlexSurface {
                 *node,
                 stretch::style::Style {
                     size: stretch::geometry::Size {
-                        width: stretch::style::Dimension::Points(window.width as f32),
-                        height: stretch::style::Dimension::Points(window.height as f32),
+                        width: stretch::style::Dimension::Points(window.width()),
+                        height: stretch::style::Dimension::Points(window.height()),
                     },
                     ..Default::default()
                 },
@@ -137,6 +137,15 @@ impl FlexSurface {
         }
     }
 
+    /// Removes the stretch nodes of despawned UI entities, detaching them from their parents
+    pub fn remove_entities(&mut self, entities: &[Entity]) {
+        for entity in entities {
+            if let Some(stretch_node) = self.entity_to_stretch.remove(entity) {
+                self.stretch.remove(stretch_node);
+            }
+        }
+    }
+
     pub fn get_layout(&self, entity: Entity) -> Result<&stretch::result::Layout, stretch::Error> {
         let stretch_node = self.entity_to_stretch.get(&entity).unwrap();
         self.stretch.layout(*stretch_node)
@@ -175,7 +184,8 @@ pub fn flex_node_system(
         flex_surface.upsert_leaf(entity, &style, *calculated_size);
     }
 
-    // TODO: handle removed nodes
+    // remove despawned nodes so they no longer take up space in their parents' layouts
+    flex_surface.remove_entities(node_transform_query.removed::<Node>());
 
     // update window children (for now assuming all Nodes live in the primary window)
     if let Some(primary_window) = windows.get_primary() {
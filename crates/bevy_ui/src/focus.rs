@@ -1,11 +1,10 @@
 use crate::Node;
-use bevy_app::{EventReader, Events};
+use bevy_app::Events;
 use bevy_core::FloatOrd;
 use bevy_ecs::prelude::*;
 use bevy_input::{mouse::MouseButton, Input};
-use bevy_math::Vec2;
 use bevy_transform::components::Transform;
-use bevy_window::CursorMoved;
+use bevy_window::Windows;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Interaction {
@@ -32,17 +31,17 @@ impl Default for FocusPolicy {
     }
 }
 
-#[derive(Default)]
-pub struct State {
-    cursor_moved_event_reader: EventReader<CursorMoved>,
-    cursor_position: Vec2,
-    hovered_entity: Option<Entity>,
+/// An event that is sent when a node is clicked: pressed and then released while the cursor is
+/// still over it
+#[derive(Debug, Clone)]
+pub struct NodeClicked {
+    pub entity: Entity,
 }
 
 pub fn ui_focus_system(
-    mut state: Local<State>,
+    windows: Res<Windows>,
     mouse_button_input: Res<Input<MouseButton>>,
-    cursor_moved_events: Res<Events<CursorMoved>>,
+    mut node_clicked_events: ResMut<Events<NodeClicked>>,
     mut node_query: Query<(
         Entity,
         &Node,
@@ -51,65 +50,46 @@ pub fn ui_focus_system(
         Option<&FocusPolicy>,
     )>,
 ) {
-    if let Some(cursor_moved) = state.cursor_moved_event_reader.latest(&cursor_moved_events) {
-        state.cursor_position = cursor_moved.position;
-    }
-
-    if mouse_button_input.just_released(MouseButton::Left) {
-        for (_entity, _node, _transform, interaction, _focus_policy) in &mut node_query.iter() {
-            if let Some(mut interaction) = interaction {
-                if *interaction == Interaction::Clicked {
-                    *interaction = Interaction::None;
-                }
-            }
-        }
-    }
+    // the window tracks the cursor in the order its events arrived, so leaving and re-entering
+    // within one update ends up in the right place
+    let cursor_position = windows
+        .get_primary()
+        .and_then(|window| window.cursor_position);
 
     let mouse_clicked = mouse_button_input.just_pressed(MouseButton::Left);
-    let mut hovered_entity = None;
+    let mouse_released = mouse_button_input.just_released(MouseButton::Left);
 
-    {
+    // the nodes under the cursor, from the topmost down to the first one that blocks focus
+    let mut hovered_entities = Vec::new();
+    if let Some(cursor_position) = cursor_position {
         let mut query_iter = node_query.iter();
         let mut moused_over_z_sorted_nodes = query_iter
             .iter()
-            .filter_map(|(entity, node, transform, interaction, focus_policy)| {
+            .filter_map(|(entity, node, transform, _interaction, focus_policy)| {
                 let position = transform.value.w_axis();
                 let ui_position = position.truncate().truncate();
                 let extents = node.size / 2.0;
                 let min = ui_position - extents;
                 let max = ui_position + extents;
                 // if the current cursor position is within the bounds of the node, consider it for clicking
-                if (min.x()..max.x()).contains(&state.cursor_position.x())
-                    && (min.y()..max.y()).contains(&state.cursor_position.y())
+                if (min.x()..max.x()).contains(&cursor_position.x())
+                    && (min.y()..max.y()).contains(&cursor_position.y())
                 {
-                    Some((entity, focus_policy, interaction, FloatOrd(position.z())))
+                    Some((
+                        entity,
+                        focus_policy.cloned().unwrap_or(FocusPolicy::Block),
+                        FloatOrd(position.z()),
+                    ))
                 } else {
-                    if let Some(mut interaction) = interaction {
-                        if *interaction == Interaction::Hovered {
-                            *interaction = Interaction::None;
-                        }
-                    }
                     None
                 }
             })
             .collect::<Vec<_>>();
 
-        moused_over_z_sorted_nodes.sort_by_key(|(_, _, _, z)| -*z);
-        for (entity, focus_policy, interaction, _) in moused_over_z_sorted_nodes {
-            if let Some(mut interaction) = interaction {
-                if mouse_clicked {
-                    // only consider nodes with ClickState "clickable"
-                    if *interaction != Interaction::Clicked {
-                        *interaction = Interaction::Clicked;
-                    }
-                } else if *interaction == Interaction::None {
-                    *interaction = Interaction::Hovered;
-                }
-            }
-
-            hovered_entity = Some(entity);
-
-            match focus_policy.cloned().unwrap_or(FocusPolicy::Block) {
+        moused_over_z_sorted_nodes.sort_by_key(|(_, _, z)| -*z);
+        for (entity, focus_policy, _) in moused_over_z_sorted_nodes {
+            hovered_entities.push(entity);
+            match focus_policy {
                 FocusPolicy::Block => {
                     break;
                 }
@@ -118,18 +98,31 @@ pub fn ui_focus_system(
         }
     }
 
-    // if there is a new hovered entity, but an entity is currently hovered, unhover the old entity
-    if let Some(new_hovered_entity) = hovered_entity {
-        if let Some(old_hovered_entity) = state.hovered_entity {
-            if new_hovered_entity != old_hovered_entity {
-                if let Ok(mut interaction) = node_query.get_mut::<Interaction>(old_hovered_entity) {
-                    if *interaction == Interaction::Hovered {
-                        *interaction = Interaction::None;
+    for (entity, _node, _transform, interaction, _focus_policy) in &mut node_query.iter() {
+        if let Some(mut interaction) = interaction {
+            let hovered = hovered_entities.contains(&entity);
+            let new_interaction = match *interaction {
+                // a press only counts as a click if it is released over the node it started on
+                Interaction::Clicked if mouse_released => {
+                    if hovered {
+                        node_clicked_events.send(NodeClicked { entity });
+                        Interaction::Hovered
+                    } else {
+                        Interaction::None
                     }
                 }
-                state.hovered_entity = None;
+                // a pressed node stays pressed while the cursor is dragged off it, until it leaves
+                // the window
+                Interaction::Clicked if cursor_position.is_some() => Interaction::Clicked,
+                _ if hovered && mouse_clicked => Interaction::Clicked,
+                _ if hovered => Interaction::Hovered,
+                _ => Interaction::None,
+            };
+
+            // only write changes, so Mutated<Interaction> queries see actual transitions
+            if *interaction != new_interaction {
+                *interaction = new_interaction;
             }
         }
-        state.hovered_entity = hovered_entity;
     }
 }
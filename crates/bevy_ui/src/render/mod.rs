@@ -4,8 +4,8 @@ use bevy_ecs::Resources;
 use bevy_render::{
     camera::ActiveCameras,
     pass::{
-        LoadOp, Operations, PassDescriptor, RenderPassDepthStencilAttachmentDescriptor,
-        TextureAttachment,
+        LoadOp, Operations, PassDescriptor, RenderPassColorAttachmentDescriptor,
+        RenderPassDepthStencilAttachmentDescriptor, TextureAttachment,
     },
     pipeline::*,
     prelude::Msaa,
@@ -90,14 +90,14 @@ impl UiRenderGraphBuilder for RenderGraph {
         pipelines.set(UI_PIPELINE_HANDLE, build_ui_pipeline(&mut shaders));
 
         let mut ui_pass_node = PassNode::<&Node>::new(PassDescriptor {
-            color_attachments: vec![msaa.color_attachment_descriptor(
-                TextureAttachment::Input("color_attachment".to_string()),
-                TextureAttachment::Input("color_resolve_target".to_string()),
-                Operations {
+            color_attachments: vec![RenderPassColorAttachmentDescriptor {
+                attachment: TextureAttachment::Input("color_attachment".to_string()),
+                resolve_target: Some(TextureAttachment::Input("color_resolve_target".to_string())),
+                ops: Operations {
                     load: LoadOp::Load,
                     store: true,
                 },
-            )],
+            }],
             depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
                 attachment: TextureAttachment::Input("depth".to_string()),
                 depth_ops: Some(Operations {
@@ -110,17 +110,14 @@ impl UiRenderGraphBuilder for RenderGraph {
         });
 
         ui_pass_node.add_camera(camera::UI_CAMERA);
+        ui_pass_node.use_msaa_sample_count();
         self.add_node(node::UI_PASS, ui_pass_node);
 
         self.add_slot_edge(
             base::node::PRIMARY_SWAP_CHAIN,
             WindowSwapChainNode::OUT_TEXTURE,
             node::UI_PASS,
-            if msaa.samples > 1 {
-                "color_resolve_target"
-            } else {
-                "color_attachment"
-            },
+            "color_resolve_target",
         )
         .unwrap();
 
@@ -132,15 +129,13 @@ impl UiRenderGraphBuilder for RenderGraph {
         )
         .unwrap();
 
-        if msaa.samples > 1 {
-            self.add_slot_edge(
-                base::node::MAIN_SAMPLED_COLOR_ATTACHMENT,
-                WindowSwapChainNode::OUT_TEXTURE,
-                node::UI_PASS,
-                "color_attachment",
-            )
-            .unwrap();
-        }
+        self.add_slot_edge(
+            base::node::MAIN_SAMPLED_COLOR_ATTACHMENT,
+            WindowSwapChainNode::OUT_TEXTURE,
+            node::UI_PASS,
+            "color_attachment",
+        )
+        .unwrap();
 
         // ensure ui pass runs after main pass
         self.add_node_edge(base::node::MAIN_PASS, node::UI_PASS)
@@ -9,7 +9,7 @@ use bevy_render::{
     texture::Texture,
 };
 use bevy_sprite::TextureAtlas;
-use bevy_text::{DrawableText, Font, FontAtlasSet, TextStyle};
+use bevy_text::{DrawableText, Font, FontAtlasSet, TextLayout, TextStyle};
 use bevy_transform::prelude::Transform;
 
 #[derive(Default, Clone)]
@@ -24,9 +24,9 @@ pub fn text_system(
     fonts: Res<Assets<Font>>,
     mut font_atlas_sets: ResMut<Assets<FontAtlasSet>>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    mut query: Query<(Changed<Text>, &mut CalculatedSize)>,
+    mut query: Query<(Changed<Text>, &mut TextLayout, &mut CalculatedSize)>,
 ) {
-    for (text, mut calculated_size) in &mut query.iter() {
+    for (text, mut text_layout, mut calculated_size) in &mut query.iter() {
         let font_atlases = font_atlas_sets
             .get_or_insert_with(Handle::from_id(text.font.id), || {
                 FontAtlasSet::new(text.font)
@@ -37,7 +37,7 @@ pub fn text_system(
         // resource generation needs to happen AFTER the render graph systems. maybe draw systems should execute within the
         // render graph so ordering like this can be taken into account? Maybe the RENDER_GRAPH_SYSTEMS stage should be removed entirely
         // in favor of node.update()? Regardless, in the immediate short term the current approach is fine.
-        let width = font_atlases.add_glyphs_to_atlas(
+        font_atlases.add_glyphs_to_atlas(
             &fonts,
             &mut texture_atlases,
             &mut textures,
@@ -45,7 +45,16 @@ pub fn text_system(
             &text.value,
         );
 
-        calculated_size.size = Size::new(width, text.style.font_size);
+        // only lay the glyphs out again if the string, font size or bounds changed
+        if text_layout.update(
+            text.font,
+            fonts.get(&text.font).unwrap(),
+            text.style.font_size,
+            text.style.max_width,
+            &text.value,
+        ) {
+            calculated_size.size = Size::new(text_layout.size.x(), text_layout.size.y());
+        }
     }
 }
 
@@ -58,9 +67,9 @@ pub fn draw_text_system(
     texture_atlases: Res<Assets<TextureAtlas>>,
     mut render_resource_bindings: ResMut<RenderResourceBindings>,
     mut asset_render_resource_bindings: ResMut<AssetRenderResourceBindings>,
-    mut query: Query<(&mut Draw, &Text, &Node, &Transform)>,
+    mut query: Query<(&mut Draw, &Text, &TextLayout, &Node, &Transform)>,
 ) {
-    for (mut draw, text, node, transform) in &mut query.iter() {
+    for (mut draw, text, text_layout, node, transform) in &mut query.iter() {
         let position =
             Vec3::from(transform.value.w_axis().truncate()) - (node.size / 2.0).extend(0.0);
 
@@ -75,7 +84,7 @@ pub fn draw_text_system(
             position,
             msaa: &msaa,
             style: &text.style,
-            text: &text.value,
+            layout: &text_layout,
             container_size: node.size,
         };
         drawable_text.draw(&mut draw, &mut draw_context).unwrap();
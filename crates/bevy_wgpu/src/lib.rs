@@ -29,11 +29,19 @@ impl Plugin for WgpuPlugin {
             free_shared_buffers_system.system(),
         );
     }
+
+    fn dependencies(&self) -> Vec<std::any::TypeId> {
+        vec![
+            std::any::TypeId::of::<bevy_render::RenderPlugin>(),
+            std::any::TypeId::of::<bevy_window::WindowPlugin>(),
+        ]
+    }
 }
 
 pub fn wgpu_render_system(resources: &mut Resources) -> impl FnMut(&mut World, &mut Resources) {
     let mut wgpu_renderer = pollster::block_on(WgpuRenderer::new());
-    let resource_context = WgpuRenderResourceContext::new(wgpu_renderer.device.clone());
+    let resource_context =
+        WgpuRenderResourceContext::new(wgpu_renderer.device.clone(), wgpu_renderer.backend);
     resources.insert::<Box<dyn RenderResourceContext>>(Box::new(resource_context.clone()));
     resources.insert(SharedBuffers::new(Box::new(resource_context)));
     move |world, resources| {
@@ -46,7 +46,7 @@ impl WgpuRenderGraphExecutor {
                 // s.spawn(move |_| {
                 let mut render_context = WgpuRenderContext::new(device, render_resource_context);
                 for job in jobs_chunk.iter_mut() {
-                    for node_state in job.node_states.iter_mut() {
+                    'nodes: for node_state in job.node_states.iter_mut() {
                         // bind inputs from connected node outputs
                         for (i, mut input_slot) in node_state.input_slots.iter_mut().enumerate() {
                             if let Edge::SlotEdge {
@@ -56,15 +56,17 @@ impl WgpuRenderGraphExecutor {
                             } = node_state.edges.get_input_slot_edge(i).unwrap()
                             {
                                 let node_outputs = node_outputs.read();
-                                let outputs = if let Some(outputs) = node_outputs.get(output_node) {
-                                    outputs
-                                } else {
-                                    panic!("node inputs not set")
-                                };
-
-                                let output_resource =
-                                    outputs.get(*output_index).expect("output should be set");
-                                input_slot.resource = Some(output_resource);
+                                let output_resource = node_outputs
+                                    .get(output_node)
+                                    .and_then(|outputs| outputs.get(*output_index));
+                                match output_resource {
+                                    Some(output_resource) => {
+                                        input_slot.resource = Some(output_resource)
+                                    }
+                                    // an input wasn't produced, e.g. because the window a node
+                                    // draws to was closed. the node and its dependents are skipped.
+                                    None => continue 'nodes,
+                                }
                             } else {
                                 panic!("no edge connected to input")
                             }
@@ -13,7 +13,7 @@ use bevy_render::{
         RenderResourceId, SamplerId, TextureId,
     },
     shader::Shader,
-    texture::{Extent3d, SamplerDescriptor, TextureDescriptor},
+    texture::{Extent3d, SamplerDescriptor, TextureDescriptor, TextureFormat},
 };
 use bevy_window::{Window, WindowId};
 use std::{borrow::Cow, ops::Range, sync::Arc};
@@ -22,13 +22,15 @@ use wgpu::util::DeviceExt;
 #[derive(Clone)]
 pub struct WgpuRenderResourceContext {
     pub device: Arc<wgpu::Device>,
+    pub backend: wgpu::Backend,
     pub resources: WgpuResources,
 }
 
 impl WgpuRenderResourceContext {
-    pub fn new(device: Arc<wgpu::Device>) -> Self {
+    pub fn new(device: Arc<wgpu::Device>, backend: wgpu::Backend) -> Self {
         WgpuRenderResourceContext {
             device,
+            backend,
             resources: WgpuResources::default(),
         }
     }
@@ -38,6 +40,13 @@ impl WgpuRenderResourceContext {
         window_surfaces.insert(window_id, surface);
     }
 
+    /// Drops the swap chain and surface of a closed window
+    pub fn remove_window_surface(&self, window_id: WindowId) {
+        // the swap chain must not outlive its surface
+        self.resources.window_swap_chains.write().remove(&window_id);
+        self.resources.window_surfaces.write().remove(&window_id);
+    }
+
     pub fn copy_buffer_to_buffer(
         &self,
         command_encoder: &mut wgpu::CommandEncoder,
@@ -514,6 +523,30 @@ impl RenderResourceContext for WgpuRenderResourceContext {
         self.resources.bind_groups.write().clear();
     }
 
+    fn supported_sample_counts(&self, format: TextureFormat) -> Vec<u32> {
+        // wgpu 0.6 can't be asked which sample counts an adapter supports for a format, so these
+        // are the counts the backends and formats are known to support. unusual devices might
+        // support more or fewer.
+        match format {
+            // these formats have no multisampling support
+            TextureFormat::Rg32Uint
+            | TextureFormat::Rg32Sint
+            | TextureFormat::Rg32Float
+            | TextureFormat::Rgba16Uint
+            | TextureFormat::Rgba16Sint
+            | TextureFormat::Rgba32Uint
+            | TextureFormat::Rgba32Sint
+            | TextureFormat::Rgba32Float => vec![1],
+            _ => match self.backend {
+                // native backends support the full range, the web and GL only guarantee 4x
+                wgpu::Backend::Vulkan | wgpu::Backend::Metal | wgpu::Backend::Dx12 => {
+                    vec![1, 2, 4, 8]
+                }
+                _ => vec![1, 4],
+            },
+        }
+    }
+
     fn get_buffer_info(&self, buffer: BufferId) -> Option<BufferInfo> {
         self.resources.buffer_infos.read().get(&buffer).cloned()
     }
@@ -5,14 +5,16 @@ use bevy_render::{
     render_graph::{DependentNodeStager, RenderGraph, RenderGraphStager},
     renderer::RenderResourceContext,
 };
-use bevy_window::{WindowCreated, WindowResized, Windows};
+use bevy_window::{WindowClosed, WindowCreated, WindowResized, Windows};
 use std::{ops::Deref, sync::Arc};
 pub struct WgpuRenderer {
     pub instance: wgpu::Instance,
     pub device: Arc<wgpu::Device>,
+    pub backend: wgpu::Backend,
     pub queue: wgpu::Queue,
     pub window_resized_event_reader: EventReader<WindowResized>,
     pub window_created_event_reader: EventReader<WindowCreated>,
+    pub window_closed_event_reader: EventReader<WindowClosed>,
     pub intialized: bool,
 }
 
@@ -47,9 +49,11 @@ impl WgpuRenderer {
         WgpuRenderer {
             instance,
             device,
+            backend: adapter.get_info().backend,
             queue,
             window_resized_event_reader: Default::default(),
             window_created_event_reader: Default::default(),
+            window_closed_event_reader: Default::default(),
             intialized: false,
         }
     }
@@ -80,6 +84,17 @@ impl WgpuRenderer {
         }
     }
 
+    pub fn handle_window_closed_events(&mut self, resources: &Resources) {
+        let render_resource_context = resources.get::<Box<dyn RenderResourceContext>>().unwrap();
+        let render_resource_context = render_resource_context
+            .downcast_ref::<WgpuRenderResourceContext>()
+            .unwrap();
+        let window_closed_events = resources.get::<Events<WindowClosed>>().unwrap();
+        for window_closed_event in self.window_closed_event_reader.iter(&window_closed_events) {
+            render_resource_context.remove_window_surface(window_closed_event.id);
+        }
+    }
+
     pub fn run_graph(&mut self, world: &mut World, resources: &mut Resources) {
         let mut render_graph = resources.get_mut::<RenderGraph>().unwrap();
         // stage nodes
@@ -103,6 +118,8 @@ impl WgpuRenderer {
     pub fn update(&mut self, world: &mut World, resources: &mut Resources) {
         self.handle_window_created_events(resources);
         self.run_graph(world, resources);
+        // closed windows are skipped by the graph, so their swap chains are no longer in use
+        self.handle_window_closed_events(resources);
 
         let render_resource_context = resources.get::<Box<dyn RenderResourceContext>>().unwrap();
         render_resource_context.drop_all_swap_chain_textures();
@@ -123,11 +123,13 @@ impl<'a> From<&'a OwnedWgpuVertexBufferDescriptor> for wgpu::VertexBufferDescrip
 
 impl WgpuFrom<Color> for wgpu::Color {
     fn from(color: Color) -> Self {
+        // wgpu expects linear colors, and converts them for sRGB targets
+        let [r, g, b, a] = color.as_linear_rgba_f32();
         wgpu::Color {
-            r: color.r as f64,
-            g: color.g as f64,
-            b: color.b as f64,
-            a: color.a as f64,
+            r: r as f64,
+            g: g as f64,
+            b: b as f64,
+            a: a as f64,
         }
     }
 }
@@ -563,8 +565,8 @@ impl WgpuFrom<&Window> for wgpu::SwapChainDescriptor {
         wgpu::SwapChainDescriptor {
             usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
             format: wgpu::TextureFormat::Bgra8UnormSrgb,
-            width: window.width,
-            height: window.height,
+            width: window.physical_width,
+            height: window.physical_height,
             present_mode: if window.vsync {
                 wgpu::PresentMode::Fifo
             } else {
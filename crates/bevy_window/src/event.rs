@@ -1,12 +1,20 @@
 use super::{WindowDescriptor, WindowId};
 use bevy_math::Vec2;
 
-/// A window event that is sent whenever a window has been resized.
+/// A window event that is sent whenever a window has been resized, with its new logical size.
 #[derive(Debug, Clone)]
 pub struct WindowResized {
     pub id: WindowId,
-    pub width: usize,
-    pub height: usize,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// An event that is sent whenever a window's scale factor changes, e.g. when it is moved to a
+/// monitor with a different DPI. It is followed by a [WindowResized] event.
+#[derive(Debug, Clone)]
+pub struct WindowScaleFactorChanged {
+    pub id: WindowId,
+    pub scale_factor: f64,
 }
 
 /// An event that indicates that a new window should be created.
@@ -28,6 +36,12 @@ pub struct WindowCreated {
     pub id: WindowId,
 }
 
+/// An event that is sent whenever a window has been closed and removed from [Windows](crate::Windows).
+#[derive(Debug, Clone)]
+pub struct WindowClosed {
+    pub id: WindowId,
+}
+
 /// An event that is sent whenever a close was requested for a window. For example: when the "close" button
 /// is pressed on a window.
 #[derive(Debug, Clone)]
@@ -35,8 +49,16 @@ pub struct WindowCloseRequested {
     pub id: WindowId,
 }
 
+/// An event that is sent whenever the cursor moves within a window, with its position in logical
+/// pixels from the bottom left corner of the window.
 #[derive(Debug, Clone)]
 pub struct CursorMoved {
     pub id: WindowId,
     pub position: Vec2,
 }
+
+/// An event that is sent whenever the cursor leaves a window.
+#[derive(Debug, Clone)]
+pub struct CursorLeft {
+    pub id: WindowId,
+}
@@ -17,6 +17,8 @@ use bevy_ecs::IntoQuerySystem;
 
 pub struct WindowPlugin {
     pub add_primary_window: bool,
+    /// Exit when the primary window's close button is pressed, and close other windows when
+    /// theirs is
     pub exit_on_close: bool,
 }
 
@@ -32,11 +34,14 @@ impl Default for WindowPlugin {
 impl Plugin for WindowPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_event::<WindowResized>()
+            .add_event::<WindowScaleFactorChanged>()
             .add_event::<CreateWindow>()
             .add_event::<WindowCreated>()
             .add_event::<WindowCloseRequested>()
             .add_event::<CloseWindow>()
+            .add_event::<WindowClosed>()
             .add_event::<CursorMoved>()
+            .add_event::<CursorLeft>()
             .init_resource::<Windows>();
 
         if self.add_primary_window {
@@ -1,4 +1,4 @@
-use crate::WindowCloseRequested;
+use crate::{CloseWindow, WindowCloseRequested};
 use bevy_app::{
     prelude::{EventReader, Events},
     AppExit,
@@ -10,17 +10,19 @@ pub struct ExitOnWindowCloseState {
     event_reader: EventReader<WindowCloseRequested>,
 }
 
+/// Exits the app when closing the primary window is requested, and closes any other window whose
+/// close is requested
 pub fn exit_on_window_close_system(
     mut state: Local<ExitOnWindowCloseState>,
     mut app_exit_events: ResMut<Events<AppExit>>,
+    mut close_window_events: ResMut<Events<CloseWindow>>,
     window_close_requested_events: Res<Events<WindowCloseRequested>>,
 ) {
-    if state
-        .event_reader
-        .iter(&window_close_requested_events)
-        .next()
-        .is_some()
-    {
-        app_exit_events.send(AppExit);
+    for event in state.event_reader.iter(&window_close_requested_events) {
+        if event.id.is_primary() {
+            app_exit_events.send(AppExit);
+        } else {
+            close_window_events.send(CloseWindow { id: event.id });
+        }
     }
 }
@@ -17,6 +17,7 @@ impl WindowId {
     }
 }
 
+use bevy_math::Vec2;
 use std::fmt;
 
 impl fmt::Display for WindowId {
@@ -31,15 +32,41 @@ impl Default for WindowId {
     }
 }
 
+/// The current state of a window
+///
+/// The size, scale factor and `mode` describe the live window. Change them with the setters,
+/// which queue the change for the window backend to apply.
+///
+/// Sizes are either physical, counting the pixels of the display, or logical, which is the
+/// physical size divided by the scale factor. Cameras and UI layout use logical sizes, so content
+/// has the same apparent size on any display.
 #[derive(Debug)]
 pub struct Window {
     pub id: WindowId,
-    pub width: u32,
-    pub height: u32,
+    pub physical_width: u32,
+    pub physical_height: u32,
+    /// The ratio of physical pixels to logical pixels, e.g. `2.0` on most HiDPI displays
+    pub scale_factor: f64,
     pub title: String,
     pub vsync: bool,
     pub resizable: bool,
     pub mode: WindowMode,
+    pub cursor_locked: bool,
+    pub cursor_visible: bool,
+    /// The cursor's position in logical pixels from the bottom left corner of the window, or
+    /// `None` while the cursor is outside of it. Kept up to date by the window backend, in the
+    /// order its cursor events arrive.
+    pub cursor_position: Option<Vec2>,
+    command_queue: Vec<WindowCommand>,
+}
+
+/// A change to a live window, queued by the [Window] setters
+#[derive(Debug, Clone, Copy)]
+pub enum WindowCommand {
+    SetWindowMode { mode: WindowMode },
+    SetResolution { width: f32, height: f32 },
+    SetCursorLock { locked: bool },
+    SetCursorVisible { visible: bool },
 }
 
 /// Defines the way a window is displayed
@@ -47,7 +74,7 @@ pub struct Window {
 /// defines whether a videomode is chosen that best fits the width and height
 /// in the Window structure, or if these are ignored.
 /// E.g. when use_size is set to false the best video mode possible is chosen.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WindowMode {
     Windowed,
     BorderlessFullscreen,
@@ -58,25 +85,82 @@ impl Window {
     pub fn new(id: WindowId, window_descriptor: &WindowDescriptor) -> Self {
         Window {
             id,
-            height: window_descriptor.height,
-            width: window_descriptor.width,
+            // the backend corrects these once it knows the window's scale factor
+            physical_width: window_descriptor.width as u32,
+            physical_height: window_descriptor.height as u32,
+            scale_factor: 1.0,
             title: window_descriptor.title.clone(),
             vsync: window_descriptor.vsync,
             resizable: window_descriptor.resizable,
             mode: window_descriptor.mode,
+            cursor_locked: window_descriptor.cursor_locked,
+            cursor_visible: window_descriptor.cursor_visible,
+            cursor_position: None,
+            command_queue: Vec::new(),
         }
     }
+
+    /// The logical width of the window
+    pub fn width(&self) -> f32 {
+        (self.physical_width as f64 / self.scale_factor) as f32
+    }
+
+    /// The logical height of the window
+    pub fn height(&self) -> f32 {
+        (self.physical_height as f64 / self.scale_factor) as f32
+    }
+
+    /// Switches between windowed and fullscreen modes. Leaving fullscreen restores the window's
+    /// previous size and position.
+    pub fn set_mode(&mut self, mode: WindowMode) {
+        self.mode = mode;
+        self.command_queue
+            .push(WindowCommand::SetWindowMode { mode });
+    }
+
+    /// Requests a new size in logical pixels. In fullscreen modes, this is the size the window
+    /// returns to when it is windowed again, and the video mode to pick for
+    /// `WindowMode::Fullscreen { use_size: true }`.
+    ///
+    /// The window's size is updated when it reports its new size, along with a
+    /// [WindowResized](crate::WindowResized) event.
+    pub fn set_resolution(&mut self, width: f32, height: f32) {
+        self.command_queue
+            .push(WindowCommand::SetResolution { width, height });
+    }
+
+    /// Confines the cursor to the window
+    pub fn set_cursor_lock(&mut self, locked: bool) {
+        self.cursor_locked = locked;
+        self.command_queue
+            .push(WindowCommand::SetCursorLock { locked });
+    }
+
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        self.cursor_visible = visible;
+        self.command_queue
+            .push(WindowCommand::SetCursorVisible { visible });
+    }
+
+    /// Takes the changes queued since the last call, for the window backend to apply
+    pub fn drain_commands(&mut self) -> impl Iterator<Item = WindowCommand> + '_ {
+        self.command_queue.drain(..)
+    }
 }
 
 #[derive(Debug, Clone)]
 #[allow(clippy::manual_non_exhaustive)]
 pub struct WindowDescriptor {
-    pub width: u32,
-    pub height: u32,
+    /// Logical width
+    pub width: f32,
+    /// Logical height
+    pub height: f32,
     pub title: String,
     pub vsync: bool,
     pub resizable: bool,
     pub mode: WindowMode,
+    pub cursor_locked: bool,
+    pub cursor_visible: bool,
 
     // this is a manual implementation of the non exhaustive pattern,
     // especially made to allow ..Default::default()
@@ -88,11 +172,13 @@ impl Default for WindowDescriptor {
     fn default() -> Self {
         WindowDescriptor {
             title: "bevy".to_string(),
-            width: 1280,
-            height: 720,
+            width: 1280.0,
+            height: 720.0,
             vsync: true,
             resizable: true,
             mode: WindowMode::Windowed,
+            cursor_locked: false,
+            cursor_visible: true,
             __non_exhaustive: (),
         }
     }
@@ -1,10 +1,17 @@
 use bevy_input::{
     keyboard::{ElementState, KeyCode, KeyboardInput},
     mouse::MouseButton,
+    touch::{TouchInput, TouchPhase},
 };
+use bevy_math::Vec2;
+use bevy_window::WindowId;
 
-pub fn convert_keyboard_input(keyboard_input: &winit::event::KeyboardInput) -> KeyboardInput {
+pub fn convert_keyboard_input(
+    keyboard_input: &winit::event::KeyboardInput,
+    window_id: WindowId,
+) -> KeyboardInput {
     KeyboardInput {
+        window_id,
         scan_code: keyboard_input.scancode,
         state: convert_element_state(keyboard_input.state),
         key_code: keyboard_input.virtual_keycode.map(convert_virtual_key_code),
@@ -27,6 +34,29 @@ pub fn convert_mouse_button(mouse_button: winit::event::MouseButton) -> MouseBut
     }
 }
 
+/// Converts a winit touch, whose `y` is measured from the top of a window `height` logical pixels
+/// tall
+pub fn convert_touch_input(
+    touch_input: winit::event::Touch,
+    window_id: WindowId,
+    scale_factor: f64,
+    height: f32,
+) -> TouchInput {
+    let location = touch_input.location.to_logical::<f32>(scale_factor);
+    TouchInput {
+        window_id,
+        phase: match touch_input.phase {
+            winit::event::TouchPhase::Started => TouchPhase::Started,
+            winit::event::TouchPhase::Moved => TouchPhase::Moved,
+            winit::event::TouchPhase::Ended => TouchPhase::Ended,
+            winit::event::TouchPhase::Cancelled => TouchPhase::Cancelled,
+        },
+        // move origin to bottom left, like cursor positions
+        position: Vec2::new(location.x, height - location.y),
+        id: touch_input.id,
+    }
+}
+
 pub fn convert_virtual_key_code(virtual_key_code: winit::event::VirtualKeyCode) -> KeyCode {
     match virtual_key_code {
         winit::event::VirtualKeyCode::Key1 => KeyCode::Key1,
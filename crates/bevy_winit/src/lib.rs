@@ -4,6 +4,7 @@ mod winit_windows;
 use bevy_input::{
     keyboard::KeyboardInput,
     mouse::{MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
+    touch::TouchInput,
 };
 pub use winit_config::*;
 pub use winit_windows::*;
@@ -12,7 +13,8 @@ use bevy_app::{prelude::*, AppExit};
 use bevy_ecs::Resources;
 use bevy_math::Vec2;
 use bevy_window::{
-    CreateWindow, CursorMoved, Window, WindowCloseRequested, WindowCreated, WindowResized, Windows,
+    CloseWindow, CreateWindow, CursorLeft, CursorMoved, Window, WindowCloseRequested, WindowClosed,
+    WindowCreated, WindowResized, WindowScaleFactorChanged, Windows,
 };
 use event::Event;
 use winit::{
@@ -33,6 +35,10 @@ impl Plugin for WinitPlugin {
             .init_resource::<WinitWindows>()
             .set_runner(winit_runner);
     }
+
+    fn dependencies(&self) -> Vec<std::any::TypeId> {
+        vec![std::any::TypeId::of::<bevy_window::WindowPlugin>()]
+    }
 }
 
 fn run<F>(event_loop: EventLoop<()>, event_handler: F) -> !
@@ -81,6 +87,7 @@ where
 pub fn winit_runner(mut app: App) {
     let mut event_loop = EventLoop::new();
     let mut create_window_event_reader = EventReader::<CreateWindow>::default();
+    let mut close_window_event_reader = EventReader::<CloseWindow>::default();
     let mut app_exit_event_reader = EventReader::<AppExit>::default();
 
     handle_create_window_events(
@@ -112,88 +119,145 @@ pub fn winit_runner(mut app: App) {
         }
 
         match event {
-            event::Event::WindowEvent {
-                event: WindowEvent::Resized(size),
-                window_id: winit_window_id,
-                ..
-            } => {
-                let winit_windows = app.resources.get_mut::<WinitWindows>().unwrap();
-                let mut windows = app.resources.get_mut::<Windows>().unwrap();
-                let window_id = winit_windows.get_window_id(winit_window_id).unwrap();
-                let mut window = windows.get_mut(window_id).unwrap();
-                window.width = size.width;
-                window.height = size.height;
-
-                let mut resize_events = app.resources.get_mut::<Events<WindowResized>>().unwrap();
-                resize_events.send(WindowResized {
-                    id: window_id,
-                    height: window.height as usize,
-                    width: window.width as usize,
-                });
-            }
             event::Event::WindowEvent {
                 event,
                 window_id: winit_window_id,
                 ..
-            } => match event {
-                WindowEvent::CloseRequested => {
-                    let mut window_close_requested_events = app
-                        .resources
-                        .get_mut::<Events<WindowCloseRequested>>()
-                        .unwrap();
-                    let winit_windows = app.resources.get_mut::<WinitWindows>().unwrap();
-                    let window_id = winit_windows.get_window_id(winit_window_id).unwrap();
-                    window_close_requested_events.send(WindowCloseRequested { id: window_id });
-                }
-                WindowEvent::KeyboardInput { ref input, .. } => {
-                    let mut keyboard_input_events =
-                        app.resources.get_mut::<Events<KeyboardInput>>().unwrap();
-                    keyboard_input_events.send(converters::convert_keyboard_input(input));
-                }
-                WindowEvent::CursorMoved { position, .. } => {
-                    let mut cursor_moved_events =
-                        app.resources.get_mut::<Events<CursorMoved>>().unwrap();
-                    let winit_windows = app.resources.get_mut::<WinitWindows>().unwrap();
-                    let window_id = winit_windows.get_window_id(winit_window_id).unwrap();
-                    let window = winit_windows.get_window(window_id).unwrap();
-                    let inner_size = window.inner_size();
-                    // move origin to bottom left
-                    let y_position = inner_size.height as f32 - position.y as f32;
-                    cursor_moved_events.send(CursorMoved {
-                        id: window_id,
-                        position: Vec2::new(position.x as f32, y_position as f32),
-                    });
-                }
-                WindowEvent::MouseInput { state, button, .. } => {
-                    let mut mouse_button_input_events =
-                        app.resources.get_mut::<Events<MouseButtonInput>>().unwrap();
-                    mouse_button_input_events.send(MouseButtonInput {
-                        button: converters::convert_mouse_button(button),
-                        state: converters::convert_element_state(state),
-                    });
-                }
-                WindowEvent::MouseWheel { delta, .. } => match delta {
-                    event::MouseScrollDelta::LineDelta(x, y) => {
-                        let mut mouse_wheel_input_events =
-                            app.resources.get_mut::<Events<MouseWheel>>().unwrap();
-                        mouse_wheel_input_events.send(MouseWheel {
-                            unit: MouseScrollUnit::Line,
-                            x,
-                            y,
+            } => {
+                let winit_windows = app.resources.get::<WinitWindows>().unwrap();
+                let window_id = match winit_windows.get_window_id(winit_window_id) {
+                    Some(window_id) => window_id,
+                    // events can still arrive for a window that was just closed
+                    None => return,
+                };
+                let winit_window = winit_windows.get_window(window_id).unwrap();
+                match event {
+                    WindowEvent::Resized(size) => {
+                        let mut windows = app.resources.get_mut::<Windows>().unwrap();
+                        let mut window = windows.get_mut(window_id).unwrap();
+                        window.physical_width = size.width;
+                        window.physical_height = size.height;
+
+                        let mut resize_events =
+                            app.resources.get_mut::<Events<WindowResized>>().unwrap();
+                        resize_events.send(WindowResized {
+                            id: window_id,
+                            width: window.width(),
+                            height: window.height(),
+                        });
+                    }
+                    WindowEvent::ScaleFactorChanged {
+                        scale_factor,
+                        new_inner_size,
+                    } => {
+                        let mut windows = app.resources.get_mut::<Windows>().unwrap();
+                        let mut window = windows.get_mut(window_id).unwrap();
+                        window.scale_factor = scale_factor;
+                        window.physical_width = new_inner_size.width;
+                        window.physical_height = new_inner_size.height;
+
+                        let mut scale_factor_changed_events = app
+                            .resources
+                            .get_mut::<Events<WindowScaleFactorChanged>>()
+                            .unwrap();
+                        scale_factor_changed_events.send(WindowScaleFactorChanged {
+                            id: window_id,
+                            scale_factor,
+                        });
+                        // the logical size changes along with the scale factor
+                        let mut resize_events =
+                            app.resources.get_mut::<Events<WindowResized>>().unwrap();
+                        resize_events.send(WindowResized {
+                            id: window_id,
+                            width: window.width(),
+                            height: window.height(),
+                        });
+                    }
+                    WindowEvent::CloseRequested => {
+                        let mut window_close_requested_events = app
+                            .resources
+                            .get_mut::<Events<WindowCloseRequested>>()
+                            .unwrap();
+                        window_close_requested_events.send(WindowCloseRequested { id: window_id });
+                    }
+                    WindowEvent::KeyboardInput { ref input, .. } => {
+                        let mut keyboard_input_events =
+                            app.resources.get_mut::<Events<KeyboardInput>>().unwrap();
+                        keyboard_input_events
+                            .send(converters::convert_keyboard_input(input, window_id));
+                    }
+                    WindowEvent::CursorMoved { position, .. } => {
+                        let mut cursor_moved_events =
+                            app.resources.get_mut::<Events<CursorMoved>>().unwrap();
+                        let scale_factor = winit_window.scale_factor();
+                        let inner_size = winit_window.inner_size().to_logical::<f32>(scale_factor);
+                        let position = position.to_logical::<f32>(scale_factor);
+                        // move origin to bottom left
+                        let y_position = inner_size.height - position.y;
+                        let position = Vec2::new(position.x, y_position);
+                        let mut windows = app.resources.get_mut::<Windows>().unwrap();
+                        windows.get_mut(window_id).unwrap().cursor_position = Some(position);
+                        cursor_moved_events.send(CursorMoved {
+                            id: window_id,
+                            position,
                         });
                     }
-                    event::MouseScrollDelta::PixelDelta(p) => {
-                        let mut mouse_wheel_input_events =
-                            app.resources.get_mut::<Events<MouseWheel>>().unwrap();
-                        mouse_wheel_input_events.send(MouseWheel {
-                            unit: MouseScrollUnit::Pixel,
-                            x: p.x as f32,
-                            y: p.y as f32,
+                    WindowEvent::CursorLeft { .. } => {
+                        let mut windows = app.resources.get_mut::<Windows>().unwrap();
+                        windows.get_mut(window_id).unwrap().cursor_position = None;
+                        let mut cursor_left_events =
+                            app.resources.get_mut::<Events<CursorLeft>>().unwrap();
+                        cursor_left_events.send(CursorLeft { id: window_id });
+                    }
+                    WindowEvent::Touch(touch) => {
+                        let mut touch_input_events =
+                            app.resources.get_mut::<Events<TouchInput>>().unwrap();
+                        let scale_factor = winit_window.scale_factor();
+                        let height = winit_window
+                            .inner_size()
+                            .to_logical::<f32>(scale_factor)
+                            .height;
+                        touch_input_events.send(converters::convert_touch_input(
+                            touch,
+                            window_id,
+                            scale_factor,
+                            height,
+                        ));
+                    }
+                    WindowEvent::MouseInput { state, button, .. } => {
+                        let mut mouse_button_input_events =
+                            app.resources.get_mut::<Events<MouseButtonInput>>().unwrap();
+                        mouse_button_input_events.send(MouseButtonInput {
+                            window_id,
+                            button: converters::convert_mouse_button(button),
+                            state: converters::convert_element_state(state),
                         });
                     }
-                },
-                _ => {}
-            },
+                    WindowEvent::MouseWheel { delta, .. } => match delta {
+                        event::MouseScrollDelta::LineDelta(x, y) => {
+                            let mut mouse_wheel_input_events =
+                                app.resources.get_mut::<Events<MouseWheel>>().unwrap();
+                            mouse_wheel_input_events.send(MouseWheel {
+                                window_id,
+                                unit: MouseScrollUnit::Line,
+                                x,
+                                y,
+                            });
+                        }
+                        event::MouseScrollDelta::PixelDelta(p) => {
+                            let mut mouse_wheel_input_events =
+                                app.resources.get_mut::<Events<MouseWheel>>().unwrap();
+                            mouse_wheel_input_events.send(MouseWheel {
+                                window_id,
+                                unit: MouseScrollUnit::Pixel,
+                                x: p.x as f32,
+                                y: p.y as f32,
+                            });
+                        }
+                    },
+                    _ => {}
+                }
+            }
             event::Event::DeviceEvent { ref event, .. } => {
                 if let DeviceEvent::MouseMotion { delta } = event {
                     let mut mouse_motion_events =
@@ -204,12 +268,19 @@ pub fn winit_runner(mut app: App) {
                 }
             }
             event::Event::MainEventsCleared => {
+                handle_close_window_events(&mut app.resources, &mut close_window_event_reader);
                 handle_create_window_events(
                     &mut app.resources,
                     event_loop,
                     &mut create_window_event_reader,
                 );
                 app.update();
+                // the renderer released the surfaces of closed windows during the update
+                app.resources
+                    .get_mut::<WinitWindows>()
+                    .unwrap()
+                    .drop_closed_windows();
+                handle_window_commands(&mut app.resources);
             }
             _ => (),
         }
@@ -221,6 +292,32 @@ pub fn winit_runner(mut app: App) {
     }
 }
 
+fn handle_window_commands(resources: &mut Resources) {
+    let mut winit_windows = resources.get_mut::<WinitWindows>().unwrap();
+    let mut windows = resources.get_mut::<Windows>().unwrap();
+    for window in windows.iter_mut() {
+        winit_windows.apply_commands(window);
+    }
+}
+
+fn handle_close_window_events(
+    resources: &mut Resources,
+    close_window_event_reader: &mut EventReader<CloseWindow>,
+) {
+    let mut winit_windows = resources.get_mut::<WinitWindows>().unwrap();
+    let mut windows = resources.get_mut::<Windows>().unwrap();
+    let close_window_events = resources.get::<Events<CloseWindow>>().unwrap();
+    let mut window_closed_events = resources.get_mut::<Events<WindowClosed>>().unwrap();
+    for close_window_event in close_window_event_reader.iter(&close_window_events) {
+        if windows.remove(close_window_event.id).is_some() {
+            winit_windows.remove_window(close_window_event.id);
+            window_closed_events.send(WindowClosed {
+                id: close_window_event.id,
+            });
+        }
+    }
+}
+
 fn handle_create_window_events(
     resources: &mut Resources,
     event_loop: &EventLoopWindowTarget<()>,
@@ -231,8 +328,8 @@ fn handle_create_window_events(
     let create_window_events = resources.get::<Events<CreateWindow>>().unwrap();
     let mut window_created_events = resources.get_mut::<Events<WindowCreated>>().unwrap();
     for create_window_event in create_window_event_reader.iter(&create_window_events) {
-        let window = Window::new(create_window_event.id, &create_window_event.descriptor);
-        winit_windows.create_window(event_loop, &window);
+        let mut window = Window::new(create_window_event.id, &create_window_event.descriptor);
+        winit_windows.create_window(event_loop, &mut window);
         let window_id = window.id;
         windows.add(window);
         window_created_events.send(WindowCreated { id: window_id });
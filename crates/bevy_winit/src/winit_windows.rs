@@ -1,18 +1,29 @@
 use bevy_utils::HashMap;
-use bevy_window::{Window, WindowId, WindowMode};
+use bevy_window::{Window, WindowCommand, WindowId, WindowMode};
+use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
+
+/// The size and position a fullscreen window returns to when it is windowed again
+#[derive(Debug, Clone, Copy)]
+pub struct WindowedState {
+    pub size: PhysicalSize<u32>,
+    pub position: Option<PhysicalPosition<i32>>,
+}
 
 #[derive(Default)]
 pub struct WinitWindows {
     pub windows: HashMap<winit::window::WindowId, winit::window::Window>,
     pub window_id_to_winit: HashMap<WindowId, winit::window::WindowId>,
     pub winit_to_window_id: HashMap<winit::window::WindowId, WindowId>,
+    pub windowed_states: HashMap<WindowId, WindowedState>,
+    /// Closed windows that stay open until the renderer has released their surfaces
+    pub closed_windows: Vec<winit::window::Window>,
 }
 
 impl WinitWindows {
     pub fn create_window(
         &mut self,
         event_loop: &winit::event_loop::EventLoopWindowTarget<()>,
-        window: &Window,
+        window: &mut Window,
     ) {
         #[cfg(target_os = "windows")]
         let mut winit_window_builder = {
@@ -29,12 +40,16 @@ impl WinitWindows {
             )),
             WindowMode::Fullscreen { use_size } => winit_window_builder.with_fullscreen(Some(
                 winit::window::Fullscreen::Exclusive(match use_size {
-                    true => get_fitting_videomode(&event_loop.primary_monitor(), &window),
+                    true => get_fitting_videomode(
+                        &event_loop.primary_monitor(),
+                        window.physical_width,
+                        window.physical_height,
+                    ),
                     false => get_best_videomode(&event_loop.primary_monitor()),
                 }),
             )),
             _ => winit_window_builder
-                .with_inner_size(winit::dpi::PhysicalSize::new(window.width, window.height))
+                .with_inner_size(LogicalSize::new(window.width(), window.height()))
                 .with_resizable(window.resizable),
         };
 
@@ -43,12 +58,150 @@ impl WinitWindows {
             .build(&event_loop)
             .unwrap();
 
+        if window.cursor_locked {
+            if let Err(err) = winit_window.set_cursor_grab(true) {
+                log::error!("failed to lock the cursor: {}", err);
+            }
+        }
+        winit_window.set_cursor_visible(window.cursor_visible);
+        if window.mode != WindowMode::Windowed {
+            self.windowed_states.insert(
+                window.id,
+                WindowedState {
+                    size: PhysicalSize::new(window.physical_width, window.physical_height),
+                    position: None,
+                },
+            );
+        }
+
+        let inner_size = winit_window.inner_size();
+        window.physical_width = inner_size.width;
+        window.physical_height = inner_size.height;
+        window.scale_factor = winit_window.scale_factor();
+
         self.window_id_to_winit.insert(window.id, winit_window.id());
         self.winit_to_window_id.insert(winit_window.id(), window.id);
 
         self.windows.insert(winit_window.id(), winit_window);
     }
 
+    /// Stops tracking the winit window of `id`, which is closed by the next
+    /// [WinitWindows::drop_closed_windows]
+    pub fn remove_window(&mut self, id: WindowId) -> bool {
+        let winit_id = match self.window_id_to_winit.remove(&id) {
+            Some(winit_id) => winit_id,
+            None => return false,
+        };
+        self.winit_to_window_id.remove(&winit_id);
+        self.windowed_states.remove(&id);
+        match self.windows.remove(&winit_id) {
+            Some(winit_window) => {
+                self.closed_windows.push(winit_window);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drops the windows removed since the last call, which closes them. This has to wait until
+    /// the renderer has handled their `WindowClosed` events, as their surfaces must not outlive them
+    pub fn drop_closed_windows(&mut self) {
+        self.closed_windows.clear();
+    }
+
+    /// Applies the changes queued on `window` to its winit window
+    pub fn apply_commands(&mut self, window: &mut Window) {
+        // borrow the field on its own, so `windowed_states` can still be updated below
+        let windows = &self.windows;
+        let winit_window = match self
+            .window_id_to_winit
+            .get(&window.id)
+            .and_then(|id| windows.get(id))
+        {
+            Some(winit_window) => winit_window,
+            None => return,
+        };
+        let commands = window.drain_commands().collect::<Vec<_>>();
+        for command in commands {
+            match command {
+                WindowCommand::SetWindowMode { mode } => {
+                    let fullscreen = winit_window.fullscreen();
+                    match mode {
+                        WindowMode::Windowed => {
+                            winit_window.set_fullscreen(None);
+                            if let Some(state) = self.windowed_states.remove(&window.id) {
+                                winit_window.set_inner_size(state.size);
+                                if let Some(position) = state.position {
+                                    winit_window.set_outer_position(position);
+                                }
+                            }
+                        }
+                        _ => {
+                            if fullscreen.is_none() {
+                                self.windowed_states.insert(
+                                    window.id,
+                                    WindowedState {
+                                        size: winit_window.inner_size(),
+                                        position: winit_window.outer_position().ok(),
+                                    },
+                                );
+                            }
+                            let monitor = winit_window.current_monitor();
+                            let size = self
+                                .windowed_states
+                                .get(&window.id)
+                                .map(|state| state.size)
+                                .unwrap_or_else(|| winit_window.inner_size());
+                            winit_window.set_fullscreen(Some(match mode {
+                                WindowMode::Fullscreen { use_size: true } => {
+                                    winit::window::Fullscreen::Exclusive(get_fitting_videomode(
+                                        &monitor,
+                                        size.width,
+                                        size.height,
+                                    ))
+                                }
+                                WindowMode::Fullscreen { use_size: false } => {
+                                    winit::window::Fullscreen::Exclusive(get_best_videomode(
+                                        &monitor,
+                                    ))
+                                }
+                                _ => winit::window::Fullscreen::Borderless(monitor),
+                            }));
+                        }
+                    }
+                }
+                WindowCommand::SetResolution { width, height } => {
+                    let size = LogicalSize::new(width, height)
+                        .to_physical::<u32>(winit_window.scale_factor());
+                    match self.windowed_states.get_mut(&window.id) {
+                        // fullscreen windows keep their size until they are windowed
+                        Some(state) => {
+                            state.size = size;
+                            if let WindowMode::Fullscreen { use_size: true } = window.mode {
+                                winit_window.set_fullscreen(Some(
+                                    winit::window::Fullscreen::Exclusive(get_fitting_videomode(
+                                        &winit_window.current_monitor(),
+                                        size.width,
+                                        size.height,
+                                    )),
+                                ));
+                            }
+                        }
+                        None => winit_window.set_inner_size(size),
+                    }
+                }
+                WindowCommand::SetCursorLock { locked } => {
+                    if let Err(err) = winit_window.set_cursor_grab(locked) {
+                        log::error!("failed to lock the cursor: {}", err);
+                    }
+                }
+                WindowCommand::SetCursorVisible { visible } => {
+                    winit_window.set_cursor_visible(visible);
+                }
+            }
+        }
+    }
+
     pub fn get_window(&self, id: WindowId) -> Option<&winit::window::Window> {
         self.window_id_to_winit
             .get(&id)
@@ -61,7 +214,8 @@ impl WinitWindows {
 }
 fn get_fitting_videomode(
     monitor: &winit::monitor::MonitorHandle,
-    window: &Window,
+    width: u32,
+    height: u32,
 ) -> winit::monitor::VideoMode {
     let mut modes = monitor.video_modes().collect::<Vec<_>>();
 
@@ -74,11 +228,9 @@ fn get_fitting_videomode(
 
     modes.sort_by(|a, b| {
         use std::cmp::Ordering::*;
-        match abs_diff(a.size().width, window.width).cmp(&abs_diff(b.size().width, window.width)) {
+        match abs_diff(a.size().width, width).cmp(&abs_diff(b.size().width, width)) {
             Equal => {
-                match abs_diff(a.size().height, window.height)
-                    .cmp(&abs_diff(b.size().height, window.height))
-                {
+                match abs_diff(a.size().height, height).cmp(&abs_diff(b.size().height, height)) {
                     Equal => b.refresh_rate().cmp(&a.refresh_rate()),
                     default => default,
                 }
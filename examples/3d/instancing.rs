@@ -0,0 +1,71 @@
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// This example draws a large number of cubes that share a mesh and material with a single
+/// instanced draw call. Each cube is still its own entity with its own position and color.
+/// Compare its performance to the "spawner" example, which draws every cube separately.
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_plugin(FrameTimeDiagnosticsPlugin::default())
+        .add_plugin(LogDiagnosticsPlugin::default())
+        .add_startup_system(setup.system())
+        .add_system(move_cubes.system())
+        .run();
+}
+
+fn move_cubes(time: Res<Time>, mut query: Query<(&Instance, &mut Translation)>) {
+    for (_instance, mut translation) in &mut query.iter() {
+        let phase = translation.x() * 0.2 + translation.y() * 0.1;
+        translation.set_z((time.seconds_since_startup as f32 + phase).sin() * 2.0);
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands
+        // light
+        .spawn(LightComponents {
+            translation: Translation::new(4.0, -4.0, 5.0),
+            ..Default::default()
+        })
+        // camera
+        .spawn(Camera3dComponents {
+            transform: Transform::new_sync_disabled(Mat4::face_toward(
+                Vec3::new(0.0, 15.0, 150.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.0, 1.0),
+            )),
+            ..Default::default()
+        })
+        // the instanced mesh: its mesh and material are used to draw every instance
+        .spawn(PbrComponents {
+            mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+            material: materials.add(Color::WHITE.into()),
+            ..Default::default()
+        })
+        .with(InstancedMesh::default());
+    let instanced_mesh = commands.current_entity().unwrap();
+
+    let mut rng = StdRng::from_entropy();
+    for _ in 0..10000 {
+        commands.spawn((
+            Instance {
+                instanced_mesh,
+                color: Color::rgb(
+                    rng.gen_range(0.0, 1.0),
+                    rng.gen_range(0.0, 1.0),
+                    rng.gen_range(0.0, 1.0),
+                ),
+            },
+            Transform::default(),
+            Translation::new(rng.gen_range(-50.0, 50.0), rng.gen_range(-50.0, 50.0), 0.0),
+        ));
+    }
+}
@@ -1,13 +1,14 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, render::renderer::RenderResourceContext};
 
 /// This example shows how to configure Multi-Sample Anti-Aliasing. Setting the sample count higher will result in smoother edges,
 /// but it will also increase the cost to render those edges. The range should generally be somewhere between 1 (no multi sampling,
-/// but cheap) to 8 (crisp but expensive)
+/// but cheap) to 8 (crisp but expensive). The sample count can also be changed while the app is running.
 fn main() {
     App::build()
         .add_resource(Msaa { samples: 4 })
         .add_default_plugins()
         .add_startup_system(setup.system())
+        .add_system(cycle_msaa.system())
         .run();
 }
 
@@ -40,3 +41,20 @@ fn setup(
             ..Default::default()
         });
 }
+
+/// pressing M cycles through the supported sample counts
+fn cycle_msaa(
+    keyboard_input: Res<Input<KeyCode>>,
+    render_resource_context: Res<Box<dyn RenderResourceContext>>,
+    mut msaa: ResMut<Msaa>,
+) {
+    if keyboard_input.just_pressed(KeyCode::M) {
+        let samples = Msaa::supported_sample_counts(&**render_resource_context);
+        let index = samples
+            .iter()
+            .position(|samples| *samples == msaa.samples)
+            .unwrap_or(0);
+        msaa.samples = samples[(index + 1) % samples.len()];
+        println!("MSAA samples: {}", msaa.samples);
+    }
+}
@@ -29,7 +29,7 @@ fn setup(
 ) {
     let cube_handle = meshes.add(Mesh::from(shape::Cube { size: 1.0 }));
     let cube_material_handle = materials.add(StandardMaterial {
-        albedo: Color::rgb(0.5, 0.4, 0.3),
+        base_color: Color::rgb(0.5, 0.4, 0.3),
         ..Default::default()
     });
 
@@ -1,5 +1,5 @@
 use bevy::{
-    diagnostic::{FrameTimeDiagnosticsPlugin, PrintDiagnosticsPlugin},
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
     prelude::*,
 };
 use rand::{rngs::StdRng, Rng, SeedableRng};
@@ -12,7 +12,7 @@ fn main() {
     App::build()
         .add_default_plugins()
         .add_plugin(FrameTimeDiagnosticsPlugin::default())
-        .add_plugin(PrintDiagnosticsPlugin::default())
+        .add_plugin(LogDiagnosticsPlugin::default())
         .add_startup_system(setup.system())
         .add_system(move_cubes.system())
         .run();
@@ -26,7 +26,7 @@ fn move_cubes(
     for (mut translation, material_handle) in &mut query.iter() {
         let material = materials.get_mut(&material_handle).unwrap();
         translation.0 += Vec3::new(1.0, 0.0, 0.0) * time.delta_seconds;
-        material.albedo =
+        material.base_color =
             Color::BLUE * Vec3::splat((3.0 * time.seconds_since_startup as f32).sin());
     }
 }
@@ -58,7 +58,7 @@ fn setup(
         commands.spawn(PbrComponents {
             mesh: cube_handle,
             material: materials.add(StandardMaterial {
-                albedo: Color::rgb(
+                base_color: Color::rgb(
                     rng.gen_range(0.0, 1.0),
                     rng.gen_range(0.0, 1.0),
                     rng.gen_range(0.0, 1.0),
@@ -41,7 +41,7 @@ struct PrintMessageState {
 
 fn print_message_system(mut state: ResMut<PrintMessageState>, time: Res<Time>) {
     state.timer.tick(time.delta_seconds);
-    if state.timer.finished {
+    if state.timer.finished() {
         println!("{}", state.message);
     }
 }
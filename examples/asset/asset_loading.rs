@@ -39,7 +39,7 @@ fn setup(
 
     // You can also add assets directly to their Assets<T> storage:
     let material_handle = materials.add(StandardMaterial {
-        albedo: Color::rgb(0.5, 0.4, 0.3),
+        base_color: Color::rgb(0.5, 0.4, 0.3),
         ..Default::default()
     });
 
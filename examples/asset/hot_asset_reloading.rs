@@ -28,7 +28,7 @@ fn setup(
 
     // Create a material for the mesh:
     let material_handle = materials.add(StandardMaterial {
-        albedo: Color::rgb(0.5, 0.4, 0.3),
+        base_color: Color::rgb(0.5, 0.4, 0.3),
         ..Default::default()
     });
 
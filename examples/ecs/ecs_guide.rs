@@ -289,11 +289,18 @@ fn main() {
         .add_stage_after(stage::UPDATE, "after_round")
         .add_system_to_stage("before_round", new_round_system.system())
         .add_system_to_stage("before_round", new_player_system.system())
-        .add_system_to_stage("after_round", score_check_system.system())
-        .add_system_to_stage("after_round", game_over_system.system())
-        // score_check_system will run before game_over_system because score_check_system modifies GameState and game_over_system
-        // reads GameState. This works, but it's a bit confusing. In practice, it would be clearer to create a new stage that runs
-        // before "after_round"
+        .add_system_to_stage(
+            "after_round",
+            score_check_system.system().label("score_check"),
+        )
+        .add_system_to_stage(
+            "after_round",
+            game_over_system.system().after("score_check"),
+        )
+        // Within a stage, systems can be ordered explicitly by labeling them and running other systems before or after those
+        // labels. score_check_system would run before game_over_system anyway, because score_check_system modifies GameState,
+        // game_over_system reads GameState, and score_check_system was added first. But that is easy to break by accident. The
+        // label makes the order explicit, and keeps it no matter which system is added first.
         // This call to run() starts the app we just built!
         .run();
 }
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+
+/// This example shows how to run systems only in a given app state, and when entering or leaving
+/// it. The app starts in the menu, and pressing space starts (or restarts) the game.
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_state(AppState::Menu)
+        .on_state_enter(AppState::Menu, enter_menu.system())
+        .on_state_update(AppState::Menu, menu.system())
+        .on_state_exit(AppState::Menu, exit_menu.system())
+        .on_state_enter(AppState::Playing, enter_playing.system())
+        .on_state_update(AppState::Playing, playing.system())
+        .run();
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AppState {
+    Menu,
+    Playing,
+}
+
+fn enter_menu() {
+    println!("entered the menu, press space to play");
+}
+
+fn menu(keyboard_input: Res<Input<KeyCode>>, mut state: ResMut<State<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        state.set(AppState::Playing);
+    }
+}
+
+fn exit_menu() {
+    println!("left the menu");
+}
+
+fn enter_playing(state: Res<State<AppState>>) {
+    println!("started playing, coming from {:?}", state.previous());
+}
+
+// pressing space restarts the game, which runs its enter systems again. Setting the state that is
+// already active wouldn't do anything.
+fn playing(keyboard_input: Res<Input<KeyCode>>, mut state: ResMut<State<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        state.restart();
+    } else if keyboard_input.just_pressed(KeyCode::Escape) {
+        state.set(AppState::Menu);
+    }
+}
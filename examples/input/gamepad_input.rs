@@ -0,0 +1,43 @@
+use bevy::{
+    input::gamepad::{Gamepad, GamepadEvent, GamepadEventType},
+    prelude::*,
+};
+
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_system(gamepad_system.system())
+        .run();
+}
+
+/// This system prints gamepad connections and the state of the first gamepad's South button and
+/// left stick
+fn gamepad_system(
+    mut gamepad_event_reader: Local<EventReader<GamepadEvent>>,
+    gamepad_events: Res<Events<GamepadEvent>>,
+    button_input: Res<Input<GamepadButton>>,
+    axis: Res<Axis<GamepadAxis>>,
+) {
+    for GamepadEvent(gamepad, event_type) in gamepad_event_reader.iter(&gamepad_events) {
+        match event_type {
+            GamepadEventType::Connected => println!("{:?} connected", gamepad),
+            GamepadEventType::Disconnected => println!("{:?} disconnected", gamepad),
+        }
+    }
+
+    let south = GamepadButton(Gamepad(0), GamepadButtonType::South);
+    if button_input.just_pressed(south) {
+        println!("South button just pressed");
+    }
+    if button_input.just_released(south) {
+        println!("South button just released");
+    }
+
+    let x = axis.get(GamepadAxis(Gamepad(0), GamepadAxisType::LeftStickX));
+    let y = axis.get(GamepadAxis(Gamepad(0), GamepadAxisType::LeftStickY));
+    if let (Some(x), Some(y)) = (x, y) {
+        if x != 0.0 || y != 0.0 {
+            println!("left stick: ({}, {})", x, y);
+        }
+    }
+}
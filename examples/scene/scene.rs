@@ -144,6 +144,7 @@ fn infotext_system(mut commands: Commands, asset_server: Res<AssetServer>) {
                 style: TextStyle {
                     font_size: 50.0,
                     color: Color::WHITE,
+                    ..Default::default()
                 },
             },
             ..Default::default()
@@ -0,0 +1,65 @@
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::shape,
+        pipeline::{DynamicBinding, PipelineDescriptor, PipelineSpecialization, RenderPipeline},
+        shader::ShaderStages,
+    },
+};
+
+/// This example illustrates loading shaders from files. Shaders loaded this way are reloaded when
+/// they, or a file they `#import`, change on disk. Shaders that fail to compile are logged and the
+/// last working version keeps rendering.
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut pipelines: ResMut<Assets<PipelineDescriptor>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    // Tell the asset server to watch for asset changes on disk:
+    asset_server.watch_for_changes().unwrap();
+
+    // Create a new shader pipeline from shader files. Try editing hot.frag or lighting.glsl, which
+    // hot.frag imports, while the example is running.
+    let pipeline_handle = pipelines.add(PipelineDescriptor::default_config(ShaderStages {
+        vertex: asset_server.load("assets/shaders/hot.vert").unwrap(),
+        fragment: Some(asset_server.load("assets/shaders/hot.frag").unwrap()),
+    }));
+
+    // Setup our world
+    commands
+        // cube
+        .spawn(MeshComponents {
+            mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+            render_pipelines: RenderPipelines::from_pipelines(vec![RenderPipeline::specialized(
+                pipeline_handle,
+                PipelineSpecialization {
+                    dynamic_bindings: vec![
+                        // Transform
+                        DynamicBinding {
+                            bind_group: 1,
+                            binding: 0,
+                        },
+                    ],
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        })
+        // camera
+        .spawn(Camera3dComponents {
+            transform: Transform::new_sync_disabled(Mat4::face_toward(
+                Vec3::new(3.0, 5.0, -8.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            )),
+            ..Default::default()
+        });
+}
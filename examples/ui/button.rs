@@ -86,6 +86,7 @@ fn setup(
                     style: TextStyle {
                         font_size: 40.0,
                         color: Color::rgb(0.8, 0.8, 0.8),
+                        ..Default::default()
                     },
                 },
                 ..Default::default()
@@ -38,9 +38,9 @@ fn atlas_render_system(
         return;
     }
     if let Some(set) = font_atlas_sets.get(&state.handle.as_handle::<FontAtlasSet>()) {
-        if let Some((_size, font_atlas)) = set.iter().next() {
+        if let Some((_size, font_atlases)) = set.iter().next() {
             state.added = true;
-            let texture_atlas = texture_atlases.get(&font_atlas.texture_atlas).unwrap();
+            let texture_atlas = texture_atlases.get(&font_atlases[0].texture_atlas).unwrap();
             commands.spawn(ImageComponents {
                 material: materials.add(texture_atlas.texture.into()),
                 style: Style {
@@ -61,7 +61,7 @@ fn atlas_render_system(
 fn text_update_system(mut state: ResMut<State>, time: Res<Time>, mut query: Query<&mut Text>) {
     for mut text in &mut query.iter() {
         state.timer.tick(time.delta_seconds);
-        if state.timer.finished {
+        if state.timer.finished() {
             text.value = format!("{}", rand::random::<u8>() as char);
             state.timer.reset();
         }
@@ -84,6 +84,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut state: ResM
                 style: TextStyle {
                     font_size: 60.0,
                     color: Color::WHITE,
+                    ..Default::default()
                 },
             },
             ..Default::default()
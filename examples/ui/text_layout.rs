@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+/// This example illustrates how to wrap and align multi-line text, like the text of a dialogue box
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let font_handle = asset_server.load("assets/fonts/FiraSans-Bold.ttf").unwrap();
+    commands
+        // ui camera
+        .spawn(UiCameraComponents::default())
+        // dialogue box
+        .spawn(NodeComponents {
+            style: Style {
+                size: Size::new(Val::Px(500.0), Val::Px(200.0)),
+                margin: Rect::all(Val::Auto),
+                padding: Rect::all(Val::Px(20.0)),
+                ..Default::default()
+            },
+            material: materials.add(Color::rgb(0.1, 0.1, 0.2).into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextComponents {
+                style: Style {
+                    flex_grow: 1.0,
+                    ..Default::default()
+                },
+                text: Text {
+                    value: "Long lines are wrapped at word boundaries to fit the box.\n\
+                            Newlines start a new line, and each line is centered."
+                        .to_string(),
+                    font: font_handle,
+                    style: TextStyle {
+                        font_size: 30.0,
+                        color: Color::WHITE,
+                        alignment: TextAlignment {
+                            horizontal: HorizontalAlign::Center,
+                            vertical: VerticalAlign::Center,
+                        },
+                        max_width: Some(460.0),
+                    },
+                },
+                ..Default::default()
+            });
+        });
+}
@@ -65,6 +65,7 @@ fn setup(
                                     style: TextStyle {
                                         font_size: 30.0,
                                         color: Color::WHITE,
+                                        ..Default::default()
                                     },
                                 },
                                 ..Default::default()
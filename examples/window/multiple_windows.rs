@@ -35,8 +35,8 @@ fn setup(
     create_window_events.send(CreateWindow {
         id: window_id,
         descriptor: WindowDescriptor {
-            width: 800,
-            height: 600,
+            width: 800.0,
+            height: 600.0,
             vsync: false,
             title: "second window".to_string(),
             ..Default::default()
@@ -159,7 +159,7 @@ fn setup(
 
     // create a material for the mesh
     let material_handle = materials.add(StandardMaterial {
-        albedo: Color::rgb(0.5, 0.4, 0.3),
+        base_color: Color::rgb(0.5, 0.4, 0.3),
         ..Default::default()
     });
 
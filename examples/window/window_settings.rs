@@ -1,17 +1,42 @@
-use bevy::{prelude::*, window::WindowMode};
+use bevy::{
+    prelude::*,
+    window::{WindowId, WindowMode},
+};
 
-/// This example illustrates how to customize the default window settings
+/// This example illustrates how to customize the default window settings and change them at runtime
 fn main() {
     App::build()
         .add_resource(WindowDescriptor {
             title: "I am a window!".to_string(),
-            width: 300,
-            height: 300,
+            width: 300.0,
+            height: 300.0,
             vsync: true,
             resizable: false,
             mode: WindowMode::Fullscreen { use_size: false },
             ..Default::default()
         })
         .add_default_plugins()
+        .add_system(toggle_window_settings_system.system())
         .run();
 }
+
+/// Press F to toggle fullscreen and L to lock the cursor to the window
+fn toggle_window_settings_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut windows: ResMut<Windows>,
+) {
+    let window = windows.get_mut(WindowId::primary()).unwrap();
+    if keyboard_input.just_pressed(KeyCode::F) {
+        let mode = match window.mode {
+            WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+            _ => WindowMode::Windowed,
+        };
+        window.set_mode(mode);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::L) {
+        let locked = !window.cursor_locked;
+        window.set_cursor_lock(locked);
+        window.set_cursor_visible(!locked);
+    }
+}
@@ -1,37 +1,60 @@
-use crate::app::AppBuilder;
+use crate::app::{AppBuilder, PluginGroup, PluginGroupBuilder};
 
-pub trait AddDefaultPlugins {
-    fn add_default_plugins(&mut self) -> &mut Self;
-}
+/// The plugins added by [AddDefaultPlugins::add_default_plugins]
+///
+/// Use [AppBuilder::add_plugins_with] to leave some of them out or to slot in plugins of your own:
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # struct PhysicsPlugin;
+/// # impl Plugin for PhysicsPlugin { fn build(&self, _app: &mut AppBuilder) {} }
+/// App::build().add_plugins_with(DefaultPlugins, |group| {
+///     group
+///         .disable::<bevy::diagnostic::DiagnosticsPlugin>()
+///         .add_before::<bevy::render::RenderPlugin, _>(PhysicsPlugin)
+/// });
+/// ```
+/// Plugins that depend on a disabled plugin must be disabled too.
+pub struct DefaultPlugins;
 
-impl AddDefaultPlugins for AppBuilder {
-    fn add_default_plugins(&mut self) -> &mut Self {
-        self.add_plugin(bevy_type_registry::TypeRegistryPlugin::default());
-        self.add_plugin(bevy_core::CorePlugin::default());
-        self.add_plugin(bevy_transform::TransformPlugin::default());
-        self.add_plugin(bevy_diagnostic::DiagnosticsPlugin::default());
-        self.add_plugin(bevy_input::InputPlugin::default());
-        self.add_plugin(bevy_window::WindowPlugin::default());
-        self.add_plugin(bevy_asset::AssetPlugin::default());
-        self.add_plugin(bevy_scene::ScenePlugin::default());
-        self.add_plugin(bevy_render::RenderPlugin::default());
-        self.add_plugin(bevy_sprite::SpritePlugin::default());
-        self.add_plugin(bevy_pbr::PbrPlugin::default());
-        self.add_plugin(bevy_ui::UiPlugin::default());
-        self.add_plugin(bevy_text::TextPlugin::default());
+impl PluginGroup for DefaultPlugins {
+    fn build(&mut self, group: &mut PluginGroupBuilder) {
+        group.add(bevy_type_registry::TypeRegistryPlugin::default());
+        group.add(bevy_core::CorePlugin::default());
+        group.add(bevy_transform::TransformPlugin::default());
+        group.add(bevy_diagnostic::DiagnosticsPlugin::default());
+        group.add(bevy_input::InputPlugin::default());
+        group.add(bevy_window::WindowPlugin::default());
+        group.add(bevy_asset::AssetPlugin::default());
+        group.add(bevy_scene::ScenePlugin::default());
+        group.add(bevy_render::RenderPlugin::default());
+        group.add(bevy_sprite::SpritePlugin::default());
+        group.add(bevy_pbr::PbrPlugin::default());
+        group.add(bevy_ui::UiPlugin::default());
+        group.add(bevy_text::TextPlugin::default());
 
         #[cfg(feature = "bevy_audio")]
-        self.add_plugin(bevy_audio::AudioPlugin::default());
+        group.add(bevy_audio::AudioPlugin::default());
+
+        #[cfg(feature = "bevy_gilrs")]
+        group.add(bevy_gilrs::GilrsPlugin::default());
 
         #[cfg(feature = "bevy_gltf")]
-        self.add_plugin(bevy_gltf::GltfPlugin::default());
+        group.add(bevy_gltf::GltfPlugin::default());
 
         #[cfg(feature = "bevy_winit")]
-        self.add_plugin(bevy_winit::WinitPlugin::default());
+        group.add(bevy_winit::WinitPlugin::default());
 
         #[cfg(feature = "bevy_wgpu")]
-        self.add_plugin(bevy_wgpu::WgpuPlugin::default());
+        group.add(bevy_wgpu::WgpuPlugin::default());
+    }
+}
 
-        self
+pub trait AddDefaultPlugins {
+    fn add_default_plugins(&mut self) -> &mut Self;
+}
+
+impl AddDefaultPlugins for AppBuilder {
+    fn add_default_plugins(&mut self) -> &mut Self {
+        self.add_plugins(DefaultPlugins)
     }
 }